@@ -1,5 +1,5 @@
 // [Korea Environment Corporation]: Integration of Real-Time Measurement Information by Station API (측정소별 실시간 측정정보 조회 API 연동)
-//  .route("/exteranl-pm", get(get_external_pm_data_handler))
+//  .route("/external-pm", get(get_external_pm_data_handler))  (old "/exteranl-pm" kept as an alias, see src/bin/server.rs)
 
 use crate::{
     models::{
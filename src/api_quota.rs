@@ -0,0 +1,159 @@
+// src/api_quota.rs
+
+// 공공데이터포털 서비스 키의 일일 요청 한도 추적 ({schema}.api_quota_usage)
+// 실시간 수집 실행이 끝날 때 실행에서 보낸 에어코리아 요청 수를 (키 해시, KST 날짜) 행에 더하고,
+// 응답 meta 에 오늘 사용량(quotaUsedToday)과 API_DAILY_QUOTA, 80% 초과 경고(quotaWarning)를 기록
+// 남은 한도보다 측정소가 많으면 모두 실패하기 전에 최근에 갱신된 측정소부터 다음 실행으로 미룸 (MAX_STATIONS_PER_RUN 과 같은 선택)
+// 테이블이 없으면 추적하지 않음
+
+use chrono::{DateTime, NaiveDate, Utc};
+use deadpool_postgres::Client as DbClient;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tokio_postgres::error::SqlState;
+use tracing::warn;
+
+use crate::db_schema::sql;
+use crate::timeutil::{to_source_time, KST_OFFSET};
+
+// 이 비율을 넘으면 meta.quotaWarning
+const QUOTA_WARNING_RATIO: f64 = 0.8;
+
+pub const GET_QUOTA_USAGE_QUERY: &str = r#"
+SELECT request_count
+FROM {schema}.api_quota_usage
+WHERE key_hash = $1 AND usage_date = $2;
+"#;
+
+pub const ADD_QUOTA_USAGE_QUERY: &str = r#"
+INSERT INTO {schema}.api_quota_usage (key_hash, usage_date, request_count, updated_at)
+VALUES ($1, $2, $3, now())
+ON CONFLICT (key_hash, usage_date)
+DO UPDATE SET
+    request_count = {schema}.api_quota_usage.request_count + EXCLUDED.request_count,
+    updated_at = now()
+RETURNING request_count;
+"#;
+
+// API_DAILY_QUOTA 환경 변수 (키의 일일 요청 한도, 미설정 시 사용량만 기록)
+pub fn daily_quota() -> Option<i64> {
+    std::env::var("API_DAILY_QUOTA")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|quota| *quota > 0)
+}
+
+// 서비스 키는 DB 에 그대로 남기지 않음
+// 실행 간에 같은 행을 찾아야 하므로 Rust 버전에 따라 바뀌지 않는 SHA-256 (hex) 사용
+pub fn key_hash(service_key: &str) -> String {
+    format!("{:x}", Sha256::digest(service_key.as_bytes()))
+}
+
+// 한도가 초기화되는 KST 기준 날짜
+pub fn quota_date(now: DateTime<Utc>) -> NaiveDate {
+    to_source_time(now, KST_OFFSET).date_naive()
+}
+
+// 오늘 사용량 (행이 없으면 0, 테이블이 없거나 조회에 실패하면 None = 추적하지 않음)
+pub async fn load_used(client: &DbClient, key_hash: &str, date: NaiveDate) -> Option<i64> {
+    match client
+        .query_opt(sql(GET_QUOTA_USAGE_QUERY).as_str(), &[&key_hash, &date])
+        .await
+    {
+        Ok(row) => Some(
+            row.and_then(|row| row.try_get::<_, i64>("request_count").ok())
+                .unwrap_or(0),
+        ),
+        Err(e) if e.code() == Some(&SqlState::UNDEFINED_TABLE) => None,
+        Err(e) => {
+            warn!("API 사용량 조회 실패, 한도 확인 없이 수집: {:?}", e);
+            None
+        }
+    }
+}
+
+// 실행의 요청 수를 더하고 더한 뒤의 오늘 사용량 반환 (실패하면 None)
+pub async fn add_usage(
+    client: &DbClient,
+    key_hash: &str,
+    date: NaiveDate,
+    requests: i64,
+) -> Option<i64> {
+    match client
+        .query_one(
+            sql(ADD_QUOTA_USAGE_QUERY).as_str(),
+            &[&key_hash, &date, &requests],
+        )
+        .await
+        .and_then(|row| row.try_get::<_, i64>("request_count"))
+    {
+        Ok(used) => Some(used),
+        Err(e) => {
+            warn!("API 사용량 기록 실패: {:?}", e);
+            None
+        }
+    }
+}
+
+// 남은 한도로 조회할 측정소 수 (측정소 하나를 요청 하나로 계산, 한도가 없으면 None)
+pub fn station_limit(used: i64, daily_quota: Option<i64>) -> Option<usize> {
+    daily_quota.map(|quota| usize::try_from(quota.saturating_sub(used)).unwrap_or(0))
+}
+
+// 실행 결과의 한도 사용 현황 (meta)
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuotaUsage {
+    // 이번 실행을 더한 오늘 사용량
+    pub used_today: i64,
+    pub daily_quota: Option<i64>,
+    // 이번 실행에서 보낸 요청 수
+    pub run_requests: i64,
+    // 한도 때문에 미룬 측정소 수
+    pub deferred: usize,
+}
+
+impl QuotaUsage {
+    pub fn warning(&self) -> bool {
+        self.daily_quota
+            .is_some_and(|quota| self.used_today as f64 > quota as f64 * QUOTA_WARNING_RATIO)
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "quotaUsedToday": self.used_today,
+            "apiDailyQuota": self.daily_quota,
+            "quotaWarning": self.warning(),
+            "quotaRunRequests": self.run_requests,
+            "quotaDeferred": self.deferred,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_hash_is_sha256_hex() {
+        assert_eq!(
+            key_hash("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_ne!(key_hash("abc"), key_hash("abd"));
+    }
+
+    #[test]
+    fn station_limit_is_remaining_quota() {
+        assert_eq!(station_limit(90, Some(100)), Some(10));
+        assert_eq!(station_limit(120, Some(100)), Some(0));
+        assert_eq!(station_limit(90, None), None);
+    }
+
+    #[test]
+    fn quota_date_rolls_over_at_kst_midnight() {
+        let before = "2024-03-01T14:59:59Z".parse::<DateTime<Utc>>().unwrap();
+        let after = "2024-03-01T15:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(quota_date(before).to_string(), "2024-03-01");
+        assert_eq!(quota_date(after).to_string(), "2024-03-02");
+    }
+}
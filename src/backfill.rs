@@ -0,0 +1,229 @@
+// src/backfill.rs
+
+// 이력 백필 (mode: "backfill")
+// 측정소별 DAILY 응답에는 최대 24개의 시간별 항목이 있지만 실시간 수집은 items[0] 만 저장하므로,
+// 응답의 전체 항목을 {schema}.external_pm_history 에 한 번에 insert 하여 누락된 시간을 채움
+
+use chrono::{DateTime, Utc};
+use deadpool_postgres::Client as DbClient;
+use futures::StreamExt;
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::db_error::{classify_db_error, describe_db_error};
+use crate::db_schema::sql;
+use crate::failure::FailureKind;
+use crate::handler::{
+    acquire_permit, new_run_id, per_station_timeout, station_stream, with_station_deadline,
+    FetchOptions, IngestReport, StationResult, SubRegionInfo,
+};
+use crate::inline_stations::StationListSource;
+use crate::phases::Phases;
+use crate::provider::airkorea::AIRKOREA_PROVIDER_KEY;
+use crate::provider::{AirKoreaProvider, Reading};
+use crate::state::{get_client_with_retry, ServerState};
+
+// 이미 저장된 (sub_region_id, recorded_at) 은 건너뜀
+pub const INSERT_EXTERNAL_PM_HISTORY_QUERY: &str = r#"
+INSERT INTO {schema}.external_pm_history (sub_region_id, pm10, pm25, recorded_at)
+SELECT $1, pm10, pm25, recorded_at
+FROM UNNEST($2::float8[], $3::float8[], $4::timestamptz[]) AS t(pm10, pm25, recorded_at)
+ON CONFLICT DO NOTHING;
+"#;
+
+// 측정값 목록을 한 번의 쿼리로 insert, 새로 저장된 행 수 반환
+pub async fn insert_history(
+    client: &DbClient,
+    sub_region_id: i32,
+    readings: &[Reading],
+) -> Result<u64, tokio_postgres::Error> {
+    let pm10: Vec<Option<f64>> = readings.iter().map(|reading| reading.pm10).collect();
+    let pm25: Vec<Option<f64>> = readings.iter().map(|reading| reading.pm25).collect();
+    let recorded_at: Vec<DateTime<Utc>> =
+        readings.iter().map(|reading| reading.recorded_at).collect();
+
+    client
+        .execute(
+            sql(INSERT_EXTERNAL_PM_HISTORY_QUERY).as_str(),
+            &[&sub_region_id, &pm10, &pm25, &recorded_at],
+        )
+        .await
+}
+
+// sub_region 목록 조회 후 측정소별 전체 항목을 이력 테이블에 insert
+pub async fn run_backfill(
+    state: Arc<ServerState>,
+    options: &FetchOptions,
+) -> Result<IngestReport, anyhow::Error> {
+    let start = tokio::time::Instant::now();
+    let run_id = options.run_id.clone().unwrap_or_else(new_run_id);
+
+    let db_client = state.pool.get().await?;
+
+    let rows = state
+        .sub_region_queries
+        .fetch_rows(
+            &db_client,
+            options.sub_region_ids.as_ref(),
+            options.include_disabled,
+        )
+        .await?;
+    drop(db_client);
+
+    // 전체 수집에서 sub_region 이 없으면 설정 오류로 구분
+    let no_sub_regions = rows.is_empty() && options.sub_region_ids.is_none();
+
+    let semaphore = options.semaphore();
+    let airkorea = AirKoreaProvider::new(state.api_client.clone(), state.air_quality_api_key())
+        .with_source_offset(state.source_offset);
+    let per_station_timeout = per_station_timeout();
+    let dry_run = options.dry_run;
+
+    let mut stations = Vec::new();
+    let mut results = Vec::new();
+
+    for (index, row) in rows.iter().enumerate() {
+        let sub_region = match SubRegionInfo::try_from_row(row) {
+            Ok(sub_region) => sub_region,
+            Err(e) => {
+                let error_message = format!("sub_region row {} : {}", index, e.message);
+                results.push(StationResult::failed(
+                    e.sub_region_id.unwrap_or_default(),
+                    "",
+                    FailureKind::RowMapping,
+                    error_message,
+                ));
+                continue;
+            }
+        };
+
+        // 이력 API 는 AirKorea 측정소 이름으로만 조회 가능
+        let sub_region_id = sub_region.sub_region_id;
+        let pm_station = match sub_region.pm_station {
+            Some(pm_station) if sub_region.provider == AIRKOREA_PROVIDER_KEY => pm_station,
+            _ => continue,
+        };
+
+        stations.push((sub_region_id, pm_station));
+    }
+
+    // 측정소 future 는 태스크로 만들지 않고 station_stream 에서 실행
+    let state = &state;
+    let airkorea = &airkorea;
+    let semaphore = &semaphore;
+    let stations = station_stream(stations.into_iter().map(|(sub_region_id, pm_station)| {
+        let task_label = pm_station.clone();
+
+        let station_task = async move {
+            let _permit = match acquire_permit(semaphore.clone(), sub_region_id, &pm_station).await
+            {
+                Ok(permit) => permit,
+                Err(result) => return result,
+            };
+
+            with_station_deadline(
+                sub_region_id,
+                &pm_station,
+                per_station_timeout,
+                backfill_station(state, airkorea, sub_region_id, &pm_station, dry_run),
+            )
+            .await
+        };
+
+        (sub_region_id, task_label, station_task)
+    }));
+    results.extend(stations.collect::<Vec<_>>().await);
+
+    Ok(IngestReport {
+        run_id,
+        results,
+        skipped_fresh: 0,
+        deferred: 0,
+        cache_hits: 0,
+        db_read_only: false,
+        no_sub_regions,
+        advanced: None,
+        data_frozen: false,
+        elapsed: start.elapsed(),
+        warnings: Vec::new(),
+        latest_cache_failures: 0,
+        disabled_sub_regions: None,
+        diagnostics: None,
+        phases: Phases::default(),
+        station_list_source: StationListSource::Db,
+        quota: None,
+    })
+}
+
+// 단일 측정소의 전체 항목 조회 및 이력 insert
+async fn backfill_station(
+    state: &ServerState,
+    airkorea: &AirKoreaProvider,
+    sub_region_id: i32,
+    pm_station: &str,
+    dry_run: bool,
+) -> StationResult {
+    let readings = match airkorea.fetch_history(pm_station).await {
+        Ok(readings) => readings,
+        Err(e) => return StationResult::failed(sub_region_id, pm_station, e.kind, e.message),
+    };
+
+    if dry_run {
+        return StationResult::success(
+            sub_region_id,
+            pm_station,
+            history_json(sub_region_id, pm_station, readings.len(), 0),
+        );
+    }
+
+    let (db_client, checkout_retries) = match get_client_with_retry(&state.pool).await {
+        Ok(client) => client,
+        Err(e) => {
+            let error_message = format!("{} : Failed to get db client: {:?}", pm_station, e);
+            return StationResult::failed(
+                sub_region_id,
+                pm_station,
+                FailureKind::DbPool,
+                error_message,
+            );
+        }
+    };
+
+    let result = match insert_history(&db_client, sub_region_id, &readings).await {
+        Ok(inserted) => StationResult::success(
+            sub_region_id,
+            pm_station,
+            history_json(sub_region_id, pm_station, readings.len(), inserted),
+        ),
+        Err(e) => {
+            let error_message = format!(
+                "{} : History insert failed: {}",
+                pm_station,
+                describe_db_error(&e)
+            );
+            StationResult::failed(
+                sub_region_id,
+                pm_station,
+                classify_db_error(&e),
+                error_message,
+            )
+        }
+    };
+
+    result.with_checkout_retries(checkout_retries)
+}
+
+// 측정소별 백필 결과 (응답 항목 수, 새로 저장된 행 수)
+fn history_json(
+    sub_region_id: i32,
+    pm_station: &str,
+    fetched: usize,
+    inserted: u64,
+) -> serde_json::Value {
+    json!({
+        "sub_region_id": sub_region_id,
+        "pm_station": pm_station,
+        "fetched": fetched,
+        "inserted": inserted,
+    })
+}
@@ -0,0 +1,461 @@
+// src/bin/cli.rs
+
+// Lambda 를 거치지 않고 로컬에서 수집을 직접 실행하는 CLI
+// 예) cargo run --bin cli -- --dry-run --station 중구
+//     cargo run --bin cli -- --format json   (Lambda 응답과 같은 data/meta JSON)
+//     cargo run --bin cli -- --loop --interval 600   (상시 실행: 10분마다 수집, Ctrl+C / SIGTERM 으로 종료)
+//     cargo run --bin cli -- --verify schema   (수집 없이 대상 DB 테이블/컬럼 확인, 불일치 시 종료 코드 1)
+
+use clap::{Parser, ValueEnum};
+use environment_lambda::handler::{
+    build_response_body, new_run_id, run_realtime_ingest, FetchOptions, IngestReport, StationStatus,
+};
+use environment_lambda::schema_check;
+use environment_lambda::state::{initialize_state_from_env, ServerState};
+use environment_lambda::ticker::shutdown_signal;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info};
+use tracing_subscriber::EnvFilter;
+
+#[derive(Debug, Parser)]
+#[command(name = "cli", about = "외부 PM 데이터 수집 로컬 실행")]
+pub struct Cli {
+    /// 외부 API 조회만 하고 DB 에 저장하지 않음
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// 지정한 측정소만 수집 (여러 번 지정 가능)
+    #[arg(long = "station", value_name = "NAME")]
+    pub stations: Vec<String>,
+
+    /// 저장된 측정 시각이 N분보다 오래된 측정소만 수집 (기본: PM_REFRESH_OLDER_THAN_MINUTES)
+    #[arg(long, value_name = "MINUTES")]
+    pub since: Option<i64>,
+
+    /// 시도 전체를 한 번의 호출로 조회하여 해당 시도 측정소만 수집 (예: 서울)
+    #[arg(long, value_name = "SIDO")]
+    pub sido: Option<String>,
+
+    /// 출력 형식
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+
+    /// 한 번 실행하고 끝내지 않고 --interval 마다 계속 수집 (종료 신호까지)
+    #[arg(long = "loop")]
+    pub loop_mode: bool,
+
+    /// --loop 의 수집 간격 (초)
+    #[arg(long, value_name = "SECS", default_value_t = 300, requires = "loop_mode", value_parser = clap::value_parser!(u64).range(1..))]
+    pub interval: u64,
+
+    /// 수집하지 않고 대상 DB 확인만 실행 (결과는 JSON)
+    #[arg(long, value_enum, value_name = "TARGET", conflicts_with = "loop_mode")]
+    pub verify: Option<VerifyTarget>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum VerifyTarget {
+    /// 테이블/컬럼 존재 여부와 타입
+    Schema,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// 측정소별 한 줄 표
+    Table,
+    /// data/meta JSON
+    Json,
+}
+
+impl Cli {
+    pub fn fetch_options(&self) -> FetchOptions {
+        FetchOptions {
+            stations: (!self.stations.is_empty()).then(|| self.stations.clone()),
+            dry_run: self.dry_run,
+            refresh_older_than: self
+                .since
+                .map(chrono::Duration::minutes)
+                .or_else(FetchOptions::refresh_older_than_from_env),
+            sido_name: self.sido.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // .env 파일이 있으면 환경 변수로 로드
+    dotenv::dotenv().ok();
+
+    // 로깅 초기화 (결과 JSON 은 stdout, 로그는 stderr)
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
+
+    let cli = Cli::parse();
+
+    let state = Arc::new(initialize_state_from_env().await?);
+    if let Some(VerifyTarget::Schema) = cli.verify {
+        return verify_schema(&state).await;
+    }
+    if cli.loop_mode {
+        run_loop(state, &cli).await;
+        return Ok(());
+    }
+
+    let report = run_realtime_ingest(state, &cli.fetch_options()).await?;
+    print_report(cli.format, report)
+}
+
+// 스키마 확인 결과 출력, 불일치가 있으면 종료 코드 1
+async fn verify_schema(state: &ServerState) -> anyhow::Result<()> {
+    let db_client = state.pool.get().await?;
+    let report = schema_check::verify_schema(&db_client, &state.sub_region_queries).await?;
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&report.to_json(&new_run_id()))?
+    );
+    if !report.passed() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn print_report(format: OutputFormat, report: IngestReport) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Table => print!("{}", render_table(&report)),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&build_response_body(report))?
+        ),
+    }
+    Ok(())
+}
+
+// --loop: 첫 수집은 즉시, 이후 interval 마다 수집 (ServerState 는 반복 간 재사용)
+// 한 번의 실패는 로그만 남기고 다음 틱에 다시 수집
+async fn run_loop(state: Arc<ServerState>, cli: &Cli) {
+    let interval = Duration::from_secs(cli.interval);
+    let options = cli.fetch_options();
+    info!("Polling loop started (interval {:?})", interval);
+
+    poll_loop(interval, shutdown_signal(), || {
+        let state = state.clone();
+        let options = &options;
+        async move {
+            let result = run_realtime_ingest(state, options).await;
+            if let Err(e) = result.and_then(|report| print_report(cli.format, report)) {
+                error!("Polling loop ingest failed: {:?}", e);
+            }
+        }
+    })
+    .await;
+
+    state.pool.close();
+    info!("Polling loop stopped.");
+}
+
+// 틱마다 run 실행 (run 이 끝날 때까지 기다리므로 실행이 겹치지 않고, interval 보다 길어져 밀린 틱은 건너뜀)
+// 종료 신호가 오면 진행 중인 실행을 기다리지 않고 중단 (advisory lock 은 커넥션과 함께 해제)
+async fn poll_loop<F, Fut>(interval: Duration, shutdown: impl Future<Output = ()>, mut run: F)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = &mut shutdown => break,
+        }
+        tokio::select! {
+            _ = run() => {}
+            _ = &mut shutdown => break,
+        }
+    }
+}
+
+// 측정소별 결과 표와 요약 한 줄
+fn render_table(report: &IngestReport) -> String {
+    let mut lines = vec![format!(
+        "{:>13}  {:<16}  {:<7}  {:>6}  {:>6}  DATA_TIME / ERROR",
+        "SUB_REGION_ID", "STATION", "STATUS", "PM10", "PM25"
+    )];
+
+    let mut succeeded = 0;
+    for result in &report.results {
+        let line = match &result.status {
+            StationStatus::Success(data) => {
+                succeeded += 1;
+                format!(
+                    "{:>13}  {:<16}  {:<7}  {:>6}  {:>6}  {}",
+                    result.sub_region_id,
+                    result.pm_station,
+                    "OK",
+                    value_or_dash(&data["pm10Value"]),
+                    value_or_dash(&data["pm25Value"]),
+                    value_or_dash(&data["dataTime"]),
+                )
+            }
+            StationStatus::Failed { kind, message } => format!(
+                "{:>13}  {:<16}  {:<7}  {:>6}  {:>6}  {}: {}",
+                result.sub_region_id,
+                result.pm_station,
+                "FAILED",
+                "-",
+                "-",
+                kind.as_str(),
+                message
+            ),
+        };
+        lines.push(line);
+    }
+
+    lines.push(format!(
+        "run {} : {} succeeded, {} failed, {} skipped fresh, {} deferred in {:?}",
+        report.run_id,
+        succeeded,
+        report.results.len() - succeeded,
+        report.skipped_fresh,
+        report.deferred,
+        report.elapsed
+    ));
+    for warning in &report.warnings {
+        lines.push(format!("warning: {}", warning));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+fn value_or_dash(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "-".to_owned(),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use environment_lambda::failure::FailureKind;
+    use environment_lambda::handler::StationResult;
+    use environment_lambda::inline_stations::StationListSource;
+    use environment_lambda::phases::Phases;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(std::iter::once("cli").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn defaults_run_every_station_once_as_a_table() {
+        let cli = parse(&[]).unwrap();
+        assert!(!cli.dry_run && !cli.loop_mode);
+        assert_eq!(cli.format, OutputFormat::Table);
+        assert_eq!(cli.verify, None);
+
+        let options = cli.fetch_options();
+        assert_eq!(options.stations, None);
+        assert!(!options.dry_run);
+    }
+
+    #[test]
+    fn fetch_flags_map_to_fetch_options() {
+        let cli = parse(&[
+            "--dry-run",
+            "--station",
+            "중구",
+            "--station",
+            "종로구",
+            "--since",
+            "30",
+            "--sido",
+            "서울",
+            "--format",
+            "json",
+        ])
+        .unwrap();
+        assert_eq!(cli.format, OutputFormat::Json);
+
+        let options = cli.fetch_options();
+        assert!(options.dry_run);
+        assert_eq!(
+            options.stations,
+            Some(vec!["중구".to_owned(), "종로구".to_owned()])
+        );
+        assert_eq!(
+            options.refresh_older_than,
+            Some(chrono::Duration::minutes(30))
+        );
+        assert_eq!(options.sido_name.as_deref(), Some("서울"));
+    }
+
+    #[test]
+    fn loop_flags_are_validated() {
+        let cli = parse(&["--loop", "--interval", "600"]).unwrap();
+        assert!(cli.loop_mode);
+        assert_eq!(cli.interval, 600);
+
+        // --interval 은 --loop 와 함께만, 0초 간격은 거부
+        assert!(parse(&["--interval", "600"]).is_err());
+        assert!(parse(&["--loop", "--interval", "0"]).is_err());
+        // 스키마 확인은 반복 실행과 함께 쓸 수 없음
+        assert!(parse(&["--loop", "--verify", "schema"]).is_err());
+        assert_eq!(
+            parse(&["--verify", "schema"]).unwrap().verify,
+            Some(VerifyTarget::Schema)
+        );
+    }
+
+    fn station(sub_region_id: i32, pm_station: &str, status: StationStatus) -> StationResult {
+        StationResult {
+            sub_region_id,
+            pm_station: pm_station.to_owned(),
+            status,
+            checkout_retries: 0,
+            upsert_retries: 0,
+            buffered: false,
+            rejected_values: 0,
+        }
+    }
+
+    #[test]
+    fn table_lists_each_station_and_a_summary() {
+        let report = IngestReport {
+            run_id: "run-1".to_owned(),
+            results: vec![
+                station(
+                    1,
+                    "중구",
+                    StationStatus::Success(serde_json::json!({
+                        "pm10Value": 42.0,
+                        "pm25Value": null,
+                        "dataTime": "2024-05-01T04:00:00Z",
+                    })),
+                ),
+                station(
+                    2,
+                    "종로구",
+                    StationStatus::Failed {
+                        kind: FailureKind::NoData,
+                        message: "종로구 : No data".to_owned(),
+                    },
+                ),
+            ],
+            skipped_fresh: 3,
+            deferred: 4,
+            cache_hits: 0,
+            db_read_only: false,
+            no_sub_regions: false,
+            advanced: None,
+            data_frozen: false,
+            elapsed: Duration::from_millis(1500),
+            warnings: vec!["sns : throttled".to_owned()],
+            latest_cache_failures: 0,
+            disabled_sub_regions: None,
+            diagnostics: None,
+            phases: Phases::default(),
+            station_list_source: StationListSource::Db,
+            quota: None,
+        };
+
+        let table = render_table(&report);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(
+            lines[0].starts_with("SUB_REGION_ID  STATION"),
+            "{}",
+            lines[0]
+        );
+        // 값이 없으면 "-", 문자열은 따옴표 없이 출력
+        let ok: Vec<&str> = lines[1].split_whitespace().collect();
+        assert_eq!(
+            ok,
+            vec!["1", "중구", "OK", "42.0", "-", "2024-05-01T04:00:00Z"]
+        );
+        assert!(lines[2].contains("FAILED"), "{}", lines[2]);
+        assert!(
+            lines[2].ends_with("NO_DATA: 종로구 : No data"),
+            "{}",
+            lines[2]
+        );
+        assert_eq!(
+            lines[3],
+            "run run-1 : 1 succeeded, 1 failed, 3 skipped fresh, 4 deferred in 1.5s"
+        );
+        assert_eq!(lines[4], "warning: sns : throttled");
+        assert!(table.ends_with('\n'));
+    }
+
+    #[test]
+    fn unknown_values_are_rejected() {
+        assert!(parse(&["--format", "xml"]).is_err());
+        assert!(parse(&["--since", "soon"]).is_err());
+        assert!(parse(&["--verify", "data"]).is_err());
+    }
+
+    // 각 실행의 시작 시각(루프 시작 기준)을 기록하고 동시에 진행 중인 실행 수의 최댓값을 잼
+    async fn run_poll_loop(
+        interval: Duration,
+        run_time: Duration,
+        stop_after: Duration,
+    ) -> (Vec<Duration>, usize, usize) {
+        let start = tokio::time::Instant::now();
+        let starts = std::cell::RefCell::new(Vec::new());
+        let in_flight = std::cell::Cell::new(0);
+        let max_in_flight = std::cell::Cell::new(0);
+        let finished = std::cell::Cell::new(0);
+
+        poll_loop(interval, tokio::time::sleep(stop_after), || async {
+            starts.borrow_mut().push(start.elapsed());
+            in_flight.set(in_flight.get() + 1);
+            max_in_flight.set(max_in_flight.get().max(in_flight.get()));
+            tokio::time::sleep(run_time).await;
+            in_flight.set(in_flight.get() - 1);
+            finished.set(finished.get() + 1);
+        })
+        .await;
+
+        (starts.into_inner(), max_in_flight.get(), finished.get())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn short_runs_start_on_every_tick() {
+        let (starts, max_in_flight, finished) = run_poll_loop(
+            Duration::from_secs(10),
+            Duration::from_secs(1),
+            Duration::from_secs(35),
+        )
+        .await;
+
+        let secs: Vec<u64> = starts.iter().map(Duration::as_secs).collect();
+        assert_eq!(secs, vec![0, 10, 20, 30]);
+        assert_eq!((max_in_flight, finished), (1, 4));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn overlapping_ticks_are_skipped() {
+        // 실행(25초)이 간격(10초)보다 길면 그동안의 틱은 새 실행을 만들지 않음
+        let (starts, max_in_flight, finished) = run_poll_loop(
+            Duration::from_secs(10),
+            Duration::from_secs(25),
+            Duration::from_secs(90),
+        )
+        .await;
+
+        assert_eq!(max_in_flight, 1);
+        assert_eq!(starts.len(), 4, "{:?}", starts);
+        assert!(starts
+            .windows(2)
+            .all(|pair| pair[1] - pair[0] >= Duration::from_secs(25)));
+        // 종료 신호 시점(90초)에 진행 중이던 마지막 실행은 기다리지 않고 중단
+        assert_eq!(finished, 3);
+    }
+}
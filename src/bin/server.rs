@@ -0,0 +1,321 @@
+// src/bin/server.rs
+
+// 상시 실행(axum) 서버 (cargo build --no-default-features --features server)
+// Lambda 와 같은 수집 로직(lib)을 HTTP 엔드포인트로 노출
+//   GET /external-pm (?sub_region_id=1)   수집 실행 (/exteranl-pm 은 이전 경로 alias)
+//   GET /external-pm/stream               측정소별 결과를 NDJSON 으로 바로 전달
+//   GET /ingest/status                    내부 스케줄(INGEST_INTERVAL_SECS) 마지막 실행 결과
+//   GET /metrics                          Prometheus 지표 (--features prometheus)
+
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, Query, Request, State},
+    http::{
+        header::{AUTHORIZATION, CONTENT_TYPE},
+        StatusCode,
+    },
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+    Extension, Json, Router,
+};
+use deadpool_postgres::PoolError;
+use environment_lambda::handler::{
+    pool_error_status, run_realtime_ingest, FetchOptions, IngestReport, InvalidServiceKeyError,
+    OUTCOME_ALREADY_RUNNING,
+};
+use environment_lambda::ingest_guard::{auth_token_from_env, is_authorized_bearer, RateLimiter};
+use environment_lambda::legacy::legacy_response;
+use environment_lambda::messages::{message, MessageKey};
+use environment_lambda::run_lock::AlreadyRunningError;
+use environment_lambda::state::{initialize_state_from_env, ServerState};
+use environment_lambda::stream::stream_ingest;
+use environment_lambda::ticker::{self, IngestTicker, LastReport};
+use serde::Deserialize;
+use serde_json::json;
+use std::convert::Infallible;
+use std::future::IntoFuture;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio_stream::{wrappers::UnboundedReceiverStream, StreamExt};
+use tracing::{error, info};
+use tracing_subscriber::EnvFilter;
+
+// 서버 기본 포트 (SERVER_PORT 로 변경)
+const DEFAULT_SERVER_PORT: u16 = 3000;
+
+#[derive(Debug, Deserialize)]
+struct IngestQuery {
+    sub_region_id: Option<i32>,
+}
+
+// 수집 엔드포인트 인증 / IP 별 호출 제한
+#[derive(Clone)]
+struct IngestGuard {
+    auth_token: Option<String>,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl IngestGuard {
+    fn from_env() -> Self {
+        IngestGuard {
+            auth_token: auth_token_from_env(),
+            rate_limiter: Arc::new(RateLimiter::from_env()),
+        }
+    }
+}
+
+async fn ingest_guard(
+    State(guard): State<IngestGuard>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let authorization = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok());
+    if !is_authorized_bearer(authorization, guard.auth_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, message(MessageKey::Unauthorized)).into_response();
+    }
+
+    if !guard.rate_limiter.check(addr.ip()) {
+        return (StatusCode::TOO_MANY_REQUESTS, "Too Many Requests").into_response();
+    }
+
+    next.run(request).await
+}
+
+async fn get_external_pm_data_handler(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<IngestQuery>,
+) -> Response {
+    let options = FetchOptions {
+        sub_region_ids: query.sub_region_id.map(|id| vec![id]),
+        refresh_older_than: FetchOptions::refresh_older_than_from_env(),
+        run_lock: true,
+        ..Default::default()
+    };
+
+    match run_realtime_ingest(state, &options).await {
+        Ok(report) => rust_response(report),
+        Err(e) => error_response(e),
+    }
+}
+
+// 기존 axum 버전과 같은 Data / Meta 응답
+fn rust_response(report: IngestReport) -> Response {
+    let (data, meta) = legacy_response(report);
+    Json(json!({ "data": data, "meta": meta })).into_response()
+}
+
+// Lambda 핸들러와 같은 기준으로 오류 상태 코드 결정
+fn error_response(e: anyhow::Error) -> Response {
+    if let Some(already_running) = e.downcast_ref::<AlreadyRunningError>() {
+        return (
+            StatusCode::CONFLICT,
+            Json(json!({
+                "message": message(MessageKey::AlreadyRunning),
+                "meta": {
+                    "outcome": OUTCOME_ALREADY_RUNNING,
+                    "holderRunId": already_running.holder_run_id,
+                },
+            })),
+        )
+            .into_response();
+    }
+    if e.downcast_ref::<InvalidServiceKeyError>().is_some() {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "message": message(MessageKey::InvalidServiceKey) })),
+        )
+            .into_response();
+    }
+    if let Some(pool_error) = e.downcast_ref::<PoolError>() {
+        let (status_code, pool_message) = pool_error_status(pool_error);
+        let status = StatusCode::from_u16(status_code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        return (status, Json(json!({ "message": pool_message }))).into_response();
+    }
+
+    error!("수집 실패: {:?}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({ "message": message(MessageKey::InternalServerError) })),
+    )
+        .into_response()
+}
+
+// 클라이언트 연결이 끊겨도 수집은 끝까지 진행 (락도 정상 해제)
+async fn stream_external_pm_data_handler(State(state): State<Arc<ServerState>>) -> Response {
+    let options = FetchOptions {
+        run_lock: true,
+        ..Default::default()
+    };
+    let (_ingest, lines) = stream_ingest(state, options);
+    let body = Body::from_stream(UnboundedReceiverStream::new(lines).map(Ok::<_, Infallible>));
+
+    ([(CONTENT_TYPE, "application/x-ndjson")], body).into_response()
+}
+
+async fn ingest_status_handler(Extension(last_report): Extension<LastReport>) -> Response {
+    Json(ticker::status_body(&last_report).await).into_response()
+}
+
+#[cfg(feature = "prometheus")]
+async fn metrics_handler(
+    Extension(handle): Extension<metrics_exporter_prometheus::PrometheusHandle>,
+) -> String {
+    handle.render()
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // .env 파일이 있으면 환경 변수로 로드
+    dotenv::dotenv().ok();
+
+    // 로깅 초기화
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .init();
+
+    // recorder 는 라우터가 요청을 받기 전에 한 번만 설치
+    #[cfg(feature = "prometheus")]
+    let metrics_handle = environment_lambda::metrics::install_prometheus_recorder()?;
+
+    let state = Arc::new(initialize_state_from_env().await?);
+
+    // INGEST_INTERVAL_SECS 가 있으면 외부 cron 없이 내부 스케줄로 수집
+    let drain_timeout = ticker::drain_timeout_from_env();
+    let ingest_ticker = ticker::interval_from_env()
+        .map(|interval| IngestTicker::spawn(state.clone(), interval, drain_timeout));
+    let last_report = ingest_ticker
+        .as_ref()
+        .map(|t| t.last_report())
+        .unwrap_or_default();
+
+    let guarded = Router::new()
+        .route("/external-pm", get(get_external_pm_data_handler))
+        .route("/exteranl-pm", get(get_external_pm_data_handler))
+        .route("/external-pm/stream", get(stream_external_pm_data_handler))
+        .route_layer(middleware::from_fn_with_state(
+            IngestGuard::from_env(),
+            ingest_guard,
+        ));
+
+    let app = guarded
+        .route("/ingest/status", get(ingest_status_handler))
+        .layer(Extension(last_report));
+
+    #[cfg(feature = "prometheus")]
+    let app = app
+        .route("/metrics", get(metrics_handler))
+        .layer(Extension(metrics_handle));
+
+    let app = app.with_state(state.clone());
+
+    let port = std::env::var("SERVER_PORT")
+        .ok()
+        .and_then(|v| v.parse::<u16>().ok())
+        .unwrap_or(DEFAULT_SERVER_PORT);
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", port)).await?;
+    info!("Listening on port {}", port);
+
+    // SIGTERM/SIGINT: 새 요청을 받지 않고 진행 중인 요청을 drain 제한 시간까지 기다린 뒤
+    // 스케줄러 정리 및 커넥션 풀 종료 (중단된 실행은 락 커넥션을 닫아 advisory lock 을 남기지 않음)
+    let (signalled_tx, signalled_rx) = tokio::sync::oneshot::channel();
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move {
+        ticker::shutdown_signal().await;
+        let _ = signalled_tx.send(());
+    });
+    let server = server.into_future();
+    tokio::pin!(server);
+    tokio::select! {
+        result = &mut server => result?,
+        _ = signalled_rx => {
+            if tokio::time::timeout(drain_timeout, &mut server).await.is_err() {
+                error!("Drain timeout exceeded, closing remaining connections.");
+            }
+        }
+    }
+
+    ticker::shutdown_and_close(ingest_ticker, &state).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::connect_info::MockConnectInfo;
+    use tower::ServiceExt;
+
+    // 실제 수집 대신 200 을 돌려주는 라우트에 같은 가드를 적용
+    fn guarded_app(limit: u32) -> Router {
+        let guard = IngestGuard {
+            auth_token: Some("secret".to_owned()),
+            rate_limiter: Arc::new(RateLimiter::per_minute(limit)),
+        };
+        Router::new()
+            .route("/external-pm", get(|| async { "ok" }))
+            .route_layer(middleware::from_fn_with_state(guard, ingest_guard))
+            .layer(MockConnectInfo(SocketAddr::from(([10, 0, 0, 1], 40000))))
+    }
+
+    async fn send(app: &Router, authorization: Option<&str>) -> StatusCode {
+        let mut request = Request::builder().uri("/external-pm");
+        if let Some(authorization) = authorization {
+            request = request.header(AUTHORIZATION, authorization);
+        }
+        app.clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_or_wrong_bearer_with_401() {
+        let app = guarded_app(10);
+        assert_eq!(send(&app, None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            send(&app, Some("Bearer wrong")).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn allows_authorized_calls_until_rate_limited() {
+        let app = guarded_app(1);
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/external-pm")
+                    .header(AUTHORIZATION, "Bearer secret")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), 1024)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"ok");
+
+        assert_eq!(
+            send(&app, Some("Bearer secret")).await,
+            StatusCode::TOO_MANY_REQUESTS
+        );
+    }
+
+    #[tokio::test]
+    async fn unauthorized_calls_do_not_spend_the_rate_limit() {
+        let app = guarded_app(1);
+        assert_eq!(send(&app, None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(send(&app, Some("Bearer secret")).await, StatusCode::OK);
+    }
+}
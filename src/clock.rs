@@ -0,0 +1,31 @@
+// src/clock.rs
+
+// 현재 시각 주입 (ServerState.clock)
+// 실행마다 시작할 때 한 번 읽은 시각을 증분 수집 기준, 응답 requestedTime, 날씨 발표 시각 계산에 함께 사용하고,
+// 고정 시각(FixedClock)을 넣으면 시간에 따라 달라지는 동작을 같은 조건으로 재현할 수 있음
+
+use chrono::{DateTime, Utc};
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+// 시스템 시계 (기본값)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+// 항상 같은 시각을 돌려주는 시계
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
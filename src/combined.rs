@@ -0,0 +1,143 @@
+// src/combined.rs
+
+// 복합 모드: mode 가 배열(예: ["realtime", "weather"])이면 한 번의 호출에서 파이프라인을 동시에 실행
+// HTTP 클라이언트, 동시 요청 제한, DB 풀을 공유하여 스케줄을 따로 두는 것보다 콜드 스타트와 커넥션 생성을 줄임
+// 응답은 모드별 섹션(realtime: {...}, weather: {...})으로 구분
+
+use serde_json::json;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tracing::{error, warn};
+
+use crate::handler::{api_concurrency, build_response_body, run_realtime_ingest, FetchOptions};
+use crate::state::ServerState;
+use crate::weather::run_weather_ingest;
+
+pub const REALTIME_MODE: &str = "realtime";
+pub const WEATHER_MODE: &str = "weather";
+
+const DEFAULT_REALTIME_BUDGET_SHARE: f64 = 0.5;
+
+// 응답을 반환할 수 있도록 Lambda 제한 시간에서 남겨두는 시간
+const RESPONSE_MARGIN: Duration = Duration::from_secs(2);
+
+// payload 의 mode 가 배열이면 모드 목록 (문자열이 아닌 항목은 무시)
+pub fn combined_modes(payload: &serde_json::Value) -> Option<Vec<String>> {
+    let modes = payload.get("mode")?.as_array()?;
+    Some(
+        modes
+            .iter()
+            .filter_map(|mode| mode.as_str())
+            .map(str::to_owned)
+            .collect(),
+    )
+}
+
+// COMBINED_REALTIME_BUDGET_SHARE 환경 변수 (0~1, 기본 0.5): 남은 시간 중 realtime 몫, 나머지는 weather
+pub fn realtime_budget_share() -> f64 {
+    std::env::var("COMBINED_REALTIME_BUDGET_SHARE")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|share| (0.0..=1.0).contains(share))
+        .unwrap_or(DEFAULT_REALTIME_BUDGET_SHARE)
+}
+
+// 남은 시간을 (realtime, weather) 제한 시간으로 분배 (남은 시간을 모르면 제한 없음)
+pub fn split_budget(
+    remaining: Option<Duration>,
+    realtime_share: f64,
+) -> (Option<Duration>, Option<Duration>) {
+    match remaining {
+        Some(remaining) => {
+            let budget = remaining.saturating_sub(RESPONSE_MARGIN);
+            (
+                Some(budget.mul_f64(realtime_share)),
+                Some(budget.mul_f64(1.0 - realtime_share)),
+            )
+        }
+        None => (None, None),
+    }
+}
+
+// 모드별 파이프라인을 동시에 실행하고 섹션별 응답 본문 구성
+// 파이프라인 하나가 실패/시간 초과해도 다른 섹션은 그대로 반환
+pub async fn run_combined(
+    state: Arc<ServerState>,
+    options: &FetchOptions,
+    modes: &[String],
+    remaining: Option<Duration>,
+) -> serde_json::Value {
+    let options = FetchOptions {
+        http_client: Some(options.http_client()),
+        semaphore: Some(Arc::new(Semaphore::new(api_concurrency()))),
+        ..options.clone()
+    };
+    let (realtime_budget, weather_budget) = split_budget(remaining, realtime_budget_share());
+    let run_realtime = modes.iter().any(|mode| mode == REALTIME_MODE);
+    let run_weather = modes.iter().any(|mode| mode == WEATHER_MODE);
+
+    let realtime = async {
+        if !run_realtime {
+            return None;
+        }
+        let pipeline = async {
+            run_realtime_ingest(state.clone(), &options)
+                .await
+                .map(build_response_body)
+        };
+        Some(with_budget(REALTIME_MODE, realtime_budget, pipeline).await)
+    };
+    let weather = async {
+        if !run_weather {
+            return None;
+        }
+        let pipeline = async {
+            run_weather_ingest(state.clone(), &options)
+                .await
+                .map(build_response_body)
+        };
+        Some(with_budget(WEATHER_MODE, weather_budget, pipeline).await)
+    };
+    let (realtime, weather) = tokio::join!(realtime, weather);
+
+    let mut response = json!({
+        "meta": {
+            "runId": options.run_id,
+            "modes": modes,
+        }
+    });
+    for (mode, section) in [(REALTIME_MODE, realtime), (WEATHER_MODE, weather)] {
+        if let Some(section) = section {
+            response[mode] = section;
+        }
+    }
+    for mode in modes
+        .iter()
+        .filter(|mode| *mode != REALTIME_MODE && *mode != WEATHER_MODE)
+    {
+        warn!("복합 모드: 알 수 없는 모드 {}", mode);
+        response[mode.as_str()] = json!({ "error": format!("unknown mode: {}", mode) });
+    }
+
+    response
+}
+
+// 파이프라인에 제한 시간 적용, 실패/시간 초과는 섹션의 error 로 기록
+async fn with_budget<F>(mode: &str, budget: Option<Duration>, pipeline: F) -> serde_json::Value
+where
+    F: Future<Output = Result<serde_json::Value, anyhow::Error>>,
+{
+    let result = match budget {
+        Some(budget) => tokio::time::timeout(budget, pipeline)
+            .await
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("deadline exceeded ({:?})", budget))),
+        None => pipeline.await,
+    };
+
+    result.unwrap_or_else(|e| {
+        error!("{} : 복합 모드 파이프라인 실패: {:?}", mode, e);
+        json!({ "error": e.to_string() })
+    })
+}
@@ -0,0 +1,85 @@
+// src/compression.rs
+
+// 전체 측정소(~300개) 응답 본문 압축: JSON 을 gzip 압축 후 base64 인코딩
+// payload 의 compress: true 일 때만 사용 (기본은 비압축)
+
+use anyhow::Result;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+
+// 응답 본문 JSON -> gzip -> base64 문자열
+pub fn compress_body(body: &serde_json::Value) -> Result<String> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(body.to_string().as_bytes())?;
+    let compressed = encoder.finish()?;
+
+    Ok(STANDARD.encode(compressed))
+}
+
+// compress_body 의 역변환 (소비자 측 / 로컬 확인용)
+pub fn decompress_body(encoded: &str) -> Result<serde_json::Value> {
+    let compressed = STANDARD.decode(encoded)?;
+    let mut decoder = GzDecoder::new(compressed.as_slice());
+    let mut body = String::new();
+    decoder.read_to_string(&mut body)?;
+
+    Ok(serde_json::from_str(&body)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // 전체 측정소 응답과 비슷한 크기의 본문
+    fn full_sweep_body() -> serde_json::Value {
+        let data: Vec<_> = (1..=300)
+            .map(|sub_region_id| {
+                json!({
+                    "subRegionId": sub_region_id,
+                    "pm10Value": 42.0,
+                    "pm25Value": 20.0,
+                    "dataTime": "2024-05-01T04:00:00Z",
+                    "stationName": format!("측정소{}", sub_region_id),
+                })
+            })
+            .collect();
+        json!({ "data": data, "meta": { "runId": "run-1", "errorList": [] } })
+    }
+
+    #[test]
+    fn round_trip_restores_the_original_json() {
+        let body = full_sweep_body();
+        let encoded = compress_body(&body).unwrap();
+        assert_eq!(decompress_body(&encoded).unwrap(), body);
+
+        let empty = json!({});
+        assert_eq!(
+            decompress_body(&compress_body(&empty).unwrap()).unwrap(),
+            empty
+        );
+    }
+
+    #[test]
+    fn full_sweep_body_shrinks_after_encoding() {
+        let body = full_sweep_body();
+        let encoded = compress_body(&body).unwrap();
+        // base64 로 1/3 늘어나도 반복이 많은 전체 응답은 원문보다 작아야 함
+        assert!(
+            encoded.len() * 4 < body.to_string().len(),
+            "encoded {} bytes, raw {} bytes",
+            encoded.len(),
+            body.to_string().len()
+        );
+    }
+
+    #[test]
+    fn decompress_rejects_input_that_is_not_gzip_base64() {
+        assert!(decompress_body("not base64!").is_err());
+        assert!(decompress_body(&STANDARD.encode(b"{\"plain\": true}")).is_err());
+    }
+}
@@ -0,0 +1,165 @@
+// src/db_conn.rs
+
+// 비밀번호 인증 DB 접속 정보
+// DB_CONN_URL 이 있으면 그대로 사용하고, 없으면 컨테이너 플랫폼이 주입하는 PGHOST / PGPORT / PGUSER / PGPASSWORD / PGDATABASE 로 구성
+// (둘 다 있으면 DB_CONN_URL 우선, PGPORT 는 생략 시 5432)
+// 비밀번호는 로그 / 오류 메시지에 넣지 않음
+
+use anyhow::{anyhow, Result};
+use deadpool_postgres::Config;
+use std::fmt;
+
+const DEFAULT_PG_PORT: u16 = 5432;
+
+// PGPORT 외에 반드시 있어야 하는 변수
+const REQUIRED_PG_ENV_VARS: [&str; 4] = ["PGHOST", "PGUSER", "PGPASSWORD", "PGDATABASE"];
+
+pub enum DbConnConfig {
+    Url(String),
+    Discrete {
+        host: String,
+        port: u16,
+        user: String,
+        password: String,
+        dbname: String,
+    },
+}
+
+impl DbConnConfig {
+    // 누락된 PG* 변수는 한 번에 모아 오류로 반환 (빈 값은 누락으로 취급)
+    pub fn from_env() -> Result<Self> {
+        let lookup = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        if let Some(url) = lookup("DB_CONN_URL") {
+            return Ok(DbConnConfig::Url(url));
+        }
+
+        let missing: Vec<&str> = REQUIRED_PG_ENV_VARS
+            .into_iter()
+            .filter(|name| lookup(name).is_none())
+            .collect();
+        if !missing.is_empty() {
+            return Err(anyhow!(
+                "DB_CONN_URL 환경 변수 누락, PG* 환경 변수도 부족함 (누락: {})",
+                missing.join(", ")
+            ));
+        }
+
+        let port = match lookup("PGPORT") {
+            Some(port) => port
+                .parse::<u16>()
+                .map_err(|_| anyhow!("PGPORT 값이 올바른 포트 번호가 아님: {}", port))?,
+            None => DEFAULT_PG_PORT,
+        };
+
+        Ok(DbConnConfig::Discrete {
+            host: lookup("PGHOST").unwrap_or_default(),
+            port,
+            user: lookup("PGUSER").unwrap_or_default(),
+            password: lookup("PGPASSWORD").unwrap_or_default(),
+            dbname: lookup("PGDATABASE").unwrap_or_default(),
+        })
+    }
+
+    // deadpool 설정에 접속 정보 반영
+    pub fn apply(&self, cfg: &mut Config) {
+        match self {
+            DbConnConfig::Url(url) => cfg.url = Some(url.clone()),
+            DbConnConfig::Discrete {
+                host,
+                port,
+                user,
+                password,
+                dbname,
+            } => {
+                cfg.host = Some(host.clone());
+                cfg.port = Some(*port);
+                cfg.user = Some(user.clone());
+                cfg.password = Some(password.clone());
+                cfg.dbname = Some(dbname.clone());
+            }
+        }
+    }
+
+    // effective_config 의 dbAuth 값
+    pub fn source(&self) -> &'static str {
+        match self {
+            DbConnConfig::Url(_) => "url",
+            DbConnConfig::Discrete { .. } => "pg_env",
+        }
+    }
+}
+
+// URL 과 비밀번호는 출력하지 않음
+impl fmt::Debug for DbConnConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DbConnConfig::Url(_) => f.debug_tuple("Url").field(&"***").finish(),
+            DbConnConfig::Discrete {
+                host,
+                port,
+                user,
+                dbname,
+                ..
+            } => f
+                .debug_struct("Discrete")
+                .field("host", host)
+                .field("port", port)
+                .field("user", user)
+                .field("password", &"***")
+                .field("dbname", dbname)
+                .finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn discrete() -> DbConnConfig {
+        DbConnConfig::Discrete {
+            host: "db.internal".to_owned(),
+            port: 6432,
+            user: "ingest".to_owned(),
+            password: "pg-secret".to_owned(),
+            dbname: "environment".to_owned(),
+        }
+    }
+
+    #[test]
+    fn apply_sets_url_or_discrete_fields() {
+        let mut cfg = Config::new();
+        DbConnConfig::Url("postgres://u:url-secret@h/d".to_owned()).apply(&mut cfg);
+        assert_eq!(cfg.url.as_deref(), Some("postgres://u:url-secret@h/d"));
+        assert_eq!(cfg.host, None);
+
+        let mut cfg = Config::new();
+        discrete().apply(&mut cfg);
+        assert_eq!(cfg.url, None);
+        assert_eq!(cfg.host.as_deref(), Some("db.internal"));
+        assert_eq!(cfg.port, Some(6432));
+        assert_eq!(cfg.user.as_deref(), Some("ingest"));
+        assert_eq!(cfg.password.as_deref(), Some("pg-secret"));
+        assert_eq!(cfg.dbname.as_deref(), Some("environment"));
+    }
+
+    #[test]
+    fn debug_hides_url_and_password() {
+        let url = format!(
+            "{:?}",
+            DbConnConfig::Url("postgres://u:url-secret@h/d".to_owned())
+        );
+        assert_eq!(url, r#"Url("***")"#);
+
+        let discrete = format!("{:?}", discrete());
+        assert!(!discrete.contains("pg-secret"), "{}", discrete);
+        assert!(discrete.contains(r#"host: "db.internal""#), "{}", discrete);
+        assert!(discrete.contains(r#"password: "***""#), "{}", discrete);
+    }
+
+    #[test]
+    fn source_names_the_auth_kind() {
+        assert_eq!(DbConnConfig::Url(String::new()).source(), "url");
+        assert_eq!(discrete().source(), "pg_env");
+    }
+}
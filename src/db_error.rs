@@ -0,0 +1,232 @@
+// src/db_error.rs
+
+// tokio_postgres 오류 분류
+// 커넥션 끊김, 직렬화 실패, 데드락, 관리자 종료(페일오버)는 새 커넥션으로 재시도하면 성공할 수 있음
+
+use tokio_postgres::error::SqlState;
+
+use crate::failure::FailureKind;
+
+// 재시도 가능한 SQLSTATE
+// 40001: serialization_failure, 40P01: deadlock_detected, 57P01: admin_shutdown
+pub fn is_retriable_sqlstate(code: &SqlState) -> bool {
+    *code == SqlState::T_R_SERIALIZATION_FAILURE
+        || *code == SqlState::T_R_DEADLOCK_DETECTED
+        || *code == SqlState::ADMIN_SHUTDOWN
+}
+
+// 25006: read_only_sql_transaction (RDS 페일오버 중 읽기 전용 엔드포인트에 접속된 경우)
+pub fn is_read_only_sqlstate(code: &SqlState) -> bool {
+    *code == SqlState::READ_ONLY_SQL_TRANSACTION
+}
+
+pub fn is_read_only_db_error(e: &tokio_postgres::Error) -> bool {
+    e.code().is_some_and(is_read_only_sqlstate)
+}
+
+// 42703: undefined_column (컬럼이 추가되기 전의 이전 스키마)
+pub fn is_undefined_column_error(e: &tokio_postgres::Error) -> bool {
+    e.code() == Some(&SqlState::UNDEFINED_COLUMN)
+}
+
+// 재시도 가능한 오류 여부 (제약 조건 위반, 문법 오류 등은 재시도하지 않음)
+pub fn is_retriable_db_error(e: &tokio_postgres::Error) -> bool {
+    e.is_closed() || e.code().is_some_and(is_retriable_sqlstate)
+}
+
+// upsert 실패 분류 (FK/unique 위반, 커넥션 오류는 별도 분류로 기록)
+pub fn classify_db_error(e: &tokio_postgres::Error) -> FailureKind {
+    if e.is_closed() {
+        return FailureKind::DbConnection;
+    }
+
+    match e.code() {
+        Some(code) if *code == SqlState::FOREIGN_KEY_VIOLATION => FailureKind::DbForeignKey,
+        Some(code) if *code == SqlState::UNIQUE_VIOLATION => FailureKind::DbUniqueViolation,
+        Some(code) if is_read_only_sqlstate(code) => FailureKind::DbReadOnly,
+        // 57P01: admin_shutdown (페일오버 / 관리자 종료로 서버가 커넥션을 끊음)
+        Some(code) if *code == SqlState::ADMIN_SHUTDOWN => FailureKind::DbConnection,
+        // SQLSTATE 08xxx: connection_exception 계열
+        Some(code) if code.code().starts_with("08") => FailureKind::DbConnection,
+        // DB 응답이 없는 오류 (소켓, TLS 등)
+        None => FailureKind::DbConnection,
+        Some(_) => FailureKind::DbQuery,
+    }
+}
+
+// 오류 메시지 (DB 오류면 SQLSTATE, 제약 조건 이름, 상세 내용 포함)
+pub fn describe_db_error(e: &tokio_postgres::Error) -> String {
+    match e.as_db_error() {
+        Some(db_error) => format!(
+            "[{}] {}{}{}",
+            db_error.code().code(),
+            db_error.message(),
+            db_error
+                .constraint()
+                .map(|constraint| format!(" (constraint: {})", constraint))
+                .unwrap_or_default(),
+            db_error
+                .detail()
+                .map(|detail| format!(" - {}", detail))
+                .unwrap_or_default(),
+        ),
+        None => format!("{:?}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_postgres::{Client, NoTls};
+
+    // TEST_DATABASE_URL 로 직접 연결 (미설정이면 None)
+    async fn connect() -> Option<Client> {
+        let url = std::env::var("TEST_DATABASE_URL").ok()?;
+        let (client, connection) = tokio_postgres::connect(&url, NoTls).await.unwrap();
+        tokio::spawn(connection);
+        Some(client)
+    }
+
+    // 주어진 SQLSTATE 로 실패하는 쿼리의 오류
+    async fn raise(client: &Client, code: &str) -> tokio_postgres::Error {
+        client
+            .batch_execute(&format!(
+                "DO $$ BEGIN RAISE EXCEPTION 'test' USING ERRCODE = '{code}'; END $$"
+            ))
+            .await
+            .unwrap_err()
+    }
+
+    #[test]
+    fn only_transient_sqlstates_are_retriable() {
+        for code in ["40001", "40P01", "57P01"] {
+            assert!(
+                is_retriable_sqlstate(&SqlState::from_code(code)),
+                "{}",
+                code
+            );
+        }
+        for code in ["23503", "23505", "25006", "42601", "42703"] {
+            assert!(
+                !is_retriable_sqlstate(&SqlState::from_code(code)),
+                "{}",
+                code
+            );
+        }
+        assert!(is_read_only_sqlstate(&SqlState::from_code("25006")));
+    }
+
+    #[tokio::test]
+    async fn retriable_errors_from_the_server() {
+        let Some(client) = connect().await else {
+            return;
+        };
+        for code in ["40001", "40P01", "57P01"] {
+            assert!(
+                is_retriable_db_error(&raise(&client, code).await),
+                "{}",
+                code
+            );
+        }
+        for code in ["23503", "25006", "42601"] {
+            assert!(
+                !is_retriable_db_error(&raise(&client, code).await),
+                "{}",
+                code
+            );
+        }
+        assert!(is_read_only_db_error(&raise(&client, "25006").await));
+        assert!(is_undefined_column_error(&raise(&client, "42703").await));
+    }
+
+    #[tokio::test]
+    async fn terminated_connection_is_retriable() {
+        let (Some(client), Some(admin)) = (connect().await, connect().await) else {
+            return;
+        };
+        let pid: i32 = client
+            .query_one("SELECT pg_backend_pid()", &[])
+            .await
+            .unwrap()
+            .get(0);
+        admin
+            .execute("SELECT pg_terminate_backend($1)", &[&pid])
+            .await
+            .unwrap();
+
+        // 페일오버처럼 서버가 끊은 커넥션: 새 커넥션으로 재시도하면 성공할 수 있음
+        let e = client.query_one("SELECT 1", &[]).await.unwrap_err();
+        assert!(is_retriable_db_error(&e), "{:?}", e);
+        assert_eq!(classify_db_error(&e), FailureKind::DbConnection);
+    }
+
+    #[tokio::test]
+    async fn constraint_violations_are_classified_with_details() {
+        let Some(client) = connect().await else {
+            return;
+        };
+        client
+            .batch_execute(
+                "CREATE TEMP TABLE parent (id integer PRIMARY KEY);
+                 CREATE TEMP TABLE child (id integer PRIMARY KEY REFERENCES parent (id));
+                 INSERT INTO parent VALUES (1);",
+            )
+            .await
+            .unwrap();
+
+        let e = client
+            .execute("INSERT INTO child VALUES (2)", &[])
+            .await
+            .unwrap_err();
+        assert_eq!(classify_db_error(&e), FailureKind::DbForeignKey);
+        let described = describe_db_error(&e);
+        assert!(described.starts_with("[23503] "), "{}", described);
+        assert!(
+            described.contains("(constraint: child_id_fkey)"),
+            "{}",
+            described
+        );
+        assert!(described.contains(" - Key (id)=(2)"), "{}", described);
+
+        let e = client
+            .execute("INSERT INTO parent VALUES (1)", &[])
+            .await
+            .unwrap_err();
+        assert_eq!(classify_db_error(&e), FailureKind::DbUniqueViolation);
+        assert!(describe_db_error(&e).contains("(constraint: parent_pkey)"));
+    }
+
+    #[tokio::test]
+    async fn other_errors_are_classified_by_sqlstate() {
+        let Some(client) = connect().await else {
+            return;
+        };
+        assert_eq!(
+            classify_db_error(&raise(&client, "25006").await),
+            FailureKind::DbReadOnly
+        );
+        assert_eq!(
+            classify_db_error(&raise(&client, "57P01").await),
+            FailureKind::DbConnection
+        );
+        assert_eq!(
+            classify_db_error(&raise(&client, "08006").await),
+            FailureKind::DbConnection
+        );
+        assert_eq!(
+            classify_db_error(&raise(&client, "42601").await),
+            FailureKind::DbQuery
+        );
+    }
+
+    #[tokio::test]
+    async fn errors_without_a_server_response_are_connection_errors() {
+        // 열려 있지 않은 포트: DB 응답(SQLSTATE) 없이 소켓 단계에서 실패
+        let e = tokio_postgres::connect("host=127.0.0.1 port=1 user=postgres", NoTls)
+            .await
+            .map(|_| ())
+            .unwrap_err();
+        assert_eq!(classify_db_error(&e), FailureKind::DbConnection);
+        assert!(!describe_db_error(&e).starts_with('['));
+    }
+}
@@ -0,0 +1,95 @@
+// src/db_schema.rs
+
+// 테이블 스키마 접두어 (PM_DB_SCHEMA, 기본 v3)
+// staging / prod 가 같은 DB 를 스키마(v3 / v3_staging)로 나눠 쓰므로 SQL 상수는 {schema} 자리표시자로 두고
+// 실행 시 검증된 스키마 이름으로 치환하여 사용
+
+use anyhow::{anyhow, Result};
+use std::sync::OnceLock;
+
+pub const DEFAULT_DB_SCHEMA: &str = "v3";
+
+// SQL 상수의 스키마 자리표시자
+pub const SCHEMA_PLACEHOLDER: &str = "{schema}";
+
+static DB_SCHEMA: OnceLock<String> = OnceLock::new();
+
+// PM_DB_SCHEMA 환경 변수 (미설정 시 v3)
+pub fn db_schema_from_env() -> Result<String> {
+    match std::env::var("PM_DB_SCHEMA") {
+        Ok(schema) => validate_schema(&schema).map(|_| schema),
+        Err(_) => Ok(DEFAULT_DB_SCHEMA.to_owned()),
+    }
+}
+
+// 스키마 이름은 따옴표 없이 SQL 에 들어가므로 영문/숫자/밑줄만 허용
+pub fn validate_schema(schema: &str) -> Result<()> {
+    let valid = !schema.is_empty()
+        && !schema.starts_with(|c: char| c.is_ascii_digit())
+        && schema
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow!("잘못된 PM_DB_SCHEMA: {}", schema))
+    }
+}
+
+// 초기화 단계(finish_state)에서 한 번 검증 후 고정, 잘못된 값이면 수집 전에 실패
+pub fn init_from_env() -> Result<&'static str> {
+    let schema = db_schema_from_env()?;
+    Ok(DB_SCHEMA.get_or_init(|| schema).as_str())
+}
+
+// 현재 스키마 (초기화 전이면 기본값)
+pub fn db_schema() -> &'static str {
+    DB_SCHEMA
+        .get()
+        .map(String::as_str)
+        .unwrap_or(DEFAULT_DB_SCHEMA)
+}
+
+// SQL 상수의 {schema} 를 지정한 스키마로 치환
+pub fn apply_schema(query: &str, schema: &str) -> String {
+    query.replace(SCHEMA_PLACEHOLDER, schema)
+}
+
+// SQL 상수의 {schema} 를 현재 스키마로 치환
+pub fn sql(query: &str) -> String {
+    apply_schema(query, db_schema())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_schema_replaces_every_placeholder() {
+        let query =
+            "SELECT * FROM {schema}.sub_region JOIN {schema}.external_pm USING (sub_region_id)";
+        assert_eq!(
+            apply_schema(query, "v3_staging"),
+            "SELECT * FROM v3_staging.sub_region JOIN v3_staging.external_pm USING (sub_region_id)"
+        );
+        assert_eq!(apply_schema("SELECT 1", "v3_staging"), "SELECT 1");
+    }
+
+    #[test]
+    fn validate_schema_rejects_injection() {
+        for schema in ["v3", "v3_staging", "_tmp", "Schema2"] {
+            assert!(validate_schema(schema).is_ok(), "{}", schema);
+        }
+        for schema in [
+            "",
+            "3v",
+            "v3; DROP TABLE v3.sub_region",
+            "v3.sub_region",
+            "\"v3\"",
+            "v3-staging",
+            "스키마",
+        ] {
+            assert!(validate_schema(schema).is_err(), "{}", schema);
+        }
+    }
+}
@@ -0,0 +1,214 @@
+// src/diagnostics.rs
+
+// 측정소별 상위 API 응답 진단 (payload 의 diagnostics: true)
+// reqwest 는 DNS / 연결 / 첫 바이트 시간을 따로 알려주지 않으므로 측정소 조회 전체 소요 시간과
+// 마지막 응답의 상태 코드, 본문 크기를 기록하여 느린 측정소를 찾는 데 사용
+// 응답에는 소요 시간이 긴 순서로 최대 MAX_DIAGNOSTICS 개만 넣고, 같은 값을 CloudWatch EMF 로그로도 남김
+// (측정소 이름은 차원이 아닌 속성으로 기록하여 지표 수가 측정소 수만큼 늘지 않도록 함)
+
+use serde_json::json;
+use std::cell::RefCell;
+use std::future::Future;
+use std::time::Duration;
+
+pub const MAX_DIAGNOSTICS: usize = 50;
+
+// EMF 지표 네임스페이스 기본값
+const DEFAULT_EMF_NAMESPACE: &str = "ExternalPm";
+
+tokio::task_local! {
+    // 측정소 조회 중 받은 HTTP 응답 (capture 범위 밖의 호출은 기록하지 않음)
+    static UPSTREAM_RESPONSE: RefCell<Option<UpstreamResponse>>;
+}
+
+// 상위 API 응답 요약 (여러 번 호출하면 상태 코드는 마지막 응답, 본문 크기는 합계)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UpstreamResponse {
+    pub status: u16,
+    pub content_length: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StationDiagnostic {
+    pub sub_region_id: i32,
+    pub pm_station: String,
+    // 측정소 조회 전체 소요 시간 (실행 내 캐시 대기 포함)
+    pub elapsed: Duration,
+    // 실행 내 캐시로 API 를 호출하지 않았거나 응답을 받기 전에 실패하면 None
+    pub response: Option<UpstreamResponse>,
+}
+
+impl StationDiagnostic {
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "subRegionId": self.sub_region_id,
+            "stationName": self.pm_station,
+            "elapsedMs": self.elapsed.as_millis() as u64,
+            "status": self.response.map(|response| response.status),
+            "contentLength": self.response.map(|response| response.content_length),
+        })
+    }
+
+    // CloudWatch Embedded Metric Format 한 줄 (측정소는 차원 없는 속성)
+    pub fn to_emf(&self, namespace: &str, timestamp_millis: i64) -> serde_json::Value {
+        json!({
+            "_aws": {
+                "Timestamp": timestamp_millis,
+                "CloudWatchMetrics": [{
+                    "Namespace": namespace,
+                    "Dimensions": [[]],
+                    "Metrics": [
+                        {"Name": "UpstreamLatency", "Unit": "Milliseconds"},
+                        {"Name": "UpstreamContentLength", "Unit": "Bytes"},
+                    ],
+                }],
+            },
+            "UpstreamLatency": self.elapsed.as_millis() as u64,
+            "UpstreamContentLength": self.response.map(|response| response.content_length),
+            "station": self.pm_station,
+            "subRegionId": self.sub_region_id,
+            "status": self.response.map(|response| response.status),
+        })
+    }
+}
+
+// fetch 동안 받은 HTTP 응답을 함께 반환
+pub async fn capture<F: Future>(fetch: F) -> (F::Output, Option<UpstreamResponse>) {
+    UPSTREAM_RESPONSE
+        .scope(RefCell::new(None), async move {
+            let output = fetch.await;
+            let response = UPSTREAM_RESPONSE.with(|slot| slot.borrow_mut().take());
+            (output, response)
+        })
+        .await
+}
+
+// HTTP 응답 기록 (capture 범위 밖이면 아무것도 하지 않음)
+pub fn record_response(status: u16, content_length: usize) {
+    let _ = UPSTREAM_RESPONSE.try_with(|slot| {
+        let mut slot = slot.borrow_mut();
+        let total_length = slot.map_or(0, |previous| previous.content_length);
+        *slot = Some(UpstreamResponse {
+            status,
+            content_length: total_length + content_length,
+        });
+    });
+}
+
+// 소요 시간이 긴 순서로 정렬 후 최대 MAX_DIAGNOSTICS 개
+pub fn slowest_first(mut diagnostics: Vec<StationDiagnostic>) -> Vec<StationDiagnostic> {
+    diagnostics.sort_by_key(|diagnostic| std::cmp::Reverse(diagnostic.elapsed));
+    diagnostics.truncate(MAX_DIAGNOSTICS);
+    diagnostics
+}
+
+// PM_EMF_NAMESPACE 환경 변수 (기본 ExternalPm)
+pub fn emf_namespace() -> String {
+    std::env::var("PM_EMF_NAMESPACE")
+        .ok()
+        .filter(|namespace| !namespace.is_empty())
+        .unwrap_or_else(|| DEFAULT_EMF_NAMESPACE.to_owned())
+}
+
+// 측정소별 EMF 로그 출력 (CloudWatch Logs 가 stdout 의 JSON 한 줄을 지표로 변환)
+pub fn emit_emf(diagnostics: &[StationDiagnostic]) {
+    let namespace = emf_namespace();
+    let timestamp_millis = chrono::Utc::now().timestamp_millis();
+    for diagnostic in diagnostics {
+        println!("{}", diagnostic.to_emf(&namespace, timestamp_millis));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(sub_region_id: i32, elapsed_ms: u64) -> StationDiagnostic {
+        StationDiagnostic {
+            sub_region_id,
+            pm_station: "중구".to_owned(),
+            elapsed: Duration::from_millis(elapsed_ms),
+            response: Some(UpstreamResponse {
+                status: 200,
+                content_length: 512,
+            }),
+        }
+    }
+
+    // 재시도 등으로 여러 번 호출하면 상태 코드는 마지막 응답, 본문 크기는 합계
+    #[tokio::test]
+    async fn capture_keeps_the_last_status_and_the_total_size() {
+        let (output, response) = capture(async {
+            record_response(503, 100);
+            tokio::task::yield_now().await;
+            record_response(200, 400);
+            "reading"
+        })
+        .await;
+        assert_eq!(output, "reading");
+        assert_eq!(
+            response,
+            Some(UpstreamResponse {
+                status: 200,
+                content_length: 500,
+            })
+        );
+
+        // 응답을 받지 못한 조회
+        let ((), response) = capture(async {}).await;
+        assert_eq!(response, None);
+    }
+
+    #[test]
+    fn responses_outside_capture_are_ignored() {
+        record_response(200, 10);
+    }
+
+    #[test]
+    fn json_and_emf_lines_describe_the_station() {
+        let mut slow = diagnostic(7, 1_250);
+        assert_eq!(
+            slow.to_json(),
+            json!({
+                "subRegionId": 7,
+                "stationName": "중구",
+                "elapsedMs": 1250,
+                "status": 200,
+                "contentLength": 512,
+            })
+        );
+
+        let emf = slow.to_emf("ExternalPm", 1_700_000_000_000);
+        assert_eq!(emf["_aws"]["Timestamp"], 1_700_000_000_000_i64);
+        assert_eq!(
+            emf["_aws"]["CloudWatchMetrics"][0]["Namespace"],
+            "ExternalPm"
+        );
+        // 측정소는 차원이 아닌 속성
+        assert_eq!(
+            emf["_aws"]["CloudWatchMetrics"][0]["Dimensions"],
+            json!([[]])
+        );
+        assert_eq!(emf["UpstreamLatency"], 1250);
+        assert_eq!(emf["UpstreamContentLength"], 512);
+        assert_eq!(emf["station"], "중구");
+
+        slow.response = None;
+        assert_eq!(slow.to_json()["status"], serde_json::Value::Null);
+        assert_eq!(
+            slow.to_emf("ExternalPm", 0)["UpstreamContentLength"],
+            serde_json::Value::Null
+        );
+    }
+
+    #[test]
+    fn slowest_stations_come_first_up_to_the_limit() {
+        let diagnostics = (0..MAX_DIAGNOSTICS as i32 + 10)
+            .map(|sub_region_id| diagnostic(sub_region_id, sub_region_id as u64))
+            .collect();
+        let slowest = slowest_first(diagnostics);
+        assert_eq!(slowest.len(), MAX_DIAGNOSTICS);
+        assert_eq!(slowest[0].sub_region_id, MAX_DIAGNOSTICS as i32 + 9);
+        assert_eq!(slowest.last().unwrap().sub_region_id, 10);
+    }
+}
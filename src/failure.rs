@@ -0,0 +1,339 @@
+// src/failure.rs
+
+// 측정소별 실패 분류 및 분류별 로그 레벨
+// 데이터 없음 같은 soft failure 는 warn, 실제 장애는 error 로 기록
+// STATION_LOG_LEVELS 환경 변수로 분류별 레벨 재정의 (예: "NO_DATA=debug,API_ERROR=warn")
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use tracing::{debug, error, info, trace, warn, Level};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FailureKind {
+    // 측정소 목록 행 변환 실패 (NULL, 잘못된 타입)
+    RowMapping,
+    // pm_station / 좌표 미설정
+    StationConfig,
+    // pm_station 이 빈 문자열이고 좌표도 없음
+    UnmappedStation,
+    // 근접 측정소 조회 실패
+    NearbyStation,
+    // 외부 API 요청 실패
+    Request,
+    // 외부 API non-success 상태 코드
+    HttpStatus,
+    // 응답 본문 읽기 실패
+    ReadBody,
+    // 응답 본문이 PM_MAX_BODY_BYTES 초과
+    BodyTooLarge,
+    // JSON 파싱 실패
+    Parse,
+    // 응답 본문이 JSON 이 끝나기 전에 끊김 (전송 중 연결 종료)
+    TruncatedBody,
+    // API resultMsg 가 NORMAL_CODE 가 아님
+    ApiError,
+    // 응답에 측정 데이터 없음
+    NoData,
+    // 연속 NO_DATA 가 NODATA_SUSPEND_THRESHOLD 를 넘음 (측정소 이름 오류 의심)
+    SuspectedInvalidStation,
+    // 서비스 키 미등록 (SERVICE_KEY_IS_NOT_REGISTERED_ERROR), 전체 실행 중단
+    InvalidServiceKey,
+    // 커넥션 풀에서 클라이언트 획득 실패
+    DbPool,
+    // upsert 실패
+    DbQuery,
+    // 외래 키 위반 (예: sub_region_id 가 sub_region 에 없음)
+    DbForeignKey,
+    // unique 제약 조건 위반
+    DbUniqueViolation,
+    // DB 커넥션 오류 (끊김, SQLSTATE 08xxx)
+    DbConnection,
+    // 읽기 전용 DB (페일오버 중 replica, SQLSTATE 25006)
+    DbReadOnly,
+    // 상위 API 요청 제한 (429, Retry-After 재시도 후에도 계속)
+    RateLimited,
+    // 측정소별 제한 시간 초과
+    Timeout,
+    // 태스크 패닉/취소, 세마포어 닫힘 등 내부 오류
+    Internal,
+}
+
+impl FailureKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FailureKind::RowMapping => "ROW_MAPPING",
+            FailureKind::StationConfig => "STATION_CONFIG",
+            FailureKind::UnmappedStation => "UNMAPPED_STATION",
+            FailureKind::NearbyStation => "NEARBY_STATION",
+            FailureKind::Request => "REQUEST",
+            FailureKind::HttpStatus => "HTTP_STATUS",
+            FailureKind::ReadBody => "READ_BODY",
+            FailureKind::BodyTooLarge => "BODY_TOO_LARGE",
+            FailureKind::Parse => "PARSE",
+            FailureKind::TruncatedBody => "TRUNCATED_BODY",
+            FailureKind::ApiError => "API_ERROR",
+            FailureKind::NoData => "NO_DATA",
+            FailureKind::SuspectedInvalidStation => "SUSPECTED_INVALID_STATION",
+            FailureKind::InvalidServiceKey => "INVALID_SERVICE_KEY",
+            FailureKind::DbPool => "DB_POOL",
+            FailureKind::DbQuery => "DB_QUERY",
+            FailureKind::DbForeignKey => "DB_FOREIGN_KEY",
+            FailureKind::DbUniqueViolation => "DB_UNIQUE_VIOLATION",
+            FailureKind::DbConnection => "DB_CONNECTION",
+            FailureKind::DbReadOnly => "DB_READ_ONLY",
+            FailureKind::RateLimited => "RATE_LIMITED",
+            FailureKind::Timeout => "TIMEOUT",
+            FailureKind::Internal => "INTERNAL",
+        }
+    }
+
+    // 재시도하면 성공할 수 있는 실패 (네트워크, DB 등)
+    pub fn is_retriable(&self) -> bool {
+        matches!(
+            self,
+            FailureKind::NearbyStation
+                | FailureKind::Request
+                | FailureKind::HttpStatus
+                | FailureKind::ReadBody
+                | FailureKind::TruncatedBody
+                | FailureKind::DbPool
+                | FailureKind::DbQuery
+                | FailureKind::DbConnection
+                | FailureKind::DbReadOnly
+                | FailureKind::RateLimited
+                | FailureKind::Timeout
+        )
+    }
+
+    // 환경 변수 재정의가 없을 때의 기본 로그 레벨
+    fn default_log_level(&self) -> Level {
+        match self {
+            FailureKind::StationConfig
+            | FailureKind::UnmappedStation
+            | FailureKind::NoData
+            | FailureKind::SuspectedInvalidStation => Level::WARN,
+            _ => Level::ERROR,
+        }
+    }
+
+    pub fn log_level(&self) -> Level {
+        log_level_overrides()
+            .get(self.as_str())
+            .copied()
+            .unwrap_or_else(|| self.default_log_level())
+    }
+}
+
+// STATION_LOG_LEVELS 파싱 결과 (잘못된 항목은 무시)
+fn log_level_overrides() -> &'static HashMap<String, Level> {
+    static OVERRIDES: OnceLock<HashMap<String, Level>> = OnceLock::new();
+    OVERRIDES.get_or_init(|| {
+        std::env::var("STATION_LOG_LEVELS")
+            .map(|v| parse_log_levels(&v))
+            .unwrap_or_default()
+    })
+}
+
+pub fn parse_log_levels(value: &str) -> HashMap<String, Level> {
+    value
+        .split(',')
+        .filter_map(|entry| {
+            let (kind, level) = entry.split_once('=')?;
+            let level = Level::from_str(level.trim()).ok()?;
+            Some((kind.trim().to_uppercase(), level))
+        })
+        .collect()
+}
+
+// 분류별 레벨로 실패 메시지 기록
+pub fn log_failure(kind: FailureKind, message: &str) {
+    match kind.log_level() {
+        Level::ERROR => error!(kind = kind.as_str(), "{}", message),
+        Level::WARN => warn!(kind = kind.as_str(), "{}", message),
+        Level::INFO => info!(kind = kind.as_str(), "{}", message),
+        Level::DEBUG => debug!(kind = kind.as_str(), "{}", message),
+        _ => trace!(kind = kind.as_str(), "{}", message),
+    }
+}
+
+// 응답에 담을 오류 메시지 총량 기본값
+const DEFAULT_MAX_ERROR_BYTES: usize = 64 * 1024;
+
+// MAX_ERROR_BYTES 환경 변수 (기본 64 KiB)
+pub fn max_error_bytes() -> usize {
+    std::env::var("MAX_ERROR_BYTES")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_MAX_ERROR_BYTES)
+}
+
+// 오류 메시지 누적 크기 제한
+// 업스트림 장애로 모든 측정소가 실패해도 오류 목록이 128 MB Lambda 메모리를 잡아먹지 않도록,
+// 한도를 넘는 메시지는 버리고 개수만 세어 마지막에 한 줄로 표시
+pub struct ErrorBudget {
+    limit: usize,
+    retained_bytes: usize,
+    truncated: usize,
+}
+
+impl ErrorBudget {
+    pub fn new(limit: usize) -> Self {
+        ErrorBudget {
+            limit,
+            retained_bytes: 0,
+            truncated: 0,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        ErrorBudget::new(max_error_bytes())
+    }
+
+    // 한도 안이면 누적 크기에 더하고 true, 넘으면 잘린 개수만 증가
+    pub fn admit(&mut self, message: &str) -> bool {
+        if self.retained_bytes + message.len() > self.limit {
+            self.truncated += 1;
+            return false;
+        }
+        self.retained_bytes += message.len();
+        true
+    }
+
+    pub fn truncated(&self) -> usize {
+        self.truncated
+    }
+
+    // 잘린 메시지가 있으면 목록 끝에 붙일 요약
+    pub fn overflow_message(&self) -> Option<String> {
+        (self.truncated > 0).then(|| format!("{} additional errors truncated", self.truncated))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+    use tracing_subscriber::Layer;
+
+    // 기록된 이벤트의 레벨과 kind / message 필드
+    #[derive(Debug, Clone, PartialEq)]
+    struct Captured {
+        level: Level,
+        kind: String,
+        message: String,
+    }
+
+    #[derive(Clone, Default)]
+    struct CaptureLayer(Arc<Mutex<Vec<Captured>>>);
+
+    #[derive(Default)]
+    struct FieldVisitor {
+        kind: String,
+        message: String,
+    }
+
+    impl Visit for FieldVisitor {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "kind" {
+                self.kind = value.to_owned();
+            }
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "message" {
+                self.message = format!("{:?}", value);
+            }
+        }
+    }
+
+    impl<S: tracing::Subscriber> Layer<S> for CaptureLayer {
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            let mut visitor = FieldVisitor::default();
+            event.record(&mut visitor);
+            self.0.lock().unwrap().push(Captured {
+                level: *event.metadata().level(),
+                kind: visitor.kind,
+                message: visitor.message,
+            });
+        }
+    }
+
+    fn capture(f: impl FnOnce()) -> Vec<Captured> {
+        let layer = CaptureLayer::default();
+        let subscriber = tracing_subscriber::registry().with(layer.clone());
+        tracing::subscriber::with_default(subscriber, f);
+        let events = layer.0.lock().unwrap().clone();
+        events
+    }
+
+    #[test]
+    fn no_data_is_not_logged_at_error_level() {
+        let events = capture(|| log_failure(FailureKind::NoData, "중구 : No data"));
+
+        assert_eq!(
+            events,
+            vec![Captured {
+                level: Level::WARN,
+                kind: "NO_DATA".to_owned(),
+                message: "중구 : No data".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn soft_failures_warn_and_real_failures_error() {
+        let events = capture(|| {
+            for kind in [
+                FailureKind::StationConfig,
+                FailureKind::UnmappedStation,
+                FailureKind::SuspectedInvalidStation,
+                FailureKind::Request,
+                FailureKind::DbQuery,
+                FailureKind::Timeout,
+            ] {
+                log_failure(kind, "failed");
+            }
+        });
+
+        let levels: Vec<Level> = events.iter().map(|event| event.level).collect();
+        assert_eq!(
+            levels,
+            vec![
+                Level::WARN,
+                Level::WARN,
+                Level::WARN,
+                Level::ERROR,
+                Level::ERROR,
+                Level::ERROR
+            ]
+        );
+    }
+
+    #[test]
+    fn log_level_overrides_ignore_invalid_entries() {
+        let overrides = parse_log_levels("no_data=debug, API_ERROR = warn,TIMEOUT=loud,garbage");
+
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides["NO_DATA"], Level::DEBUG);
+        assert_eq!(overrides["API_ERROR"], Level::WARN);
+    }
+
+    #[test]
+    fn error_budget_admits_until_the_limit_then_counts() {
+        let mut budget = ErrorBudget::new(10);
+        assert!(budget.admit("12345"));
+        assert!(budget.admit("12345"));
+        assert!(!budget.admit("1"));
+        assert!(!budget.admit("123"));
+        assert_eq!(budget.truncated(), 2);
+        assert_eq!(
+            budget.overflow_message().as_deref(),
+            Some("2 additional errors truncated")
+        );
+
+        assert_eq!(ErrorBudget::new(10).overflow_message(), None);
+    }
+}
@@ -0,0 +1,351 @@
+// src/fallback.rs
+
+// DB 장애 시 조회한 측정값 보관 (PM_FALLBACK_SINK = "file" | "sqs")
+// 재시도 후에도 upsert(또는 커넥션 획득)에 실패한 측정값을 버리지 않고 로컬 파일(/tmp) 또는 SQS 큐에 남겨
+// 이후 실행에서 다시 저장할 수 있도록 함
+//   file: PM_FALLBACK_FILE (기본 /tmp/pm_unwritten.ndjson) 에 NDJSON 으로 추가, mode: "replay" 로 재저장
+//   sqs: PM_FALLBACK_QUEUE_URL 로 레코드마다 메시지 1건 전송 (큐 소비자가 재저장)
+
+use anyhow::{anyhow, Result};
+use aws_config::BehaviorVersion;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{Mutex, OnceCell};
+use tracing::{info, warn};
+
+use crate::db_error::{classify_db_error, describe_db_error};
+use crate::failure::FailureKind;
+use crate::handler::{new_run_id, IngestReport, StationResult};
+use crate::inline_stations::StationListSource;
+use crate::phases::Phases;
+use crate::provider::Reading;
+use crate::reprocess::upsert_as_of;
+use crate::state::ServerState;
+
+const DEFAULT_FALLBACK_FILE: &str = "/tmp/pm_unwritten.ndjson";
+
+// 저장하지 못한 측정값
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FallbackRecord {
+    pub sub_region_id: i32,
+    pub pm_station: String,
+    pub pm10: Option<f64>,
+    pub pm25: Option<f64>,
+    pub recorded_at: DateTime<Utc>,
+    // 저장 실패 사유
+    pub reason: String,
+}
+
+impl FallbackRecord {
+    pub fn new(sub_region_id: i32, pm_station: &str, reading: &Reading, reason: String) -> Self {
+        FallbackRecord {
+            sub_region_id,
+            pm_station: pm_station.to_owned(),
+            pm10: reading.pm10,
+            pm25: reading.pm25,
+            recorded_at: reading.recorded_at,
+            reason,
+        }
+    }
+
+    fn reading(&self) -> Reading {
+        Reading {
+            pm10: self.pm10,
+            pm25: self.pm25,
+            recorded_at: self.recorded_at,
+            server_time: None,
+            raw: None,
+            rejected_values: 0,
+        }
+    }
+}
+
+pub enum FallbackSink {
+    // 여러 측정소 태스크가 같은 파일에 쓰므로 줄 단위로 직렬화
+    File {
+        path: PathBuf,
+        lock: Mutex<()>,
+    },
+    // SQS 클라이언트는 첫 전송 시점에 생성
+    Sqs {
+        queue_url: String,
+        client: OnceCell<aws_sdk_sqs::Client>,
+    },
+}
+
+impl FallbackSink {
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        FallbackSink::File {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn sqs(queue_url: impl Into<String>) -> Self {
+        FallbackSink::Sqs {
+            queue_url: queue_url.into(),
+            client: OnceCell::new(),
+        }
+    }
+
+    // PM_FALLBACK_SINK 미설정이면 None (sqs 인데 큐 URL 이 없으면 오류)
+    pub fn from_env() -> Result<Option<Self>> {
+        match std::env::var("PM_FALLBACK_SINK").ok().as_deref() {
+            None | Some("") => Ok(None),
+            Some("file") => {
+                let path = std::env::var("PM_FALLBACK_FILE")
+                    .unwrap_or_else(|_| DEFAULT_FALLBACK_FILE.to_owned());
+                Ok(Some(FallbackSink::file(path)))
+            }
+            Some("sqs") => {
+                let queue_url = std::env::var("PM_FALLBACK_QUEUE_URL").map_err(|_| {
+                    anyhow!("PM_FALLBACK_SINK=sqs 에는 PM_FALLBACK_QUEUE_URL 이 필요함")
+                })?;
+                Ok(Some(FallbackSink::sqs(queue_url)))
+            }
+            Some(other) => Err(anyhow!(
+                "PM_FALLBACK_SINK 는 \"file\" 또는 \"sqs\" 만 가능: {}",
+                other
+            )),
+        }
+    }
+
+    // 레코드 1건 보관
+    pub async fn write(&self, record: &FallbackRecord) -> Result<()> {
+        let line = serde_json::to_string(record)?;
+        match self {
+            FallbackSink::File { path, lock } => {
+                let _guard = lock.lock().await;
+                let mut file = tokio::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .await
+                    .map_err(|e| anyhow!("{} 열기 실패: {:?}", path.display(), e))?;
+                file.write_all(format!("{}\n", line).as_bytes()).await?;
+                file.flush().await?;
+                Ok(())
+            }
+            FallbackSink::Sqs { queue_url, client } => {
+                let client = client
+                    .get_or_init(|| async {
+                        let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+                        aws_sdk_sqs::Client::new(&sdk_config)
+                    })
+                    .await;
+                client
+                    .send_message()
+                    .queue_url(queue_url)
+                    .message_body(line)
+                    .send()
+                    .await
+                    .map_err(|e| anyhow!("SQS send failed: {:?}", e))?;
+                Ok(())
+            }
+        }
+    }
+}
+
+// 보관 설정 시 저장하지 못한 측정값 기록, 보관 여부 반환 (보관 실패는 로그만 남김)
+pub async fn buffer_unwritten(
+    state: &ServerState,
+    sub_region_id: i32,
+    pm_station: &str,
+    reading: &Reading,
+    reason: &str,
+) -> bool {
+    let Some(fallback) = &state.fallback else {
+        return false;
+    };
+
+    let record = FallbackRecord::new(sub_region_id, pm_station, reading, reason.to_owned());
+    match fallback.write(&record).await {
+        Ok(()) => true,
+        Err(e) => {
+            warn!("{} : 미저장 측정값 보관 실패: {:?}", pm_station, e);
+            false
+        }
+    }
+}
+
+// 파일에 보관된 측정값 재저장 (mode: "replay")
+// 저장된 값이 더 최신이면 갱신하지 않으며, 다시 실패한 레코드만 파일에 남김
+pub async fn run_replay(state: Arc<ServerState>) -> Result<IngestReport> {
+    let start = tokio::time::Instant::now();
+    let run_id = new_run_id();
+    let Some(FallbackSink::File { path, lock }) = &state.fallback else {
+        return Err(anyhow!(
+            "replay 는 PM_FALLBACK_SINK=file 에서만 가능 (sqs 는 큐 소비자가 재저장)"
+        ));
+    };
+
+    // 재저장 중 새로 보관되는 레코드와 섞이지 않도록 파일 잠금 유지
+    let _guard = lock.lock().await;
+    let contents = match tokio::fs::read_to_string(path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(anyhow!("{} 읽기 실패: {:?}", path.display(), e)),
+    };
+
+    let mut results = Vec::new();
+    let mut remaining = Vec::new();
+    let mut db_client = None;
+
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: FallbackRecord = match serde_json::from_str(line) {
+            Ok(record) => record,
+            Err(e) => {
+                // 읽을 수 없는 줄은 다시 시도해도 같으므로 버림
+                results.push(StationResult::failed(
+                    0,
+                    "",
+                    FailureKind::Parse,
+                    format!("replay line {} : {}", index + 1, e),
+                ));
+                continue;
+            }
+        };
+
+        if db_client.is_none() {
+            db_client = Some(state.pool.get().await?);
+        }
+        let Some(client) = &db_client else {
+            continue;
+        };
+
+        let result =
+            match upsert_as_of(client, record.sub_region_id, &record.reading(), false).await {
+                Ok(row) => StationResult::success(
+                    record.sub_region_id,
+                    &record.pm_station,
+                    serde_json::json!({
+                        "subRegionId": record.sub_region_id,
+                        "stationName": record.pm_station,
+                        "dataTime": record.recorded_at,
+                        "updated": row.is_some(),
+                    }),
+                ),
+                Err(e) => {
+                    remaining.push(line.to_owned());
+                    let error_message = format!(
+                        "{} : Replay upsert failed: {}",
+                        record.pm_station,
+                        describe_db_error(&e)
+                    );
+                    StationResult::failed(
+                        record.sub_region_id,
+                        &record.pm_station,
+                        classify_db_error(&e),
+                        error_message,
+                    )
+                }
+            };
+        results.push(result);
+    }
+
+    let mut rewritten = remaining.join("\n");
+    if !rewritten.is_empty() {
+        rewritten.push('\n');
+    }
+    tokio::fs::write(path, rewritten)
+        .await
+        .map_err(|e| anyhow!("{} 쓰기 실패: {:?}", path.display(), e))?;
+    info!(
+        "Replayed {} buffered readings ({} kept for the next replay)",
+        results.len(),
+        remaining.len()
+    );
+
+    Ok(IngestReport {
+        run_id,
+        results,
+        skipped_fresh: 0,
+        deferred: 0,
+        cache_hits: 0,
+        db_read_only: false,
+        no_sub_regions: false,
+        advanced: None,
+        data_frozen: false,
+        elapsed: start.elapsed(),
+        warnings: Vec::new(),
+        latest_cache_failures: 0,
+        disabled_sub_regions: None,
+        diagnostics: None,
+        phases: Phases::default(),
+        station_list_source: StationListSource::Db,
+        quota: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn reading(pm10: f64) -> Reading {
+        Reading {
+            pm10: Some(pm10),
+            pm25: None,
+            recorded_at: Utc.with_ymd_and_hms(2026, 10, 16, 3, 0, 0).unwrap(),
+            server_time: None,
+            raw: None,
+            rejected_values: 0,
+        }
+    }
+
+    #[test]
+    fn record_keeps_the_reading_and_the_reason() {
+        let record = FallbackRecord::new(7, "중구", &reading(42.0), "pool timeout".to_owned());
+        let line = serde_json::to_string(&record).unwrap();
+        assert_eq!(
+            serde_json::from_str::<FallbackRecord>(&line).unwrap(),
+            record
+        );
+        assert_eq!(record.reading().pm10, Some(42.0));
+        assert_eq!(record.reading().pm25, None);
+        assert_eq!(record.reading().recorded_at, reading(42.0).recorded_at);
+    }
+
+    // 여러 측정소 태스크가 동시에 써도 줄이 섞이지 않고 모두 추가됨
+    #[tokio::test]
+    async fn concurrent_file_writes_append_whole_lines() {
+        let path = std::env::temp_dir().join(format!("pm_fallback_{}.ndjson", new_run_id()));
+        let sink = Arc::new(FallbackSink::file(&path));
+
+        let writes = (0..20).map(|sub_region_id| {
+            let sink = sink.clone();
+            tokio::spawn(async move {
+                let record = FallbackRecord::new(
+                    sub_region_id,
+                    "중구",
+                    &reading(sub_region_id as f64),
+                    "x".repeat(2_000),
+                );
+                sink.write(&record).await.unwrap();
+            })
+        });
+        for write in writes.collect::<Vec<_>>() {
+            write.await.unwrap();
+        }
+
+        let contents = tokio::fs::read_to_string(&path).await.unwrap();
+        let _ = tokio::fs::remove_file(&path).await;
+        let mut ids: Vec<i32> = contents
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<FallbackRecord>(line)
+                    .unwrap()
+                    .sub_region_id
+            })
+            .collect();
+        ids.sort();
+        assert_eq!(ids, (0..20).collect::<Vec<_>>());
+        assert!(contents.ends_with('\n'));
+    }
+}
@@ -1,25 +1,118 @@
 // src/handler.rs
 
-use chrono::{DateTime, FixedOffset, Timelike, Utc};
-use lambda_runtime::{Error, LambdaEvent};
+use chrono::{DateTime, Utc};
+use futures::stream::{self, BoxStream, StreamExt};
+use futures::FutureExt;
+#[cfg(feature = "lambda")]
+use lambda_runtime::LambdaEvent;
 use serde_json::json;
+use std::collections::HashMap;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tracing::error;
+use std::time::Duration;
+#[cfg(feature = "lambda")]
+use std::time::SystemTime;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{debug, error, info, warn};
+#[cfg(feature = "lambda")]
+use tracing::{info_span, Instrument};
+use uuid::Uuid;
 
-use crate::state::{initialize_state, ServerState};
+use crate::api_quota::{self, QuotaUsage};
+use crate::backfill::run_backfill;
+use crate::combined::{combined_modes, run_combined};
+use crate::compression;
+use crate::db_error::{
+    classify_db_error, describe_db_error, is_read_only_db_error, is_retriable_db_error,
+};
+use crate::db_schema::sql;
+use crate::diagnostics::{self, StationDiagnostic};
+use crate::effective_config::effective_config;
+use crate::failure::{log_failure, ErrorBudget, FailureKind};
+use crate::fallback::{buffer_unwritten, run_replay};
+use crate::idempotency::{self, Claim};
+use crate::init_timing;
+use crate::inline_stations::{inline_stations_from_payload, InlineStation, StationListSource};
+use crate::invocation::InvocationInfo;
+#[cfg(feature = "lambda")]
+use crate::invocation::DEADLINE_MARGIN;
+use crate::last_seen::{self, LastSeenStore};
+use crate::legacy::build_legacy_response_body;
+use crate::messages::{message, message_with, MessageKey};
+use crate::metrics;
+use crate::nearby_station::resolve_nearby_station;
+use crate::nodata;
+use crate::notifier::{self, FatalRun};
+use crate::phases::{self, Phases};
+use crate::provider::airkorea::AIRKOREA_PROVIDER_KEY;
+use crate::provider::rate_limit::{self, ApiBudget};
+use crate::provider::{
+    split_station_refs, AirKoreaProvider, OpenAqProvider, PmProvider, Reading, ReadingCache,
+};
+use crate::raw_replay::{run_raw_replay, RawReplayOptions};
+use crate::reprocess::{run_reprocess, ReprocessOptions};
+use crate::response_detail::ResponseDetail;
+use crate::run_lock::{self, AlreadyRunningError};
+use crate::schema_check;
+use crate::scrub::scrub_secrets;
+use crate::secrets;
+use crate::sink;
+use crate::state::{get_client_with_retry, shared_state_from_env, ServerState};
+use crate::station_i18n::{load_station_names_en, localize_station_names, Locale};
+use crate::stored;
+use crate::trigger::{SqsBody, SqsRecord, Trigger};
+use crate::upserted::{reconcile_batch_result, StationWriteOutcome, UpsertedRow};
+use crate::weather::run_weather_ingest;
 use anyhow::Result;
 
-use deadpool_postgres::Client as DbClient;
+use deadpool_postgres::{Client as DbClient, PoolError};
 use reqwest::Client;
+use tokio_postgres::Row;
 
-// SQL 쿼리 상수
+// SQL 쿼리 상수 ({schema} 는 실행 시 PM_DB_SCHEMA 로 치환)
+// ingest_enabled = false 인 sub_region 은 수집 중지 (NULL 은 수집)
 pub const GET_ALL_SUB_REGION_ID_AND_PM_STATION_QUERY: &str = r#"
-SELECT sub_region_id, pm_station
-FROM v3.sub_region;
+SELECT sub_region_id, pm_station, tm_x, tm_y, COALESCE(provider, 'airkorea') AS provider, nx, ny
+FROM {schema}.sub_region
+WHERE COALESCE(ingest_enabled, true);
 "#;
 
+pub const GET_SUB_REGION_ID_AND_PM_STATION_BY_IDS_QUERY: &str = r#"
+SELECT sub_region_id, pm_station, tm_x, tm_y, COALESCE(provider, 'airkorea') AS provider, nx, ny
+FROM {schema}.sub_region
+WHERE sub_region_id = ANY($1) AND COALESCE(ingest_enabled, true);
+"#;
+
+// 수집 중지 여부와 무관한 조회 (includeDisabled: true, ingest_enabled 컬럼이 없는 이전 스키마)
+pub const GET_ALL_SUB_REGION_INCLUDING_DISABLED_QUERY: &str = r#"
+SELECT sub_region_id, pm_station, tm_x, tm_y, COALESCE(provider, 'airkorea') AS provider, nx, ny
+FROM {schema}.sub_region;
+"#;
+
+pub const GET_SUB_REGION_BY_IDS_INCLUDING_DISABLED_QUERY: &str = r#"
+SELECT sub_region_id, pm_station, tm_x, tm_y, COALESCE(provider, 'airkorea') AS provider, nx, ny
+FROM {schema}.sub_region
+WHERE sub_region_id = ANY($1);
+"#;
+
+pub const COUNT_DISABLED_SUB_REGIONS_QUERY: &str = r#"
+SELECT count(*) FROM {schema}.sub_region
+WHERE NOT COALESCE(ingest_enabled, true);
+"#;
+
+// 갱신 전 값(prev_pm10 / prev_pm25)을 함께 반환하여 응답에 시간당 변화량 표시 (처음 저장하면 NULL)
+// previous 는 잠그지 않고 쿼리 시작 시점 스냅샷으로 읽음
+// (FOR UPDATE 를 붙이면 같은 행을 갱신하는 INSERT 와 잠금이 겹쳐 previous 가 비어 항상 NULL 이 됨)
 pub const UPSERT_EXTERNAL_PM_QUERY: &str = r#"
-INSERT INTO v3.external_pm (sub_region_id, pm10, pm25, recorded_at)
+WITH previous AS (
+    SELECT pm10, pm25
+    FROM {schema}.external_pm
+    WHERE sub_region_id = $1
+)
+INSERT INTO {schema}.external_pm (sub_region_id, pm10, pm25, recorded_at)
 VALUES ($1, $2, $3, $4)
 ON CONFLICT (sub_region_id) 
 DO UPDATE SET 
@@ -27,274 +120,3211 @@ DO UPDATE SET
     pm25 = EXCLUDED.pm25,
     recorded_at = EXCLUDED.recorded_at,
     update_at = now()
-RETURNING *;
+RETURNING {schema}.external_pm.*,
+    (SELECT pm10 FROM previous) AS prev_pm10,
+    (SELECT pm25 FROM previous) AS prev_pm25;
+"#;
+
+// atomic 저장: 여러 측정소를 한 번의 쿼리로 upsert (PM_UPSERT_BATCH_SIZE 개씩 나누어 실행)
+// previous 는 잠그지 않고 쿼리 시작 시점 스냅샷으로 읽어 모든 행의 이전 값을 반환
+// (FOR UPDATE OF e 를 붙이면 INSERT 가 갱신한 첫 행의 previous 가 비어 NULL 이 됨, 행 잠금은 INSERT 가 트랜잭션 끝까지 유지)
+pub const BATCH_UPSERT_EXTERNAL_PM_QUERY: &str = r#"
+WITH input AS (
+    SELECT *
+    FROM UNNEST($1::int[], $2::float8[], $3::float8[], $4::timestamptz[])
+        AS t(sub_region_id, pm10, pm25, recorded_at)
+),
+previous AS (
+    SELECT e.sub_region_id, e.pm10, e.pm25
+    FROM {schema}.external_pm e
+    JOIN input USING (sub_region_id)
+)
+INSERT INTO {schema}.external_pm (sub_region_id, pm10, pm25, recorded_at)
+SELECT sub_region_id, pm10, pm25, recorded_at
+FROM input
+ON CONFLICT (sub_region_id)
+DO UPDATE SET
+    pm10 = EXCLUDED.pm10,
+    pm25 = EXCLUDED.pm25,
+    recorded_at = EXCLUDED.recorded_at,
+    update_at = now()
+RETURNING {schema}.external_pm.*,
+    (SELECT p.pm10 FROM previous p WHERE p.sub_region_id = {schema}.external_pm.sub_region_id) AS prev_pm10,
+    (SELECT p.pm25 FROM previous p WHERE p.sub_region_id = {schema}.external_pm.sub_region_id) AS prev_pm25;
+"#;
+
+// 한 번의 batch upsert 에 넣는 최대 행 수 기본값
+const DEFAULT_UPSERT_BATCH_SIZE: usize = 500;
+
+// 증분 수집: 측정소별 마지막 측정 시각
+pub const GET_EXTERNAL_PM_RECORDED_AT_QUERY: &str = r#"
+SELECT sub_region_id, recorded_at
+FROM {schema}.external_pm;
 "#;
 
+// 외부 API 동시 요청 제한 기본값 (PM, 날씨 수집 공통, API_CONCURRENCY 로 변경)
+pub(crate) const MAX_CONCURRENT_REQUESTS: usize = 10;
+
+// 일시적인 DB 오류 시 upsert 재시도 횟수
+const MAX_UPSERT_RETRIES: u32 = 2;
+
+// 측정소별 제한 시간 기본값
+const DEFAULT_PER_STATION_TIMEOUT_SECS: u64 = 30;
+
+// on-demand 트리거 인증 헤더 (API Gateway v2 는 헤더 이름을 소문자로 전달)
+const TRIGGER_SECRET_HEADER: &str = "x-trigger-secret";
+
+// 수집 범위 옵션 (sub_region_ids / stations 가 None 이면 전체 수집)
+#[derive(Debug, Clone, Default)]
+pub struct FetchOptions {
+    pub sub_region_ids: Option<Vec<i32>>,
+    // 지정 시 해당 pm_station 만 수집
+    pub stations: Option<Vec<String>>,
+    // true 면 외부 API 조회만 하고 DB 에 저장하지 않음
+    pub dry_run: bool,
+    // 실행 식별자 (None 이면 run_ingest 에서 새로 생성)
+    pub run_id: Option<String>,
+    // 증분 수집: 저장된 측정 시각이 이보다 오래된 측정소만 수집
+    pub refresh_older_than: Option<chrono::Duration>,
+    // 시도별 수집: 시도 전체를 한 번에 조회하여 해당 시도 측정소의 sub_region 에 반영
+    pub sido_name: Option<String>,
+    // true 면 advisory lock 으로 다른 실행과 겹치지 않도록 함 (payload 의 force: true 면 생략)
+    pub run_lock: bool,
+    // 복합 모드에서 파이프라인 간 공유하는 HTTP 클라이언트 / 동시 요청 제한 (None 이면 실행마다 생성)
+    pub http_client: Option<Client>,
+    pub semaphore: Option<Arc<Semaphore>>,
+    // 측정소 처리가 끝날 때마다 결과를 전달받을 채널 (스트리밍 응답용)
+    pub progress: Option<UnboundedSender<StationResult>>,
+    // true 면 조회를 모두 마친 뒤 전체 upsert 를 한 트랜잭션으로 저장 (payload 의 atomic: true)
+    pub atomic: bool,
+    // true 면 ingest_enabled = false 인 sub_region 도 수집 (payload 의 includeDisabled: true)
+    pub include_disabled: bool,
+    // 응답의 측정소 이름 표기 (payload 의 locale)
+    pub locale: Locale,
+    // true 면 측정소별 상위 API 소요 시간 / 상태 코드 / 본문 크기를 응답과 EMF 로그로 남김 (payload 의 diagnostics: true)
+    pub diagnostics: bool,
+    // 수집 전에 연속 NO_DATA 카운터를 초기화할 sub_region (payload 의 resetNodata)
+    pub reset_nodata: Option<Vec<i32>>,
+    // 수집 마감 시각 (Lambda 제한 시각 - DEADLINE_MARGIN), 측정소별 제한 시간을 이 시각까지로 줄임
+    pub deadline: Option<tokio::time::Instant>,
+    // 지정 시 sub_region 조회 대신 이 목록으로 수집 (payload 의 stations)
+    pub inline_stations: Option<Vec<InlineStation>>,
+}
+
+impl FetchOptions {
+    // 측정소별 제한 시간 (마감 시각이 더 가까우면 남은 시간까지만)
+    pub fn station_timeout(&self, per_station_timeout: Duration) -> Duration {
+        match self.deadline {
+            Some(deadline) => per_station_timeout
+                .min(deadline.saturating_duration_since(tokio::time::Instant::now())),
+            None => per_station_timeout,
+        }
+    }
+
+    // PM_REFRESH_OLDER_THAN_MINUTES 환경 변수 (미설정 시 전체 수집)
+    pub fn refresh_older_than_from_env() -> Option<chrono::Duration> {
+        std::env::var("PM_REFRESH_OLDER_THAN_MINUTES")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .map(chrono::Duration::minutes)
+    }
+
+    pub(crate) fn http_client(&self) -> Client {
+        self.http_client.clone().unwrap_or_default()
+    }
+
+    pub(crate) fn semaphore(&self) -> Arc<Semaphore> {
+        self.semaphore
+            .clone()
+            .unwrap_or_else(|| Arc::new(Semaphore::new(api_concurrency())))
+    }
+}
+
+// 수집 실행 결과 (Lambda / CLI 공통)
+#[derive(Debug, Clone)]
+pub struct IngestReport {
+    pub run_id: String,
+    pub results: Vec<StationResult>,
+    // 증분 수집에서 최신 데이터가 있어 건너뛴 측정소 수
+    pub skipped_fresh: usize,
+    // MAX_STATIONS_PER_RUN 제한으로 다음 실행으로 미룬 측정소 수
+    pub deferred: usize,
+    // 같은 측정소를 가리키는 sub_region 에 대해 실행 내 캐시로 API 호출을 생략한 횟수
+    pub cache_hits: usize,
+    // 읽기 전용 DB(SQLSTATE 25006)를 만나 이후 측정소는 저장 없이 조회만 했는지 여부
+    pub db_read_only: bool,
+    // 전체 수집인데 sub_region 이 하나도 없음 (빈 테이블, 설정 오류)
+    pub no_sub_regions: bool,
+    // 이전 측정 시각보다 새 측정값을 받은 측정소 수 (PM_LAST_SEEN_STORE=off 면 None)
+    pub advanced: Option<usize>,
+    // 전체 수집에서 갱신된 측정소가 하나도 없음 (제공처 데이터 정지 의심)
+    pub data_frozen: bool,
+    // 실행 소요 시간
+    pub elapsed: Duration,
+    // 출력(SNS / Firehose) 전송 실패 등 실행은 끝났지만 확인이 필요한 내용
+    pub warnings: Vec<String>,
+    // 최신값 캐시(Redis) 기록에 실패한 측정소 수
+    pub latest_cache_failures: usize,
+    // ingest_enabled = false 로 수집에서 제외된 sub_region 수 (확인하지 않았거나 컬럼이 없으면 None)
+    pub disabled_sub_regions: Option<usize>,
+    // diagnostics 실행의 측정소별 상위 API 응답 (소요 시간 긴 순, 최대 MAX_DIAGNOSTICS 개)
+    pub diagnostics: Option<Vec<StationDiagnostic>>,
+    // 실행 구간별 소요 시간 (meta.phases, 기록한 구간만)
+    pub phases: Phases,
+    // 측정소 목록 출처 (meta.stationListSource)
+    pub station_list_source: StationListSource,
+    // 일일 API 한도 사용 현황 (추적하지 않으면 None)
+    pub quota: Option<QuotaUsage>,
+}
+
+// 실행 결과 코드 (meta.outcome)
+pub const OUTCOME_OK: &str = "OK";
+pub const OUTCOME_DB_READ_ONLY: &str = "DB_READ_ONLY";
+pub const OUTCOME_NO_SUB_REGIONS: &str = "NO_SUB_REGIONS";
+pub const OUTCOME_ALREADY_RUNNING: &str = "ALREADY_RUNNING";
+
+// 전체 수집에서 갱신된 측정소가 없을 때 meta.warnings 항목
+pub const DATA_FROZEN_WARNING: &str =
+    "dataFrozen: no station advanced its recorded_at in a full sweep";
+
+// 실행 식별자 (UUIDv7, 시간순 정렬 가능)
+pub fn new_run_id() -> String {
+    Uuid::now_v7().to_string()
+}
+
+// 요청 식별자: payload 의 requestId 가 UUID 이면 그대로 사용, 없거나 형식이 다르면 새로 생성
+// (호출하는 쪽에서 같은 값으로 로그/응답을 추적할 수 있도록 응답 meta.requestId 에 기록)
+pub fn request_id_from_payload(payload: &serde_json::Value) -> String {
+    match payload.get("requestId").and_then(|v| v.as_str()) {
+        Some(request_id) => match Uuid::parse_str(request_id) {
+            Ok(uuid) => uuid.to_string(),
+            Err(_) => {
+                warn!("requestId 가 UUID 형식이 아님, 새로 생성: {}", request_id);
+                new_run_id()
+            }
+        },
+        None => new_run_id(),
+    }
+}
+
+// 응답 meta 에 요청 식별자 기록 (meta 가 있는 응답만)
+fn attach_request_id(response: &mut serde_json::Value, request_id: &str) {
+    if let Some(meta) = response.get_mut("meta").and_then(|v| v.as_object_mut()) {
+        meta.insert("requestId".to_owned(), json!(request_id));
+    }
+}
+
+// payload 의 sub_region id 목록 필드 (없으면 None, 정수 배열이 아니면 오류)
+pub fn sub_region_ids_from_payload(
+    payload: &serde_json::Value,
+    field: &str,
+) -> Result<Option<Vec<i32>>> {
+    let Some(value) = payload.get(field) else {
+        return Ok(None);
+    };
+    value
+        .as_array()
+        .and_then(|ids| {
+            ids.iter()
+                .map(|id| id.as_i64().and_then(|id| i32::try_from(id).ok()))
+                .collect::<Option<Vec<i32>>>()
+        })
+        .map(Some)
+        .ok_or_else(|| anyhow::anyhow!("{} 는 정수 배열이어야 함: {}", field, value))
+}
+
+// 측정소별 처리 상태
+#[derive(Debug, Clone)]
+pub enum StationStatus {
+    Success(serde_json::Value),
+    Failed { kind: FailureKind, message: String },
+}
+
+// 측정소별 처리 결과
+#[derive(Debug, Clone)]
+pub struct StationResult {
+    pub sub_region_id: i32,
+    pub pm_station: String,
+    pub status: StationStatus,
+    // DB 커넥션 획득 재시도 횟수
+    pub checkout_retries: u32,
+    // 일시적인 DB 오류로 upsert 를 재시도한 횟수
+    pub upsert_retries: u32,
+    // 저장하지 못한 측정값을 PM_FALLBACK_SINK 에 보관했는지 여부
+    pub buffered: bool,
+    // 유효 범위(PM_VALID_RANGES) 밖이라 NULL 로 바꾼 값의 수
+    pub rejected_values: u32,
+}
+
+impl StationResult {
+    pub(crate) fn success(sub_region_id: i32, pm_station: &str, data: serde_json::Value) -> Self {
+        StationResult {
+            sub_region_id,
+            pm_station: pm_station.to_owned(),
+            status: StationStatus::Success(data),
+            checkout_retries: 0,
+            upsert_retries: 0,
+            buffered: false,
+            rejected_values: 0,
+        }
+    }
+
+    pub(crate) fn failed(
+        sub_region_id: i32,
+        pm_station: &str,
+        kind: FailureKind,
+        message: String,
+    ) -> Self {
+        log_failure(kind, &message);
+        StationResult {
+            sub_region_id,
+            pm_station: pm_station.to_owned(),
+            status: StationStatus::Failed { kind, message },
+            checkout_retries: 0,
+            upsert_retries: 0,
+            buffered: false,
+            rejected_values: 0,
+        }
+    }
+
+    pub(crate) fn with_checkout_retries(mut self, checkout_retries: u32) -> Self {
+        self.checkout_retries = checkout_retries;
+        self
+    }
+
+    pub(crate) fn with_upsert_retries(mut self, upsert_retries: u32) -> Self {
+        self.upsert_retries = upsert_retries;
+        self
+    }
+
+    pub(crate) fn with_buffered(mut self, buffered: bool) -> Self {
+        self.buffered = buffered;
+        self
+    }
+
+    pub(crate) fn with_rejected_values(mut self, rejected_values: u32) -> Self {
+        self.rejected_values = rejected_values;
+        self
+    }
+
+    // 단일 측정소 응답용 JSON
+    pub fn to_json(&self) -> serde_json::Value {
+        match &self.status {
+            StationStatus::Success(data) => data.clone(),
+            StationStatus::Failed { message, .. } => json!({
+                "subRegionId": self.sub_region_id,
+                "stationName": self.pm_station,
+                "error": message,
+            }),
+        }
+    }
+
+    // 실패(건너뛴 sub_region 포함) 항목의 구조화된 오류
+    pub fn error_json(&self) -> Option<serde_json::Value> {
+        match &self.status {
+            StationStatus::Success(_) => None,
+            StationStatus::Failed { kind, message } => Some(json!({
+                "subRegionId": self.sub_region_id,
+                "stationName": self.pm_station,
+                "kind": kind.as_str(),
+                "message": message,
+            })),
+        }
+    }
+
+    pub fn failure_kind(&self) -> Option<FailureKind> {
+        match &self.status {
+            StationStatus::Success(_) => None,
+            StationStatus::Failed { kind, .. } => Some(*kind),
+        }
+    }
+
+    pub fn is_retriable_failure(&self) -> bool {
+        matches!(&self.status, StationStatus::Failed { kind, .. } if kind.is_retriable())
+    }
+}
+
+// 서비스 키 미등록 (SERVICE_KEY_IS_NOT_REGISTERED_ERROR)
+#[derive(Debug)]
+pub struct InvalidServiceKeyError;
+
+impl std::fmt::Display for InvalidServiceKeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "service key invalid: AIR_QUALITY_API_KEY is not registered (SERVICE_KEY_IS_NOT_REGISTERED_ERROR)"
+        )
+    }
+}
+
+impl std::error::Error for InvalidServiceKeyError {}
+
+// 측정소 목록 조회 결과 한 행
+#[derive(Debug, Clone)]
+pub struct SubRegionInfo {
+    pub sub_region_id: i32,
+    pub pm_station: Option<String>,
+    pub tm_x: Option<f64>,
+    pub tm_y: Option<f64>,
+    pub provider: String,
+    // 기상청 격자 좌표
+    pub nx: Option<i32>,
+    pub ny: Option<i32>,
+    pub is_active: Option<bool>,
+    // pm_station 이 NULL 이 아닌 빈 문자열/공백이었는지 여부
+    pub blank_station: bool,
+}
+
+// 수집할 측정소 (이름이 없으면 TM 좌표로 근접 측정소 조회)
+enum StationSource {
+    Name(String),
+    Coordinates(f64, f64),
+}
+
+// 행 변환 실패 (sub_region_id 를 읽을 수 있으면 함께 기록)
+#[derive(Debug, Clone)]
+pub struct RowMapError {
+    pub sub_region_id: Option<i32>,
+    pub message: String,
+}
+
+impl SubRegionInfo {
+    // row.get 대신 try_get 으로 패닉 없이 변환
+    pub fn try_from_row(row: &Row) -> Result<Self, RowMapError> {
+        let sub_region_id = read_sub_region_id(row).map_err(|message| RowMapError {
+            sub_region_id: None,
+            message,
+        })?;
+        let column_error = |column: &str, e: tokio_postgres::Error| RowMapError {
+            sub_region_id: Some(sub_region_id),
+            message: format!("sub_region {} : invalid {}: {}", sub_region_id, column, e),
+        };
+
+        let raw_pm_station = row
+            .try_get::<_, Option<String>>("pm_station")
+            .map_err(|e| column_error("pm_station", e))?
+            .map(|pm_station| pm_station.trim().to_owned());
+        let blank_station = raw_pm_station
+            .as_ref()
+            .is_some_and(|pm_station| pm_station.is_empty());
+
+        Ok(SubRegionInfo {
+            sub_region_id,
+            // 빈 문자열/공백 측정소 이름은 NULL 과 동일하게 취급
+            pm_station: raw_pm_station.filter(|pm_station| !pm_station.is_empty()),
+            tm_x: try_get_optional(row, "tm_x").map_err(|e| column_error("tm_x", e))?,
+            tm_y: try_get_optional(row, "tm_y").map_err(|e| column_error("tm_y", e))?,
+            provider: try_get_optional(row, "provider")
+                .map_err(|e| column_error("provider", e))?
+                .unwrap_or_else(|| AIRKOREA_PROVIDER_KEY.to_owned()),
+            nx: try_get_optional(row, "nx").map_err(|e| column_error("nx", e))?,
+            ny: try_get_optional(row, "ny").map_err(|e| column_error("ny", e))?,
+            is_active: try_get_optional(row, "is_active")
+                .map_err(|e| column_error("is_active", e))?,
+            blank_station,
+        })
+    }
+}
+
+// 결과에 없는 컬럼은 None (컬럼이 추가되기 전 스키마 / 덮어쓴 쿼리 호환), 있으면 NULL 허용으로 변환
+fn try_get_optional<'a, T>(row: &'a Row, column: &str) -> Result<Option<T>, tokio_postgres::Error>
+where
+    T: tokio_postgres::types::FromSql<'a>,
+{
+    if row.columns().iter().all(|c| c.name() != column) {
+        return Ok(None);
+    }
+    row.try_get::<_, Option<T>>(column)
+}
+
+// sub_region_id 읽기 (bigint 컬럼이어도 i32 범위 내면 허용, NULL/범위 초과는 오류)
+fn read_sub_region_id(row: &Row) -> Result<i32, String> {
+    let sub_region_id = match row.try_get::<_, Option<i32>>("sub_region_id") {
+        Ok(sub_region_id) => sub_region_id.map(i64::from),
+        Err(_) => row
+            .try_get::<_, Option<i64>>("sub_region_id")
+            .map_err(|e| format!("invalid sub_region_id: {}", e))?,
+    };
+
+    let sub_region_id = sub_region_id.ok_or_else(|| "sub_region_id is NULL".to_owned())?;
+    i32::try_from(sub_region_id)
+        .map_err(|_| format!("sub_region_id {} is out of i32 range", sub_region_id))
+}
+
+// 핸들러 오류 타입 (lambda_runtime::Error 와 동일, lambda 기능 없이도 빌드되도록 직접 정의)
+pub type Error = Box<dyn std::error::Error + Send + Sync>;
+
 // AWS Lambda 핸들러 함수
+#[cfg(feature = "lambda")]
 pub async fn lambda_handler(
     event: LambdaEvent<serde_json::Value>,
 ) -> Result<serde_json::Value, Error> {
-    let payload = event.payload;
-    println!("Received event: {:?}", payload);
-
-    // 환경 변수 로드
-    let db_conn_url = std::env::var("DB_CONN_URL")
-        .map_err(|e| anyhow::anyhow!("DB_CONN_URL 환경 변수 누락: {:?}", e))?;
-    let air_quality_api_key = std::env::var("AIR_QUALITY_API_KEY")
-        .map_err(|e| anyhow::anyhow!("AIR_QUALITY_API_KEY 환경 변수 누락: {:?}", e))?;
+    // 실행 식별자: 응답 meta, 로그, SNS 메시지에 공통으로 기록
+    let run_id = new_run_id();
+    let request_id = request_id_from_payload(&event.payload);
+    // 호출 정보 (남은 실행 시간은 복합 모드의 파이프라인별 시간 분배와 측정소별 제한 시간에 사용)
+    let invocation = InvocationInfo::from_context(&event.context, SystemTime::now());
+    let span = info_span!(
+        "run",
+        run_id = %run_id,
+        request_id = %request_id,
+        aws_request_id = %invocation.aws_request_id,
+        function_version = %invocation.function_version
+    );
+    span.in_scope(|| {
+        debug!(
+            "Invocation: function={}, deadline={}, remaining={:?} (margin {:?})",
+            invocation.invoked_function_arn,
+            invocation.deadline_millis,
+            invocation.remaining,
+            DEADLINE_MARGIN
+        )
+    });
 
-    // ServerState 초기화
-    let state = initialize_state(&db_conn_url, &air_quality_api_key)
+    handle_event(run_id, request_id, event.payload, invocation)
+        .instrument(span)
         .await
-        .map_err(|e| anyhow::anyhow!("ServerState 초기화 실패: {:?}", e))?;
+}
+
+// Lambda 이벤트 payload 처리 (lambda_runtime 과 무관하므로 다른 진입점에서도 호출 가능)
+pub async fn handle_event(
+    run_id: String,
+    request_id: String,
+    payload: serde_json::Value,
+    invocation: InvocationInfo,
+) -> Result<serde_json::Value, Error> {
+    let start = tokio::time::Instant::now();
 
-    let state = Arc::new(state);
+    // 원본 payload 는 인증 헤더/키를 포함할 수 있으므로 마스킹 후 debug 레벨로만 기록
+    let event_size = payload.to_string().len();
+    debug!("Received event: {}", scrub_secrets(&payload));
 
-    // 외부 API 호출 및 데이터베이스 저장 로직
-    match get_external_pm_data_handler(state).await {
-        Ok(response) => Ok(json!({
+    // 트리거 구분 (EventBridge 예약 이벤트는 detail 을 수집 옵션으로 사용)
+    // 잘못된 SQS 이벤트는 전체 수집으로 처리하지 않고 호출을 실패시켜 재전달 / DLQ 로 넘김
+    let trigger = Trigger::from_payload(&payload)
+        .map_err(|e| Error::from(format!("{} : {:?}", request_id, e)))?;
+    let payload = match &trigger {
+        Trigger::Scheduled(event) => {
+            debug!(
+                "Scheduled event {} at {} ({:?})",
+                event.id, event.time, event.resources
+            );
+            event.options_payload()
+        }
+        _ => payload,
+    };
+    let event_mode = payload
+        .get("mode")
+        .and_then(|v| v.as_str())
+        .unwrap_or("realtime");
+    info!(
+        "Received event: {} bytes, trigger={}, mode={}",
+        event_size,
+        trigger.as_str(),
+        event_mode
+    );
+
+    // Function URL / API Gateway v2 트리거: 인증 실패 시 DB/API 접근 없이 401 반환
+    let is_http_request = trigger == Trigger::Http;
+    if is_http_request && !is_authorized(&payload) {
+        return Ok(http_response(
+            401,
+            &json!({ "message": message(MessageKey::Unauthorized) }),
+        ));
+    }
+
+    // config: "show" 이면 DB/API 접근 없이 실제 적용된 설정만 반환 (비밀 값은 가림)
+    if payload.get("config").and_then(|v| v.as_str()) == Some("show") {
+        return Ok(json!({
             "statusCode": 200,
-            "body": response,
-        })),
+            "body": effective_config(),
+        }));
+    }
+
+    // 환경 변수 로드 및 ServerState 초기화 (같은 실행 환경의 다음 호출은 초기화한 상태를 재사용)
+    let state_init_start = tokio::time::Instant::now();
+    let (state, cold_start) = shared_state_from_env()
+        .await
+        .map_err(|e| Error::from(format!("{} : {:?}", request_id, e)))?;
+    let state_init = state_init_start.elapsed();
+
+    // Function URL / API Gateway v2 트리거: 단일 sub_region 즉시 수집
+    if is_http_request {
+        return Ok(handle_http_request(state, &run_id, &payload).await);
+    }
+
+    // SQS 트리거: 레코드별로 수집 후 실패한 레코드만 batchItemFailures 로 반환
+    if let Trigger::Sqs(records) = &trigger {
+        let batch_item_failures = handle_sqs_records(state, &run_id, records).await;
+        return Ok(json!({
+            "batchItemFailures": batch_item_failures,
+        }));
+    }
+
+    // read: true 이면 외부 API 호출 / DB 쓰기 없이 저장된 값만 반환 (중복 전달 방지 / 실행 락 대상 아님)
+    if payload.get("read").and_then(|v| v.as_bool()) == Some(true) {
+        return Ok(handle_read(&state, &run_id, &request_id, &payload).await);
+    }
+
+    // verify: "schema" 이면 수집 없이 대상 DB 의 테이블/컬럼만 확인 (불일치는 422)
+    if payload.get("verify").and_then(|v| v.as_str()) == Some("schema") {
+        return Ok(handle_verify_schema(&state, &run_id, &request_id).await);
+    }
+
+    // dryRun: true 이면 외부 API 조회만 하고 DB 에 저장하지 않음
+    let dry_run = payload
+        .get("dryRun")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    // EventBridge 중복 전달 방지 (payload 의 force: true 로 우회, 저장하지 않는 dry-run 은 확인하지 않음)
+    let force = payload
+        .get("force")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let idempotency_key = (!force && !dry_run).then(|| idempotency::idempotency_key(&payload));
+
+    // 수집 모드 (기본: 실시간 PM, "weather": 초단기실황 날씨, "backfill": 응답 전체 항목을 이력 테이블에 저장,
+    // 배열이면 복합 모드)
+    let mode = payload
+        .get("mode")
+        .and_then(|v| v.as_str())
+        .unwrap_or("realtime");
+    let combined_modes = combined_modes(&payload);
+
+    // responseSchema: "legacy" 이면 axum 버전과 동일한 Data/Meta 형식으로 응답
+    let legacy_schema = payload.get("responseSchema").and_then(|v| v.as_str()) == Some("legacy");
+
+    // atomic: true 이면 측정소별 저장 대신 전체 저장을 한 트랜잭션으로 (DB 오류 시 전부 롤백)
+    let atomic = payload
+        .get("atomic")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    // includeDisabled: true 이면 수집 중지된 sub_region 도 포함 (수동 실행용)
+    let include_disabled = payload
+        .get("includeDisabled")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    // diagnostics: true 이면 측정소별 상위 API 응답 시간을 응답과 EMF 로그로 남김 (저장 동작은 같음)
+    let diagnostics = payload
+        .get("diagnostics")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    // resetNodata: [ids] 이면 해당 sub_region 의 연속 NO_DATA 카운터를 초기화한 뒤 수집 (잘못된 값은 수집 없이 400)
+    let reset_nodata = match sub_region_ids_from_payload(&payload, "resetNodata") {
+        Ok(reset_nodata) => reset_nodata,
         Err(e) => {
-            error!("핸들러 실행 중 오류 발생: {:?}", e);
-            Ok(json!({
-                "statusCode": 500,
-                "body": "Internal Server Error",
-            }))
+            return Ok(json!({
+                "statusCode": 400,
+                "body": {
+                    "message": message(MessageKey::InvalidRequest),
+                    "detail": e.to_string(),
+                    "meta": { "runId": run_id, "requestId": request_id },
+                },
+            }));
         }
-    }
-}
+    };
 
-// 실제 핸들러 로직
-async fn get_external_pm_data_handler(
-    state: Arc<ServerState>,
-) -> Result<serde_json::Value, anyhow::Error> {
-    // 데이터베이스에서 필요한 정보 조회 (모든 측정소 ID 및 이름 가져오기)
-    let db_client: DbClient = state.pool.get().await?;
+    // stations: [{"subRegionId", "pmStation"}] 이면 sub_region 조회 없이 해당 목록으로 수집 (잘못된 값은 수집 없이 400)
+    let inline_stations = match inline_stations_from_payload(&payload) {
+        Ok(inline_stations) => inline_stations,
+        Err(e) => {
+            return Ok(json!({
+                "statusCode": 400,
+                "body": {
+                    "message": message(MessageKey::InvalidRequest),
+                    "detail": e.to_string(),
+                    "meta": { "runId": run_id, "requestId": request_id },
+                },
+            }));
+        }
+    };
 
-    let rows = db_client
-        .query(GET_ALL_SUB_REGION_ID_AND_PM_STATION_QUERY, &[])
-        .await?;
+    let options = FetchOptions {
+        run_id: Some(run_id.clone()),
+        refresh_older_than: FetchOptions::refresh_older_than_from_env(),
+        run_lock: !force,
+        dry_run,
+        atomic,
+        include_disabled,
+        sido_name: payload
+            .get("sidoName")
+            .and_then(|v| v.as_str())
+            .map(str::to_owned),
+        locale: Locale::from_payload(&payload),
+        diagnostics,
+        reset_nodata,
+        deadline: invocation.deadline,
+        inline_stations,
+        ..Default::default()
+    };
 
-    // 동시성 제어를 위한 세마포어 설정
-    let semaphore = Arc::new(tokio::sync::Semaphore::new(10)); // 동시 요청 제한
-    let http_client = Client::new();
+    // asOf 가 있으면 해당 정시 항목만 재처리 (잘못된 값은 수집 없이 400)
+    let reprocess = match ReprocessOptions::from_payload(&payload, state.clock.now()) {
+        Ok(reprocess) => reprocess,
+        Err(e) => {
+            return Ok(json!({
+                "statusCode": 400,
+                "body": {
+                    "message": message(MessageKey::InvalidRequest),
+                    "detail": e.to_string(),
+                    "meta": { "runId": run_id, "requestId": request_id },
+                },
+            }));
+        }
+    };
 
-    let mut tasks = Vec::new();
-    let mut response_data = Vec::new();
-    let mut error_list = Vec::new();
+    // 잘못된 요청(400)이 키를 잡아 두지 않도록 payload 옵션을 모두 확인한 뒤에 선점
+    if let Some(key) = &idempotency_key {
+        match idempotency::claim(&state.pool, key, idempotency::ttl()).await {
+            Ok(Claim::Duplicate(summary)) => {
+                info!("{} : 이미 처리된 이벤트, 수집 생략", key);
+                return Ok(json!({
+                    "statusCode": 200,
+                    "body": {
+                        "deduplicated": true,
+                        "meta": summary,
+                    },
+                }));
+            }
+            Ok(Claim::New) => {}
+            Err(e) => error!("멱등성 확인 실패, 수집 계속 진행: {:?}", e),
+        }
+    }
 
-    for row in rows {
-        let sub_region_id: i32 = row.get("sub_region_id");
-        let pm_station: String = row.get("pm_station");
-
-        let semaphore = semaphore.clone();
-        let http_client = http_client.clone();
-        let state = state.clone();
-
-        let task = tokio::spawn(async move {
-            // 각 태스크 내에서 응답 데이터와 에러 리스트를 초기화
-            let mut local_response_data = Vec::new();
-            let mut local_error_list = Vec::new();
-
-            // 세마포어 퍼밋 획득
-            let _permit = semaphore.acquire_owned().await.unwrap();
-
-            // 새로운 DB 클라이언트 획득
-            let db_client = match state.pool.get().await {
-                Ok(client) => client,
-                Err(e) => {
-                    let error_message =
-                        format!("{} : Failed to get db client: {:?}", pm_station, e);
-                    error!("{}", error_message);
-                    return (local_response_data, vec![error_message]);
-                }
-            };
+    // report: "coverage" 이면 DB 에 쓰지 않고 측정소별 데이터 제공 현황만 집계
+    let coverage_report = payload.get("report").and_then(|v| v.as_str()) == Some("coverage");
 
-            // 외부 API 호출 파라미터 설정
-            let params = [
-                ("serviceKey", &state.air_quality_api_key),
-                ("returnType", &"json".to_string()),
-                ("numOfRows", &"1000".to_string()),
-                ("pageNo", &"1".to_string()),
-                ("stationName", &pm_station),
-                ("dataTerm", &"DAILY".to_string()),
-                ("ver", &"1.0".to_string()),
-            ];
-
-            // 외부 API 호출
-            let res = match http_client
-                .get("http://apis.data.go.kr/B552584/ArpltnInforInqireSvc/getMsrstnAcctoRltmMesureDnsty")
-                .query(&params)
-                .send()
+    let result = match mode {
+        _ if combined_modes.is_some() => Ok(run_combined(
+            state.clone(),
+            &options,
+            combined_modes.as_deref().unwrap_or_default(),
+            invocation.remaining,
+        )
+        .await),
+        _ if reprocess.is_some() => match &reprocess {
+            Some(reprocess) => run_reprocess(state.clone(), &options, reprocess)
                 .await
-            {
-                Ok(response) => response,
-                Err(e) => {
-                    let error_message = format!("{} : Request failed: {:?}", pm_station, e);
-                    error!("{}", error_message);
-                    local_error_list.push(error_message);
-                    return (local_response_data, local_error_list);
-                }
+                .map(build_response_body),
+            None => unreachable!(),
+        },
+        _ if coverage_report => {
+            let coverage_options = FetchOptions {
+                dry_run: true,
+                refresh_older_than: None,
+                ..options.clone()
             };
+            run_ingest(state.clone(), &coverage_options)
+                .await
+                .map(|report| build_coverage_body(&report))
+        }
+        "weather" => run_weather_ingest(state.clone(), &options)
+            .await
+            .map(build_response_body),
+        "backfill" => run_backfill(state.clone(), &options)
+            .await
+            .map(build_response_body),
+        // PM_RAW_RESPONSE_BUCKET 에 보관된 원문 응답 재처리 (API 호출 없음)
+        "replay_raw" => match RawReplayOptions::from_payload(&payload) {
+            Ok(replay) => run_raw_replay(state.clone(), &options, &replay)
+                .await
+                .map(build_response_body),
+            Err(e) => Err(e),
+        },
+        // PM_FALLBACK_SINK=file 에 보관된 측정값 재저장
+        "replay" => run_replay(state.clone()).await.map(build_response_body),
+        // legacy 응답에는 warnings 필드가 없으므로 발행 실패는 로그로만 기록
+        _ if legacy_schema => run_realtime_ingest(state.clone(), &options)
+            .await
+            .map(build_legacy_response_body),
+        _ => run_realtime_ingest(state.clone(), &options)
+            .await
+            .map(build_response_body),
+    };
 
-            // 응답 상태 코드 확인
-            if !res.status().is_success() {
-                let res_status = res.status();
-                let res_headers = res.headers().clone();
-                let res_text = res.text().await.unwrap_or_default();
-                let error_message = format!(
-                    "{} : Received non-success status code: {}\nHeaders: {:?}\nResponse text: {}",
-                    pm_station, res_status, res_headers, res_text
-                );
-                error!("{}", error_message);
-                local_error_list.push(error_message);
-                return (local_response_data, local_error_list);
-            }
-
-            // JSON 응답 파싱을 위해 응답 본문을 텍스트로 먼저 읽기
-            let res_text = match res.text().await {
-                Ok(text) => text,
-                Err(e) => {
-                    let error_message =
-                        format!("{} : Failed to read response text: {:?}", pm_station, e);
-                    error!("{}", error_message);
-                    local_error_list.push(error_message);
-                    return (local_response_data, local_error_list);
-                }
-            };
+    // compress: true 이면 본문을 gzip + base64 로 인코딩하여 반환
+    let compress = payload
+        .get("compress")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    // 외부 API 호출 및 데이터베이스 저장 로직
+    match result {
+        Ok(mut response) => {
+            attach_request_id(&mut response, &request_id);
+            invocation.attach(&mut response);
+            phases::attach_init_timing(
+                &mut response,
+                &run_id,
+                state_init,
+                cold_start,
+                state.pool_warmup,
+            );
 
-            // 텍스트를 JSON으로 파싱
-            let json_response: serde_json::Value = match serde_json::from_str(&res_text) {
-                Ok(json) => json,
-                Err(e) => {
-                    let error_message = format!(
-                        "{} : Failed to parse JSON response: {:?}\nResponse text: {}",
-                        pm_station, e, res_text
+            // 실패율이 FAIL_RUN_ABOVE_FAILURE_RATE 를 넘으면 호출 자체를 실패로 반환 (Lambda 재시도 / DLQ 적용)
+            let run_summary = RunSummary::from_response(&run_id, &response);
+            info!("Run summary: {}", run_summary);
+            if let Some(threshold) = failure_rate_threshold() {
+                if run_summary.failure_rate() > threshold {
+                    error!(
+                        "{} : 실패율 {:.3} 이 임계값 {} 초과, 호출 실패 처리",
+                        run_id,
+                        run_summary.failure_rate(),
+                        threshold
+                    );
+                    let fatal = FatalRun::new(
+                        notifier::FAILURE_RATE_EXCEEDED_OUTCOME,
+                        &run_id,
+                        &request_id,
+                        format!(
+                            "failure rate {:.3} above threshold {}",
+                            run_summary.failure_rate(),
+                            threshold
+                        ),
+                    )
+                    .with_response(
+                        run_summary.succeeded,
+                        run_summary.failed,
+                        &response,
                     );
-                    error!("{}", error_message);
-                    local_error_list.push(error_message);
-                    return (local_response_data, local_error_list);
+                    notifier::notify_from_env(&state.http_client, &fatal).await;
+                    // 재시도가 중복으로 처리되지 않도록 멱등성 키 해제
+                    if let Some(key) = &idempotency_key {
+                        if let Err(e) = idempotency::release(&state.pool, key).await {
+                            error!("{} : 멱등성 키 해제 실패: {:?}", key, e);
+                        }
+                    }
+                    return Err(Error::from(format!(
+                        "{} : Failure rate above threshold ({}): {}",
+                        request_id, threshold, run_summary
+                    )));
                 }
-            };
+            }
 
-            // API 응답에서 에러 메시지 확인
-            if let Some(error_message) = json_response
-                .get("response")
-                .and_then(|res| res.get("header"))
-                .and_then(|header| header.get("resultMsg"))
-                .and_then(|msg| msg.as_str())
-            {
-                if error_message != "NORMAL_CODE" {
-                    let error_message =
-                        format!("{} : API returned an error: {}", pm_station, error_message);
-                    error!("{}", error_message);
-                    local_error_list.push(error_message);
-                    return (local_response_data, local_error_list);
+            // 읽기 전용 DB 로 저장하지 못한 실행은 503 으로 반환하여 스케줄러가 전체 실행을 재시도하도록 함
+            let db_read_only = response["meta"]["outcome"] == OUTCOME_DB_READ_ONLY;
+            let status_code = if db_read_only { 503 } else { 200 };
+
+            if let Some(key) = &idempotency_key {
+                let stored = if db_read_only {
+                    idempotency::release(&state.pool, key).await
+                } else {
+                    idempotency::save_summary(&state.pool, key, &response["meta"]).await
+                };
+                if let Err(e) = stored {
+                    error!("{} : 실행 요약 저장/멱등성 키 해제 실패: {:?}", key, e);
                 }
             }
 
-            // 최신 데이터 추출
-            let latest_item = json_response
-                .get("response")
-                .and_then(|res| res.get("body"))
-                .and_then(|body| body.get("items"))
-                .and_then(|items| items.get(0));
-
-            let mut pm10_value: Option<f64> = None;
-            let mut pm25_value: Option<f64> = None;
-            let mut recorded_at_datetime_utc = Utc::now();
-
-            if let Some(item) = latest_item {
-                pm10_value = item
-                    .get("pm10Value")
-                    .and_then(|v| v.as_str())
-                    .filter(|&v| v != "-")
-                    .and_then(|v| v.parse::<f64>().ok());
-
-                pm25_value = item
-                    .get("pm25Value")
-                    .and_then(|v| v.as_str())
-                    .filter(|&v| v != "-")
-                    .and_then(|v| v.parse::<f64>().ok());
-
-                let recorded_at = item.get("dataTime").and_then(|v| v.as_str()).unwrap_or("");
-
-                let kst_offset = FixedOffset::east_opt(9 * 3600).expect("Invalid offset");
-                let recorded_at_datetime_kst =
-                    DateTime::parse_from_str(recorded_at, "%Y-%m-%d %H:%M")
-                        .unwrap_or_else(|_| Utc::now().with_timezone(&kst_offset));
-
-                recorded_at_datetime_utc = recorded_at_datetime_kst.with_timezone(&Utc);
-                recorded_at_datetime_utc = recorded_at_datetime_utc
-                    .with_minute(0)
-                    .unwrap()
-                    .with_second(0)
-                    .unwrap()
-                    .with_nanosecond(0)
-                    .unwrap();
-            } else {
-                let error_message = format!("{} : No data available in API response.", pm_station);
-                error!("{}", error_message);
-                local_error_list.push(error_message);
-                return (local_response_data, local_error_list);
-            }
-
-            // 데이터베이스에 upsert
-            match db_client
-                .query_one(
-                    UPSERT_EXTERNAL_PM_QUERY,
-                    &[
-                        &sub_region_id,
-                        &pm10_value,
-                        &pm25_value,
-                        &recorded_at_datetime_utc,
-                    ],
-                )
-                .await
-            {
-                Ok(row) => {
-                    local_response_data.push(json!({
-                        "pm10Value": row.get::<&str, Option<f64>>("pm10"),
-                        "pm25Value": row.get::<&str, Option<f64>>("pm25"),
-                        "dataTime": row.get::<&str, DateTime<Utc>>("recorded_at"),
-                        "requestedTime": row.get::<&str, DateTime<Utc>>("update_at"),
-                        "stationName": pm_station.clone(),
-                    }));
+            // responseDetail 은 저장/요약 이후 직렬화 단계에서만 적용
+            let response = ResponseDetail::from_payload(&payload).apply(
+                response,
+                &run_summary,
+                start.elapsed(),
+            );
+
+            if compress {
+                match compression::compress_body(&response) {
+                    Ok(encoded) => {
+                        return Ok(json!({
+                            "statusCode": status_code,
+                            "isBase64Encoded": true,
+                            "contentEncoding": "gzip",
+                            "body": encoded,
+                        }));
+                    }
+                    Err(e) => error!("응답 압축 실패, 비압축으로 반환: {:?}", e),
                 }
-                Err(e) => {
-                    let error_message = format!("{} : Database query failed: {:?}", pm_station, e);
-                    error!("{}", error_message);
-                    local_error_list.push(error_message);
+            }
+
+            Ok(json!({
+                "statusCode": status_code,
+                "body": response,
+            }))
+        }
+        Err(e) => {
+            error!("{} : Handler failed: {:?}", request_id, e);
+            if let Some(key) = &idempotency_key {
+                if let Err(e) = idempotency::release(&state.pool, key).await {
+                    error!("{} : 멱등성 키 해제 실패: {:?}", key, e);
                 }
             }
+            // 서비스 키 오류 / DB 접속 불가는 웹훅으로 알림
+            let fatal_outcome = if e.downcast_ref::<InvalidServiceKeyError>().is_some() {
+                Some(notifier::INVALID_SERVICE_KEY_OUTCOME)
+            } else if e.downcast_ref::<PoolError>().is_some() {
+                Some(notifier::DB_UNREACHABLE_OUTCOME)
+            } else {
+                None
+            };
+            if let Some(outcome) = fatal_outcome {
+                let fatal = FatalRun::new(outcome, &run_id, &request_id, e.to_string());
+                notifier::notify_from_env(&state.http_client, &fatal).await;
+            }
+            // 서비스 키 오류는 측정소별 오류 대신 단일 401 로 반환
+            if e.downcast_ref::<InvalidServiceKeyError>().is_some() {
+                refresh_rotated_service_key(&state).await;
+                return Ok(json!({
+                    "statusCode": 401,
+                    "body": {
+                        "message": message(MessageKey::InvalidServiceKey),
+                        "meta": { "runId": run_id, "requestId": request_id },
+                    },
+                }));
+            }
+            // 다른 실행이 진행 중: 409 와 실행 중인 run_id 반환
+            if let Some(already_running) = e.downcast_ref::<AlreadyRunningError>() {
+                return Ok(json!({
+                    "statusCode": 409,
+                    "body": {
+                        "message": message(MessageKey::AlreadyRunning),
+                        "meta": {
+                            "runId": run_id,
+                            "requestId": request_id,
+                            "outcome": OUTCOME_ALREADY_RUNNING,
+                            "holderRunId": already_running.holder_run_id,
+                        },
+                    },
+                }));
+            }
+            // 커넥션 풀 오류: 타임아웃은 일시적이므로 503, 그 외(접속/인증 실패 등)는 500
+            if let Some(pool_error) = e.downcast_ref::<PoolError>() {
+                let (status_code, pool_message) = pool_error_status(pool_error);
+                return Ok(json!({
+                    "statusCode": status_code,
+                    "body": {
+                        "message": pool_message,
+                        "meta": { "runId": run_id, "requestId": request_id },
+                    },
+                }));
+            }
+            Ok(internal_error_response(&run_id, &request_id))
+        }
+    }
+}
 
-            (local_response_data, local_error_list)
-        });
+// 서비스 키 인증 실패: Secrets Manager 의 키가 교체되었을 수 있으므로 다시 조회하여 다음 실행부터 사용
+async fn refresh_rotated_service_key(state: &ServerState) {
+    match secrets::refetch_air_quality_api_key().await {
+        Ok(Some(key)) if key != state.air_quality_api_key() => {
+            warn!("서비스 키가 교체됨, 다음 실행부터 새 키 사용");
+            state.replace_air_quality_api_key(key);
+        }
+        Ok(_) => {}
+        Err(e) => warn!("서비스 키 재조회 실패: {:?}", e),
+    }
+}
 
-        tasks.push(task);
+// 분류되지 않은 오류 응답 (500, 추적할 수 있도록 run_id / requestId 포함)
+fn internal_error_response(run_id: &str, request_id: &str) -> serde_json::Value {
+    json!({
+        "statusCode": 500,
+        "body": {
+            "message": message(MessageKey::InternalServerError),
+            "meta": { "runId": run_id, "requestId": request_id },
+        },
+    })
+}
+
+// 커넥션 풀 오류별 상태 코드와 응답 메시지
+// 응답 메시지는 MESSAGE_LANG 문구, 상세 원인은 로그로만 기록
+pub fn pool_error_status(e: &PoolError) -> (u16, String) {
+    match e {
+        PoolError::Timeout(timeout_type) => {
+            error!("Database connection pool timed out ({:?})", timeout_type);
+            (503, message(MessageKey::DbPoolTimeout))
+        }
+        PoolError::Backend(e) => {
+            error!("Database connection failed: {}", describe_db_error(e));
+            (500, message(MessageKey::DbConnectionFailed))
+        }
+        e => {
+            error!("Database connection pool error: {}", e);
+            (500, message(MessageKey::DbConnectionFailed))
+        }
     }
+}
 
-    // 모든 태스크 완료 대기 및 결과 수집
-    for task in tasks {
-        match task.await {
-            Ok((local_response_data, local_error_list_task)) => {
-                response_data.extend(local_response_data);
-                error_list.extend(local_error_list_task);
+// 스키마 확인 결과 응답 (통과 200, 불일치 422)
+async fn handle_verify_schema(
+    state: &ServerState,
+    run_id: &str,
+    request_id: &str,
+) -> serde_json::Value {
+    let report = match state.pool.get().await {
+        Ok(db_client) => schema_check::verify_schema(&db_client, &state.sub_region_queries)
+            .await
+            .map_err(anyhow::Error::from),
+        Err(e) => Err(anyhow::Error::new(e)),
+    };
+
+    match report {
+        Ok(report) => {
+            if !report.passed() {
+                warn!("{} : 스키마 불일치 발견", run_id);
             }
-            Err(e) => {
-                error!("Task failed: {:?}", e);
+            let mut response = report.to_json(run_id);
+            attach_request_id(&mut response, request_id);
+            json!({
+                "statusCode": if report.passed() { 200 } else { 422 },
+                "body": response,
+            })
+        }
+        Err(e) => {
+            error!("{} : Schema verification failed: {:?}", request_id, e);
+            match e.downcast_ref::<PoolError>() {
+                Some(pool_error) => {
+                    let (status_code, pool_message) = pool_error_status(pool_error);
+                    json!({
+                        "statusCode": status_code,
+                        "body": {
+                            "message": pool_message,
+                            "meta": { "runId": run_id, "requestId": request_id },
+                        },
+                    })
+                }
+                None => internal_error_response(run_id, request_id),
             }
         }
     }
+}
 
-    // 최종 응답 구성
-    Ok(json!({
-        "data": response_data,
-        "meta": {
-            "message": format!("SUCCESS: {}", response_data.len()),
-            "errorList": error_list,
+// 저장된 측정값 조회 (read: true)
+async fn handle_read(
+    state: &ServerState,
+    run_id: &str,
+    request_id: &str,
+    payload: &serde_json::Value,
+) -> serde_json::Value {
+    let sub_region_ids = match sub_region_ids_from_payload(payload, "subRegionIds") {
+        Ok(sub_region_ids) => sub_region_ids,
+        Err(e) => {
+            return json!({
+                "statusCode": 400,
+                "body": {
+                    "message": message(MessageKey::InvalidRequest),
+                    "detail": e.to_string(),
+                    "meta": { "runId": run_id, "requestId": request_id },
+                },
+            });
+        }
+    };
+
+    match stored::read_stored(state, run_id, sub_region_ids.as_ref()).await {
+        Ok(mut response) => {
+            attach_request_id(&mut response, request_id);
+            json!({
+                "statusCode": 200,
+                "body": response,
+            })
+        }
+        Err(e) => {
+            error!("{} : Read failed: {:?}", request_id, e);
+            match e.downcast_ref::<PoolError>() {
+                Some(pool_error) => {
+                    let (status_code, pool_message) = pool_error_status(pool_error);
+                    json!({
+                        "statusCode": status_code,
+                        "body": {
+                            "message": pool_message,
+                            "meta": { "runId": run_id, "requestId": request_id },
+                        },
+                    })
+                }
+                None => internal_error_response(run_id, request_id),
+            }
+        }
+    }
+}
+
+// 실행 요약 (성공/실패 측정소 수)
+pub struct RunSummary {
+    pub run_id: String,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+impl RunSummary {
+    // 기본 / legacy 응답 본문 모두 지원 (data 또는 data.responseData, meta.errorList)
+    pub fn from_response(run_id: &str, response: &serde_json::Value) -> Self {
+        // 복합 모드 응답은 모드별 섹션의 합계
+        if let Some(modes) = response["meta"]["modes"].as_array() {
+            let sections: Vec<RunSummary> = modes
+                .iter()
+                .filter_map(|mode| mode.as_str())
+                .map(|mode| RunSummary::from_response(run_id, &response[mode]))
+                .collect();
+            return RunSummary {
+                run_id: run_id.to_owned(),
+                succeeded: sections.iter().map(|section| section.succeeded).sum(),
+                failed: sections.iter().map(|section| section.failed).sum(),
+            };
+        }
+
+        let succeeded = response["data"]
+            .as_array()
+            .or_else(|| response["data"]["responseData"].as_array())
+            .map(|data| data.len())
+            .unwrap_or(0);
+        // MAX_ERROR_BYTES 로 잘린 오류는 errorList 끝의 요약 한 줄 대신 개수로 계산
+        let truncated = response["meta"]["errorsTruncated"].as_u64().unwrap_or(0) as usize;
+        let listed = response["meta"]["errorList"]
+            .as_array()
+            .map(|errors| errors.len())
+            .unwrap_or(0);
+        let failed = if truncated > 0 {
+            listed.saturating_sub(1) + truncated
+        } else {
+            listed
+        };
+
+        RunSummary {
+            run_id: run_id.to_owned(),
+            succeeded,
+            failed,
+        }
+    }
+
+    pub fn failure_rate(&self) -> f64 {
+        let total = self.succeeded + self.failed;
+        if total == 0 {
+            return 0.0;
         }
-    }))
+        self.failed as f64 / total as f64
+    }
+}
+
+impl std::fmt::Display for RunSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "run {} : succeeded {}, failed {} (failure rate {:.3})",
+            self.run_id,
+            self.succeeded,
+            self.failed,
+            self.failure_rate()
+        )
+    }
+}
+
+// FAIL_RUN_ABOVE_FAILURE_RATE 환경 변수 (미설정 시 실패율과 무관하게 200 반환)
+pub(crate) fn failure_rate_threshold() -> Option<f64> {
+    std::env::var("FAIL_RUN_ABOVE_FAILURE_RATE")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+}
+
+// x-trigger-secret 헤더를 TRIGGER_SECRET 환경 변수와 비교
+// (TRIGGER_SECRET 미설정 시 모든 요청 거부)
+fn is_authorized(payload: &serde_json::Value) -> bool {
+    let trigger_secret = match std::env::var("TRIGGER_SECRET") {
+        Ok(secret) if !secret.is_empty() => secret,
+        _ => {
+            error!("TRIGGER_SECRET 환경 변수 누락");
+            return false;
+        }
+    };
+
+    payload
+        .get("headers")
+        .and_then(|headers| headers.get(TRIGGER_SECRET_HEADER))
+        .and_then(|v| v.as_str())
+        .map(|secret| constant_time_eq(secret.as_bytes(), trigger_secret.as_bytes()))
+        .unwrap_or(false)
+}
+
+// 타이밍 공격 방지를 위한 고정 시간 비교
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// 프록시 통합 응답 형식 (body 는 JSON 문자열)
+fn http_response(status_code: u16, body: &serde_json::Value) -> serde_json::Value {
+    json!({
+        "statusCode": status_code,
+        "headers": { "content-type": "application/json" },
+        "body": body.to_string(),
+    })
+}
+
+// 쿼리 스트링의 sub_region_id 하나만 수집 후 StationResult 반환
+async fn handle_http_request(
+    state: Arc<ServerState>,
+    run_id: &str,
+    payload: &serde_json::Value,
+) -> serde_json::Value {
+    let sub_region_id = match payload
+        .get("queryStringParameters")
+        .and_then(|params| params.get("sub_region_id"))
+        .and_then(|v| v.as_str())
+        .and_then(|v| v.parse::<i32>().ok())
+    {
+        Some(sub_region_id) => sub_region_id,
+        None => {
+            return http_response(
+                400,
+                &json!({ "message": message(MessageKey::MissingSubRegionId) }),
+            );
+        }
+    };
+
+    let options = FetchOptions {
+        sub_region_ids: Some(vec![sub_region_id]),
+        run_id: Some(run_id.to_owned()),
+        ..Default::default()
+    };
+
+    match run_ingest(state.clone(), &options).await {
+        Ok(report) => match report.results.into_iter().next() {
+            Some(result) => {
+                let status_code = match result.status {
+                    _ if report.db_read_only => 503,
+                    StationStatus::Success(_) => 200,
+                    StationStatus::Failed { .. } => 502,
+                };
+                http_response(status_code, &result.to_json())
+            }
+            None => http_response(
+                404,
+                &json!({ "message": message_with(MessageKey::SubRegionNotFound, sub_region_id) }),
+            ),
+        },
+        Err(e) if e.downcast_ref::<InvalidServiceKeyError>().is_some() => {
+            error!("Handler failed: {:?}", e);
+            refresh_rotated_service_key(&state).await;
+            http_response(
+                401,
+                &json!({ "message": message(MessageKey::InvalidServiceKey) }),
+            )
+        }
+        Err(e) => {
+            error!("Handler failed: {:?}", e);
+            match e.downcast_ref::<PoolError>() {
+                Some(pool_error) => {
+                    let (status_code, pool_message) = pool_error_status(pool_error);
+                    http_response(status_code, &json!({ "message": pool_message }))
+                }
+                None => http_response(
+                    500,
+                    &json!({ "message": message(MessageKey::InternalServerError) }),
+                ),
+            }
+        }
+    }
+}
+
+// 레코드에 속한 측정소 중 하나라도 재시도 가능한 실패가 있으면 레코드 실패
+pub fn is_record_failed(results: &[StationResult]) -> bool {
+    results.iter().any(StationResult::is_retriable_failure)
+}
+
+// SQS 레코드별 수집 실행 후 batchItemFailures 목록 구성
+async fn handle_sqs_records(
+    state: Arc<ServerState>,
+    run_id: &str,
+    records: &[SqsRecord],
+) -> Vec<serde_json::Value> {
+    let mut batch_item_failures = Vec::new();
+
+    for record in records {
+        let message_id = record.message_id.as_str();
+        let record_failed = match record.parse_body() {
+            Ok(SqsBody { sub_region_ids }) => {
+                let options = FetchOptions {
+                    sub_region_ids: Some(sub_region_ids),
+                    run_id: Some(run_id.to_owned()),
+                    ..Default::default()
+                };
+                match run_ingest(state.clone(), &options).await {
+                    // 읽기 전용 DB 로 저장하지 못한 레코드는 재시도
+                    Ok(report) => report.db_read_only || is_record_failed(&report.results),
+                    Err(e) => {
+                        error!("{} : SQS 레코드 처리 실패: {:?}", message_id, e);
+                        true
+                    }
+                }
+            }
+            Err(e) => {
+                error!("{} : SQS 메시지 본문 파싱 실패: {:?}", message_id, e);
+                true
+            }
+        };
+
+        if record_failed {
+            batch_item_failures.push(json!({ "itemIdentifier": message_id }));
+        }
+    }
+
+    batch_item_failures
+}
+
+// 실시간 수집 실행 (Lambda / combined / axum / CLI 공통)
+// 출력(SNS / Firehose) 발행과 최신값 캐시 기록까지 마친 결과를 반환하며, 응답 형식 변환은 호출하는 쪽에서 처리
+pub async fn run_realtime_ingest(
+    state: Arc<ServerState>,
+    options: &FetchOptions,
+) -> Result<IngestReport, anyhow::Error> {
+    let mut report = run_ingest(state.clone(), options).await?;
+    let report_start = tokio::time::Instant::now();
+
+    // 스트림 발행 실패는 수집 실패가 아니므로 warnings 로만 기록
+    if !options.dry_run && !report.db_read_only {
+        report
+            .warnings
+            .extend(sink::publish_from_env(&report).await);
+    }
+    if report.data_frozen {
+        report.warnings.push(DATA_FROZEN_WARNING.to_owned());
+    }
+
+    // 저장된 최신 값을 Redis 에도 기록 (REDIS_URL 설정 시, 실패는 건수만 meta 에 기록)
+    if !options.dry_run {
+        report.latest_cache_failures = state.latest_cache.write_latest(&report).await;
+    }
+
+    // 출력(SNS / Firehose / Redis)은 한글 이름으로 마친 뒤 응답의 측정소 이름만 영문으로 교체
+    if options.locale == Locale::En {
+        match state.pool.get().await {
+            Ok(db_client) => {
+                let names_en = load_station_names_en(&db_client).await;
+                localize_station_names(&mut report, &names_en);
+            }
+            Err(e) => warn!("측정소 영문 이름 조회용 커넥션 획득 실패: {:?}", e),
+        }
+    }
+    report
+        .phases
+        .record(phases::REPORT_PHASE, report_start.elapsed());
+
+    Ok(report)
+}
+
+// 측정소별 결과를 data/meta 응답 본문으로 변환
+pub fn build_response_body(report: IngestReport) -> serde_json::Value {
+    let IngestReport {
+        run_id,
+        results,
+        skipped_fresh,
+        deferred,
+        cache_hits,
+        db_read_only,
+        no_sub_regions,
+        advanced,
+        data_frozen,
+        elapsed,
+        warnings,
+        latest_cache_failures,
+        disabled_sub_regions,
+        diagnostics,
+        phases,
+        station_list_source,
+        quota,
+    } = report;
+    let mut response_data = Vec::new();
+    let mut error_list = Vec::new();
+    let retried_checkouts = results.iter().filter(|r| r.checkout_retries > 0).count();
+    let buffered_count = results.iter().filter(|r| r.buffered).count();
+    let rejected_values: u32 = results.iter().map(|r| r.rejected_values).sum();
+    let recovered_upserts = results
+        .iter()
+        .filter(|r| r.upsert_retries > 0 && matches!(r.status, StationStatus::Success(_)))
+        .count();
+
+    // 측정소/좌표 미설정 sub_region (데이터 담당자가 수정할 수 있도록 별도 표시)
+    let mut unconfigured_sub_regions = Vec::new();
+    // pm_station 이 빈 문자열인 sub_region
+    let mut unmapped_stations = Vec::new();
+    let mut errors = Vec::new();
+    // 오류 메시지 누적 크기 제한 (MAX_ERROR_BYTES), 초과분은 개수만 표시
+    let mut error_budget = ErrorBudget::from_env();
+
+    for result in results {
+        let error = result.error_json();
+        match result.status {
+            StationStatus::Success(data) => response_data.push(data),
+            StationStatus::Failed { kind, message } => {
+                match kind {
+                    FailureKind::StationConfig => {
+                        unconfigured_sub_regions.push(result.sub_region_id)
+                    }
+                    FailureKind::UnmappedStation => unmapped_stations.push(result.sub_region_id),
+                    _ => {}
+                }
+                if error_budget.admit(&message) {
+                    error_list.push(message);
+                    errors.extend(error);
+                }
+            }
+        }
+    }
+    if let Some(overflow_message) = error_budget.overflow_message() {
+        error_list.push(overflow_message);
+    }
+
+    let outcome = if db_read_only {
+        OUTCOME_DB_READ_ONLY
+    } else if no_sub_regions {
+        OUTCOME_NO_SUB_REGIONS
+    } else {
+        OUTCOME_OK
+    };
+    let meta_message = if no_sub_regions {
+        message(MessageKey::NoSubRegions)
+    } else {
+        message_with(MessageKey::Success, response_data.len())
+    };
+
+    // 최종 응답 구성
+    let mut body = json!({
+        "data": response_data,
+        "meta": {
+            "runId": run_id,
+            "outcome": outcome,
+            "message": meta_message,
+            "errorList": error_list,
+            "errors": errors,
+            "errorsTruncated": error_budget.truncated(),
+            "unconfiguredSubRegions": unconfigured_sub_regions,
+            "unmappedStations": unmapped_stations,
+            "skippedFresh": skipped_fresh,
+            "deferred": deferred,
+            "cacheHits": cache_hits,
+            "advancedCount": advanced,
+            "dataFrozen": data_frozen,
+            "retriedCheckouts": retried_checkouts,
+            "recoveredUpserts": recovered_upserts,
+            "bufferedCount": buffered_count,
+            "rejectedValues": rejected_values,
+            "warnings": warnings,
+            "latestCacheFailures": latest_cache_failures,
+            "disabledSubRegions": disabled_sub_regions,
+            "elapsedMs": elapsed.as_millis() as u64,
+            "phases": phases.to_json(),
+            "stationListSource": station_list_source.as_str(),
+        }
+    });
+    // 일일 API 한도를 추적하는 실행에서만 포함
+    if let (Some(quota), Some(meta)) = (quota, body["meta"].as_object_mut()) {
+        if let serde_json::Value::Object(fields) = quota.to_json() {
+            meta.extend(fields);
+        }
+    }
+    // diagnostics 실행에서만 포함
+    if let Some(diagnostics) = diagnostics {
+        body["diagnostics"] = diagnostics.iter().map(StationDiagnostic::to_json).collect();
+    }
+    body
+}
+
+// 측정소 데이터 제공 현황
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Coverage {
+    pub total: usize,
+    // PM10 / PM2.5 중 하나 이상 값이 있는 측정소
+    pub valid: usize,
+    // 응답은 정상이지만 측정값이 없는 측정소 (점검 중, 데이터 없음)
+    pub missing: usize,
+    // 조회 실패 (요청/응답 오류, 측정소 설정 오류 등)
+    pub invalid: usize,
+}
+
+impl Coverage {
+    pub fn from_results(results: &[StationResult]) -> Self {
+        let mut coverage = Coverage {
+            total: results.len(),
+            ..Default::default()
+        };
+        for result in results {
+            match &result.status {
+                StationStatus::Success(data)
+                    if data["pm10Value"].is_null() && data["pm25Value"].is_null() =>
+                {
+                    coverage.missing += 1
+                }
+                StationStatus::Success(_) => coverage.valid += 1,
+                StationStatus::Failed {
+                    kind: FailureKind::NoData,
+                    ..
+                } => coverage.missing += 1,
+                StationStatus::Failed { .. } => coverage.invalid += 1,
+            }
+        }
+        coverage
+    }
+}
+
+// coverage 리포트 응답 본문 (측정소별 데이터 없이 집계만 반환)
+pub fn build_coverage_body(report: &IngestReport) -> serde_json::Value {
+    let coverage = Coverage::from_results(&report.results);
+    json!({
+        "meta": {
+            "runId": report.run_id,
+            "coverage": {
+                "total": coverage.total,
+                "valid": coverage.valid,
+                "missing": coverage.missing,
+                "invalid": coverage.invalid,
+            },
+            "deferred": report.deferred,
+        }
+    })
+}
+
+// 측정소 목록 조회 후 측정소별 외부 API 호출 및 upsert 실행
+pub async fn run_ingest(
+    state: Arc<ServerState>,
+    options: &FetchOptions,
+) -> Result<IngestReport, anyhow::Error> {
+    let run_id = options.run_id.clone().unwrap_or_else(new_run_id);
+
+    // payload 측정소 목록으로 저장 없이 실행하면 DB 에 접근하지 않음
+    if options.dry_run && options.inline_stations.is_some() {
+        return ingest_stations(state, options, run_id, None).await;
+    }
+
+    // 데이터베이스에서 필요한 정보 조회 (모든 측정소 ID 및 이름 가져오기)
+    // 풀 오류는 PoolError 그대로 반환하여 handle_event 에서 503/500 으로 구분
+    let checkout_start = tokio::time::Instant::now();
+    let db_client: DbClient = match state.pool.get().await {
+        Ok(client) => client,
+        Err(e) => {
+            error!("측정소 목록 조회용 DB 커넥션 획득 실패: {:?}", e);
+            return Err(anyhow::Error::new(e));
+        }
+    };
+    init_timing::log_first(
+        &init_timing::FIRST_CHECKOUT,
+        "first_checkout",
+        checkout_start.elapsed(),
+    );
+
+    if !options.run_lock {
+        return ingest_stations(state, options, run_id, Some(&db_client)).await;
+    }
+
+    // 다른 실행이 진행 중이면 API 호출/upsert 없이 ALREADY_RUNNING 으로 종료
+    // 실행이 중간에 취소되면 guard 가 커넥션을 닫아 락 해제
+    let lock = run_lock::acquire(db_client, &run_id).await?;
+
+    let result = ingest_stations(state, options, run_id, Some(lock.client())).await;
+    lock.release().await;
+    result
+}
+
+// 측정소 목록 조회 후 측정소별 수집 (db_client 는 목록/측정 시각 조회용)
+// db_client 가 None 이면 payload 측정소 목록의 dry-run (DB 조회 / 저장 없음)
+async fn ingest_stations(
+    state: Arc<ServerState>,
+    options: &FetchOptions,
+    run_id: String,
+    db_client: Option<&DbClient>,
+) -> Result<IngestReport, anyhow::Error> {
+    let start = tokio::time::Instant::now();
+    // 실행 전체에서 같은 현재 시각 사용
+    let now = state.clock.now();
+    let mut phases = Phases::default();
+
+    // 측정소 목록: payload 의 stations, 없으면 sub_region 조회
+    let (sub_regions, station_list_source): (Vec<Result<SubRegionInfo, RowMapError>>, _) =
+        match (&options.inline_stations, db_client) {
+            (Some(inline_stations), _) => (
+                inline_stations
+                    .iter()
+                    .map(|station| Ok(station.to_sub_region()))
+                    .collect(),
+                StationListSource::Payload,
+            ),
+            (None, Some(db_client)) => {
+                let rows = state
+                    .sub_region_queries
+                    .fetch_rows(
+                        db_client,
+                        options.sub_region_ids.as_ref(),
+                        options.include_disabled,
+                    )
+                    .await?;
+                phases.record(phases::STATION_LIST_QUERY, start.elapsed());
+                init_timing::log_first(&init_timing::FIRST_QUERY, "first_query", start.elapsed());
+                (
+                    rows.iter().map(SubRegionInfo::try_from_row).collect(),
+                    StationListSource::Db,
+                )
+            }
+            (None, None) => return Err(anyhow::anyhow!("측정소 목록 없이 DB 접근 없는 수집 불가")),
+        };
+    let sub_region_count = sub_regions.len();
+
+    // 수집 중지된 sub_region 수 (meta.disabledSubRegions, payload 목록이면 확인하지 않음)
+    let disabled_sub_regions = match db_client {
+        Some(db_client)
+            if !options.include_disabled && station_list_source == StationListSource::Db =>
+        {
+            state.sub_region_queries.count_disabled(db_client).await
+        }
+        _ => None,
+    };
+
+    // 전체 수집에서 sub_region 이 없으면 성공(SUCCESS: 0)이 아닌 설정 오류로 구분
+    let no_sub_regions = sub_region_count == 0 && options.sub_region_ids.is_none();
+    if no_sub_regions {
+        warn!("sub_region 목록이 비어 있음: 수집할 측정소 없음");
+    }
+
+    // 증분 수집: 측정 시각이 cutoff 이후인 측정소는 API 호출 생략
+    let fresh_cutoff = options
+        .refresh_older_than
+        .map(|older_than| now - older_than);
+    let last_seen_store = LastSeenStore::from_env();
+    let last_recorded_at = match db_client {
+        Some(db_client)
+            if fresh_cutoff.is_some()
+                || max_stations_per_run().is_some()
+                || last_seen_store == LastSeenStore::Db
+                || api_quota::daily_quota().is_some() =>
+        {
+            fetch_last_recorded_at(db_client).await?
+        }
+        _ => HashMap::new(),
+    };
+    let mut skipped_fresh = 0;
+
+    // 연속 NO_DATA 카운터: 운영자 초기화 후, 조회를 생략할 측정소 확인 (NODATA_SUSPEND_SKIP)
+    if let (Some(db_client), Some(reset_ids)) = (
+        db_client,
+        options.reset_nodata.as_ref().filter(|_| !options.dry_run),
+    ) {
+        nodata::reset(db_client, reset_ids).await;
+    }
+    let nodata_threshold = nodata::suspend_threshold();
+    let nodata_counts = match db_client {
+        Some(db_client) if nodata::skip_suspected() => nodata::load_counts(db_client).await,
+        _ => HashMap::new(),
+    };
+
+    // 동시성 제어를 위한 세마포어 설정 (API 조회와 DB 쓰기는 별도 제한)
+    let semaphore = options.semaphore();
+    // 상위 API 가 429 를 보내면 남은 실행 동안 퍼밋을 줄임
+    let api_budget = ApiBudget::new(semaphore.clone(), api_concurrency());
+    let mut run = StationRun::new(
+        options.dry_run,
+        db_write_concurrency(state.pool.status().max_size)?,
+        now,
+    );
+    if options.atomic && !options.dry_run {
+        run = run.with_staged_writes();
+    }
+    if options.diagnostics {
+        run = run.with_diagnostics();
+    }
+    let http_client = options.http_client();
+    let per_station_timeout = per_station_timeout();
+
+    // 제공처 (sub_region.provider 로 선택)
+    let mut airkorea = AirKoreaProvider::new(state.api_client.clone(), state.air_quality_api_key())
+        .with_source_offset(state.source_offset);
+
+    // 시도별 수집: 측정소마다 호출하지 않고 시도 전체를 한 번에 조회
+    if let Some(sido_name) = &options.sido_name {
+        // 한도 추적에 포함되도록 측정소 조회와 같은 예산 범위에서 요청
+        let province_readings =
+            rate_limit::scope(api_budget.clone(), airkorea.fetch_province(sido_name))
+                .await
+                .map_err(|e| {
+                    if e.kind == FailureKind::InvalidServiceKey {
+                        anyhow::Error::new(InvalidServiceKeyError)
+                    } else {
+                        anyhow::anyhow!(e.message)
+                    }
+                })?;
+        info!(
+            "{} : fetched {} stations in one province request",
+            sido_name,
+            province_readings.len()
+        );
+        airkorea = airkorea.with_province_readings(province_readings);
+    }
+    let openaq = OpenAqProvider::new(
+        http_client,
+        state.openaq_api_key.clone(),
+        state.clock.clone(),
+    );
+
+    let mut results = Vec::new();
+    let mut candidates = Vec::new();
+
+    for (index, sub_region) in sub_regions.into_iter().enumerate() {
+        // 잘못된 타입/NULL 컬럼은 패닉 대신 해당 행만 건너뛰고 오류로 기록
+        let SubRegionInfo {
+            sub_region_id,
+            pm_station,
+            tm_x,
+            tm_y,
+            provider: provider_key,
+            blank_station,
+            ..
+        } = match sub_region {
+            Ok(sub_region) => sub_region,
+            Err(e) => {
+                let error_message = format!("sub_region row {} : {}", index, e.message);
+                results.push(StationResult::failed(
+                    e.sub_region_id.unwrap_or_default(),
+                    "",
+                    FailureKind::RowMapping,
+                    error_message,
+                ));
+                continue;
+            }
+        };
+
+        if let Some(cutoff) = fresh_cutoff {
+            if last_recorded_at
+                .get(&sub_region_id)
+                .is_some_and(|recorded_at| *recorded_at > cutoff)
+            {
+                skipped_fresh += 1;
+                continue;
+            }
+        }
+
+        // --station 지정 시 해당 측정소만 수집
+        if let Some(stations) = &options.stations {
+            if !pm_station
+                .as_ref()
+                .is_some_and(|pm_station| stations.contains(pm_station))
+            {
+                continue;
+            }
+        }
+
+        // 시도별 수집: 시도 응답에 없는 측정소(다른 시도, 좌표만 설정된 sub_region)는 제외
+        // 쉼표로 구분한 여러 측정소면 하나라도 있으면 대상
+        if let Some(province_readings) = airkorea.province_readings() {
+            if provider_key != airkorea.provider_key()
+                || !pm_station.as_ref().is_some_and(|pm_station| {
+                    split_station_refs(pm_station)
+                        .iter()
+                        .any(|station_ref| province_readings.contains_key(*station_ref))
+                })
+            {
+                continue;
+            }
+        }
+
+        // 측정소 이름 오류가 의심되는 sub_region 은 카운터를 초기화할 때까지 조회 생략
+        if let Some(&count) = nodata_counts
+            .get(&sub_region_id)
+            .filter(|count| **count > nodata_threshold)
+        {
+            results.push(nodata::skipped_result(
+                sub_region_id,
+                pm_station.as_deref().unwrap_or(""),
+                count,
+            ));
+            continue;
+        }
+
+        // 측정소 이름도 TM 좌표도 없으면 API 호출 없이 미설정 sub_region 으로 기록
+        let station_source = match (pm_station, tm_x.zip(tm_y)) {
+            (Some(pm_station), _) => StationSource::Name(pm_station),
+            (None, Some((tm_x, tm_y))) => StationSource::Coordinates(tm_x, tm_y),
+            // 빈 문자열 측정소는 매핑 누락으로 별도 기록 (항상 "no data" 로 실패하므로 API 호출 생략)
+            (None, None) if blank_station => {
+                let error_message = format!(
+                    "sub_region {} : pm_station is empty and tm coordinates are not configured",
+                    sub_region_id
+                );
+                results.push(StationResult::failed(
+                    sub_region_id,
+                    "",
+                    FailureKind::UnmappedStation,
+                    error_message,
+                ));
+                continue;
+            }
+            (None, None) => {
+                let error_message = format!(
+                    "sub_region {} : pm_station and tm coordinates are not configured",
+                    sub_region_id
+                );
+                results.push(StationResult::failed(
+                    sub_region_id,
+                    "",
+                    FailureKind::StationConfig,
+                    error_message,
+                ));
+                continue;
+            }
+        };
+
+        candidates.push(StationCandidate {
+            sub_region_id,
+            provider_key,
+            station_source,
+        });
+    }
+
+    // 일일 API 한도: 오늘 사용량 확인 (테이블이 없으면 추적하지 않음)
+    let quota_key = api_quota::key_hash(&state.air_quality_api_key());
+    let quota_date = api_quota::quota_date(now);
+    let daily_quota = api_quota::daily_quota();
+    let quota_used = match db_client {
+        Some(db_client) => api_quota::load_used(db_client, &quota_key, quota_date).await,
+        None => None,
+    };
+    let quota_limit = quota_used.and_then(|used| api_quota::station_limit(used, daily_quota));
+
+    // MAX_STATIONS_PER_RUN / 남은 API 한도: 오래된 측정소부터 선택하고 나머지는 다음 실행으로 미룸
+    let station_limit = match (max_stations_per_run(), quota_limit) {
+        (Some(max), Some(limit)) => Some(max.min(limit)),
+        (max, limit) => max.or(limit),
+    };
+    let candidate_count = candidates.len();
+    let (selected, deferred) = select_stale_first(candidates, &last_recorded_at, station_limit);
+    let quota_deferred = quota_limit.map_or(0, |limit| {
+        deferred.min(candidate_count.saturating_sub(limit))
+    });
+    let mut warnings = Vec::new();
+    if quota_deferred > 0 {
+        let warning = format!(
+            "API daily quota nearly exhausted ({} of {} used), deferred {} stations",
+            quota_used.unwrap_or_default(),
+            daily_quota.unwrap_or_default(),
+            quota_deferred
+        );
+        warn!("{} : {}", run_id, warning);
+        warnings.push(warning);
+    }
+
+    // 측정소 future 는 태스크로 만들지 않고 station_stream 에서 실행
+    let state = &state;
+    let airkorea = &airkorea;
+    let openaq = &openaq;
+    let run = &run;
+    let semaphore = &semaphore;
+    let api_budget = &api_budget;
+    let mut stations = station_stream(selected.into_iter().map(
+        |StationCandidate {
+             sub_region_id,
+             provider_key,
+             station_source,
+         }| {
+            let station_label = match &station_source {
+                StationSource::Name(pm_station) => pm_station.clone(),
+                StationSource::Coordinates(..) => String::new(),
+            };
+            let task_label = station_label.clone();
+
+            let station_task = async move {
+                // API 호출 퍼밋 획득 (조회가 끝나면 process_station 에서 반납)
+                let api_permit =
+                    match acquire_permit(semaphore.clone(), sub_region_id, &station_label).await {
+                        Ok(permit) => permit,
+                        Err(result) => return result,
+                    };
+
+                // 측정소 하나가 전체 실행 시간을 잡아먹지 않도록 측정소별 제한 시간 적용
+                with_station_deadline(
+                    sub_region_id,
+                    &station_label,
+                    options.station_timeout(per_station_timeout),
+                    async move {
+                        // pm_station 이 없으면 TM 좌표로 근접 측정소 조회
+                        let pm_station = match station_source {
+                            StationSource::Name(pm_station) => pm_station,
+                            StationSource::Coordinates(tm_x, tm_y) => {
+                                match resolve_nearby_station(state, sub_region_id, tm_x, tm_y).await
+                                {
+                                    Ok(pm_station) => pm_station,
+                                    Err(e) => {
+                                        let error_message = format!(
+                                        "sub_region {} : Failed to resolve nearby station: {:?}",
+                                        sub_region_id, e
+                                    );
+                                        return StationResult::failed(
+                                            sub_region_id,
+                                            "",
+                                            FailureKind::NearbyStation,
+                                            error_message,
+                                        );
+                                    }
+                                }
+                            }
+                        };
+
+                        if provider_key == airkorea.provider_key() {
+                            process_station(
+                                state,
+                                airkorea,
+                                run,
+                                sub_region_id,
+                                &pm_station,
+                                api_permit,
+                            )
+                            .await
+                        } else if provider_key == openaq.provider_key() {
+                            process_station(
+                                state,
+                                openaq,
+                                run,
+                                sub_region_id,
+                                &pm_station,
+                                api_permit,
+                            )
+                            .await
+                        } else {
+                            let error_message =
+                                format!("{} : Unknown provider: {}", pm_station, provider_key);
+                            StationResult::failed(
+                                sub_region_id,
+                                &pm_station,
+                                FailureKind::StationConfig,
+                                error_message,
+                            )
+                        }
+                    },
+                )
+                .await
+            };
+
+            (
+                sub_region_id,
+                task_label,
+                rate_limit::scope(api_budget.clone(), station_task),
+            )
+        },
+    ));
+
+    // 완료되는 순서대로 결과 수집
+    let fetch_start = tokio::time::Instant::now();
+    while let Some(result) = stations.next().await {
+        // 측정소마다 같은 오류를 반복하지 않고 실행 전체를 단일 오류로 종료 (남은 측정소는 스트림과 함께 취소)
+        if result.failure_kind() == Some(FailureKind::InvalidServiceKey) {
+            return Err(anyhow::Error::new(InvalidServiceKeyError));
+        }
+        // 스트리밍 응답: 완료된 측정소 결과를 순서대로 바로 전달 (수신 측이 끊겨도 수집은 계속)
+        if let Some(progress) = &options.progress {
+            let _ = progress.send(result.clone());
+        }
+        results.push(result);
+    }
+    phases.record(phases::FETCH_PHASE, fetch_start.elapsed());
+
+    // atomic: 측정소별로 모아 둔 측정값을 한 트랜잭션으로 저장
+    let write_start = tokio::time::Instant::now();
+    if let (Some(staged_writes), Some(db_client)) = (&run.staged_writes, db_client) {
+        let writes = std::mem::take(&mut *staged_writes.lock().unwrap_or_else(|e| e.into_inner()));
+        commit_staged_writes(db_client, writes, &mut results).await?;
+    }
+
+    // 연속 NO_DATA 카운터 갱신 (임계값을 넘은 측정소는 SUSPECTED_INVALID_STATION 으로 표시)
+    if let Some(db_client) = db_client.filter(|_| !options.dry_run && !run.is_db_read_only()) {
+        nodata::update_counts(db_client, &mut results, nodata_threshold).await;
+    }
+    phases.record(phases::WRITE_PHASE, write_start.elapsed());
+
+    // 실행에서 보낸 요청 수를 오늘 사용량에 더함 (dry-run 도 한도를 쓰므로 기록, 읽기 전용 DB 는 기록 불가)
+    let run_requests = i64::try_from(api_budget.requests()).unwrap_or(i64::MAX);
+    let quota = match (db_client, quota_used) {
+        (Some(db_client), Some(used)) if !run.is_db_read_only() => {
+            let used_today = if run_requests > 0 {
+                api_quota::add_usage(db_client, &quota_key, quota_date, run_requests)
+                    .await
+                    .unwrap_or(used + run_requests)
+            } else {
+                used
+            };
+            Some(QuotaUsage {
+                used_today,
+                daily_quota,
+                run_requests,
+                deferred: quota_deferred,
+            })
+        }
+        _ => None,
+    };
+
+    for result in &results {
+        metrics::record_station_result(result);
+    }
+
+    // diagnostics: 측정소 전체를 EMF 로 남기고 응답에는 느린 측정소만
+    let diagnostics = run.diagnostics.as_ref().map(|diagnostics| {
+        let diagnostics =
+            std::mem::take(&mut *diagnostics.lock().unwrap_or_else(|e| e.into_inner()));
+        diagnostics::emit_emf(&diagnostics);
+        diagnostics::slowest_first(diagnostics)
+    });
+
+    // 이전 측정 시각 대비 갱신된 측정소 수
+    let advanced = match last_seen_store {
+        LastSeenStore::Db => Some(last_seen::count_advanced(&results, &last_recorded_at)),
+        LastSeenStore::Memory => {
+            let advanced = last_seen::count_advanced(&results, &last_seen::memory_snapshot());
+            last_seen::remember(&results);
+            Some(advanced)
+        }
+        LastSeenStore::Off => None,
+    };
+    let full_sweep = options.sub_region_ids.is_none()
+        && options.stations.is_none()
+        && options.inline_stations.is_none();
+    let succeeded = results
+        .iter()
+        .any(|result| matches!(result.status, StationStatus::Success(_)));
+    let data_frozen = full_sweep && succeeded && advanced == Some(0);
+    if data_frozen {
+        warn!(
+            "{} : 전체 수집에서 측정 시각이 갱신된 측정소 없음 (dataFrozen)",
+            run_id
+        );
+    }
+
+    Ok(IngestReport {
+        run_id,
+        results,
+        skipped_fresh,
+        deferred,
+        cache_hits: run.reading_cache.hits(),
+        db_read_only: run.is_db_read_only(),
+        no_sub_regions,
+        advanced,
+        data_frozen,
+        elapsed: start.elapsed(),
+        warnings,
+        latest_cache_failures: 0,
+        disabled_sub_regions,
+        diagnostics,
+        phases,
+        station_list_source,
+        quota,
+    })
+}
+
+// 모아 둔 측정값을 한 트랜잭션으로 upsert (하나라도 실패하면 전부 롤백하고 해당 측정소들을 실패로 기록)
+// 측정소 순서에 따른 잠금 경합을 줄이도록 sub_region_id 순으로 저장
+// 쿼리 하나의 파라미터 / 잠금 범위가 커지지 않도록 PM_UPSERT_BATCH_SIZE 개씩 나누어 순서대로 실행
+// (같은 트랜잭션의 쿼리는 한 커넥션에서 차례로만 실행 가능)
+async fn commit_staged_writes(
+    db_client: &DbClient,
+    mut writes: Vec<StagedWrite>,
+    results: &mut [StationResult],
+) -> Result<(), anyhow::Error> {
+    if writes.is_empty() {
+        return Ok(());
+    }
+    writes.sort_by_key(|write| write.sub_region_id);
+
+    let upsert_query = sql(BATCH_UPSERT_EXTERNAL_PM_QUERY);
+    let batch_size = upsert_batch_size();
+    let batch_count = writes.len().div_ceil(batch_size);
+    db_client.batch_execute("BEGIN").await?;
+
+    let mut outcomes = Vec::new();
+    let mut failure = None;
+    for (batch_index, batch) in writes.chunks(batch_size).enumerate() {
+        match upsert_batch(db_client, upsert_query.as_str(), batch).await {
+            Ok(batch_outcomes) => outcomes.extend(batch_outcomes),
+            Err(e) => {
+                failure = Some((batch_index, batch, e));
+                break;
+            }
+        }
+    }
+
+    let Some((batch_index, failed_batch, e)) = failure else {
+        db_client.batch_execute("COMMIT").await?;
+        apply_write_outcomes(outcomes, results);
+        info!(
+            "Committed {} upserts in one transaction ({} batches)",
+            writes.len(),
+            batch_count
+        );
+        return Ok(());
+    };
+
+    db_client.batch_execute("ROLLBACK").await?;
+    let kind = classify_db_error(&e);
+    let error_message = format!(
+        "Atomic upsert failed in batch {}/{} ({} ~ {}), all {} writes rolled back: {}",
+        batch_index + 1,
+        batch_count,
+        failed_batch[0].pm_station,
+        failed_batch[failed_batch.len() - 1].pm_station,
+        writes.len(),
+        describe_db_error(&e)
+    );
+    error!("{}", error_message);
+    for result in results.iter_mut() {
+        let staged = writes
+            .iter()
+            .any(|write| write.sub_region_id == result.sub_region_id);
+        if staged && matches!(result.status, StationStatus::Success(_)) {
+            let rolled_back_message =
+                format!("{} : rolled back ({})", result.pm_station, error_message);
+            *result = StationResult::failed(
+                result.sub_region_id,
+                &result.pm_station,
+                kind,
+                rolled_back_message,
+            );
+        }
+    }
+    Ok(())
+}
+
+// 커밋된 batch 결과를 측정소 결과에 반영
+// 갱신하지 않은(SkippedStale) 측정소는 수집한 값 그대로 두고, 행을 읽지 못한 측정소만 ROW_MAPPING 실패로 기록
+fn apply_write_outcomes(outcomes: Vec<StationWriteOutcome>, results: &mut [StationResult]) {
+    let mut by_sub_region: HashMap<i32, StationWriteOutcome> = HashMap::new();
+    for outcome in outcomes {
+        match outcome {
+            StationWriteOutcome::Written { sub_region_id, .. }
+            | StationWriteOutcome::SkippedStale { sub_region_id }
+            | StationWriteOutcome::RowError { sub_region_id, .. } => {
+                by_sub_region.insert(sub_region_id, outcome);
+            }
+            StationWriteOutcome::Unexpected {
+                sub_region_id,
+                message,
+            } => warn!(
+                "Unexpected upsert row (sub_region_id {:?}): {}",
+                sub_region_id, message
+            ),
+        }
+    }
+
+    for result in results.iter_mut() {
+        match by_sub_region.remove(&result.sub_region_id) {
+            Some(StationWriteOutcome::Written { data, .. }) => {
+                result.status = StationStatus::Success(data);
+            }
+            Some(StationWriteOutcome::SkippedStale { sub_region_id }) => {
+                debug!(
+                    "sub_region {} not updated, stored reading is newer",
+                    sub_region_id
+                );
+            }
+            Some(StationWriteOutcome::RowError { message, .. }) => {
+                *result = StationResult::failed(
+                    result.sub_region_id,
+                    &result.pm_station,
+                    FailureKind::RowMapping,
+                    message,
+                )
+                .with_checkout_retries(result.checkout_retries)
+                .with_upsert_retries(result.upsert_retries);
+            }
+            Some(StationWriteOutcome::Unexpected { .. }) | None => {}
+        }
+    }
+}
+
+// 측정값 batch 하나를 upsert 하고 RETURNING 행을 측정소별 결과로 대조
+async fn upsert_batch(
+    db_client: &DbClient,
+    upsert_query: &str,
+    batch: &[StagedWrite],
+) -> Result<Vec<StationWriteOutcome>, tokio_postgres::Error> {
+    let sub_region_ids: Vec<i32> = batch.iter().map(|write| write.sub_region_id).collect();
+    let pm10: Vec<Option<f64>> = batch.iter().map(|write| write.reading.pm10).collect();
+    let pm25: Vec<Option<f64>> = batch.iter().map(|write| write.reading.pm25).collect();
+    let recorded_at: Vec<DateTime<Utc>> = batch
+        .iter()
+        .map(|write| write.reading.recorded_at)
+        .collect();
+
+    let rows = db_client
+        .query(upsert_query, &[&sub_region_ids, &pm10, &pm25, &recorded_at])
+        .await?;
+    Ok(reconcile_batch_result(batch, UpsertedRow::from_rows(&rows)))
+}
+
+// 수집 대상 측정소
+pub struct StationCandidate {
+    pub sub_region_id: i32,
+    pub provider_key: String,
+    station_source: StationSource,
+}
+
+// MAX_STATIONS_PER_RUN 환경 변수 (미설정 시 제한 없음)
+pub fn max_stations_per_run() -> Option<usize> {
+    std::env::var("MAX_STATIONS_PER_RUN")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+}
+
+// 저장된 측정 시각이 오래된 순(없으면 가장 먼저)으로 최대 max 개 선택, 나머지 개수 반환
+// 처리된 측정소는 측정 시각이 갱신되므로 연속 실행 시 선택 대상이 순환됨
+pub fn select_stale_first(
+    mut candidates: Vec<StationCandidate>,
+    last_recorded_at: &HashMap<i32, DateTime<Utc>>,
+    max: Option<usize>,
+) -> (Vec<StationCandidate>, usize) {
+    let Some(max) = max else {
+        return (candidates, 0);
+    };
+
+    candidates.sort_by_key(|candidate| {
+        (
+            last_recorded_at.get(&candidate.sub_region_id).copied(),
+            candidate.sub_region_id,
+        )
+    });
+
+    let deferred = candidates.len().saturating_sub(max);
+    candidates.truncate(max);
+    (candidates, deferred)
+}
+
+// PM_UPSERT_BATCH_SIZE 환경 변수 (기본 500, atomic 저장의 쿼리당 최대 행 수)
+pub fn upsert_batch_size() -> usize {
+    std::env::var("PM_UPSERT_BATCH_SIZE")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|batch_size| *batch_size > 0)
+        .unwrap_or(DEFAULT_UPSERT_BATCH_SIZE)
+}
+
+// MAX_IN_FLIGHT_TASKS 환경 변수 (동시에 생성해 두는 측정소 태스크 수, 기본 API 동시 호출 수의 2배)
+// API 동시 호출 수보다 작으면 API 퍼밋을 다 쓰지 못하므로 API 동시 호출 수 이상으로 맞춤
+pub(crate) fn max_in_flight_tasks() -> usize {
+    let api_concurrency = api_concurrency();
+    std::env::var("MAX_IN_FLIGHT_TASKS")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(api_concurrency * 2)
+        .max(api_concurrency)
+}
+
+// 측정소 future 를 태스크로 만들지 않고 max_in_flight_tasks 개까지만 이 스트림 안에서 동시에 실행
+// stations 는 순서대로 하나씩 꺼내므로 다음 측정소의 future 는 진행 중인 측정소가 끝나야 생성되어,
+// 측정소가 수천 개여도 메모리가 일정하고, 스트림을 버리면 진행 중인 측정소도 함께 취소됨
+// 측정소 하나의 패닉은 catch_station_panic 으로 실패 결과로 바꿔 실행 전체를 멈추지 않음
+// (제한 시간은 퍼밋 대기를 빼고 적용하도록 호출하는 쪽에서 with_station_deadline 으로 감쌈)
+// 반환 스트림은 Send 를 명시한 BoxStream: run_ingest 처럼 spawn 되는 future 안에서 쓰면
+// impl Stream 의 클로저 수명 추론 때문에 Send 판정이 실패함
+pub(crate) fn station_stream<'a, I, F>(stations: I) -> BoxStream<'a, StationResult>
+where
+    I: IntoIterator<Item = (i32, String, F)>,
+    I::IntoIter: Send + 'a,
+    F: Future<Output = StationResult> + Send + 'a,
+{
+    stream::iter(stations)
+        .map(|(sub_region_id, task_label, station_task)| {
+            catch_station_panic(sub_region_id, task_label, station_task)
+        })
+        .buffer_unordered(max_in_flight_tasks())
+        .boxed()
+}
+
+// 태스크로 분리하지 않으므로 측정소 하나의 패닉은 Internal 실패로 변환
+pub(crate) async fn catch_station_panic<F>(
+    sub_region_id: i32,
+    task_label: String,
+    station_task: F,
+) -> StationResult
+where
+    F: Future<Output = StationResult>,
+{
+    match AssertUnwindSafe(station_task).catch_unwind().await {
+        Ok(result) => result,
+        Err(_) => {
+            let error_message = format!(
+                "sub_region {} {} : Task failed: panicked",
+                sub_region_id, task_label
+            );
+            StationResult::failed(
+                sub_region_id,
+                &task_label,
+                FailureKind::Internal,
+                error_message,
+            )
+        }
+    }
+}
+
+// 세마포어 퍼밋 획득 (세마포어가 닫힌 경우 측정소 실패로 기록)
+pub(crate) async fn acquire_permit(
+    semaphore: Arc<Semaphore>,
+    sub_region_id: i32,
+    pm_station: &str,
+) -> Result<OwnedSemaphorePermit, StationResult> {
+    semaphore.acquire_owned().await.map_err(|e| {
+        let error_message = format!(
+            "{} : Failed to acquire semaphore permit: {:?}",
+            pm_station, e
+        );
+        StationResult::failed(
+            sub_region_id,
+            pm_station,
+            FailureKind::Internal,
+            error_message,
+        )
+    })
+}
+
+// sub_region 별 마지막 측정 시각 (조회할 수 없는 행은 건너뜀)
+async fn fetch_last_recorded_at(
+    db_client: &DbClient,
+) -> Result<HashMap<i32, DateTime<Utc>>, anyhow::Error> {
+    let rows = db_client
+        .query(sql(GET_EXTERNAL_PM_RECORDED_AT_QUERY).as_str(), &[])
+        .await?;
+
+    Ok(rows
+        .iter()
+        .filter_map(|row| {
+            let sub_region_id = row.try_get::<_, i32>("sub_region_id").ok()?;
+            let recorded_at = row
+                .try_get::<_, Option<DateTime<Utc>>>("recorded_at")
+                .ok()??;
+            Some((sub_region_id, recorded_at))
+        })
+        .collect())
+}
+
+// PM_PER_STATION_TIMEOUT_SECS 환경 변수 (기본 30초)
+pub(crate) fn per_station_timeout() -> Duration {
+    let secs = std::env::var("PM_PER_STATION_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_PER_STATION_TIMEOUT_SECS);
+    Duration::from_secs(secs)
+}
+
+// API_CONCURRENCY 환경 변수 (제공처 API 동시 호출 수, 기본 10)
+pub(crate) fn api_concurrency() -> usize {
+    std::env::var("API_CONCURRENCY")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|limit| *limit > 0)
+        .unwrap_or(MAX_CONCURRENT_REQUESTS)
+}
+
+// DB_WRITE_CONCURRENCY 환경 변수 (동시 upsert 수)
+// 미설정 시 기존 동작과 같이 API 동시 호출 수와 풀 최대 크기 중 작은 값,
+// 풀보다 크게 설정하면 남는 퍼밋은 커넥션 대기만 하므로 설정 오류로 처리
+pub(crate) fn db_write_concurrency(pool_max_size: usize) -> Result<usize> {
+    let limit = std::env::var("DB_WRITE_CONCURRENCY")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|limit| *limit > 0);
+    match limit {
+        Some(limit) if limit > pool_max_size => Err(anyhow::anyhow!(
+            "DB_WRITE_CONCURRENCY({}) 가 DB 풀 최대 크기({}) 보다 큽니다",
+            limit,
+            pool_max_size
+        )),
+        Some(limit) => Ok(limit),
+        None => Ok(api_concurrency().min(pool_max_size)),
+    }
+}
+
+// 측정소 단위 작업(HTTP + 파싱 + upsert)에 제한 시간 적용, 초과 시 Timeout 으로 기록
+pub(crate) async fn with_station_deadline<F>(
+    sub_region_id: i32,
+    pm_station: &str,
+    deadline: Duration,
+    station_task: F,
+) -> StationResult
+where
+    F: Future<Output = StationResult>,
+{
+    // 실행 마감이 이미 지났으면 작업을 시작하지 않음 (timeout 은 첫 poll 을 먼저 하므로 빠르게 끝난 호출이 Timeout 을 앞지를 수 있음)
+    let outcome = if deadline.is_zero() {
+        None
+    } else {
+        tokio::time::timeout(deadline, station_task).await.ok()
+    };
+    match outcome {
+        Some(result) => result,
+        None => {
+            let error_message = format!(
+                "{} : Station deadline exceeded ({:?})",
+                pm_station, deadline
+            );
+            StationResult::failed(
+                sub_region_id,
+                pm_station,
+                FailureKind::Timeout,
+                error_message,
+            )
+        }
+    }
+}
+
+// 한 번의 수집 실행에서 측정소 작업들이 공유하는 상태
+#[derive(Clone)]
+struct StationRun {
+    reading_cache: Arc<ReadingCache>,
+    dry_run: bool,
+    // 읽기 전용 DB 가 확인되면 남은 측정소는 조회만 하고 저장하지 않음
+    db_read_only: Arc<AtomicBool>,
+    // API 동시 호출 수와 별도로 동시 upsert 수 제한 (DB_WRITE_CONCURRENCY)
+    db_write_permits: Arc<Semaphore>,
+    // atomic 실행: 측정소별로 저장하지 않고 모아 두었다가 실행 끝에 한 트랜잭션으로 저장
+    staged_writes: Option<Arc<std::sync::Mutex<Vec<StagedWrite>>>>,
+    // diagnostics 실행: 측정소별 상위 API 응답 기록
+    diagnostics: Option<Arc<std::sync::Mutex<Vec<StationDiagnostic>>>>,
+    // 실행 시작 시각 (저장하지 않은 응답의 requestedTime)
+    now: DateTime<Utc>,
+}
+
+// atomic 실행에서 저장을 미룬 측정값
+#[derive(Debug, Clone)]
+pub(crate) struct StagedWrite {
+    pub(crate) sub_region_id: i32,
+    pub(crate) pm_station: String,
+    pub(crate) reading: Reading,
+}
+
+impl StationRun {
+    fn new(dry_run: bool, db_write_concurrency: usize, now: DateTime<Utc>) -> Self {
+        StationRun {
+            reading_cache: Arc::new(ReadingCache::new()),
+            dry_run,
+            db_read_only: Arc::new(AtomicBool::new(false)),
+            db_write_permits: Arc::new(Semaphore::new(db_write_concurrency)),
+            staged_writes: None,
+            diagnostics: None,
+            now,
+        }
+    }
+
+    fn with_staged_writes(mut self) -> Self {
+        self.staged_writes = Some(Arc::new(std::sync::Mutex::new(Vec::new())));
+        self
+    }
+
+    fn with_diagnostics(mut self) -> Self {
+        self.diagnostics = Some(Arc::new(std::sync::Mutex::new(Vec::new())));
+        self
+    }
+
+    fn is_db_read_only(&self) -> bool {
+        self.db_read_only.load(Ordering::Relaxed)
+    }
+}
+
+// 단일 측정소에 대한 제공처 조회 및 upsert
+// api_permit 은 조회가 끝나면 반납하고, upsert 는 DB 쓰기 퍼밋을 따로 받아 수행
+async fn process_station<P: PmProvider>(
+    state: &ServerState,
+    provider: &P,
+    run: &StationRun,
+    sub_region_id: i32,
+    pm_station: &str,
+    api_permit: OwnedSemaphorePermit,
+) -> StationResult {
+    // 제공처 API 호출 및 최신 측정값 파싱
+    let fetch_start = tokio::time::Instant::now();
+    let (fetched, upstream_response) =
+        diagnostics::capture(run.reading_cache.get_or_fetch_all(provider, pm_station)).await;
+    let fetch_elapsed = fetch_start.elapsed();
+    metrics::record_fetch(fetch_elapsed);
+    drop(api_permit);
+
+    if let Some(diagnostics) = &run.diagnostics {
+        diagnostics
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(StationDiagnostic {
+                sub_region_id,
+                pm_station: pm_station.to_owned(),
+                elapsed: fetch_elapsed,
+                response: upstream_response,
+            });
+    }
+
+    let reading = match fetched {
+        Ok(reading) => reading,
+        Err(e) => return StationResult::failed(sub_region_id, pm_station, e.kind, e.message),
+    };
+
+    // 유효 범위 밖이라 NULL 로 저장하는 값의 수 (meta.rejectedValues)
+    let rejected_values = reading.rejected_values;
+    store_reading(state, run, sub_region_id, pm_station, reading)
+        .await
+        .with_rejected_values(rejected_values)
+}
+
+// 조회한 측정값 upsert (dry-run / 읽기 전용 DB / atomic 실행은 저장하지 않고 결과만 반환)
+async fn store_reading(
+    state: &ServerState,
+    run: &StationRun,
+    sub_region_id: i32,
+    pm_station: &str,
+    reading: Reading,
+) -> StationResult {
+    // dry-run / 읽기 전용 DB: DB 에 접근하지 않고 조회 결과만 반환
+    if run.dry_run || run.is_db_read_only() {
+        return StationResult::success(
+            sub_region_id,
+            pm_station,
+            fetched_pm_json(&reading, sub_region_id, pm_station, run.now),
+        );
+    }
+
+    // atomic 실행: 저장은 실행 끝의 트랜잭션에서 (응답 값은 커밋 후 저장된 행으로 교체)
+    if let Some(staged_writes) = &run.staged_writes {
+        staged_writes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(StagedWrite {
+                sub_region_id,
+                pm_station: pm_station.to_owned(),
+                reading: reading.clone(),
+            });
+        return StationResult::success(
+            sub_region_id,
+            pm_station,
+            fetched_pm_json(&reading, sub_region_id, pm_station, run.now),
+        );
+    }
+
+    // DB 쓰기 퍼밋 획득 (API 조회와 독립적으로 동시 upsert 수 제한)
+    let _db_write_permit =
+        match acquire_permit(run.db_write_permits.clone(), sub_region_id, pm_station).await {
+            Ok(permit) => permit,
+            Err(result) => return result,
+        };
+
+    // 새로운 DB 클라이언트 획득 (일시적인 풀 고갈은 재시도)
+    let (mut db_client, mut checkout_retries) = match get_client_with_retry(&state.pool).await {
+        Ok(client) => client,
+        Err(e) => {
+            let error_message = format!("{} : Failed to get db client: {:?}", pm_station, e);
+            // 조회한 측정값은 PM_FALLBACK_SINK 에 보관하여 이후 실행에서 재저장
+            let buffered =
+                buffer_unwritten(state, sub_region_id, pm_station, &reading, &error_message).await;
+            return StationResult::failed(
+                sub_region_id,
+                pm_station,
+                FailureKind::DbPool,
+                error_message,
+            )
+            .with_buffered(buffered);
+        }
+    };
+
+    // 데이터베이스에 upsert (일시적인 DB 오류는 새 커넥션으로 재시도)
+    let mut upsert_retries = 0;
+    let upsert_query = sql(UPSERT_EXTERNAL_PM_QUERY);
+    let upsert_start = tokio::time::Instant::now();
+    let upsert_result = loop {
+        let result = db_client
+            .query_one(
+                upsert_query.as_str(),
+                &[
+                    &sub_region_id,
+                    &reading.pm10,
+                    &reading.pm25,
+                    &reading.recorded_at,
+                ],
+            )
+            .await;
+
+        let should_retry = upsert_retries < MAX_UPSERT_RETRIES
+            && result.as_ref().err().is_some_and(is_retriable_db_error);
+        if !should_retry {
+            break result;
+        }
+
+        upsert_retries += 1;
+        warn!(
+            "{} : Upsert failed, retrying on a fresh connection ({}/{}): {:?}",
+            pm_station,
+            upsert_retries,
+            MAX_UPSERT_RETRIES,
+            result.as_ref().err()
+        );
+
+        // 기존 커넥션은 끊겼을 수 있으므로 풀에서 새로 획득
+        // (쓰기 퍼밋 수만큼 커넥션을 잡은 채 새 커넥션을 기다리면 풀이 고갈되므로 기존 커넥션을 먼저 반환)
+        drop(db_client);
+        match get_client_with_retry(&state.pool).await {
+            Ok((client, retries)) => {
+                db_client = client;
+                checkout_retries += retries;
+            }
+            Err(_) => break result,
+        }
+    };
+
+    metrics::record_upsert(upsert_start.elapsed());
+
+    let result = match upsert_result {
+        Ok(row) => match upserted_pm_json(&row, pm_station) {
+            Ok(data) => StationResult::success(
+                sub_region_id,
+                pm_station,
+                with_reading_fields(data, &reading),
+            ),
+            Err(e) => {
+                let error_message =
+                    format!("{} : Failed to read upserted row: {:?}", pm_station, e);
+                StationResult::failed(
+                    sub_region_id,
+                    pm_station,
+                    FailureKind::RowMapping,
+                    error_message,
+                )
+            }
+        },
+        // 읽기 전용 DB: 측정소마다 오류를 남기지 않고 실행 전체를 조회 전용으로 전환, 조회 결과는 응답에 포함
+        Err(e) if is_read_only_db_error(&e) => {
+            if !run.db_read_only.swap(true, Ordering::Relaxed) {
+                warn!(
+                    "{} : Database is read-only ({}), skipping writes for the rest of the run",
+                    pm_station,
+                    describe_db_error(&e)
+                );
+            }
+            StationResult::success(
+                sub_region_id,
+                pm_station,
+                fetched_pm_json(&reading, sub_region_id, pm_station, run.now),
+            )
+        }
+        Err(e) => {
+            let error_message = format!(
+                "{} : Database query failed: {}",
+                pm_station,
+                describe_db_error(&e)
+            );
+            // 재시도 후에도 일시적인 DB 오류면 조회한 측정값 보관 (데이터 오류는 재저장해도 실패하므로 제외)
+            let kind = classify_db_error(&e);
+            let buffered = kind.is_retriable()
+                && buffer_unwritten(state, sub_region_id, pm_station, &reading, &error_message)
+                    .await;
+            StationResult::failed(sub_region_id, pm_station, kind, error_message)
+                .with_buffered(buffered)
+        }
+    };
+
+    result
+        .with_checkout_retries(checkout_retries)
+        .with_upsert_retries(upsert_retries)
+}
+
+// 저장하지 않은 조회 결과를 응답 JSON 으로 변환 (dry-run, 읽기 전용 DB)
+pub(crate) fn fetched_pm_json(
+    reading: &Reading,
+    sub_region_id: i32,
+    pm_station: &str,
+    requested_at: DateTime<Utc>,
+) -> serde_json::Value {
+    // 저장 전 값과 비교하지 않으므로 변화량은 없음
+    let data = json!({
+        "subRegionId": sub_region_id,
+        "pm10Value": reading.pm10,
+        "pm25Value": reading.pm25,
+        "pm10Delta": null,
+        "pm25Delta": null,
+        "dataTime": reading.recorded_at,
+        "requestedTime": requested_at,
+        "stationName": pm_station,
+    });
+    with_reading_fields(data, reading)
+}
+
+// 응답 항목에 측정값 부가 정보 추가
+// upstreamLagSeconds: 상위 데이터 지연 (응답 Date 헤더 - dataTime, 헤더가 없으면 null), 우리 쪽 지연(requestedTime - dataTime)과 구분하기 위한 값
+// raw: PM_RAW_SAMPLE_RATE 로 샘플링된 응답의 원문 JSON (샘플이 아니면 필드 없음)
+pub(crate) fn with_reading_fields(
+    mut data: serde_json::Value,
+    reading: &Reading,
+) -> serde_json::Value {
+    data["upstreamLagSeconds"] = json!(reading.upstream_lag_seconds());
+    if let Some(raw) = &reading.raw {
+        data["raw"] = raw.clone();
+    }
+    data
+}
+
+// upsert RETURNING 행을 응답 JSON 으로 변환
+pub(crate) fn upserted_pm_json(
+    row: &Row,
+    pm_station: &str,
+) -> Result<serde_json::Value, tokio_postgres::Error> {
+    UpsertedRow::from_row(row).map(|row| row.to_json(pm_station))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::failure::max_error_bytes;
+    use crate::provider::{AirKoreaProvider, ApiEnvelope, MockApiClient, PmProvider};
+    use chrono::TimeZone;
+    use std::sync::atomic::AtomicUsize;
+
+    // 리터럴 SELECT 결과 한 행 (TEST_DATABASE_URL 미설정이면 None)
+    async fn literal_row(select: &str) -> Option<Row> {
+        let url = std::env::var("TEST_DATABASE_URL").ok()?;
+        let (client, connection) = tokio_postgres::connect(&url, tokio_postgres::NoTls)
+            .await
+            .unwrap();
+        tokio::spawn(connection);
+        Some(client.query_one(select, &[]).await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn sub_region_row_maps_optional_and_blank_columns() {
+        let Some(row) = literal_row(
+            "SELECT 7::bigint AS sub_region_id, '  ' AS pm_station, 1.5::float8 AS tm_x, NULL::float8 AS tm_y",
+        )
+        .await
+        else {
+            return;
+        };
+        let sub_region = SubRegionInfo::try_from_row(&row).unwrap();
+        assert_eq!(sub_region.sub_region_id, 7);
+        // 공백 측정소 이름은 NULL 로 취급하되 구분 표시
+        assert_eq!(sub_region.pm_station, None);
+        assert!(sub_region.blank_station);
+        assert_eq!((sub_region.tm_x, sub_region.tm_y), (Some(1.5), None));
+        // 결과에 없는 선택 컬럼은 기본값
+        assert_eq!(sub_region.provider, AIRKOREA_PROVIDER_KEY);
+        assert_eq!((sub_region.nx, sub_region.is_active), (None, None));
+    }
+
+    #[tokio::test]
+    async fn sub_region_row_errors_instead_of_panicking() {
+        let cases = [
+            (
+                "SELECT NULL::int AS sub_region_id, '중구' AS pm_station",
+                None,
+                "is NULL",
+            ),
+            (
+                "SELECT 3000000000::bigint AS sub_region_id, '중구' AS pm_station",
+                None,
+                "out of i32 range",
+            ),
+            (
+                "SELECT 'x'::text AS sub_region_id, '중구' AS pm_station",
+                None,
+                "invalid sub_region_id",
+            ),
+            (
+                "SELECT 4 AS sub_region_id, 12 AS pm_station",
+                Some(4),
+                "invalid pm_station",
+            ),
+            (
+                "SELECT 5 AS sub_region_id, NULL::text AS pm_station, '1.5'::text AS tm_x",
+                Some(5),
+                "invalid tm_x",
+            ),
+        ];
+        for (select, sub_region_id, expected) in cases {
+            let Some(row) = literal_row(select).await else {
+                return;
+            };
+            let e = SubRegionInfo::try_from_row(&row).unwrap_err();
+            assert_eq!(e.sub_region_id, sub_region_id, "{}", select);
+            assert!(e.message.contains(expected), "{} : {}", select, e.message);
+        }
+    }
+
+    #[tokio::test]
+    async fn station_stream_creates_futures_only_as_slots_free_up() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let gate = Arc::new(Semaphore::new(0));
+        let total = max_in_flight_tasks() + 5;
+
+        let stations = (0..total as i32).map(|sub_region_id| {
+            created.fetch_add(1, Ordering::SeqCst);
+            let gate = gate.clone();
+            let station_task = async move {
+                let _open = gate.acquire().await;
+                StationResult::success(sub_region_id, "중구", json!({}))
+            };
+            (sub_region_id, "중구".to_owned(), station_task)
+        });
+        let mut stations = std::pin::pin!(station_stream(stations));
+
+        // 아무 측정소도 끝나지 않았으면 max_in_flight_tasks 개까지만 생성
+        assert!(
+            tokio::time::timeout(Duration::from_millis(20), stations.next())
+                .await
+                .is_err()
+        );
+        assert_eq!(created.load(Ordering::SeqCst), max_in_flight_tasks());
+
+        gate.add_permits(total);
+        let mut finished = 0;
+        while stations.next().await.is_some() {
+            finished += 1;
+        }
+        assert_eq!(finished, total);
+        assert_eq!(created.load(Ordering::SeqCst), total);
+    }
+
+    #[tokio::test]
+    async fn station_stream_turns_a_panic_into_an_internal_failure() {
+        let stations = (1..=3).map(|sub_region_id| {
+            let station_task = async move {
+                if sub_region_id == 2 {
+                    panic!("boom");
+                }
+                StationResult::success(sub_region_id, "중구", json!({}))
+            };
+            (
+                sub_region_id,
+                format!("station-{}", sub_region_id),
+                station_task,
+            )
+        });
+        let mut results: Vec<StationResult> = station_stream(stations).collect().await;
+        results.sort_by_key(|result| result.sub_region_id);
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].failure_kind(), None);
+        assert_eq!(results[1].failure_kind(), Some(FailureKind::Internal));
+        assert_eq!(results[1].pm_station, "station-2");
+        assert_eq!(results[2].failure_kind(), None);
+    }
+
+    #[tokio::test]
+    async fn station_panic_keeps_sub_region_and_label() {
+        let result = catch_station_panic(9, "60,127".to_owned(), async {
+            let readings: Vec<f64> = Vec::new();
+            StationResult::success(9, "60,127", json!({ "pm10": readings[0] }))
+        })
+        .await;
+
+        assert_eq!(result.sub_region_id, 9);
+        assert_eq!(result.pm_station, "60,127");
+        let StationStatus::Failed { kind, message } = &result.status else {
+            panic!("panic should become a failure");
+        };
+        assert_eq!(*kind, FailureKind::Internal);
+        assert_eq!(message, "sub_region 9 60,127 : Task failed: panicked");
+    }
+
+    #[tokio::test]
+    async fn closed_semaphore_fails_only_that_station() {
+        let semaphore = Arc::new(Semaphore::new(1));
+        assert!(acquire_permit(semaphore.clone(), 1, "중구").await.is_ok());
+
+        // 세마포어가 닫히면 패닉 대신 해당 측정소 실패로 기록
+        semaphore.close();
+        let result = acquire_permit(semaphore, 2, "종로구").await.unwrap_err();
+        assert_eq!(result.sub_region_id, 2);
+        assert_eq!(result.pm_station, "종로구");
+        assert_eq!(result.failure_kind(), Some(FailureKind::Internal));
+    }
+
+    #[tokio::test]
+    async fn station_deadline_records_timeout() {
+        let result = with_station_deadline(7, "중구", Duration::from_millis(10), async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            StationResult::success(7, "중구", json!({}))
+        })
+        .await;
+        assert_eq!(result.failure_kind(), Some(FailureKind::Timeout));
+    }
+
+    #[tokio::test]
+    async fn exhausted_deadline_skips_ready_station_task() {
+        let result = with_station_deadline(7, "중구", Duration::ZERO, async {
+            StationResult::success(7, "중구", json!({}))
+        })
+        .await;
+        assert_eq!(result.failure_kind(), Some(FailureKind::Timeout));
+    }
+
+    #[test]
+    fn supplied_request_id_is_echoed_and_missing_one_is_generated() {
+        let supplied = "6f1c2a9e-3b7d-4e51-9c0a-8d2f4b6e1a73";
+        assert_eq!(
+            request_id_from_payload(&json!({ "requestId": supplied })),
+            supplied
+        );
+
+        // 없거나 UUID 형식이 아니면 새로 생성
+        let generated = request_id_from_payload(&json!({}));
+        assert!(Uuid::parse_str(&generated).is_ok());
+        assert_ne!(generated, request_id_from_payload(&json!({})));
+        let replaced = request_id_from_payload(&json!({ "requestId": "not-a-uuid" }));
+        assert!(Uuid::parse_str(&replaced).is_ok());
+    }
+
+    #[test]
+    fn run_ids_are_time_ordered_uuid_v7() {
+        let ids: Vec<String> = (0..3)
+            .map(|_| {
+                let id = new_run_id();
+                std::thread::sleep(Duration::from_millis(2));
+                id
+            })
+            .collect();
+        for id in &ids {
+            assert_eq!(Uuid::parse_str(id).unwrap().get_version_num(), 7);
+        }
+        // 문자열 정렬 순서가 생성 순서와 같아야 함
+        let mut sorted = ids.clone();
+        sorted.sort();
+        assert_eq!(sorted, ids);
+
+        let body = build_response_body(report(Vec::new()));
+        assert_eq!(body["meta"]["runId"], "run-1");
+    }
+
+    #[test]
+    fn internal_error_response_carries_run_and_request_ids() {
+        let response = internal_error_response("run-1", "req-1");
+        assert_eq!(response["statusCode"], 500);
+        assert_eq!(
+            response["body"]["message"],
+            message(MessageKey::InternalServerError)
+        );
+        assert_eq!(response["body"]["meta"]["runId"], "run-1");
+        assert_eq!(response["body"]["meta"]["requestId"], "req-1");
+    }
+
+    fn report(results: Vec<StationResult>) -> IngestReport {
+        IngestReport {
+            run_id: "run-1".to_owned(),
+            results,
+            skipped_fresh: 0,
+            deferred: 0,
+            cache_hits: 0,
+            db_read_only: false,
+            no_sub_regions: false,
+            advanced: None,
+            data_frozen: false,
+            elapsed: Duration::ZERO,
+            warnings: Vec::new(),
+            latest_cache_failures: 0,
+            disabled_sub_regions: None,
+            diagnostics: None,
+            phases: Phases::default(),
+            station_list_source: StationListSource::Db,
+            quota: None,
+        }
+    }
+
+    #[test]
+    fn entries_carry_sub_region_id_for_shared_stations() {
+        let reading = Reading {
+            pm10: Some(42.0),
+            pm25: Some(20.0),
+            recorded_at: Utc.with_ymd_and_hms(2024, 5, 1, 4, 0, 0).unwrap(),
+            server_time: None,
+            raw: None,
+            rejected_values: 0,
+        };
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 4, 10, 0).unwrap();
+        // 두 sub_region 이 같은 측정소(중구)를 공유
+        let results = vec![
+            StationResult::success(1, "중구", fetched_pm_json(&reading, 1, "중구", now)),
+            StationResult::success(2, "중구", fetched_pm_json(&reading, 2, "중구", now)),
+            StationResult::failed(
+                3,
+                "종로구",
+                FailureKind::NoData,
+                "종로구 : No data available in API response.".to_owned(),
+            ),
+            StationResult::failed(
+                4,
+                "",
+                FailureKind::StationConfig,
+                "sub_region 4 : pm_station and tm coordinates are not configured".to_owned(),
+            ),
+        ];
+        assert_eq!(
+            results[2].to_json(),
+            json!({
+                "subRegionId": 3,
+                "stationName": "종로구",
+                "error": "종로구 : No data available in API response.",
+            })
+        );
+
+        let body = build_response_body(report(results));
+        let data_ids: Vec<_> = body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| (entry["subRegionId"].clone(), entry["stationName"].clone()))
+            .collect();
+        assert_eq!(
+            data_ids,
+            vec![(json!(1), json!("중구")), (json!(2), json!("중구"))]
+        );
+        assert_eq!(
+            body["meta"]["errors"],
+            json!([
+                {
+                    "subRegionId": 3,
+                    "stationName": "종로구",
+                    "kind": "NO_DATA",
+                    "message": "종로구 : No data available in API response.",
+                },
+                {
+                    "subRegionId": 4,
+                    "stationName": "",
+                    "kind": "STATION_CONFIG",
+                    "message": "sub_region 4 : pm_station and tm coordinates are not configured",
+                },
+            ])
+        );
+    }
+
+    // 측정값 유무/실패 종류별 집계만 담긴 본문인지 저장된 응답 본문과 비교
+    #[test]
+    fn coverage_body_matches_the_golden_body() {
+        let mut report = report(vec![
+            StationResult::success(1, "중구", json!({ "pm10Value": 42.0, "pm25Value": null })),
+            StationResult::success(2, "종로구", json!({ "pm10Value": null, "pm25Value": 15.0 })),
+            // 응답은 정상이지만 값이 없으면 missing
+            StationResult::success(3, "용산구", json!({ "pm10Value": null, "pm25Value": null })),
+            StationResult::failed(4, "강남구", FailureKind::NoData, "no data".to_owned()),
+            StationResult::failed(5, "서초구", FailureKind::Timeout, "timeout".to_owned()),
+        ]);
+        report.deferred = 3;
+
+        let golden: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/golden/coverage_response.json")).unwrap();
+        assert_eq!(build_coverage_body(&report), golden);
+    }
+
+    #[test]
+    fn report_warnings_and_timing_reach_meta() {
+        let mut report = report(vec![StationResult::success(1, "중구", json!({}))]);
+        report.elapsed = Duration::from_millis(1234);
+        report.warnings = vec!["sns : throttled".to_owned(), DATA_FROZEN_WARNING.to_owned()];
+        report.latest_cache_failures = 2;
+
+        let meta = &build_response_body(report)["meta"];
+        assert_eq!(
+            meta["warnings"],
+            json!(["sns : throttled", DATA_FROZEN_WARNING])
+        );
+        assert_eq!(meta["latestCacheFailures"], 2);
+        assert_eq!(meta["elapsedMs"], 1234);
+    }
+
+    #[test]
+    fn run_summary_counts_both_response_shapes() {
+        let summary = RunSummary::from_response(
+            "run-1",
+            &json!({ "data": [{}, {}, {}], "meta": { "errorList": ["a"] } }),
+        );
+        assert_eq!((summary.succeeded, summary.failed), (3, 1));
+        assert_eq!(summary.failure_rate(), 0.25);
+
+        // legacy 응답 (data.responseData)
+        let summary = RunSummary::from_response(
+            "run-1",
+            &json!({ "data": { "responseData": [{}] }, "meta": { "errorList": ["a", "b", "c"] } }),
+        );
+        assert_eq!((summary.succeeded, summary.failed), (1, 3));
+
+        // 잘린 오류: 목록 끝의 요약 한 줄 대신 잘린 개수로 계산
+        let summary = RunSummary::from_response(
+            "run-1",
+            &json!({
+                "data": [],
+                "meta": { "errorList": ["a", "5 additional errors truncated"], "errorsTruncated": 5 },
+            }),
+        );
+        assert_eq!((summary.succeeded, summary.failed), (0, 6));
+
+        // 측정소가 없으면 실패율 0
+        let summary = RunSummary::from_response("run-1", &json!({ "data": [], "meta": {} }));
+        assert_eq!(summary.failure_rate(), 0.0);
+    }
+
+    #[test]
+    fn run_summary_adds_up_combined_mode_sections() {
+        let response = json!({
+            "meta": { "modes": ["realtime", "weather"] },
+            "realtime": { "data": [{}, {}], "meta": { "errorList": ["a"] } },
+            "weather": { "data": [{}], "meta": { "errorList": ["b", "c"] } },
+        });
+        let summary = RunSummary::from_response("run-1", &response);
+        assert_eq!((summary.succeeded, summary.failed), (3, 3));
+        assert_eq!(
+            summary.to_string(),
+            "run run-1 : succeeded 3, failed 3 (failure rate 0.500)"
+        );
+    }
+
+    #[test]
+    fn blank_and_missing_stations_are_listed_separately() {
+        let results = vec![
+            StationResult::failed(
+                1,
+                "",
+                FailureKind::UnmappedStation,
+                "sub_region 1 : pm_station is empty and tm coordinates are not configured"
+                    .to_owned(),
+            ),
+            StationResult::failed(
+                2,
+                "",
+                FailureKind::StationConfig,
+                "sub_region 2 : pm_station and tm coordinates are not configured".to_owned(),
+            ),
+            StationResult::success(3, "중구", json!({})),
+        ];
+        let body = build_response_body(report(results));
+        assert_eq!(body["meta"]["unmappedStations"], json!([1]));
+        assert_eq!(body["meta"]["unconfiguredSubRegions"], json!([2]));
+        assert_eq!(body["meta"]["errors"][0]["kind"], "UNMAPPED_STATION");
+    }
+
+    #[test]
+    fn response_meta_sums_rejected_values() {
+        let results = vec![
+            StationResult::success(1, "중구", json!({})).with_rejected_values(2),
+            StationResult::success(2, "종로구", json!({})),
+            StationResult::failed(3, "강남구", FailureKind::DbQuery, "failed".to_owned())
+                .with_rejected_values(1),
+        ];
+        let body = build_response_body(report(results));
+        assert_eq!(body["meta"]["rejectedValues"], 3);
+    }
+
+    // 300개 측정소가 모두 수 MB 오류 페이지를 받아도 응답의 오류 목록은 MAX_ERROR_BYTES 안에서 끝남
+    #[tokio::test]
+    async fn error_list_stays_bounded_when_every_station_gets_a_huge_error_page() {
+        let html = format!("<html>{}</html>", "x".repeat(2 * 1024 * 1024));
+        let stations: Vec<String> = (0..300).map(|i| format!("측정소{}", i)).collect();
+        let mock = stations.iter().fold(MockApiClient::new(), |mock, station| {
+            mock.with_envelope(
+                station,
+                ApiEnvelope::new(reqwest::StatusCode::SERVICE_UNAVAILABLE, html.clone()),
+            )
+        });
+        let provider = AirKoreaProvider::new(Arc::new(mock), "test-key".to_owned());
+
+        let mut results = Vec::new();
+        for (sub_region_id, station) in stations.iter().enumerate() {
+            let e = provider.fetch(station).await.unwrap_err();
+            results.push(StationResult::failed(
+                sub_region_id as i32,
+                station,
+                e.kind,
+                e.message,
+            ));
+        }
+        let body = build_response_body(report(results));
+
+        let error_list = body["meta"]["errorList"].as_array().unwrap();
+        let retained: usize = error_list
+            .iter()
+            .map(|message| message.as_str().unwrap().len())
+            .sum();
+        assert!(retained <= max_error_bytes() + 64, "{}", retained);
+        let truncated = body["meta"]["errorsTruncated"].as_u64().unwrap() as usize;
+        assert!(truncated > 0);
+        assert_eq!(error_list.len() - 1 + truncated, 300);
+        assert_eq!(
+            error_list.last().unwrap(),
+            &json!(format!("{} additional errors truncated", truncated))
+        );
+        assert!(body.to_string().len() < 4 * max_error_bytes());
+    }
+
+    fn candidate(sub_region_id: i32) -> StationCandidate {
+        StationCandidate {
+            sub_region_id,
+            provider_key: AIRKOREA_PROVIDER_KEY.to_owned(),
+            station_source: StationSource::Name(format!("station-{}", sub_region_id)),
+        }
+    }
+
+    // 연속 실행: 처리한 측정소는 측정 시각이 갱신되므로 다음 실행은 나머지 측정소부터 선택
+    #[test]
+    fn stale_first_selection_rotates_across_consecutive_runs() {
+        let base = Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+        let mut last_recorded_at: HashMap<i32, DateTime<Utc>> = HashMap::from([
+            (1, base),
+            (2, base - chrono::Duration::hours(2)),
+            (3, base - chrono::Duration::hours(1)),
+        ]);
+        // 4, 5 는 저장된 값이 없으므로 가장 먼저
+
+        let mut selected_per_run = Vec::new();
+        for run in 1..=3 {
+            let candidates = (1..=5).map(candidate).collect();
+            let (selected, deferred) = select_stale_first(candidates, &last_recorded_at, Some(2));
+            assert_eq!(deferred, 3);
+            let ids: Vec<i32> = selected.iter().map(|c| c.sub_region_id).collect();
+            for sub_region_id in &ids {
+                last_recorded_at.insert(*sub_region_id, base + chrono::Duration::hours(run));
+            }
+            selected_per_run.push(ids);
+        }
+
+        assert_eq!(selected_per_run, vec![vec![4, 5], vec![2, 3], vec![1, 4]]);
+    }
+
+    #[test]
+    fn no_limit_keeps_every_station() {
+        let candidates = (1..=3).map(candidate).collect();
+        let (selected, deferred) = select_stale_first(candidates, &HashMap::new(), None);
+        assert_eq!(selected.len(), 3);
+        assert_eq!(deferred, 0);
+    }
+
+    // 접속하지 않는 풀 (port 1 은 연결 거부)
+    fn unreachable_state() -> ServerState {
+        let pool = deadpool_postgres::Config {
+            url: Some("postgres://unused@127.0.0.1:1/unused".to_owned()),
+            ..Default::default()
+        }
+        .create_pool(
+            Some(deadpool_postgres::Runtime::Tokio1),
+            tokio_postgres::NoTls,
+        )
+        .unwrap();
+        ServerState::new(pool, "test-key".to_owned(), None, None)
+    }
+
+    #[tokio::test]
+    async fn read_rejects_non_integer_ids_before_touching_the_db() {
+        let response = handle_read(
+            &unreachable_state(),
+            "run-1",
+            "req-1",
+            &json!({ "read": true, "subRegionIds": [1, "2"] }),
+        )
+        .await;
+        assert_eq!(response["statusCode"], 400);
+        assert_eq!(
+            response["body"]["message"],
+            message(MessageKey::InvalidRequest)
+        );
+        assert_eq!(
+            response["body"]["detail"],
+            "subRegionIds 는 정수 배열이어야 함: [1,\"2\"]"
+        );
+        assert_eq!(response["body"]["meta"]["requestId"], "req-1");
+    }
+
+    #[tokio::test]
+    async fn read_reports_an_unreachable_db_as_a_connection_failure() {
+        let response = handle_read(
+            &unreachable_state(),
+            "run-1",
+            "req-1",
+            &json!({ "read": true }),
+        )
+        .await;
+        assert_eq!(response["statusCode"], 500);
+        assert_eq!(
+            response["body"]["message"],
+            message(MessageKey::DbConnectionFailed)
+        );
+        assert_eq!(response["body"]["meta"]["runId"], "run-1");
+    }
 }
@@ -0,0 +1,255 @@
+// src/http_body.rs
+
+// 외부 API 응답 본문을 최대 크기까지만 읽기 (PM_MAX_BODY_BYTES, 기본 1 MiB)
+// 비정상적으로 큰 응답을 여러 태스크가 동시에 메모리에 올리지 않도록 청크 단위로 읽으며 제한 초과 시 중단
+
+use std::fmt;
+
+use crate::failure::FailureKind;
+use crate::scrub::scrub_text;
+
+const DEFAULT_MAX_BODY_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug)]
+pub enum ReadBodyError {
+    // 제한 크기 초과
+    TooLarge { limit: usize },
+    // 본문 수신 실패
+    Read(reqwest::Error),
+}
+
+impl ReadBodyError {
+    pub fn kind(&self) -> FailureKind {
+        match self {
+            ReadBodyError::TooLarge { .. } => FailureKind::BodyTooLarge,
+            ReadBodyError::Read(_) => FailureKind::ReadBody,
+        }
+    }
+}
+
+impl fmt::Display for ReadBodyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadBodyError::TooLarge { limit } => {
+                write!(f, "response too large (limit: {} bytes)", limit)
+            }
+            ReadBodyError::Read(e) => write!(f, "{}", scrub_text(&format!("{:?}", e))),
+        }
+    }
+}
+
+impl std::error::Error for ReadBodyError {}
+
+// PM_MAX_BODY_BYTES 환경 변수 (기본 1 MiB)
+pub fn max_body_bytes() -> usize {
+    std::env::var("PM_MAX_BODY_BYTES")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_MAX_BODY_BYTES)
+}
+
+// 응답 본문을 PM_MAX_BODY_BYTES 까지만 텍스트로 읽기
+pub async fn read_text(res: reqwest::Response) -> Result<String, ReadBodyError> {
+    read_text_capped(res, max_body_bytes()).await
+}
+
+pub async fn read_text_capped(
+    mut res: reqwest::Response,
+    limit: usize,
+) -> Result<String, ReadBodyError> {
+    // Content-Length 가 있으면 본문을 받기 전에 거부
+    if res.content_length().is_some_and(|len| len > limit as u64) {
+        return Err(ReadBodyError::TooLarge { limit });
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = res.chunk().await.map_err(ReadBodyError::Read)? {
+        if body.len() + chunk.len() > limit {
+            return Err(ReadBodyError::TooLarge { limit });
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+// 오류 메시지에 포함할 본문 최대 크기 기본값
+const DEFAULT_ERROR_BODY_BYTES: usize = 2 * 1024;
+
+// PM_ERROR_BODY_BYTES 환경 변수 (기본 2 KiB)
+pub fn error_body_bytes() -> usize {
+    std::env::var("PM_ERROR_BODY_BYTES")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_ERROR_BODY_BYTES)
+}
+
+// PM_VERBOSE_ERRORS=true 면 오류 메시지에 응답 헤더까지 포함
+pub fn verbose_errors() -> bool {
+    std::env::var("PM_VERBOSE_ERRORS")
+        .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+        .unwrap_or(false)
+}
+
+// 본문이 JSON 이 끝나기 전에 끝났는지 (빈 본문 포함, describe_json_error 의 TRUNCATED_BODY 와 같은 기준)
+pub fn is_truncated_json(body: &str) -> bool {
+    matches!(serde_json::from_str::<serde::de::IgnoredAny>(body), Err(e) if e.is_eof())
+}
+
+// JSON 파싱 오류 분류와 메시지
+// 본문이 JSON 중간에서 끝났으면(unexpected EOF) 형식 오류가 아니라 전송 중 끊긴 응답이므로 재시도 대상으로 분류
+pub fn describe_json_error(
+    label: &str,
+    e: &serde_json::Error,
+    body: &str,
+) -> (FailureKind, String) {
+    if e.is_eof() {
+        return (
+            FailureKind::TruncatedBody,
+            format!(
+                "{} : Response body truncated ({} bytes, JSON ended early): {}",
+                label,
+                body.len(),
+                e
+            ),
+        );
+    }
+    (
+        FailureKind::Parse,
+        format!(
+            "{} : Failed to parse JSON response: {:?}\nResponse text: {}",
+            label,
+            e,
+            truncate_body(body)
+        ),
+    )
+}
+
+// 오류 메시지용 본문 (업스트림 장애 시 수 MB 의 HTML 오류 페이지가 그대로 쌓이지 않도록 잘라냄)
+pub fn truncate_body(body: &str) -> String {
+    truncate_body_to(body, error_body_bytes())
+}
+
+pub fn truncate_body_to(body: &str, limit: usize) -> String {
+    if body.len() <= limit {
+        return body.to_owned();
+    }
+
+    // UTF-8 문자 경계에서 자름
+    let mut end = limit;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}… ({} bytes total)", &body[..end], body.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // 준비된 HTTP 응답 하나를 돌려주는 로컬 서버로 요청
+    async fn respond_with(response: String) -> reqwest::Response {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket.write_all(response.as_bytes()).await;
+            let _ = socket.shutdown().await;
+        });
+        reqwest::get(format!("http://{}/", addr)).await.unwrap()
+    }
+
+    fn with_length(body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    }
+
+    // Content-Length 없이 청크로 나누어 보내는 응답
+    fn chunked(chunks: &[&str]) -> String {
+        let mut response =
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n".to_owned();
+        for chunk in chunks {
+            response.push_str(&format!("{:x}\r\n{}\r\n", chunk.len(), chunk));
+        }
+        response.push_str("0\r\n\r\n");
+        response
+    }
+
+    #[tokio::test]
+    async fn body_within_the_limit_is_read() {
+        let res = respond_with(with_length("0123456789")).await;
+        assert_eq!(read_text_capped(res, 10).await.unwrap(), "0123456789");
+
+        let res = respond_with(chunked(&["01234", "56789"])).await;
+        assert_eq!(read_text_capped(res, 10).await.unwrap(), "0123456789");
+    }
+
+    #[tokio::test]
+    async fn oversized_body_is_rejected() {
+        // Content-Length 로 미리 거부
+        let res = respond_with(with_length("0123456789a")).await;
+        let e = read_text_capped(res, 10).await.unwrap_err();
+        assert!(matches!(e, ReadBodyError::TooLarge { limit: 10 }));
+        assert_eq!(e.kind(), FailureKind::BodyTooLarge);
+        assert_eq!(e.to_string(), "response too large (limit: 10 bytes)");
+
+        // 길이를 모르는 응답은 읽는 도중 제한을 넘으면 중단
+        let res = respond_with(chunked(&["01234", "56789", "a"])).await;
+        assert!(matches!(
+            read_text_capped(res, 10).await.unwrap_err(),
+            ReadBodyError::TooLarge { limit: 10 }
+        ));
+    }
+
+    #[tokio::test]
+    async fn connection_cut_mid_body_is_a_read_error() {
+        // Content-Length 보다 먼저 연결이 끊김
+        let res = respond_with(
+            "HTTP/1.1 200 OK\r\nContent-Length: 100\r\nConnection: close\r\n\r\nshort".to_owned(),
+        )
+        .await;
+        let e = read_text_capped(res, 1024).await.unwrap_err();
+        assert_eq!(e.kind(), FailureKind::ReadBody);
+    }
+
+    #[test]
+    fn multi_megabyte_body_is_cut_to_the_limit() {
+        let body = format!("<html>{}</html>", "x".repeat(5 * 1024 * 1024));
+        let truncated = truncate_body_to(&body, 2048);
+
+        assert!(truncated.starts_with("<html>xxx"));
+        assert!(truncated.len() < 2048 + 64, "{}", truncated.len());
+        assert!(truncated.ends_with(&format!("… ({} bytes total)", body.len())));
+    }
+
+    #[test]
+    fn truncation_keeps_utf8_boundaries_and_short_bodies() {
+        // 한글은 3바이트: 4바이트 제한이면 첫 글자만 남음
+        assert_eq!(truncate_body_to("미세먼지", 4), "미… (12 bytes total)");
+        assert_eq!(truncate_body_to("미세먼지", 12), "미세먼지");
+        assert_eq!(truncate_body_to("", 0), "");
+    }
+
+    #[test]
+    fn json_error_messages_stay_bounded() {
+        let garbage = format!("<html>{}", "x".repeat(3 * 1024 * 1024));
+        let e = serde_json::from_str::<serde_json::Value>(&garbage).unwrap_err();
+        let (kind, message) = describe_json_error("중구", &e, &garbage);
+        assert_eq!(kind, FailureKind::Parse);
+        assert!(message.len() < 4 * 1024, "{}", message.len());
+
+        // 끊긴 JSON 은 본문 없이 크기만 기록
+        let cut = format!("{{\"items\": [\"{}", "x".repeat(3 * 1024 * 1024));
+        let e = serde_json::from_str::<serde_json::Value>(&cut).unwrap_err();
+        let (kind, message) = describe_json_error("중구", &e, &cut);
+        assert_eq!(kind, FailureKind::TruncatedBody);
+        assert!(message.len() < 256, "{}", message);
+    }
+}
@@ -0,0 +1,150 @@
+// src/idempotency.rs
+
+// EventBridge 중복 전달 방지: 이벤트 id(없으면 payload 해시)를 키로 TTL 내 재실행을 막음
+// 1차로 warm 컨테이너의 메모리 캐시, 2차로 {schema}.ingest_idempotency 테이블을 확인
+
+use anyhow::Result;
+use deadpool_postgres::Pool;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::db_schema::sql;
+
+// 만료된 키만 갱신하므로 영향 받은 행이 1이면 이번 실행이 키를 선점한 것
+pub const CLAIM_INGEST_IDEMPOTENCY_QUERY: &str = r#"
+INSERT INTO {schema}.ingest_idempotency (event_id, processed_at)
+VALUES ($1, now())
+ON CONFLICT (event_id)
+DO UPDATE SET
+    processed_at = now(),
+    summary = NULL
+WHERE {schema}.ingest_idempotency.processed_at < now() - make_interval(secs => $2);
+"#;
+
+pub const GET_INGEST_IDEMPOTENCY_SUMMARY_QUERY: &str = r#"
+SELECT summary::text AS summary
+FROM {schema}.ingest_idempotency
+WHERE event_id = $1;
+"#;
+
+pub const SAVE_INGEST_IDEMPOTENCY_SUMMARY_QUERY: &str = r#"
+UPDATE {schema}.ingest_idempotency
+SET summary = $2::text::jsonb
+WHERE event_id = $1;
+"#;
+
+pub const DELETE_INGEST_IDEMPOTENCY_QUERY: &str = r#"
+DELETE FROM {schema}.ingest_idempotency
+WHERE event_id = $1;
+"#;
+
+const DEFAULT_IDEMPOTENCY_TTL_SECS: u64 = 300;
+
+pub enum Claim {
+    // 처음 처리하는 이벤트
+    New,
+    // TTL 내에 이미 처리된 이벤트 (처리 중이면 summary 는 None)
+    Duplicate(Option<serde_json::Value>),
+}
+
+type MemoryCache = Mutex<HashMap<String, (Instant, Option<serde_json::Value>)>>;
+
+fn memory_cache() -> &'static MemoryCache {
+    static CACHE: OnceLock<MemoryCache> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// IDEMPOTENCY_TTL_SECS 환경 변수 (기본 300초)
+pub fn ttl() -> Duration {
+    let secs = std::env::var("IDEMPOTENCY_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_IDEMPOTENCY_TTL_SECS);
+    Duration::from_secs(secs)
+}
+
+// EventBridge 이벤트 id, 없으면 payload 해시
+// 키는 테이블에 남아 다른 빌드의 컨테이너와도 비교되므로 Rust 버전과 무관한 SHA-256 (hex) 사용
+pub fn idempotency_key(payload: &serde_json::Value) -> String {
+    if let Some(id) = payload.get("id").and_then(|v| v.as_str()) {
+        return id.to_owned();
+    }
+
+    format!(
+        "payload:{:x}",
+        Sha256::digest(payload.to_string().as_bytes())
+    )
+}
+
+// 키 선점 시도 (INSERT ... ON CONFLICT 로 원자적으로 확인 및 설정)
+pub async fn claim(pool: &Pool, key: &str, ttl: Duration) -> Result<Claim> {
+    {
+        let mut cache = memory_cache().lock().unwrap_or_else(|e| e.into_inner());
+        cache.retain(|_, (claimed_at, _)| claimed_at.elapsed() < ttl);
+        if let Some((_, summary)) = cache.get(key) {
+            return Ok(Claim::Duplicate(summary.clone()));
+        }
+    }
+
+    let db_client = pool.get().await?;
+    let claimed = db_client
+        .execute(
+            sql(CLAIM_INGEST_IDEMPOTENCY_QUERY).as_str(),
+            &[&key, &ttl.as_secs_f64()],
+        )
+        .await?;
+
+    if claimed == 0 {
+        let summary = db_client
+            .query_opt(sql(GET_INGEST_IDEMPOTENCY_SUMMARY_QUERY).as_str(), &[&key])
+            .await?
+            .and_then(|row| row.try_get::<_, Option<String>>("summary").ok().flatten())
+            .and_then(|summary| serde_json::from_str(&summary).ok());
+        return Ok(Claim::Duplicate(summary));
+    }
+
+    memory_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(key.to_owned(), (Instant::now(), None));
+
+    Ok(Claim::New)
+}
+
+// 실행 완료 후 요약 저장 (중복 이벤트에 그대로 반환)
+pub async fn save_summary(pool: &Pool, key: &str, summary: &serde_json::Value) -> Result<()> {
+    if let Some((_, cached)) = memory_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_mut(key)
+    {
+        *cached = Some(summary.clone());
+    }
+
+    let db_client = pool.get().await?;
+    db_client
+        .execute(
+            sql(SAVE_INGEST_IDEMPOTENCY_SUMMARY_QUERY).as_str(),
+            &[&key, &summary.to_string()],
+        )
+        .await?;
+
+    Ok(())
+}
+
+// 실행 실패 시 키 반환 (재시도가 중복으로 처리되지 않도록)
+pub async fn release(pool: &Pool, key: &str) -> Result<()> {
+    memory_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(key);
+
+    let db_client = pool.get().await?;
+    db_client
+        .execute(sql(DELETE_INGEST_IDEMPOTENCY_QUERY).as_str(), &[&key])
+        .await?;
+
+    Ok(())
+}
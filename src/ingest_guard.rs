@@ -0,0 +1,127 @@
+// src/ingest_guard.rs
+
+// 상시 실행(axum) 배포의 수집 엔드포인트 보호
+// - INGEST_AUTH_TOKEN bearer 토큰 확인 (없거나 다르면 401)
+// - 클라이언트 IP 별 토큰 버킷 (기본 분당 2회, 초과 시 429)
+// axum 미들웨어에서 사용 (src/bin/server.rs)
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::handler::constant_time_eq;
+
+// 분당 허용 호출 수 기본값
+pub const DEFAULT_INGEST_RATE_PER_MINUTE: u32 = 2;
+
+// Authorization 헤더 값이 "Bearer <INGEST_AUTH_TOKEN>" 인지 확인 (토큰 미설정 시 항상 거부)
+pub fn is_authorized_bearer(authorization: Option<&str>, expected_token: Option<&str>) -> bool {
+    let expected_token = match expected_token {
+        Some(token) if !token.is_empty() => token,
+        _ => return false,
+    };
+
+    authorization
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| constant_time_eq(token.trim().as_bytes(), expected_token.as_bytes()))
+        .unwrap_or(false)
+}
+
+pub fn auth_token_from_env() -> Option<String> {
+    std::env::var("INGEST_AUTH_TOKEN").ok()
+}
+
+// IP 별 토큰 버킷 (버킷 크기 = 분당 허용 수, 시간에 비례하여 충전)
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    buckets: Mutex<HashMap<IpAddr, (f64, Instant)>>,
+}
+
+impl RateLimiter {
+    pub fn per_minute(limit: u32) -> Self {
+        let capacity = f64::from(limit.max(1));
+        RateLimiter {
+            capacity,
+            refill_per_sec: capacity / 60.0,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // INGEST_RATE_PER_MINUTE 환경 변수 (기본 2)
+    pub fn from_env() -> Self {
+        let limit = std::env::var("INGEST_RATE_PER_MINUTE")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_INGEST_RATE_PER_MINUTE);
+        Self::per_minute(limit)
+    }
+
+    // 호출 허용 여부 (허용되면 토큰 하나 소모)
+    pub fn check(&self, ip: IpAddr) -> bool {
+        self.check_at(ip, Instant::now())
+    }
+
+    pub fn check_at(&self, ip: IpAddr, now: Instant) -> bool {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        // 가득 찬 버킷은 기록할 필요가 없으므로 오래된 항목 정리
+        let full_after = Duration::from_secs_f64(self.capacity / self.refill_per_sec);
+        buckets
+            .retain(|_, (_, updated_at)| now.saturating_duration_since(*updated_at) < full_after);
+
+        let (tokens, updated_at) = buckets.entry(ip).or_insert((self.capacity, now));
+        let elapsed = now.saturating_duration_since(*updated_at).as_secs_f64();
+        *tokens = (*tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        *updated_at = now;
+
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bearer_must_match_configured_token() {
+        assert!(is_authorized_bearer(Some("Bearer secret"), Some("secret")));
+        assert!(!is_authorized_bearer(Some("Bearer wrong"), Some("secret")));
+        assert!(!is_authorized_bearer(Some("secret"), Some("secret")));
+        assert!(!is_authorized_bearer(None, Some("secret")));
+        // 토큰을 설정하지 않았으면 어떤 헤더도 허용하지 않음
+        assert!(!is_authorized_bearer(Some("Bearer "), Some("")));
+        assert!(!is_authorized_bearer(Some("Bearer secret"), None));
+    }
+
+    #[test]
+    fn bucket_allows_limit_then_refills_over_time() {
+        let limiter = RateLimiter::per_minute(2);
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let start = Instant::now();
+
+        assert!(limiter.check_at(ip, start));
+        assert!(limiter.check_at(ip, start));
+        assert!(!limiter.check_at(ip, start));
+
+        // 분당 2회: 30초마다 토큰 하나 충전
+        assert!(!limiter.check_at(ip, start + Duration::from_secs(29)));
+        assert!(limiter.check_at(ip, start + Duration::from_secs(31)));
+    }
+
+    #[test]
+    fn buckets_are_per_ip() {
+        let limiter = RateLimiter::per_minute(1);
+        let start = Instant::now();
+
+        assert!(limiter.check_at("10.0.0.1".parse().unwrap(), start));
+        assert!(!limiter.check_at("10.0.0.1".parse().unwrap(), start));
+        assert!(limiter.check_at("10.0.0.2".parse().unwrap(), start));
+    }
+}
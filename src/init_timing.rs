@@ -0,0 +1,170 @@
+// src/init_timing.rs
+
+// 콜드 스타트 구간별 소요 시간 (init_timing span 의 phase / elapsed_ms 필드로 기록)
+// 풀 생성, TLS/IAM 토큰, sub_region 쿼리 확인, pre-warm, 첫 쿼리 중 어느 구간이 느린지 구분하기 위한 용도
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tracing::{info, info_span};
+
+// 컨테이너(프로세스)의 첫 실행에서만 기록하는 구간
+pub struct FirstUse(AtomicBool);
+
+impl FirstUse {
+    pub const fn new() -> Self {
+        FirstUse(AtomicBool::new(false))
+    }
+
+    // 처음 호출될 때만 true
+    fn take(&self) -> bool {
+        !self.0.swap(true, Ordering::Relaxed)
+    }
+}
+
+impl Default for FirstUse {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub static FIRST_CHECKOUT: FirstUse = FirstUse::new();
+pub static FIRST_QUERY: FirstUse = FirstUse::new();
+
+// 구간 소요 시간 기록
+pub fn log_phase(phase: &str, elapsed: Duration) {
+    let _span = info_span!("init_timing").entered();
+    info!(
+        phase = phase,
+        elapsed_ms = elapsed.as_millis() as u64,
+        "Init phase finished"
+    );
+}
+
+// 컨테이너의 첫 실행에서만 기록
+pub fn log_first(first_use: &FirstUse, phase: &str, elapsed: Duration) {
+    if first_use.take() {
+        log_phase(phase, elapsed);
+    }
+}
+
+// future 실행 시간을 구간으로 기록
+pub async fn timed<F: Future>(phase: &str, future: F) -> F::Output {
+    let start = tokio::time::Instant::now();
+    let output = future.await;
+    log_phase(phase, start.elapsed());
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+    use tracing_subscriber::Layer;
+
+    // 기록된 이벤트의 span 이름과 phase / elapsed_ms 필드
+    #[derive(Debug, Clone, PartialEq)]
+    struct Captured {
+        span: Option<String>,
+        phase: String,
+        elapsed_ms: u64,
+    }
+
+    #[derive(Clone, Default)]
+    struct CaptureLayer(Arc<Mutex<Vec<Captured>>>);
+
+    #[derive(Default)]
+    struct FieldVisitor {
+        phase: String,
+        elapsed_ms: u64,
+    }
+
+    impl Visit for FieldVisitor {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "phase" {
+                self.phase = value.to_owned();
+            }
+        }
+
+        fn record_u64(&mut self, field: &Field, value: u64) {
+            if field.name() == "elapsed_ms" {
+                self.elapsed_ms = value;
+            }
+        }
+
+        fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+    }
+
+    impl<S> Layer<S> for CaptureLayer
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        fn on_event(&self, event: &tracing::Event<'_>, ctx: Context<'_, S>) {
+            let mut visitor = FieldVisitor::default();
+            event.record(&mut visitor);
+            self.0.lock().unwrap().push(Captured {
+                span: ctx.event_span(event).map(|span| span.name().to_owned()),
+                phase: visitor.phase,
+                elapsed_ms: visitor.elapsed_ms,
+            });
+        }
+    }
+
+    fn capture_layer() -> (CaptureLayer, impl tracing::Subscriber) {
+        let layer = CaptureLayer::default();
+        let subscriber = tracing_subscriber::registry().with(layer.clone());
+        (layer, subscriber)
+    }
+
+    #[test]
+    fn phase_is_logged_inside_the_init_timing_span() {
+        let (layer, subscriber) = capture_layer();
+        tracing::subscriber::with_default(subscriber, || {
+            log_phase("pool_create", Duration::from_millis(1_234));
+        });
+
+        assert_eq!(
+            *layer.0.lock().unwrap(),
+            vec![Captured {
+                span: Some("init_timing".to_owned()),
+                phase: "pool_create".to_owned(),
+                elapsed_ms: 1_234,
+            }]
+        );
+    }
+
+    // 웜 컨테이너의 이후 실행은 기록하지 않음
+    #[test]
+    fn first_use_phases_are_logged_once() {
+        let first_use = FirstUse::new();
+        let (layer, subscriber) = capture_layer();
+        tracing::subscriber::with_default(subscriber, || {
+            log_first(&first_use, "first_query", Duration::from_millis(5));
+            log_first(&first_use, "first_query", Duration::from_millis(6));
+        });
+
+        let events = layer.0.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].elapsed_ms, 5);
+    }
+
+    #[tokio::test]
+    async fn timed_returns_the_output_and_logs_the_elapsed_time() {
+        let (layer, subscriber) = capture_layer();
+        let _default = tracing::subscriber::set_default(subscriber);
+
+        let output = timed("secrets", async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            "air-key"
+        })
+        .await;
+
+        assert_eq!(output, "air-key");
+        let events = layer.0.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].phase, "secrets");
+        assert!(events[0].elapsed_ms >= 20, "{:?}", events[0]);
+    }
+}
@@ -0,0 +1,184 @@
+// src/inline_stations.rs
+
+// payload 로 받은 측정소 목록 (stations: [{"subRegionId": 12, "pmStation": "중구"}, ...])
+// 재해 복구 훈련처럼 sub_region 행이 아직 없는 DB 로 수집할 때 sub_region 조회 대신 사용
+// 목록은 수집 전에 검증 (비어 있지 않은 목록, 측정소 이름 필수, sub_region_id 중복 불가, 최대 MAX_INLINE_STATIONS 개)
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+use crate::handler::SubRegionInfo;
+use crate::provider::airkorea::AIRKOREA_PROVIDER_KEY;
+
+pub const MAX_INLINE_STATIONS: usize = 1000;
+
+// 측정소 목록 출처 (meta.stationListSource)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StationListSource {
+    #[default]
+    Db,
+    Payload,
+}
+
+impl StationListSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StationListSource::Db => "db",
+            StationListSource::Payload => "payload",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct InlineStation {
+    pub sub_region_id: i32,
+    pub pm_station: String,
+}
+
+impl InlineStation {
+    // 에어코리아 측정소 이름으로 조회 (좌표 / 격자 / provider 는 기본값)
+    pub fn to_sub_region(&self) -> SubRegionInfo {
+        SubRegionInfo {
+            sub_region_id: self.sub_region_id,
+            pm_station: Some(self.pm_station.trim().to_owned()),
+            tm_x: None,
+            tm_y: None,
+            provider: AIRKOREA_PROVIDER_KEY.to_owned(),
+            nx: None,
+            ny: None,
+            is_active: None,
+            blank_station: false,
+        }
+    }
+}
+
+// payload 의 stations 필드 (없으면 None, 형식이 다르거나 검증에 실패하면 오류)
+pub fn inline_stations_from_payload(
+    payload: &serde_json::Value,
+) -> Result<Option<Vec<InlineStation>>> {
+    let Some(value) = payload.get("stations") else {
+        return Ok(None);
+    };
+    let stations = Vec::<InlineStation>::deserialize(value)
+        .map_err(|e| anyhow!("stations 형식 오류: {}", e))?;
+    validate(&stations)?;
+    Ok(Some(stations))
+}
+
+fn validate(stations: &[InlineStation]) -> Result<()> {
+    if stations.is_empty() {
+        return Err(anyhow!("stations 가 비어 있음"));
+    }
+    if stations.len() > MAX_INLINE_STATIONS {
+        return Err(anyhow!(
+            "stations 는 최대 {} 개 (받은 개수: {})",
+            MAX_INLINE_STATIONS,
+            stations.len()
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    for station in stations {
+        if station.pm_station.trim().is_empty() {
+            return Err(anyhow!(
+                "sub_region {} : pmStation 이 비어 있음",
+                station.sub_region_id
+            ));
+        }
+        if !seen.insert(station.sub_region_id) {
+            return Err(anyhow!(
+                "sub_region {} : subRegionId 중복",
+                station.sub_region_id
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse(stations: serde_json::Value) -> Result<Option<Vec<InlineStation>>> {
+        inline_stations_from_payload(&json!({ "stations": stations }))
+    }
+
+    #[test]
+    fn absent_field_means_the_db_list() {
+        assert_eq!(inline_stations_from_payload(&json!({})).unwrap(), None);
+    }
+
+    #[test]
+    fn parses_stations_and_maps_them_to_airkorea_sub_regions() {
+        let stations = parse(json!([
+            { "subRegionId": 12, "pmStation": " 중구 " },
+            { "subRegionId": 13, "pmStation": "종로구" },
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(stations.len(), 2);
+
+        let sub_region = stations[0].to_sub_region();
+        assert_eq!(sub_region.sub_region_id, 12);
+        assert_eq!(sub_region.pm_station.as_deref(), Some("중구"));
+        assert_eq!(sub_region.provider, AIRKOREA_PROVIDER_KEY);
+        assert_eq!((sub_region.tm_x, sub_region.nx), (None, None));
+        assert!(!sub_region.blank_station);
+    }
+
+    #[test]
+    fn rejects_malformed_lists() {
+        for (stations, expected) in [
+            (json!({}), "stations 형식 오류"),
+            (
+                json!([{ "subRegionId": "12", "pmStation": "중구" }]),
+                "stations 형식 오류",
+            ),
+            (json!([{ "subRegionId": 12 }]), "stations 형식 오류"),
+            (
+                json!([{ "subRegionId": 12, "pmStation": "중구", "provider": "openaq" }]),
+                "stations 형식 오류",
+            ),
+            (json!([]), "stations 가 비어 있음"),
+            (
+                json!([{ "subRegionId": 12, "pmStation": "  " }]),
+                "sub_region 12 : pmStation 이 비어 있음",
+            ),
+            (
+                json!([
+                    { "subRegionId": 12, "pmStation": "중구" },
+                    { "subRegionId": 12, "pmStation": "종로구" },
+                ]),
+                "sub_region 12 : subRegionId 중복",
+            ),
+        ] {
+            let e = parse(stations.clone()).unwrap_err().to_string();
+            assert!(e.starts_with(expected), "{} : {}", stations, e);
+        }
+    }
+
+    #[test]
+    fn limits_the_list_size() {
+        let stations = |count: usize| {
+            (0..count)
+                .map(|i| json!({ "subRegionId": i, "pmStation": format!("측정소{}", i) }))
+                .collect::<serde_json::Value>()
+        };
+        assert!(parse(stations(MAX_INLINE_STATIONS)).is_ok());
+        assert_eq!(
+            parse(stations(MAX_INLINE_STATIONS + 1))
+                .unwrap_err()
+                .to_string(),
+            "stations 는 최대 1000 개 (받은 개수: 1001)"
+        );
+    }
+
+    #[test]
+    fn source_names() {
+        assert_eq!(StationListSource::default().as_str(), "db");
+        assert_eq!(StationListSource::Payload.as_str(), "payload");
+    }
+}
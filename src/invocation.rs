@@ -0,0 +1,137 @@
+// src/invocation.rs
+
+// Lambda 호출 정보 (LambdaEvent::context 에서 추출)
+// AWS 요청 ID 는 루트 span 과 응답 meta.awsRequestId 에 기록하여 사용자 문의를 CloudWatch 로그와 연결하고,
+// 호출 제한 시각은 응답을 쓸 여유(DEADLINE_MARGIN)를 뺀 수집 마감 시각으로 FetchOptions.deadline 에 전달
+
+use std::time::Duration;
+#[cfg(feature = "lambda")]
+use std::time::SystemTime;
+
+#[cfg(feature = "lambda")]
+use lambda_runtime::Context;
+
+// 제한 시각 전에 결과 저장 / 응답 반환에 남겨 두는 시간
+pub const DEADLINE_MARGIN: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone)]
+pub struct InvocationInfo {
+    pub aws_request_id: String,
+    pub invoked_function_arn: String,
+    pub function_version: String,
+    // 호출 제한 시각 (epoch 밀리초)
+    pub deadline_millis: u64,
+    // 추출 시점 기준 남은 실행 시간 (제한 시각이 지났으면 None)
+    pub remaining: Option<Duration>,
+    // 수집 마감 시각 (제한 시각 - DEADLINE_MARGIN)
+    pub deadline: Option<tokio::time::Instant>,
+}
+
+impl InvocationInfo {
+    #[cfg(feature = "lambda")]
+    pub fn from_context(context: &Context, now: SystemTime) -> Self {
+        let remaining = context.deadline().duration_since(now).ok();
+        InvocationInfo {
+            aws_request_id: context.request_id.clone(),
+            invoked_function_arn: context.invoked_function_arn.clone(),
+            function_version: context.env_config.version.clone(),
+            deadline_millis: context.deadline,
+            remaining,
+            deadline: remaining.map(|remaining| {
+                tokio::time::Instant::now() + remaining.saturating_sub(DEADLINE_MARGIN)
+            }),
+        }
+    }
+
+    // 응답 meta 에 AWS 요청 ID 기록 (meta 가 있는 응답만)
+    pub fn attach(&self, response: &mut serde_json::Value) {
+        if let Some(meta) = response.get_mut("meta").and_then(|v| v.as_object_mut()) {
+            meta.insert(
+                "awsRequestId".to_owned(),
+                serde_json::json!(self.aws_request_id),
+            );
+        }
+    }
+}
+
+#[cfg(all(test, feature = "lambda"))]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::UNIX_EPOCH;
+
+    fn context(deadline_millis: u64) -> Context {
+        let mut context = Context::default();
+        context.request_id = "aws-req-1".to_owned();
+        context.deadline = deadline_millis;
+        context.invoked_function_arn =
+            "arn:aws:lambda:ap-northeast-2:1:function:pm-ingest".to_owned();
+        context.env_config = Arc::new(lambda_runtime::Config {
+            version: "7".to_owned(),
+            ..Default::default()
+        });
+        context
+    }
+
+    #[tokio::test]
+    async fn extracts_ids_and_leaves_the_margin_before_the_deadline() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let deadline_millis = 1_700_000_000_000 + 10_000;
+        let before = tokio::time::Instant::now();
+        let info = InvocationInfo::from_context(&context(deadline_millis), now);
+
+        assert_eq!(info.aws_request_id, "aws-req-1");
+        assert_eq!(
+            info.invoked_function_arn,
+            "arn:aws:lambda:ap-northeast-2:1:function:pm-ingest"
+        );
+        assert_eq!(info.function_version, "7");
+        assert_eq!(info.deadline_millis, deadline_millis);
+        assert_eq!(info.remaining, Some(Duration::from_secs(10)));
+
+        // 남은 10초 - 여유 3초
+        let until_deadline = info.deadline.unwrap() - before;
+        assert!(
+            until_deadline >= Duration::from_secs(7),
+            "{:?}",
+            until_deadline
+        );
+        assert!(
+            until_deadline < Duration::from_secs(8),
+            "{:?}",
+            until_deadline
+        );
+    }
+
+    #[tokio::test]
+    async fn remaining_shorter_than_the_margin_ends_collection_now() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let info = InvocationInfo::from_context(&context(1_700_000_001_000), now);
+
+        assert_eq!(info.remaining, Some(Duration::from_secs(1)));
+        assert!(info.deadline.unwrap() <= tokio::time::Instant::now());
+    }
+
+    #[tokio::test]
+    async fn passed_deadline_has_no_remaining_time() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let info = InvocationInfo::from_context(&context(1_699_999_999_000), now);
+
+        assert_eq!(info.remaining, None);
+        assert_eq!(info.deadline, None);
+    }
+
+    #[test]
+    fn attach_writes_aws_request_id_only_into_meta() {
+        let info = InvocationInfo::from_context(&context(0), UNIX_EPOCH);
+
+        let mut response = serde_json::json!({ "meta": { "runId": "run-1" } });
+        info.attach(&mut response);
+        assert_eq!(response["meta"]["awsRequestId"], "aws-req-1");
+        assert_eq!(response["meta"]["runId"], "run-1");
+
+        let mut without_meta = serde_json::json!({ "statusCode": 200 });
+        info.attach(&mut without_meta);
+        assert_eq!(without_meta, serde_json::json!({ "statusCode": 200 }));
+    }
+}
@@ -0,0 +1,130 @@
+// src/last_seen.rs
+
+// 측정소별 마지막 측정 시각 저장소: 이번 실행에서 recorded_at 이 갱신된 측정소 수(advancedCount)를 세어
+// 전체 수집에서 하나도 갱신되지 않으면 제공처 데이터가 멈춘 것(dataFrozen)으로 경고
+// PM_LAST_SEEN_STORE 환경 변수로 비교 기준 선택
+//   db (기본): {PM_DB_SCHEMA}.external_pm 에 저장된 recorded_at
+//   memory: warm 컨테이너 메모리에 남긴 이전 실행 결과 (DB 조회 없이, 콜드 스타트 직후에는 비교 불가)
+//   off: 집계하지 않음
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use crate::handler::{StationResult, StationStatus};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LastSeenStore {
+    #[default]
+    Db,
+    Memory,
+    Off,
+}
+
+impl LastSeenStore {
+    // 알 수 없는 값은 기본값(db)
+    pub fn from_env() -> Self {
+        match std::env::var("PM_LAST_SEEN_STORE").as_deref() {
+            Ok("memory") => LastSeenStore::Memory,
+            Ok("off") => LastSeenStore::Off,
+            _ => LastSeenStore::Db,
+        }
+    }
+}
+
+type MemoryStore = Mutex<HashMap<i32, DateTime<Utc>>>;
+
+fn memory_store() -> &'static MemoryStore {
+    static STORE: OnceLock<MemoryStore> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// 메모리 저장소의 이전 실행 측정 시각
+pub fn memory_snapshot() -> HashMap<i32, DateTime<Utc>> {
+    memory_store()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+// 이번 실행의 성공 결과를 메모리 저장소에 기록
+pub fn remember(results: &[StationResult]) {
+    let mut store = memory_store().lock().unwrap_or_else(|e| e.into_inner());
+    for result in results {
+        if let Some(recorded_at) = recorded_at(result) {
+            store.insert(result.sub_region_id, recorded_at);
+        }
+    }
+}
+
+// 성공 결과의 측정 시각 (dataTime)
+pub fn recorded_at(result: &StationResult) -> Option<DateTime<Utc>> {
+    match &result.status {
+        StationStatus::Success(data) => serde_json::from_value(data["dataTime"].clone()).ok(),
+        StationStatus::Failed { .. } => None,
+    }
+}
+
+// 이전 측정 시각보다 새로운(또는 이전 값이 없는) 측정값을 받은 측정소 수
+pub fn count_advanced(results: &[StationResult], previous: &HashMap<i32, DateTime<Utc>>) -> usize {
+    results
+        .iter()
+        .filter(|result| {
+            recorded_at(result).is_some_and(|recorded_at| {
+                previous
+                    .get(&result.sub_region_id)
+                    .is_none_or(|previous| recorded_at > *previous)
+            })
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::failure::FailureKind;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 16, hour, 0, 0).unwrap()
+    }
+
+    fn success(sub_region_id: i32, recorded_at: DateTime<Utc>) -> StationResult {
+        StationResult::success(sub_region_id, "중구", json!({ "dataTime": recorded_at }))
+    }
+
+    #[test]
+    fn recorded_at_reads_data_time_of_successes_only() {
+        assert_eq!(recorded_at(&success(1, at(3))), Some(at(3)));
+        let failed = StationResult::failed(1, "중구", FailureKind::NoData, "no data".to_owned());
+        assert_eq!(recorded_at(&failed), None);
+    }
+
+    #[test]
+    fn only_newer_or_unseen_readings_count_as_advanced() {
+        let previous = HashMap::from([(1, at(3)), (2, at(3)), (3, at(4))]);
+        let results = vec![
+            // 갱신됨
+            success(1, at(4)),
+            // 같은 시각
+            success(2, at(3)),
+            // 이전보다 과거
+            success(3, at(3)),
+            // 이전 값 없음
+            success(4, at(1)),
+            StationResult::failed(5, "종로구", FailureKind::NoData, "no data".to_owned()),
+        ];
+        assert_eq!(count_advanced(&results, &previous), 2);
+        assert_eq!(count_advanced(&[], &previous), 0);
+    }
+
+    #[test]
+    fn remembered_readings_become_the_next_baseline() {
+        remember(&[success(9001, at(5))]);
+        let snapshot = memory_snapshot();
+        assert_eq!(snapshot.get(&9001), Some(&at(5)));
+        assert_eq!(count_advanced(&[success(9001, at(5))], &snapshot), 0);
+        assert_eq!(count_advanced(&[success(9001, at(6))], &snapshot), 1);
+    }
+}
@@ -0,0 +1,292 @@
+// src/latest_cache.rs
+
+// 최신 측정값 Redis(ElastiCache) 캐시 (REDIS_URL 설정 시)
+// 조회 API 가 매 요청마다 Postgres 를 읽지 않도록 저장에 성공한 sub_region 별 결과를
+// pm:latest:{sub_region_id} 에 JSON 으로 기록 (TTL 2시간)
+// DB 저장이 기준이므로 Redis 실패는 수집 실패가 아닌 meta 의 실패 건수로만 기록
+
+use anyhow::{anyhow, Result};
+use redis::aio::{ConnectionManager, ConnectionManagerConfig};
+use std::time::Duration;
+use tokio::sync::OnceCell;
+use tracing::{info, warn};
+
+use crate::handler::{IngestReport, StationStatus};
+
+pub const LATEST_KEY_PREFIX: &str = "pm:latest:";
+
+// 최신 값 보관 시간 (수집 주기 1시간 기준 두 번 실패하면 만료)
+const LATEST_TTL_SECS: u64 = 2 * 60 * 60;
+
+// 파이프라인 1회에 보내는 SET 명령 수
+const PIPELINE_BATCH_SIZE: usize = 50;
+
+// 연결/응답 제한 시간과 재연결 횟수 (기본 설정은 재시도 간격이 최대 60초라 Redis 장애 시
+// 연결 시도만 몇 분이 걸려 Lambda 제한 시간을 넘기므로 짧게 제한)
+const REDIS_TIMEOUT: Duration = Duration::from_secs(2);
+const REDIS_CONNECT_RETRIES: usize = 2;
+const REDIS_RETRY_MAX_DELAY_MS: u64 = 500;
+
+pub fn latest_key(sub_region_id: i32) -> String {
+    format!("{}{}", LATEST_KEY_PREFIX, sub_region_id)
+}
+
+// ServerState 는 호출마다 새로 만들어지므로 연결은 warm 컨테이너 전체에서 하나만 유지
+fn connection_cell() -> &'static OnceCell<ConnectionManager> {
+    static CONNECTION: OnceCell<ConnectionManager> = OnceCell::const_new();
+    &CONNECTION
+}
+
+// REDIS_URL 이 없으면 비활성, 연결은 첫 기록 시점에 생성
+#[derive(Debug, Clone, Default)]
+pub struct LatestCache {
+    redis_url: Option<String>,
+}
+
+impl LatestCache {
+    pub fn from_env() -> Self {
+        LatestCache {
+            redis_url: std::env::var("REDIS_URL")
+                .ok()
+                .filter(|url| !url.is_empty()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.redis_url.is_some()
+    }
+
+    async fn connection(&self) -> Result<ConnectionManager> {
+        let redis_url = self
+            .redis_url
+            .as_deref()
+            .ok_or_else(|| anyhow!("REDIS_URL 환경 변수 누락"))?;
+
+        let connection = connection_cell()
+            .get_or_try_init(|| async {
+                let client = redis::Client::open(redis_url)?;
+                let config = ConnectionManagerConfig::new()
+                    .set_number_of_retries(REDIS_CONNECT_RETRIES)
+                    .set_max_delay(REDIS_RETRY_MAX_DELAY_MS)
+                    .set_connection_timeout(REDIS_TIMEOUT)
+                    .set_response_timeout(REDIS_TIMEOUT);
+                let connection = ConnectionManager::new_with_config(client, config).await?;
+                info!("Redis connection established.");
+                Ok::<_, redis::RedisError>(connection)
+            })
+            .await
+            .map_err(|e| anyhow!("Redis 연결 실패: {:?}", e))?;
+
+        // ConnectionManager 는 clone 해도 같은 연결을 공유
+        Ok(connection.clone())
+    }
+
+    // 성공한 측정소 결과를 배치 단위 파이프라인으로 기록, 기록하지 못한 건수 반환
+    pub async fn write_latest(&self, report: &IngestReport) -> usize {
+        if !self.is_enabled() || report.db_read_only {
+            return 0;
+        }
+
+        let entries: Vec<(String, String)> = report
+            .results
+            .iter()
+            .filter_map(|result| match &result.status {
+                StationStatus::Success(data) => {
+                    Some((latest_key(result.sub_region_id), data.to_string()))
+                }
+                StationStatus::Failed { .. } => None,
+            })
+            .collect();
+        if entries.is_empty() {
+            return 0;
+        }
+
+        let mut connection = match self.connection().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                warn!("{:?}", e);
+                return entries.len();
+            }
+        };
+
+        let mut failures = 0;
+        for batch in entries.chunks(PIPELINE_BATCH_SIZE) {
+            let mut pipe = redis::pipe();
+            for (key, value) in batch {
+                pipe.set_ex(key, value, LATEST_TTL_SECS).ignore();
+            }
+
+            let result: redis::RedisResult<()> = pipe.query_async(&mut connection).await;
+            if let Err(e) = result {
+                warn!("Redis 최신 값 기록 실패 ({} 건): {:?}", batch.len(), e);
+                failures += batch.len();
+            }
+        }
+
+        failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::failure::FailureKind;
+    use crate::handler::StationResult;
+    use crate::inline_stations::StationListSource;
+    use crate::phases::Phases;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    // 이 키를 기록하는 명령에는 오류 응답
+    const REJECTED_KEY: &str = "pm:latest:55";
+
+    type Commands = Arc<Mutex<Vec<Vec<String>>>>;
+
+    // 받은 명령을 기록하고 +OK (REJECTED_KEY 는 -ERR) 로 응답하는 Redis 서버
+    async fn fake_redis() -> (String, Commands) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("redis://{}", listener.local_addr().unwrap());
+        let commands: Commands = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = commands.clone();
+        tokio::spawn(async move {
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                let recorded = recorded.clone();
+                tokio::spawn(async move {
+                    let (read, mut write) = socket.into_split();
+                    let mut reader = BufReader::new(read);
+                    let mut line = String::new();
+                    loop {
+                        line.clear();
+                        if reader.read_line(&mut line).await.unwrap_or(0) == 0 {
+                            return;
+                        }
+                        let count: usize = line.trim_end().trim_start_matches('*').parse().unwrap();
+                        let mut command = Vec::with_capacity(count);
+                        for _ in 0..count {
+                            line.clear();
+                            reader.read_line(&mut line).await.unwrap();
+                            let len: usize =
+                                line.trim_end().trim_start_matches('$').parse().unwrap();
+                            let mut arg = vec![0; len + 2];
+                            reader.read_exact(&mut arg).await.unwrap();
+                            arg.truncate(len);
+                            command.push(String::from_utf8(arg).unwrap());
+                        }
+                        let reply: &[u8] = if command.iter().any(|arg| arg == REJECTED_KEY) {
+                            b"-ERR injected\r\n"
+                        } else {
+                            b"+OK\r\n"
+                        };
+                        recorded.lock().unwrap().push(command);
+                        write.write_all(reply).await.unwrap();
+                    }
+                });
+            }
+        });
+
+        (url, commands)
+    }
+
+    fn report(results: Vec<StationResult>) -> IngestReport {
+        IngestReport {
+            run_id: "run-1".to_owned(),
+            results,
+            skipped_fresh: 0,
+            deferred: 0,
+            cache_hits: 0,
+            db_read_only: false,
+            no_sub_regions: false,
+            advanced: None,
+            data_frozen: false,
+            elapsed: Duration::ZERO,
+            warnings: Vec::new(),
+            latest_cache_failures: 0,
+            disabled_sub_regions: None,
+            diagnostics: None,
+            phases: Phases::default(),
+            station_list_source: StationListSource::Db,
+            quota: None,
+        }
+    }
+
+    fn stations(ids: std::ops::RangeInclusive<i32>) -> Vec<StationResult> {
+        ids.map(|id| StationResult::success(id, "중구", json!({ "subRegionId": id })))
+            .collect()
+    }
+
+    // SETEX 명령만 (연결 시 보내는 CLIENT SETINFO 등 제외)
+    fn setex_keys(commands: &Commands) -> Vec<String> {
+        commands
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|command| command[0].eq_ignore_ascii_case("SETEX"))
+            .map(|command| command[1].clone())
+            .collect()
+    }
+
+    // 연결은 프로세스 전역으로 한 번만 만들어지므로 연결 실패와 성공을 한 테스트 안에서 순서대로 확인
+    #[tokio::test]
+    async fn successes_are_written_in_batches_and_failures_are_counted() {
+        // REDIS_URL 미설정이면 비활성
+        let disabled = LatestCache::default();
+        assert!(!disabled.is_enabled());
+        assert_eq!(disabled.write_latest(&report(stations(1..=3))).await, 0);
+
+        // 연결할 수 없으면 기록하려던 건수 전체가 실패 (실패한 연결은 재사용하지 않음)
+        let unreachable = LatestCache {
+            redis_url: Some("redis://127.0.0.1:1".to_owned()),
+        };
+        assert!(unreachable.is_enabled());
+        let start = std::time::Instant::now();
+        assert_eq!(unreachable.write_latest(&report(stations(1..=3))).await, 3);
+        // 재연결 대기로 실행 시간을 잡아먹지 않음
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "{:?}",
+            start.elapsed()
+        );
+
+        let (url, commands) = fake_redis().await;
+        let cache = LatestCache {
+            redis_url: Some(url),
+        };
+
+        // 실패한 측정소는 기록하지 않음
+        let mut results = stations(1..=2);
+        results.push(StationResult::failed(
+            3,
+            "용산구",
+            FailureKind::Timeout,
+            "timeout".to_owned(),
+        ));
+        assert_eq!(cache.write_latest(&report(results)).await, 0);
+        assert_eq!(setex_keys(&commands), vec![latest_key(1), latest_key(2)]);
+        let first = commands
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|command| command[0].eq_ignore_ascii_case("SETEX"))
+            .cloned()
+            .unwrap();
+        assert_eq!(
+            first,
+            vec!["SETEX", "pm:latest:1", "7200", r#"{"subRegionId":1}"#]
+        );
+
+        // 읽기 전용 DB 로 저장하지 못한 실행은 캐시에도 기록하지 않음
+        commands.lock().unwrap().clear();
+        let mut read_only = report(stations(1..=2));
+        read_only.db_read_only = true;
+        assert_eq!(cache.write_latest(&read_only).await, 0);
+        assert!(setex_keys(&commands).is_empty());
+
+        // 50 건 단위 배치: 오류가 난 배치(51~60)만 실패로 집계
+        assert_eq!(cache.write_latest(&report(stations(1..=60))).await, 10);
+        assert_eq!(setex_keys(&commands).len(), 60);
+    }
+}
@@ -0,0 +1,176 @@
+// src/legacy.rs
+
+// axum 버전(original_codes_without_lambda)의 응답 형식
+// responseSchema: "legacy" 요청 시 기존 Data / Meta / ResponseData 구조 그대로 응답
+
+#![allow(non_snake_case)]
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::failure::ErrorBudget;
+use crate::handler::{IngestReport, StationStatus, OUTCOME_DB_READ_ONLY};
+use crate::messages::{message_with, MessageKey};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseData {
+    // 여러 sub_region 이 같은 측정소를 공유하므로 sub_region_id 로 구분
+    #[serde(default)]
+    pub subRegionId: Option<i32>,
+    pub pm10Value: Option<f64>,
+    pub pm25Value: Option<f64>,
+    pub dataTime: Option<DateTime<Utc>>,
+    pub requestedTime: DateTime<Utc>,
+    pub stationName: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Data {
+    pub responseData: Vec<ResponseData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Meta {
+    pub timeTaken: String,
+    pub message: String,
+    pub errorList: Vec<String>,
+}
+
+// 수집 결과를 기존 Data / Meta 로 변환 (Lambda legacy 응답, axum 서버 공통)
+pub fn legacy_response(report: IngestReport) -> (Data, Meta) {
+    let mut response_data: Vec<ResponseData> = Vec::new();
+    let mut error_list = Vec::new();
+    // 오류 메시지 누적 크기 제한 (MAX_ERROR_BYTES)
+    let mut error_budget = ErrorBudget::from_env();
+
+    for result in report.results {
+        match result.status {
+            StationStatus::Success(data) => {
+                if let Ok(data) = serde_json::from_value(data) {
+                    response_data.push(data);
+                }
+            }
+            StationStatus::Failed { message, .. } => {
+                if error_budget.admit(&message) {
+                    error_list.push(message);
+                }
+            }
+        }
+    }
+    if let Some(overflow_message) = error_budget.overflow_message() {
+        error_list.push(overflow_message);
+    }
+
+    let count = response_data.len();
+    (
+        Data {
+            responseData: response_data,
+        },
+        Meta {
+            timeTaken: format!("{:?}", report.elapsed),
+            message: message_with(MessageKey::Success, count),
+            errorList: error_list,
+        },
+    )
+}
+
+// rust_response(Data, Meta) 와 동일한 응답 본문
+// 읽기 전용 DB 로 저장하지 못한 실행은 meta.outcome 으로 구분 (Lambda 에서 503 반환)
+pub fn build_legacy_response_body(report: IngestReport) -> serde_json::Value {
+    let db_read_only = report.db_read_only;
+    let (data, meta) = legacy_response(report);
+
+    let mut response = serde_json::json!({
+        "data": data,
+        "meta": meta,
+    });
+    if db_read_only {
+        response["meta"]["outcome"] = serde_json::json!(OUTCOME_DB_READ_ONLY);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::failure::FailureKind;
+    use crate::handler::StationResult;
+    use crate::inline_stations::StationListSource;
+    use crate::phases::Phases;
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn report() -> IngestReport {
+        let results = vec![
+            StationResult::success(
+                1,
+                "중구",
+                json!({
+                    "subRegionId": 1,
+                    "pm10Value": 42.0,
+                    "pm25Value": 20.0,
+                    "pm10Delta": 2.0,
+                    "pm25Delta": null,
+                    "dataTime": "2024-05-01T04:00:00Z",
+                    "requestedTime": "2024-05-01T04:10:00Z",
+                    "stationName": "중구",
+                    "upstreamLagSeconds": 600,
+                }),
+            ),
+            StationResult::failed(
+                2,
+                "종로구",
+                FailureKind::NoData,
+                "종로구 : No data available in API response.".to_owned(),
+            ),
+            StationResult::success(
+                3,
+                "강남구",
+                json!({
+                    "subRegionId": 3,
+                    "pm10Value": null,
+                    "pm25Value": 15.0,
+                    "dataTime": "2024-05-01T04:00:00Z",
+                    "requestedTime": "2024-05-01T04:10:00Z",
+                    "stationName": "강남구",
+                }),
+            ),
+        ];
+        IngestReport {
+            run_id: "run-1".to_owned(),
+            results,
+            skipped_fresh: 0,
+            deferred: 0,
+            cache_hits: 0,
+            db_read_only: false,
+            no_sub_regions: false,
+            advanced: None,
+            data_frozen: false,
+            elapsed: Duration::from_millis(1234),
+            warnings: Vec::new(),
+            latest_cache_failures: 0,
+            disabled_sub_regions: None,
+            diagnostics: None,
+            phases: Phases::default(),
+            station_list_source: StationListSource::Db,
+            quota: None,
+        }
+    }
+
+    // axum 버전 응답과 같은 모양인지 저장된 응답 본문과 비교 (새 필드가 섞여 들어가지 않음)
+    #[test]
+    fn matches_the_golden_legacy_body() {
+        let golden: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/golden/legacy_response.json")).unwrap();
+        assert_eq!(build_legacy_response_body(report()), golden);
+    }
+
+    #[test]
+    fn read_only_run_adds_only_the_outcome() {
+        let mut report = report();
+        report.db_read_only = true;
+        let body = build_legacy_response_body(report);
+        assert_eq!(body["meta"]["outcome"], OUTCOME_DB_READ_ONLY);
+        assert_eq!(body["meta"].as_object().unwrap().len(), 4);
+    }
+}
@@ -0,0 +1,54 @@
+// src/lib.rs
+
+// Lambda 바이너리(main.rs)와 로컬 실행용 CLI(bin/cli.rs)가 공유하는 수집 로직
+
+pub mod api_quota;
+pub mod backfill;
+pub mod clock;
+pub mod combined;
+pub mod compression;
+pub mod db_conn;
+pub mod db_error;
+pub mod db_schema;
+pub mod diagnostics;
+pub mod effective_config;
+pub mod failure;
+pub mod fallback;
+pub mod handler;
+pub mod http_body;
+pub mod idempotency;
+pub mod ingest_guard;
+pub mod init_timing;
+pub mod inline_stations;
+pub mod invocation;
+pub mod last_seen;
+pub mod latest_cache;
+pub mod legacy;
+pub mod messages;
+pub mod metrics;
+pub mod nearby_station;
+pub mod nodata;
+pub mod notifier;
+pub mod params;
+pub mod phases;
+pub mod provider;
+pub mod raw_replay;
+pub mod rds_iam;
+pub mod reprocess;
+pub mod response_detail;
+pub mod run_lock;
+pub mod schema_check;
+pub mod scrub;
+pub mod secrets;
+pub mod sink;
+pub mod state;
+pub mod station_i18n;
+pub mod stored;
+pub mod stream;
+pub mod sub_region_query;
+pub mod ticker;
+pub mod timeutil;
+pub mod trigger;
+pub mod upserted;
+pub mod validity;
+pub mod weather;
@@ -1,11 +1,12 @@
 // src/main.rs
 
+// 핸들러 future 가 모드별 수집 future 를 모두 포함하여 기본 깊이(128)로는 layout 계산이 끝나지 않음
+#![recursion_limit = "256"]
+
+use environment_lambda::handler;
 use lambda_runtime::{service_fn, Error};
 use tracing_subscriber::EnvFilter;
 
-mod handler;
-mod state;
-
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     // 로깅 초기화
@@ -0,0 +1,207 @@
+// src/messages.rs
+
+// 응답 메시지 언어 (MESSAGE_LANG = "en" | "ko", 기본 en)
+// 응답 meta.message 와 최상위 오류 본문의 message 는 이 목록의 문구만 사용하여 언어를 한 가지로 맞춤
+// (로그 문구와 오류 kind / outcome 값은 언어와 무관)
+
+use std::fmt::Display;
+use std::sync::OnceLock;
+use tracing::warn;
+
+static MESSAGE_LANG: OnceLock<Lang> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    #[default]
+    En,
+    Ko,
+}
+
+impl Lang {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "en" => Some(Lang::En),
+            "ko" => Some(Lang::Ko),
+            _ => None,
+        }
+    }
+
+    // 잘못된 값이면 경고 후 기본값
+    pub fn from_env() -> Self {
+        match std::env::var("MESSAGE_LANG") {
+            Ok(value) => Lang::parse(&value).unwrap_or_else(|| {
+                warn!(
+                    "MESSAGE_LANG 는 \"en\" 또는 \"ko\" 만 가능, en 사용: {}",
+                    value
+                );
+                Lang::En
+            }),
+            Err(_) => Lang::En,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::Ko => "ko",
+        }
+    }
+}
+
+// 현재 언어 (처음 사용할 때 환경 변수에서 한 번 읽음)
+pub fn lang() -> Lang {
+    *MESSAGE_LANG.get_or_init(Lang::from_env)
+}
+
+// 메시지 키 ({} 는 인자 자리)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKey {
+    // 수집 성공 측정소 수
+    Success,
+    NoSubRegions,
+    Unauthorized,
+    InternalServerError,
+    // 요청 값 오류 (상세 내용은 detail 필드)
+    InvalidRequest,
+    InvalidServiceKey,
+    AlreadyRunning,
+    // 커넥션 풀 타임아웃 (503)
+    DbPoolTimeout,
+    // 접속/인증 실패 등 그 외 풀 오류 (500)
+    DbConnectionFailed,
+    MissingSubRegionId,
+    SubRegionNotFound,
+}
+
+impl MessageKey {
+    pub fn template(&self, lang: Lang) -> &'static str {
+        match (self, lang) {
+            (MessageKey::Success, Lang::En) => "SUCCESS: {}",
+            (MessageKey::Success, Lang::Ko) => "성공: {}",
+            (MessageKey::NoSubRegions, Lang::En) => "no sub_regions configured",
+            (MessageKey::NoSubRegions, Lang::Ko) => "설정된 sub_region 없음",
+            (MessageKey::Unauthorized, Lang::En) => "Unauthorized",
+            (MessageKey::Unauthorized, Lang::Ko) => "인증 실패",
+            (MessageKey::InternalServerError, Lang::En) => "Internal Server Error",
+            (MessageKey::InternalServerError, Lang::Ko) => "서버 내부 오류",
+            (MessageKey::InvalidRequest, Lang::En) => "invalid request",
+            (MessageKey::InvalidRequest, Lang::Ko) => "잘못된 요청",
+            (MessageKey::InvalidServiceKey, Lang::En) => {
+                "service key invalid: AIR_QUALITY_API_KEY is not registered"
+            }
+            (MessageKey::InvalidServiceKey, Lang::Ko) => {
+                "서비스 키 오류: AIR_QUALITY_API_KEY 가 등록되지 않음"
+            }
+            (MessageKey::AlreadyRunning, Lang::En) => "another ingest run is in progress",
+            (MessageKey::AlreadyRunning, Lang::Ko) => "다른 수집 실행이 진행 중",
+            (MessageKey::DbPoolTimeout, Lang::En) => {
+                "database connection pool timed out; the database may be overloaded, retry later"
+            }
+            (MessageKey::DbPoolTimeout, Lang::Ko) => {
+                "데이터베이스 커넥션 풀 타임아웃, 잠시 후 다시 시도"
+            }
+            (MessageKey::DbConnectionFailed, Lang::En) => "database connection failed",
+            (MessageKey::DbConnectionFailed, Lang::Ko) => "데이터베이스 연결 실패",
+            (MessageKey::MissingSubRegionId, Lang::En) => {
+                "missing or invalid sub_region_id parameter"
+            }
+            (MessageKey::MissingSubRegionId, Lang::Ko) => {
+                "sub_region_id 파라미터 누락 또는 잘못된 값"
+            }
+            (MessageKey::SubRegionNotFound, Lang::En) => "sub_region_id {} not found",
+            (MessageKey::SubRegionNotFound, Lang::Ko) => "sub_region_id {} 없음",
+        }
+    }
+}
+
+// 지정한 언어의 메시지
+pub fn message_in(lang: Lang, key: MessageKey) -> String {
+    key.template(lang).to_owned()
+}
+
+// 지정한 언어의 메시지 (인자 1개)
+pub fn message_with_in(lang: Lang, key: MessageKey, arg: impl Display) -> String {
+    key.template(lang).replacen("{}", &arg.to_string(), 1)
+}
+
+// 현재 언어의 메시지
+pub fn message(key: MessageKey) -> String {
+    message_in(lang(), key)
+}
+
+// 현재 언어의 메시지 (인자 1개)
+pub fn message_with(key: MessageKey, arg: impl Display) -> String {
+    message_with_in(lang(), key, arg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_KEYS: [MessageKey; 11] = [
+        MessageKey::Success,
+        MessageKey::NoSubRegions,
+        MessageKey::Unauthorized,
+        MessageKey::InternalServerError,
+        MessageKey::InvalidRequest,
+        MessageKey::InvalidServiceKey,
+        MessageKey::AlreadyRunning,
+        MessageKey::DbPoolTimeout,
+        MessageKey::DbConnectionFailed,
+        MessageKey::MissingSubRegionId,
+        MessageKey::SubRegionNotFound,
+    ];
+
+    #[test]
+    fn parse_accepts_only_en_and_ko() {
+        assert_eq!(Lang::parse("en"), Some(Lang::En));
+        assert_eq!(Lang::parse(" KO "), Some(Lang::Ko));
+        assert_eq!(Lang::parse("ja"), None);
+    }
+
+    // 실패한 실행에서 응답에 나가는 문구 (서비스 키 오류 401, 내부 오류 500, 성공 0건)
+    #[test]
+    fn failing_run_messages_in_both_languages() {
+        assert_eq!(
+            message_in(Lang::En, MessageKey::InvalidServiceKey),
+            "service key invalid: AIR_QUALITY_API_KEY is not registered"
+        );
+        assert_eq!(
+            message_in(Lang::Ko, MessageKey::InvalidServiceKey),
+            "서비스 키 오류: AIR_QUALITY_API_KEY 가 등록되지 않음"
+        );
+        assert_eq!(
+            message_in(Lang::En, MessageKey::InternalServerError),
+            "Internal Server Error"
+        );
+        assert_eq!(
+            message_in(Lang::Ko, MessageKey::InternalServerError),
+            "서버 내부 오류"
+        );
+        assert_eq!(
+            message_with_in(Lang::En, MessageKey::Success, 0),
+            "SUCCESS: 0"
+        );
+        assert_eq!(message_with_in(Lang::Ko, MessageKey::Success, 0), "성공: 0");
+        assert_eq!(
+            message_with_in(Lang::Ko, MessageKey::SubRegionNotFound, 42),
+            "sub_region_id 42 없음"
+        );
+    }
+
+    #[test]
+    fn every_key_has_distinct_english_and_korean_text() {
+        for key in ALL_KEYS {
+            let (en, ko) = (key.template(Lang::En), key.template(Lang::Ko));
+            assert!(!en.is_empty() && !ko.is_empty(), "{:?}", key);
+            assert_ne!(en, ko, "{:?}", key);
+            // 인자 자리 수는 두 언어가 같음
+            assert_eq!(
+                en.matches("{}").count(),
+                ko.matches("{}").count(),
+                "{:?}",
+                key
+            );
+        }
+    }
+}
@@ -0,0 +1,193 @@
+// src/metrics.rs
+
+// 수집 지표 (metrics 크레이트)
+// recorder 를 설치하지 않으면 매크로 호출은 아무 것도 하지 않으므로 Lambda 빌드에서는 오버헤드 없음
+// 상시 실행(axum) 배포에서는 prometheus feature 로 빌드한 뒤 install_prometheus_recorder 로 /metrics 에 노출
+
+use std::time::Duration;
+
+use crate::handler::{StationResult, StationStatus};
+
+pub const STATIONS_SUCCEEDED_TOTAL: &str = "stations_succeeded_total";
+pub const STATIONS_FAILED_TOTAL: &str = "stations_failed_total";
+pub const STATION_FETCH_SECONDS: &str = "station_fetch_seconds";
+pub const DB_UPSERT_SECONDS: &str = "db_upsert_seconds";
+
+// 측정소 처리 결과 (실패는 kind 라벨로 구분)
+pub fn record_station_result(result: &StationResult) {
+    match &result.status {
+        StationStatus::Success(_) => ::metrics::counter!(STATIONS_SUCCEEDED_TOTAL).increment(1),
+        StationStatus::Failed { kind, .. } => {
+            ::metrics::counter!(STATIONS_FAILED_TOTAL, "kind" => kind.as_str()).increment(1)
+        }
+    }
+}
+
+// 제공처 API 조회 소요 시간
+pub fn record_fetch(elapsed: Duration) {
+    ::metrics::histogram!(STATION_FETCH_SECONDS).record(elapsed.as_secs_f64());
+}
+
+// upsert 소요 시간 (재시도 포함)
+pub fn record_upsert(elapsed: Duration) {
+    ::metrics::histogram!(DB_UPSERT_SECONDS).record(elapsed.as_secs_f64());
+}
+
+// 전역 Prometheus recorder 설치 (반환된 handle.render() 를 /metrics 응답으로 사용)
+#[cfg(feature = "prometheus")]
+pub fn install_prometheus_recorder() -> anyhow::Result<metrics_exporter_prometheus::PrometheusHandle>
+{
+    metrics_exporter_prometheus::PrometheusBuilder::new()
+        .install_recorder()
+        .map_err(|e| anyhow::anyhow!("Prometheus recorder 설치 실패: {:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::failure::FailureKind;
+    use ::metrics::{
+        Counter, CounterFn, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+        SharedString, Unit,
+    };
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    // 기록된 값을 "이름{라벨=값}" 별로 모으는 recorder
+    #[derive(Default)]
+    struct TestRecorder {
+        values: Arc<Mutex<HashMap<String, Vec<f64>>>>,
+    }
+
+    struct Handle {
+        key: String,
+        values: Arc<Mutex<HashMap<String, Vec<f64>>>>,
+    }
+
+    impl Handle {
+        fn push(&self, value: f64) {
+            self.values
+                .lock()
+                .unwrap()
+                .entry(self.key.clone())
+                .or_default()
+                .push(value);
+        }
+    }
+
+    impl CounterFn for Handle {
+        fn increment(&self, value: u64) {
+            self.push(value as f64);
+        }
+
+        fn absolute(&self, value: u64) {
+            self.push(value as f64);
+        }
+    }
+
+    impl HistogramFn for Handle {
+        fn record(&self, value: f64) {
+            self.push(value);
+        }
+    }
+
+    impl TestRecorder {
+        fn handle(&self, key: &Key) -> Arc<Handle> {
+            let labels: Vec<String> = key
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect();
+            Arc::new(Handle {
+                key: format!("{}{{{}}}", key.name(), labels.join(",")),
+                values: self.values.clone(),
+            })
+        }
+
+        fn values(&self, key: &str) -> Vec<f64> {
+            self.values
+                .lock()
+                .unwrap()
+                .get(key)
+                .cloned()
+                .unwrap_or_default()
+        }
+    }
+
+    impl Recorder for TestRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::from_arc(self.handle(key))
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::from_arc(self.handle(key))
+        }
+    }
+
+    #[test]
+    fn station_results_are_counted_by_outcome_and_failure_kind() {
+        let recorder = TestRecorder::default();
+        ::metrics::with_local_recorder(&recorder, || {
+            record_station_result(&StationResult::success(1, "중구", serde_json::json!({})));
+            record_station_result(&StationResult::success(2, "종로구", serde_json::json!({})));
+            record_station_result(&StationResult::failed(
+                3,
+                "용산구",
+                FailureKind::Timeout,
+                "timeout".to_owned(),
+            ));
+        });
+
+        assert_eq!(
+            recorder.values("stations_succeeded_total{}"),
+            vec![1.0, 1.0]
+        );
+        assert_eq!(
+            recorder.values("stations_failed_total{kind=TIMEOUT}"),
+            vec![1.0]
+        );
+    }
+
+    #[test]
+    fn durations_are_recorded_in_seconds() {
+        let recorder = TestRecorder::default();
+        ::metrics::with_local_recorder(&recorder, || {
+            record_fetch(Duration::from_millis(250));
+            record_upsert(Duration::from_millis(1500));
+        });
+
+        assert_eq!(recorder.values("station_fetch_seconds{}"), vec![0.25]);
+        assert_eq!(recorder.values("db_upsert_seconds{}"), vec![1.5]);
+    }
+
+    // 전역 recorder 는 프로세스당 한 번만 설치할 수 있으므로 이 테스트에서만 설치
+    #[cfg(feature = "prometheus")]
+    #[test]
+    fn prometheus_recorder_renders_recorded_metrics() {
+        let handle = install_prometheus_recorder().unwrap();
+        record_station_result(&StationResult::failed(
+            3,
+            "용산구",
+            FailureKind::DbPool,
+            "pool".to_owned(),
+        ));
+        record_fetch(Duration::from_millis(10));
+
+        let rendered = handle.render();
+        assert!(
+            rendered.contains("stations_failed_total{kind=\"DB_POOL\"}"),
+            "{}",
+            rendered
+        );
+        assert!(rendered.contains(STATION_FETCH_SECONDS), "{}", rendered);
+        // 두 번째 설치는 오류
+        assert!(install_prometheus_recorder().is_err());
+    }
+}
@@ -0,0 +1,135 @@
+// src/nearby_station.rs
+
+// [한국환경공단] 측정소정보 조회 API - TM 기준좌표 근접측정소 목록 조회 (getNearbyMsrstnList)
+// pm_station 이 비어 있는 sub_region 의 tm_x/tm_y 로 가장 가까운 측정소를 찾아 sub_region 에 저장
+
+use anyhow::{anyhow, Result};
+use tracing::info;
+
+use crate::db_schema::sql;
+use crate::params::{to_query_pairs, NearbyStationParams};
+use crate::state::ServerState;
+
+pub const NEARBY_STATION_API_URL: &str =
+    "http://apis.data.go.kr/B552584/MsrstnInfoInqireSvc/getNearbyMsrstnList";
+
+pub const UPDATE_SUB_REGION_PM_STATION_QUERY: &str = r#"
+UPDATE {schema}.sub_region
+SET pm_station = $2
+WHERE sub_region_id = $1;
+"#;
+
+// TM 좌표 기준 가장 가까운 측정소 이름 조회 후 {schema}.sub_region 에 캐시
+pub async fn resolve_nearby_station(
+    state: &ServerState,
+    sub_region_id: i32,
+    tm_x: f64,
+    tm_y: f64,
+) -> Result<String> {
+    let params = to_query_pairs(&NearbyStationParams::new(
+        &state.air_quality_api_key(),
+        tm_x,
+        tm_y,
+    ));
+
+    // 측정소 조회와 같은 ApiClient 를 거치므로 한도 사용량 기록/재시도가 동일하게 적용
+    let envelope = state
+        .api_client
+        .fetch_nearby_station(&format!("{},{}", tm_x, tm_y), &params)
+        .await
+        .map_err(|e| anyhow!("Nearby station request failed: {}", e.message))?;
+
+    if !envelope.status.is_success() {
+        return Err(anyhow!(
+            "Nearby station request received non-success status code: {}",
+            envelope.status
+        ));
+    }
+
+    let json_response: serde_json::Value = serde_json::from_str(&envelope.body)
+        .map_err(|e| anyhow!("Failed to parse nearby station response: {:?}", e))?;
+
+    let station_name = parse_nearest_station(&json_response)?;
+
+    // 조회된 측정소 이름을 sub_region 에 저장하여 다음 실행부터는 바로 사용
+    let db_client = state.pool.get().await?;
+    db_client
+        .execute(
+            sql(UPDATE_SUB_REGION_PM_STATION_QUERY).as_str(),
+            &[&sub_region_id, &station_name],
+        )
+        .await?;
+    info!(
+        "sub_region {} : resolved nearby station {}",
+        sub_region_id, station_name
+    );
+
+    Ok(station_name)
+}
+
+// 근접측정소 응답에서 가장 가까운 측정소(items[0].stationName) 추출
+// (API 는 거리순으로 정렬된 목록을 반환)
+pub fn parse_nearest_station(json_response: &serde_json::Value) -> Result<String> {
+    if let Some(result_msg) = json_response
+        .get("response")
+        .and_then(|res| res.get("header"))
+        .and_then(|header| header.get("resultMsg"))
+        .and_then(|msg| msg.as_str())
+    {
+        if result_msg != "NORMAL_CODE" {
+            return Err(anyhow!(
+                "Nearby station API returned an error: {}",
+                result_msg
+            ));
+        }
+    }
+
+    json_response
+        .get("response")
+        .and_then(|res| res.get("body"))
+        .and_then(|body| body.get("items"))
+        .and_then(|items| items.get(0))
+        .and_then(|item| item.get("stationName"))
+        .and_then(|name| name.as_str())
+        .filter(|name| !name.is_empty())
+        .map(|name| name.to_owned())
+        .ok_or_else(|| anyhow!("No nearby station in API response."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response(result_msg: &str, items: serde_json::Value) -> serde_json::Value {
+        json!({
+            "response": {
+                "header": { "resultCode": "00", "resultMsg": result_msg },
+                "body": { "items": items },
+            }
+        })
+    }
+
+    #[test]
+    fn picks_the_first_station_in_distance_order() {
+        let json_response = response(
+            "NORMAL_CODE",
+            json!([{ "stationName": "중구", "tm": 0.8 }, { "stationName": "종로구", "tm": 1.9 }]),
+        );
+        assert_eq!(parse_nearest_station(&json_response).unwrap(), "중구");
+    }
+
+    #[test]
+    fn rejects_error_codes_and_empty_lists() {
+        let error =
+            parse_nearest_station(&response("SERVICE_KEY_IS_NOT_REGISTERED_ERROR", json!([])))
+                .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("SERVICE_KEY_IS_NOT_REGISTERED_ERROR"));
+
+        for items in [json!([]), json!([{ "stationName": "" }]), json!(null)] {
+            assert!(parse_nearest_station(&response("NORMAL_CODE", items)).is_err());
+        }
+    }
+}
@@ -0,0 +1,212 @@
+// src/nodata.rs
+
+// 연속 NO_DATA 측정소 추적 ({schema}.pm_nodata_counter.consecutive_nodata)
+// stationName 이 잘못되면 API 는 NORMAL_CODE / totalCount 0 을 돌려주어 일시적인 데이터 없음과 구분되지 않으므로,
+// sub_region 별로 연속 NO_DATA 실행 수를 세어 NODATA_SUSPEND_THRESHOLD(기본 48)를 넘으면 SUSPECTED_INVALID_STATION 으로 분류
+// NODATA_SUSPEND_SKIP=true 면 카운터를 초기화할 때까지 해당 측정소는 조회하지 않음 (payload 의 resetNodata: [ids])
+// 테이블이 없으면 추적하지 않음
+
+use deadpool_postgres::Client as DbClient;
+use std::collections::HashMap;
+use tokio_postgres::error::SqlState;
+use tracing::{info, warn};
+
+use crate::db_schema::sql;
+use crate::failure::{log_failure, FailureKind};
+use crate::handler::{StationResult, StationStatus};
+
+const DEFAULT_NODATA_SUSPEND_THRESHOLD: i32 = 48;
+
+pub const GET_NODATA_COUNTS_QUERY: &str = r#"
+SELECT sub_region_id, consecutive_nodata
+FROM {schema}.pm_nodata_counter
+WHERE consecutive_nodata > 0;
+"#;
+
+pub const INCREMENT_NODATA_QUERY: &str = r#"
+INSERT INTO {schema}.pm_nodata_counter (sub_region_id, consecutive_nodata, updated_at)
+SELECT sub_region_id, 1, now()
+FROM unnest($1::int[]) AS sub_region_id
+ON CONFLICT (sub_region_id)
+DO UPDATE SET
+    consecutive_nodata = {schema}.pm_nodata_counter.consecutive_nodata + 1,
+    updated_at = now()
+RETURNING sub_region_id, consecutive_nodata;
+"#;
+
+pub const RESET_NODATA_QUERY: &str = r#"
+DELETE FROM {schema}.pm_nodata_counter
+WHERE sub_region_id = ANY($1);
+"#;
+
+// NODATA_SUSPEND_THRESHOLD 환경 변수 (기본 48, 이 값을 넘으면 SUSPECTED_INVALID_STATION)
+pub fn suspend_threshold() -> i32 {
+    std::env::var("NODATA_SUSPEND_THRESHOLD")
+        .ok()
+        .and_then(|v| v.parse::<i32>().ok())
+        .filter(|threshold| *threshold > 0)
+        .unwrap_or(DEFAULT_NODATA_SUSPEND_THRESHOLD)
+}
+
+// NODATA_SUSPEND_SKIP=true 면 임계값을 넘은 측정소는 조회 생략
+pub fn skip_suspected() -> bool {
+    std::env::var("NODATA_SUSPEND_SKIP")
+        .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+        .unwrap_or(false)
+}
+
+// sub_region 별 연속 NO_DATA 실행 수 (테이블이 없거나 조회에 실패하면 빈 목록)
+pub async fn load_counts(client: &DbClient) -> HashMap<i32, i32> {
+    match client
+        .query(sql(GET_NODATA_COUNTS_QUERY).as_str(), &[])
+        .await
+    {
+        Ok(rows) => rows
+            .iter()
+            .filter_map(|row| {
+                let sub_region_id: i32 = row.try_get("sub_region_id").ok()?;
+                let count: i32 = row.try_get("consecutive_nodata").ok()?;
+                Some((sub_region_id, count))
+            })
+            .collect(),
+        Err(e) if e.code() == Some(&SqlState::UNDEFINED_TABLE) => HashMap::new(),
+        Err(e) => {
+            warn!("연속 NO_DATA 카운터 조회 실패, 모든 측정소 조회: {:?}", e);
+            HashMap::new()
+        }
+    }
+}
+
+// 운영자 초기화 (payload 의 resetNodata)
+pub async fn reset(client: &DbClient, sub_region_ids: &[i32]) {
+    if sub_region_ids.is_empty() {
+        return;
+    }
+    match client
+        .execute(sql(RESET_NODATA_QUERY).as_str(), &[&sub_region_ids])
+        .await
+    {
+        Ok(reset_count) => info!(
+            "연속 NO_DATA 카운터 초기화: {} 개 (요청 {:?})",
+            reset_count, sub_region_ids
+        ),
+        Err(e) if e.code() == Some(&SqlState::UNDEFINED_TABLE) => {
+            warn!("pm_nodata_counter 테이블 없음, resetNodata 무시")
+        }
+        Err(e) => warn!("연속 NO_DATA 카운터 초기화 실패: {:?}", e),
+    }
+}
+
+// 실행 결과로 카운터 갱신 (NO_DATA 는 증가, 성공은 초기화)
+// 증가 후 임계값을 넘은 측정소는 결과를 SUSPECTED_INVALID_STATION 으로 바꿈
+pub async fn update_counts(client: &DbClient, results: &mut [StationResult], threshold: i32) {
+    let mut nodata_ids = Vec::new();
+    let mut success_ids = Vec::new();
+    for result in results.iter() {
+        match &result.status {
+            StationStatus::Success(_) => success_ids.push(result.sub_region_id),
+            StationStatus::Failed {
+                kind: FailureKind::NoData,
+                ..
+            } => nodata_ids.push(result.sub_region_id),
+            StationStatus::Failed { .. } => {}
+        }
+    }
+
+    if !success_ids.is_empty() {
+        if let Err(e) = client
+            .execute(sql(RESET_NODATA_QUERY).as_str(), &[&success_ids])
+            .await
+        {
+            if e.code() != Some(&SqlState::UNDEFINED_TABLE) {
+                warn!("연속 NO_DATA 카운터 초기화 실패: {:?}", e);
+            }
+            return;
+        }
+    }
+    if nodata_ids.is_empty() {
+        return;
+    }
+
+    let counts: HashMap<i32, i32> = match client
+        .query(sql(INCREMENT_NODATA_QUERY).as_str(), &[&nodata_ids])
+        .await
+    {
+        Ok(rows) => rows
+            .iter()
+            .filter_map(|row| {
+                let sub_region_id: i32 = row.try_get("sub_region_id").ok()?;
+                let count: i32 = row.try_get("consecutive_nodata").ok()?;
+                Some((sub_region_id, count))
+            })
+            .collect(),
+        Err(e) => {
+            if e.code() != Some(&SqlState::UNDEFINED_TABLE) {
+                warn!("연속 NO_DATA 카운터 증가 실패: {:?}", e);
+            }
+            return;
+        }
+    };
+
+    for result in results.iter_mut() {
+        let is_nodata = matches!(
+            result.status,
+            StationStatus::Failed {
+                kind: FailureKind::NoData,
+                ..
+            }
+        );
+        let Some(&count) = counts.get(&result.sub_region_id) else {
+            continue;
+        };
+        if is_nodata && count > threshold {
+            let kind = FailureKind::SuspectedInvalidStation;
+            let message = suspected_message(&result.pm_station, count, false);
+            log_failure(kind, &message);
+            result.status = StationStatus::Failed { kind, message };
+        }
+    }
+}
+
+// 조회를 생략한 측정소 결과 (NODATA_SUSPEND_SKIP)
+pub fn skipped_result(sub_region_id: i32, pm_station: &str, count: i32) -> StationResult {
+    StationResult::failed(
+        sub_region_id,
+        pm_station,
+        FailureKind::SuspectedInvalidStation,
+        suspected_message(pm_station, count, true),
+    )
+}
+
+fn suspected_message(pm_station: &str, count: i32, skipped: bool) -> String {
+    let action = if skipped { ", skipped" } else { "" };
+    format!(
+        "{} : No data for {} consecutive runs, station name may be invalid{} (reset with resetNodata)",
+        pm_station, count, action
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skipped_stations_are_reported_as_suspected() {
+        let result = skipped_result(7, "중구", 49);
+        assert_eq!(
+            result.failure_kind(),
+            Some(FailureKind::SuspectedInvalidStation)
+        );
+        let StationStatus::Failed { message, .. } = &result.status else {
+            panic!("{:?}", result.status);
+        };
+        assert_eq!(
+            message,
+            "중구 : No data for 49 consecutive runs, station name may be invalid, skipped (reset with resetNodata)"
+        );
+        assert_eq!(
+            suspected_message("중구", 49, false),
+            "중구 : No data for 49 consecutive runs, station name may be invalid (reset with resetNodata)"
+        );
+    }
+}
@@ -0,0 +1,351 @@
+// src/notifier.rs
+
+// 치명적인 실행 결과 웹훅 알림 (WEBHOOK_URL 설정 시, Slack incoming webhook 호환)
+// 서비스 키 오류, DB 접속 불가, 실패율 임계값 초과로 끝난 실행만 알리며,
+// 알림 실패는 로그로만 남기고 응답은 최대 NOTIFY_DEADLINE 까지만 지연
+
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::warn;
+
+// 요청 1회 제한 시간
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(3);
+
+// 실패 시 재시도 횟수
+const WEBHOOK_RETRIES: usize = 1;
+
+// 재시도를 포함한 전체 제한 시간
+const NOTIFY_DEADLINE: Duration = Duration::from_secs(4);
+
+// 알림에 포함하는 오류 종류 수
+const TOP_ERROR_KINDS: usize = 3;
+
+pub const INVALID_SERVICE_KEY_OUTCOME: &str = "INVALID_SERVICE_KEY";
+pub const DB_UNREACHABLE_OUTCOME: &str = "DB_UNREACHABLE";
+pub const FAILURE_RATE_EXCEEDED_OUTCOME: &str = "FAILURE_RATE_EXCEEDED";
+
+// 알림 내용
+#[derive(Debug, Clone)]
+pub struct FatalRun {
+    pub outcome: &'static str,
+    pub run_id: String,
+    pub request_id: String,
+    pub message: String,
+    pub succeeded: usize,
+    pub failed: usize,
+    // (오류 종류, 측정소 수), 많은 순
+    pub top_error_kinds: Vec<(String, usize)>,
+}
+
+impl FatalRun {
+    pub fn new(outcome: &'static str, run_id: &str, request_id: &str, message: String) -> Self {
+        FatalRun {
+            outcome,
+            run_id: run_id.to_owned(),
+            request_id: request_id.to_owned(),
+            message,
+            succeeded: 0,
+            failed: 0,
+            top_error_kinds: Vec::new(),
+        }
+    }
+
+    // 응답 본문의 측정소 수 / meta.errors 의 오류 종류 반영
+    pub fn with_response(
+        mut self,
+        succeeded: usize,
+        failed: usize,
+        response: &serde_json::Value,
+    ) -> Self {
+        self.succeeded = succeeded;
+        self.failed = failed;
+        self.top_error_kinds = top_error_kinds(response, TOP_ERROR_KINDS);
+        self
+    }
+}
+
+// meta.errors 의 kind 별 개수 상위 limit 개 (개수가 같으면 이름순)
+pub fn top_error_kinds(response: &serde_json::Value, limit: usize) -> Vec<(String, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for error in response["meta"]["errors"].as_array().into_iter().flatten() {
+        if let Some(kind) = error["kind"].as_str() {
+            *counts.entry(kind).or_default() += 1;
+        }
+    }
+
+    let mut kinds: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(kind, count)| (kind.to_owned(), count))
+        .collect();
+    kinds.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    kinds.truncate(limit);
+    kinds
+}
+
+// Slack 호환 payload (text 는 알림 미리보기, blocks 는 상세)
+pub fn webhook_payload(run: &FatalRun) -> serde_json::Value {
+    let text = format!("PM ingest {} : {}", run.outcome, run.message);
+
+    let error_kinds = if run.top_error_kinds.is_empty() {
+        "-".to_owned()
+    } else {
+        run.top_error_kinds
+            .iter()
+            .map(|(kind, count)| format!("{} ({})", kind, count))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    json!({
+        "text": text,
+        "blocks": [
+            {
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": format!("*{}*", text) },
+            },
+            {
+                "type": "section",
+                "fields": [
+                    { "type": "mrkdwn", "text": format!("*Succeeded*\n{}", run.succeeded) },
+                    { "type": "mrkdwn", "text": format!("*Failed*\n{}", run.failed) },
+                    { "type": "mrkdwn", "text": format!("*Top errors*\n{}", error_kinds) },
+                    {
+                        "type": "mrkdwn",
+                        "text": format!("*Run*\n{} / {}", run.run_id, run.request_id),
+                    },
+                ],
+            },
+        ],
+    })
+}
+
+pub struct Notifier {
+    http_client: Client,
+    webhook_url: String,
+}
+
+impl Notifier {
+    pub fn new(http_client: Client, webhook_url: impl Into<String>) -> Self {
+        Notifier {
+            http_client,
+            webhook_url: webhook_url.into(),
+        }
+    }
+
+    // WEBHOOK_URL 이 없으면 None
+    pub fn from_env(http_client: Client) -> Option<Self> {
+        let webhook_url = std::env::var("WEBHOOK_URL")
+            .ok()
+            .filter(|url| !url.is_empty())?;
+        Some(Notifier::new(http_client, webhook_url))
+    }
+
+    // 알림 전송 (실패는 로그로만 기록)
+    pub async fn notify(&self, run: &FatalRun) {
+        let payload = webhook_payload(run).to_string();
+        match tokio::time::timeout(NOTIFY_DEADLINE, self.post_with_retry(&payload)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => warn!("{} : 웹훅 알림 실패: {}", run.run_id, e),
+            Err(_) => warn!(
+                "{} : 웹훅 알림 시간 초과 ({:?})",
+                run.run_id, NOTIFY_DEADLINE
+            ),
+        }
+    }
+
+    async fn post_with_retry(&self, payload: &str) -> Result<(), String> {
+        let mut last_error = String::new();
+        for _ in 0..=WEBHOOK_RETRIES {
+            let result = self
+                .http_client
+                .post(&self.webhook_url)
+                .timeout(WEBHOOK_TIMEOUT)
+                .header(CONTENT_TYPE, "application/json")
+                .body(payload.to_owned())
+                .send()
+                .await;
+            match result {
+                Ok(res) if res.status().is_success() => return Ok(()),
+                Ok(res) => last_error = format!("HTTP {}", res.status()),
+                Err(e) => last_error = format!("{:?}", e),
+            }
+        }
+        Err(last_error)
+    }
+}
+
+// WEBHOOK_URL 설정 시 알림 (미설정이면 아무것도 하지 않음)
+pub async fn notify_from_env(http_client: &Client, run: &FatalRun) {
+    if let Some(notifier) = Notifier::from_env(http_client.clone()) {
+        notifier.notify(run).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    // 받은 요청 본문을 기록하고 준비된 상태 코드를 순서대로 돌려주는 로컬 웹훅 서버
+    // (준비된 상태 코드가 다 떨어지면 응답하지 않고 연결만 유지)
+    async fn webhook_server(statuses: Vec<u16>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+        let recorded = bodies.clone();
+        tokio::spawn(async move {
+            let mut statuses = statuses.into_iter();
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let body = read_body(&mut socket).await;
+                recorded.lock().unwrap().push(body);
+                match statuses.next() {
+                    Some(status) => {
+                        let response = format!(
+                            "HTTP/1.1 {} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                            status
+                        );
+                        let _ = socket.write_all(response.as_bytes()).await;
+                    }
+                    None => {
+                        tokio::spawn(async move {
+                            tokio::time::sleep(Duration::from_secs(60)).await;
+                            drop(socket);
+                        });
+                    }
+                }
+            }
+        });
+        (url, bodies)
+    }
+
+    async fn read_body(socket: &mut TcpStream) -> String {
+        let mut request = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = socket.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let content_length = text[..header_end]
+                    .lines()
+                    .find_map(|line| {
+                        line.to_ascii_lowercase()
+                            .strip_prefix("content-length:")
+                            .map(|v| v.trim().parse::<usize>().unwrap())
+                    })
+                    .unwrap_or(0);
+                if request.len() >= header_end + 4 + content_length || n == 0 {
+                    return String::from_utf8_lossy(&request[header_end + 4..]).to_string();
+                }
+            }
+        }
+    }
+
+    fn fatal_run() -> FatalRun {
+        let response = json!({
+            "meta": {
+                "errors": [
+                    { "kind": "TIMEOUT" },
+                    { "kind": "NO_DATA" },
+                    { "kind": "TIMEOUT" },
+                    { "kind": "HTTP_STATUS" },
+                    { "kind": "API_ERROR" },
+                    { "kind": "NO_DATA" },
+                    { "kind": "TIMEOUT" },
+                ],
+            },
+        });
+        FatalRun::new(
+            FAILURE_RATE_EXCEEDED_OUTCOME,
+            "run-1",
+            "req-1",
+            "failure rate 0.700 above threshold 0.5".to_owned(),
+        )
+        .with_response(3, 7, &response)
+    }
+
+    #[test]
+    fn top_error_kinds_are_counted_most_first() {
+        assert_eq!(
+            fatal_run().top_error_kinds,
+            vec![
+                ("TIMEOUT".to_owned(), 3),
+                ("NO_DATA".to_owned(), 2),
+                // 개수가 같으면 이름순
+                ("API_ERROR".to_owned(), 1),
+            ]
+        );
+        assert!(top_error_kinds(&json!({ "meta": {} }), 3).is_empty());
+    }
+
+    #[test]
+    fn payload_has_a_preview_text_and_run_fields() {
+        let payload = webhook_payload(&fatal_run());
+        let text = "PM ingest FAILURE_RATE_EXCEEDED : failure rate 0.700 above threshold 0.5";
+        assert_eq!(payload["text"], text);
+        assert_eq!(payload["blocks"][0]["text"]["text"], format!("*{}*", text));
+        let fields: Vec<&str> = payload["blocks"][1]["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|field| field["text"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                "*Succeeded*\n3",
+                "*Failed*\n7",
+                "*Top errors*\nTIMEOUT (3), NO_DATA (2), API_ERROR (1)",
+                "*Run*\nrun-1 / req-1",
+            ]
+        );
+
+        // 응답 없이 끝난 실행 (서비스 키 오류 등)
+        let payload = webhook_payload(&FatalRun::new(
+            INVALID_SERVICE_KEY_OUTCOME,
+            "run-2",
+            "req-2",
+            "invalid service key".to_owned(),
+        ));
+        assert_eq!(payload["blocks"][1]["fields"][2]["text"], "*Top errors*\n-");
+    }
+
+    #[tokio::test]
+    async fn failed_post_is_retried_once() {
+        let (url, bodies) = webhook_server(vec![500, 200]).await;
+        Notifier::new(Client::new(), url).notify(&fatal_run()).await;
+
+        let bodies = bodies.lock().unwrap();
+        assert_eq!(bodies.len(), 2);
+        let payload: serde_json::Value = serde_json::from_str(&bodies[1]).unwrap();
+        assert_eq!(payload, webhook_payload(&fatal_run()));
+    }
+
+    #[tokio::test]
+    async fn notification_gives_up_after_the_retry() {
+        let (url, bodies) = webhook_server(vec![500, 503, 200]).await;
+        Notifier::new(Client::new(), url).notify(&fatal_run()).await;
+        assert_eq!(bodies.lock().unwrap().len(), WEBHOOK_RETRIES + 1);
+    }
+
+    // 응답하지 않는 웹훅도 실행 응답을 NOTIFY_DEADLINE 이상 지연시키지 않음
+    #[tokio::test]
+    async fn hanging_webhook_is_bounded_by_the_deadline() {
+        let (url, _bodies) = webhook_server(Vec::new()).await;
+        let start = tokio::time::Instant::now();
+        Notifier::new(Client::new(), url).notify(&fatal_run()).await;
+        let elapsed = start.elapsed();
+        assert!(elapsed >= WEBHOOK_TIMEOUT, "{:?}", elapsed);
+        assert!(
+            elapsed < NOTIFY_DEADLINE + Duration::from_millis(500),
+            "{:?}",
+            elapsed
+        );
+    }
+}
@@ -0,0 +1,225 @@
+// src/params.rs
+
+// 엔드포인트별 쿼리 파라미터 (serde 로 직렬화하여 파라미터 이름 오타로 NODATA 가 나는 것을 방지)
+// reqwest 의 .query() 가 serde_urlencoded 로 직렬화하므로 구조체를 그대로 전달
+
+use serde::Serialize;
+
+// 공공데이터포털 서비스 키 정규화
+// 포털의 "Encoding" 키(이미 퍼센트 인코딩됨)를 그대로 넣으면 이중 인코딩되므로 한 번 디코딩한 "Decoding" 키로 통일
+pub fn normalize_service_key(service_key: &str) -> String {
+    if !service_key.contains('%') {
+        return service_key.to_owned();
+    }
+
+    serde_urlencoded::from_str::<Vec<(String, String)>>(&format!("serviceKey={}", service_key))
+        .ok()
+        .and_then(|pairs| pairs.into_iter().next())
+        .map(|(_, decoded)| decoded)
+        .unwrap_or_else(|| service_key.to_owned())
+}
+
+// 구조체를 (이름, 값) 목록으로 변환 (추가 파라미터 병합용, None 필드는 제외)
+pub fn to_query_pairs<T: Serialize>(params: &T) -> Vec<(String, String)> {
+    match serde_json::to_value(params) {
+        Ok(serde_json::Value::Object(fields)) => fields
+            .into_iter()
+            .filter_map(|(key, value)| match value {
+                serde_json::Value::Null => None,
+                serde_json::Value::String(value) => Some((key, value)),
+                value => Some((key, value.to_string())),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// [한국환경공단] 측정소별 실시간 측정정보 조회 (getMsrstnAcctoRltmMesureDnsty)
+#[derive(Debug, Clone, Serialize)]
+pub struct RealtimeParams {
+    #[serde(rename = "serviceKey")]
+    pub service_key: String,
+    #[serde(rename = "returnType")]
+    pub return_type: String,
+    #[serde(rename = "numOfRows")]
+    pub num_of_rows: u32,
+    #[serde(rename = "pageNo")]
+    pub page_no: u32,
+    #[serde(rename = "stationName")]
+    pub station_name: String,
+    #[serde(rename = "dataTerm")]
+    pub data_term: String,
+    pub ver: String,
+}
+
+impl RealtimeParams {
+    pub fn new(service_key: &str, station_name: &str) -> Self {
+        RealtimeParams {
+            service_key: normalize_service_key(service_key),
+            return_type: "json".to_owned(),
+            num_of_rows: 1000,
+            page_no: 1,
+            station_name: station_name.to_owned(),
+            data_term: "DAILY".to_owned(),
+            ver: "1.0".to_owned(),
+        }
+    }
+}
+
+// [한국환경공단] 시도별 실시간 측정정보 조회 (getCtprvnRltmMesureDnsty)
+#[derive(Debug, Clone, Serialize)]
+pub struct ProvinceRealtimeParams {
+    #[serde(rename = "serviceKey")]
+    pub service_key: String,
+    #[serde(rename = "returnType")]
+    pub return_type: String,
+    #[serde(rename = "numOfRows")]
+    pub num_of_rows: u32,
+    #[serde(rename = "pageNo")]
+    pub page_no: u32,
+    #[serde(rename = "sidoName")]
+    pub sido_name: String,
+    pub ver: String,
+}
+
+impl ProvinceRealtimeParams {
+    pub fn new(service_key: &str, sido_name: &str) -> Self {
+        ProvinceRealtimeParams {
+            service_key: normalize_service_key(service_key),
+            return_type: "json".to_owned(),
+            num_of_rows: 1000,
+            page_no: 1,
+            sido_name: sido_name.to_owned(),
+            ver: "1.0".to_owned(),
+        }
+    }
+}
+
+// [한국환경공단] TM 기준좌표 근접측정소 목록 조회 (getNearbyMsrstnList)
+#[derive(Debug, Clone, Serialize)]
+pub struct NearbyStationParams {
+    #[serde(rename = "serviceKey")]
+    pub service_key: String,
+    #[serde(rename = "returnType")]
+    pub return_type: String,
+    #[serde(rename = "tmX")]
+    pub tm_x: f64,
+    #[serde(rename = "tmY")]
+    pub tm_y: f64,
+    pub ver: String,
+}
+
+impl NearbyStationParams {
+    pub fn new(service_key: &str, tm_x: f64, tm_y: f64) -> Self {
+        NearbyStationParams {
+            service_key: normalize_service_key(service_key),
+            return_type: "json".to_owned(),
+            tm_x,
+            tm_y,
+            ver: "1.1".to_owned(),
+        }
+    }
+}
+
+// [기상청] 초단기실황 조회 (getUltraSrtNcst)
+#[derive(Debug, Clone, Serialize)]
+pub struct UltraSrtNcstParams {
+    #[serde(rename = "serviceKey")]
+    pub service_key: String,
+    #[serde(rename = "pageNo")]
+    pub page_no: u32,
+    #[serde(rename = "numOfRows")]
+    pub num_of_rows: u32,
+    #[serde(rename = "dataType")]
+    pub data_type: String,
+    pub base_date: String,
+    pub base_time: String,
+    pub nx: i32,
+    pub ny: i32,
+}
+
+impl UltraSrtNcstParams {
+    pub fn new(service_key: &str, base_date: &str, base_time: &str, nx: i32, ny: i32) -> Self {
+        UltraSrtNcstParams {
+            service_key: normalize_service_key(service_key),
+            page_no: 1,
+            num_of_rows: 1000,
+            data_type: "JSON".to_owned(),
+            base_date: base_date.to_owned(),
+            base_time: base_time.to_owned(),
+            nx,
+            ny,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 쿼리 문자열 스냅샷 (서비스 키는 가려서 비교)
+    fn snapshot<T: Serialize>(params: &T) -> String {
+        serde_urlencoded::to_string(params)
+            .unwrap()
+            .replace("serviceKey=test-key", "serviceKey=***")
+    }
+
+    #[test]
+    fn realtime_query_snapshot() {
+        assert_eq!(
+            snapshot(&RealtimeParams::new("test-key", "중구")),
+            "serviceKey=***&returnType=json&numOfRows=1000&pageNo=1\
+             &stationName=%EC%A4%91%EA%B5%AC&dataTerm=DAILY&ver=1.0"
+        );
+    }
+
+    #[test]
+    fn province_query_snapshot() {
+        assert_eq!(
+            snapshot(&ProvinceRealtimeParams::new("test-key", "서울")),
+            "serviceKey=***&returnType=json&numOfRows=1000&pageNo=1\
+             &sidoName=%EC%84%9C%EC%9A%B8&ver=1.0"
+        );
+    }
+
+    #[test]
+    fn nearby_station_query_snapshot() {
+        assert_eq!(
+            snapshot(&NearbyStationParams::new("test-key", 244148.5, 412423.75)),
+            "serviceKey=***&returnType=json&tmX=244148.5&tmY=412423.75&ver=1.1"
+        );
+    }
+
+    #[test]
+    fn weather_query_snapshot() {
+        assert_eq!(
+            snapshot(&UltraSrtNcstParams::new(
+                "test-key", "20240501", "1300", 60, 127
+            )),
+            "serviceKey=***&pageNo=1&numOfRows=1000&dataType=JSON\
+             &base_date=20240501&base_time=1300&nx=60&ny=127"
+        );
+    }
+
+    #[test]
+    fn encoded_service_key_is_sent_encoded_once() {
+        assert_eq!(normalize_service_key("abc%2Bdef%3D%3D"), "abc+def==");
+        assert_eq!(normalize_service_key("abc+def=="), "abc+def==");
+
+        let query =
+            serde_urlencoded::to_string(RealtimeParams::new("abc%2Bdef%3D%3D", "중구")).unwrap();
+        assert!(
+            query.starts_with("serviceKey=abc%2Bdef%3D%3D&"),
+            "{}",
+            query
+        );
+    }
+
+    #[test]
+    fn query_pairs_keep_every_field_as_strings() {
+        let pairs = to_query_pairs(&UltraSrtNcstParams::new("k", "20240501", "1300", 60, 127));
+        assert_eq!(pairs.len(), 8);
+        assert!(pairs.contains(&("nx".to_owned(), "60".to_owned())));
+        assert!(pairs.contains(&("base_time".to_owned(), "1300".to_owned())));
+    }
+}
@@ -0,0 +1,122 @@
+// src/phases.rs
+
+// 실행 구간별 소요 시간 (응답 meta.phases, 실행 끝에 구조화 로그 한 건)
+// 콜드 스타트가 느릴 때 상태 초기화 / sub_region 조회 / 측정소 조회 / 저장 / 응답 준비 중 어디가 느린지 구분
+// 구간 이름: stateInit, poolWarmup (콜드 스타트만), stationListQuery, fetchPhase, writePhase, reportPhase
+
+use serde_json::json;
+use std::time::Duration;
+use tracing::info;
+
+pub const STATE_INIT: &str = "stateInit";
+pub const POOL_WARMUP: &str = "poolWarmup";
+pub const STATION_LIST_QUERY: &str = "stationListQuery";
+pub const FETCH_PHASE: &str = "fetchPhase";
+pub const WRITE_PHASE: &str = "writePhase";
+pub const REPORT_PHASE: &str = "reportPhase";
+
+// 기록 순서를 유지 (같은 구간을 다시 기록하면 합산)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Phases {
+    entries: Vec<(&'static str, Duration)>,
+}
+
+impl Phases {
+    pub fn record(&mut self, phase: &'static str, elapsed: Duration) {
+        match self.entries.iter_mut().find(|(name, _)| *name == phase) {
+            Some((_, total)) => *total += elapsed,
+            None => self.entries.push((phase, elapsed)),
+        }
+    }
+
+    // {"stationListQuery": 12, ...} (밀리초)
+    pub fn to_json(&self) -> serde_json::Value {
+        self.entries
+            .iter()
+            .map(|(name, elapsed)| ((*name).to_owned(), json!(elapsed.as_millis() as u64)))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+}
+
+// 응답 meta 에 상태 초기화 시간과 콜드 스타트 여부를 더하고 전체 구간을 한 번에 로그로 남김
+// pool_warmup 은 콜드 스타트(상태를 이번 호출에서 초기화)일 때만 기록
+pub fn attach_init_timing(
+    response: &mut serde_json::Value,
+    run_id: &str,
+    state_init: Duration,
+    cold_start: bool,
+    pool_warmup: Option<Duration>,
+) {
+    let Some(meta) = response.get_mut("meta").and_then(|v| v.as_object_mut()) else {
+        return;
+    };
+    meta.insert("coldStart".to_owned(), json!(cold_start));
+    let phases = meta.entry("phases").or_insert_with(|| json!({}));
+    if let Some(phases) = phases.as_object_mut() {
+        phases.insert(STATE_INIT.to_owned(), json!(state_init.as_millis() as u64));
+        if let Some(pool_warmup) = pool_warmup.filter(|_| cold_start) {
+            phases.insert(
+                POOL_WARMUP.to_owned(),
+                json!(pool_warmup.as_millis() as u64),
+            );
+        }
+    }
+    info!(
+        run_id = run_id,
+        cold_start = cold_start,
+        phases = %phases,
+        "Run phases"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_sums_repeated_phases() {
+        let mut phases = Phases::default();
+        phases.record(STATION_LIST_QUERY, Duration::from_millis(12));
+        phases.record(FETCH_PHASE, Duration::from_millis(300));
+        phases.record(STATION_LIST_QUERY, Duration::from_millis(3));
+
+        let json = phases.to_json();
+        assert_eq!(json, json!({ "stationListQuery": 15, "fetchPhase": 300 }));
+        assert_eq!(Phases::default().to_json(), json!({}));
+    }
+
+    #[test]
+    fn init_timing_is_added_to_meta() {
+        let mut response = json!({ "meta": { "phases": { "fetchPhase": 40 } } });
+        attach_init_timing(
+            &mut response,
+            "run-1",
+            Duration::from_millis(250),
+            true,
+            Some(Duration::from_millis(80)),
+        );
+        assert_eq!(response["meta"]["coldStart"], true);
+        assert_eq!(
+            response["meta"]["phases"],
+            json!({ "fetchPhase": 40, "stateInit": 250, "poolWarmup": 80 })
+        );
+
+        // 재사용 상태: poolWarmup 은 남기지 않고, phases 가 없는 응답에도 stateInit 추가
+        let mut response = json!({ "meta": {} });
+        attach_init_timing(
+            &mut response,
+            "run-2",
+            Duration::ZERO,
+            false,
+            Some(Duration::from_millis(80)),
+        );
+        assert_eq!(response["meta"]["coldStart"], false);
+        assert_eq!(response["meta"]["phases"], json!({ "stateInit": 0 }));
+
+        // meta 가 없는 응답은 그대로
+        let mut response = json!({ "statusCode": 400 });
+        attach_init_timing(&mut response, "run-3", Duration::ZERO, true, None);
+        assert_eq!(response, json!({ "statusCode": 400 }));
+    }
+}
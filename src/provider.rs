@@ -0,0 +1,141 @@
+// src/provider.rs
+
+// 대기질 데이터 제공처 추상화
+// sub_region 의 provider 컬럼(기본 'airkorea')으로 측정소별 제공처를 선택하고,
+// 동시성 제어와 upsert 는 제공처와 무관하게 handler 에서 처리
+
+pub mod airkorea;
+pub mod api_client;
+pub mod cache;
+pub mod openaq;
+pub mod rate_limit;
+pub mod raw_sample;
+
+use chrono::{DateTime, Utc};
+use std::future::Future;
+
+use crate::failure::FailureKind;
+
+pub use airkorea::AirKoreaProvider;
+pub use api_client::{ApiClient, ApiEnvelope, MockApiClient, ReqwestApiClient};
+pub use cache::ReadingCache;
+pub use openaq::OpenAqProvider;
+
+// 제공처와 무관한 측정값 (recorded_at 은 정시 단위 UTC)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reading {
+    pub pm10: Option<f64>,
+    pub pm25: Option<f64>,
+    pub recorded_at: DateTime<Utc>,
+    // 상위 API 응답의 Date 헤더 (서버 시각, 헤더가 없거나 API 를 거치지 않은 값이면 None)
+    pub server_time: Option<DateTime<Utc>>,
+    // PM_RAW_SAMPLE_RATE 로 샘플링된 응답의 원문 JSON (그 외에는 None)
+    pub raw: Option<serde_json::Value>,
+    // 유효 범위 밖이라 None 으로 바꾼 값의 수
+    pub rejected_values: u32,
+}
+
+impl Reading {
+    // 상위 데이터 지연 (서버 시각 - 측정 시각, 초)
+    pub fn upstream_lag_seconds(&self) -> Option<i64> {
+        self.server_time
+            .map(|server_time| (server_time - self.recorded_at).num_seconds())
+    }
+}
+
+// 제공처 조회 실패 (kind 는 로그 레벨/재시도 판단에 사용)
+#[derive(Debug, Clone)]
+pub struct FetchError {
+    pub kind: FailureKind,
+    pub message: String,
+}
+
+impl FetchError {
+    pub fn new(kind: FailureKind, message: String) -> Self {
+        FetchError { kind, message }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, FetchError>;
+
+// 쉼표로 구분한 측정소 목록 (두 측정소에 걸친 sub_region 은 "A,B")
+pub fn split_station_refs(station_ref: &str) -> Vec<&str> {
+    station_ref
+        .split(',')
+        .map(str::trim)
+        .filter(|station_ref| !station_ref.is_empty())
+        .collect()
+}
+
+// 여러 측정소의 평균 (항목별로 None 은 제외, 측정 시각은 가장 최근 값)
+pub fn average_readings(readings: &[Reading]) -> Option<Reading> {
+    if let [reading] = readings {
+        return Some(reading.clone());
+    }
+    let recorded_at = readings.iter().map(|reading| reading.recorded_at).max()?;
+
+    let average = |values: Vec<f64>| {
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    };
+    Some(Reading {
+        pm10: average(readings.iter().filter_map(|reading| reading.pm10).collect()),
+        pm25: average(readings.iter().filter_map(|reading| reading.pm25).collect()),
+        recorded_at,
+        server_time: readings
+            .iter()
+            .filter_map(|reading| reading.server_time)
+            .max(),
+        // 여러 응답의 평균이므로 원문 하나로 대표할 수 없음
+        raw: None,
+        rejected_values: readings.iter().map(|reading| reading.rejected_values).sum(),
+    })
+}
+
+pub trait PmProvider: Send + Sync {
+    // sub_region.provider 컬럼 값
+    fn provider_key(&self) -> &str;
+
+    // station_ref: 제공처별 측정소 식별자 (에어코리아: 측정소 이름, OpenAQ: location id)
+    fn fetch(&self, station_ref: &str) -> impl Future<Output = Result<Reading>> + Send;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn reading(hour: u32, server_time: Option<(u32, u32)>) -> Reading {
+        Reading {
+            pm10: Some(40.0),
+            pm25: None,
+            recorded_at: Utc.with_ymd_and_hms(2024, 5, 1, hour, 0, 0).unwrap(),
+            server_time: server_time
+                .map(|(hour, min)| Utc.with_ymd_and_hms(2024, 5, 1, hour, min, 0).unwrap()),
+            raw: None,
+            rejected_values: 0,
+        }
+    }
+
+    #[test]
+    fn upstream_lag_is_server_time_minus_recorded_at() {
+        assert_eq!(reading(4, Some((4, 20))).upstream_lag_seconds(), Some(1200));
+        assert_eq!(reading(4, None).upstream_lag_seconds(), None);
+        // 서버 시계가 측정 시각보다 이르면 음수 그대로
+        assert_eq!(reading(4, Some((3, 59))).upstream_lag_seconds(), Some(-60));
+    }
+
+    #[test]
+    fn average_keeps_the_latest_server_time() {
+        let average = average_readings(&[
+            reading(3, Some((4, 20))),
+            reading(4, None),
+            reading(4, Some((4, 5))),
+        ])
+        .unwrap();
+        assert_eq!(average.recorded_at, reading(4, None).recorded_at);
+        assert_eq!(average.upstream_lag_seconds(), Some(1200));
+
+        let average = average_readings(&[reading(3, None), reading(4, None)]).unwrap();
+        assert_eq!(average.upstream_lag_seconds(), None);
+    }
+}
@@ -0,0 +1,889 @@
+// src/provider/airkorea.rs
+
+// [한국환경공단] 측정소별 실시간 측정정보 조회 API (getMsrstnAcctoRltmMesureDnsty)
+// 시도별 수집 시 시도별 실시간 측정정보 조회 API (getCtprvnRltmMesureDnsty) 한 번으로 시도 내 전체 측정소 조회
+
+use chrono::FixedOffset;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::warn;
+
+use super::raw_sample;
+use super::{ApiClient, ApiEnvelope, FetchError, PmProvider, Reading, Result};
+use crate::failure::FailureKind;
+use crate::http_body::{describe_json_error, truncate_body};
+use crate::params::{to_query_pairs, ProvinceRealtimeParams, RealtimeParams};
+use crate::timeutil::{parse_source_data_time, truncate_to_hour, KST_OFFSET};
+use crate::validity::valid_ranges;
+
+pub const AIRKOREA_PROVIDER_KEY: &str = "airkorea";
+
+// 등록되지 않은(잘못된) 서비스 키 응답 (JSON 이 아닌 XML 로 오는 경우도 있음)
+pub const SERVICE_KEY_NOT_REGISTERED: &str = "SERVICE_KEY_IS_NOT_REGISTERED_ERROR";
+
+pub const AIRKOREA_API_URL: &str =
+    "http://apis.data.go.kr/B552584/ArpltnInforInqireSvc/getMsrstnAcctoRltmMesureDnsty";
+
+pub const AIRKOREA_PROVINCE_API_URL: &str =
+    "http://apis.data.go.kr/B552584/ArpltnInforInqireSvc/getCtprvnRltmMesureDnsty";
+
+// 시도별 응답의 측정소 이름 -> 측정값 (측정값 파싱 실패도 측정소별 결과로 보관)
+pub type ProvinceReadings = HashMap<String, Result<Reading>>;
+
+pub struct AirKoreaProvider {
+    api_client: Arc<dyn ApiClient>,
+    service_key: String,
+    // PM_EXTRA_QUERY_PARAMS 로 추가/재정의할 파라미터
+    extra_query_params: Vec<(String, String)>,
+    // 시도별 수집: 미리 조회한 시도 전체 측정값 (설정 시 측정소별 API 호출 생략)
+    province_readings: Option<ProvinceReadings>,
+    // dataTime 의 시간대 (ServerState.source_offset, 기본 KST)
+    source_offset: FixedOffset,
+    // 원문을 data[].raw 로 남길 성공 응답 비율 (PM_RAW_SAMPLE_RATE)
+    raw_sample_rate: f64,
+}
+
+impl AirKoreaProvider {
+    pub fn new(api_client: Arc<dyn ApiClient>, service_key: String) -> Self {
+        let extra_query_params = std::env::var("PM_EXTRA_QUERY_PARAMS")
+            .map(|v| parse_extra_query_params(&v))
+            .unwrap_or_default();
+
+        AirKoreaProvider {
+            api_client,
+            service_key,
+            extra_query_params,
+            province_readings: None,
+            source_offset: KST_OFFSET,
+            raw_sample_rate: raw_sample::raw_sample_rate(),
+        }
+    }
+
+    pub fn with_source_offset(mut self, source_offset: FixedOffset) -> Self {
+        self.source_offset = source_offset;
+        self
+    }
+
+    pub fn with_raw_sample_rate(mut self, raw_sample_rate: f64) -> Self {
+        self.raw_sample_rate = raw_sample_rate;
+        self
+    }
+
+    pub fn with_province_readings(mut self, province_readings: ProvinceReadings) -> Self {
+        self.province_readings = Some(province_readings);
+        self
+    }
+
+    pub fn province_readings(&self) -> Option<&ProvinceReadings> {
+        self.province_readings.as_ref()
+    }
+
+    // 시도 내 전체 측정소의 최신 측정값을 한 번의 호출로 조회
+    pub async fn fetch_province(&self, sido_name: &str) -> Result<ProvinceReadings> {
+        let params = to_query_pairs(&ProvinceRealtimeParams::new(&self.service_key, sido_name));
+        let envelope = self.api_client.fetch_province(sido_name, &params).await?;
+        let json_response = json_from_envelope(sido_name, &envelope)?;
+
+        parse_province_readings(sido_name, &json_response, self.source_offset)
+    }
+
+    // 측정소 응답의 전체 항목 조회 (DAILY 응답은 최대 24개의 시간별 항목, backfill 모드에서 사용)
+    pub async fn fetch_history(&self, pm_station: &str) -> Result<Vec<Reading>> {
+        let params = self.query_params(pm_station);
+        let envelope = self.api_client.fetch_station(pm_station, &params).await?;
+        let json_response = json_from_envelope(pm_station, &envelope)?;
+
+        parse_all_readings(pm_station, &json_response, self.source_offset)
+    }
+
+    // 기본 파라미터에 추가 파라미터를 덮어씀 (serviceKey, stationName 은 항상 핸들러 값 사용)
+    pub fn query_params(&self, pm_station: &str) -> Vec<(String, String)> {
+        let mut params = to_query_pairs(&RealtimeParams::new(&self.service_key, pm_station));
+
+        for (key, value) in &self.extra_query_params {
+            if key == "serviceKey" || key == "stationName" {
+                continue;
+            }
+            match params.iter_mut().find(|(param, _)| param == key) {
+                Some(param) => param.1 = value.clone(),
+                None => params.push((key.clone(), value.clone())),
+            }
+        }
+
+        params
+    }
+}
+
+// 실패 상태 코드 분류 (429 는 ApiClient 의 Retry-After 재시도를 모두 쓴 뒤)
+fn status_failure_kind(status: StatusCode) -> FailureKind {
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        FailureKind::RateLimited
+    } else {
+        FailureKind::HttpStatus
+    }
+}
+
+// PM_EXTRA_QUERY_PARAMS (JSON 객체, 예: {"ver": "1.3", "dataTerm": "MONTH"}) 파싱
+// 문자열이 아닌 값은 JSON 표현 그대로 사용, 잘못된 형식이면 무시
+pub fn parse_extra_query_params(value: &str) -> Vec<(String, String)> {
+    match serde_json::from_str::<serde_json::Value>(value) {
+        Ok(serde_json::Value::Object(fields)) => fields
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    serde_json::Value::String(value) => value,
+                    value => value.to_string(),
+                };
+                (key, value)
+            })
+            .collect(),
+        _ => {
+            warn!("PM_EXTRA_QUERY_PARAMS 는 JSON 객체여야 함, 무시: {}", value);
+            Vec::new()
+        }
+    }
+}
+
+impl PmProvider for AirKoreaProvider {
+    fn provider_key(&self) -> &str {
+        AIRKOREA_PROVIDER_KEY
+    }
+
+    async fn fetch(&self, pm_station: &str) -> Result<Reading> {
+        // 시도별 수집: 미리 조회한 응답에서 측정소 측정값 반환
+        if let Some(province_readings) = &self.province_readings {
+            return province_readings
+                .get(pm_station)
+                .cloned()
+                .unwrap_or_else(|| {
+                    Err(FetchError::new(
+                        FailureKind::NoData,
+                        format!("{} : Station not found in province response.", pm_station),
+                    ))
+                });
+        }
+
+        // 외부 API 호출 파라미터 설정
+        let params = self.query_params(pm_station);
+
+        // 외부 API 호출 (상태 코드와 무관하게 본문을 읽은 뒤 한 곳에서 결과 분류)
+        let envelope = self.api_client.fetch_station(pm_station, &params).await?;
+
+        // 파싱이 끝나면 원문은 바로 해제 (오류 메시지에는 잘라낸 본문만 보관, 샘플로 선택된 성공 응답만 원문 유지)
+        let server_time = envelope.server_date;
+        let outcome = classify_http_response(
+            pm_station,
+            envelope.status,
+            &envelope.headers,
+            &envelope.body,
+            self.source_offset,
+        );
+        let raw = matches!(outcome, ResponseOutcome::Success(_))
+            .then(|| raw_sample::sample_body(&envelope.body, self.raw_sample_rate))
+            .flatten();
+        drop(envelope);
+
+        match outcome {
+            ResponseOutcome::Success(reading) => Ok(Reading {
+                server_time,
+                raw,
+                ..reading
+            }),
+            outcome => Err(outcome.into_error()),
+        }
+    }
+}
+
+// 서비스 키 / 상태 코드 확인 및 JSON 파싱 (시도별 / 이력 조회)
+fn json_from_envelope(label: &str, envelope: &ApiEnvelope) -> Result<serde_json::Value> {
+    check_envelope(label, envelope.status, &envelope.headers, &envelope.body)
+        .map_err(ResponseOutcome::into_error)
+}
+
+// 응답 분류 결과
+#[derive(Debug)]
+pub enum ResponseOutcome {
+    Success(Reading),
+    // 일시적인 오류 (5xx, 429): 재시도하면 성공할 수 있음
+    Retryable(FetchError),
+    // 데이터 없음 등 측정소 단위의 정상적인 실패
+    SoftFail(FetchError),
+    // 잘못된 요청/키, 파싱 불가 응답
+    HardFail(FetchError),
+}
+
+impl ResponseOutcome {
+    // 실패 분류의 오류 (Success 는 호출하지 않음)
+    fn into_error(self) -> FetchError {
+        match self {
+            ResponseOutcome::Success(_) => FetchError::new(
+                FailureKind::Internal,
+                "unexpected successful response outcome".to_owned(),
+            ),
+            ResponseOutcome::Retryable(e)
+            | ResponseOutcome::SoftFail(e)
+            | ResponseOutcome::HardFail(e) => e,
+        }
+    }
+}
+
+// 상태 코드, 헤더, 본문으로 응답 분류 (본문 내용은 classify_response 로 판단)
+pub fn classify_http_response(
+    pm_station: &str,
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &str,
+    source_offset: FixedOffset,
+) -> ResponseOutcome {
+    let json_response = match check_envelope(pm_station, status, headers, body) {
+        Ok(json_response) => json_response,
+        Err(outcome) => return outcome,
+    };
+
+    match parse_latest_reading(pm_station, &json_response, source_offset) {
+        Ok(reading) => ResponseOutcome::Success(reading),
+        Err(e) if e.kind == FailureKind::NoData => ResponseOutcome::SoftFail(e),
+        Err(e) if e.kind.is_retriable() => ResponseOutcome::Retryable(e),
+        Err(e) => ResponseOutcome::HardFail(e),
+    }
+}
+
+// 서비스 키 오류, 상태 코드, JSON 파싱 확인 (측정소별 / 시도별 응답 공통)
+fn check_envelope(
+    label: &str,
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &str,
+) -> std::result::Result<serde_json::Value, ResponseOutcome> {
+    // 서비스 키 오류는 XML 로 오는 경우도 있으므로 상태 코드/파싱 전에 확인
+    if body.contains(SERVICE_KEY_NOT_REGISTERED) {
+        return Err(ResponseOutcome::HardFail(FetchError::new(
+            FailureKind::InvalidServiceKey,
+            format!(
+                "{} : API returned an error: {}",
+                label, SERVICE_KEY_NOT_REGISTERED
+            ),
+        )));
+    }
+
+    if !status.is_success() {
+        let headers = if headers.is_empty() {
+            String::new()
+        } else {
+            format!("\nHeaders: {:?}", headers)
+        };
+        let error = FetchError::new(
+            status_failure_kind(status),
+            format!(
+                "{} : Received non-success status code: {}{}\nResponse text: {}",
+                label,
+                status,
+                headers,
+                truncate_body(body)
+            ),
+        );
+        return Err(
+            if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+                ResponseOutcome::Retryable(error)
+            } else {
+                ResponseOutcome::HardFail(error)
+            },
+        );
+    }
+
+    // 텍스트를 JSON으로 파싱
+    serde_json::from_str(body).map_err(|e| {
+        // 전송 중 끊긴 본문은 다시 요청하면 성공할 수 있으므로 재시도 대상
+        let (kind, error_message) = describe_json_error(label, &e, body);
+        let error = FetchError::new(kind, error_message);
+        if kind.is_retriable() {
+            ResponseOutcome::Retryable(error)
+        } else {
+            ResponseOutcome::HardFail(error)
+        }
+    })
+}
+
+// 응답에서 최신 측정값(items[0]) 추출
+pub fn parse_latest_reading(
+    pm_station: &str,
+    json_response: &serde_json::Value,
+    source_offset: FixedOffset,
+) -> Result<Reading> {
+    let items = response_items(pm_station, json_response)?;
+
+    // 최신 데이터 추출
+    let item = items.first().ok_or_else(|| no_data_error(pm_station))?;
+
+    parse_item(pm_station, item, source_offset)
+}
+
+// 응답의 전체 항목을 측정값 목록으로 변환 (dataTime 을 파싱할 수 없는 항목은 제외)
+pub fn parse_all_readings(
+    pm_station: &str,
+    json_response: &serde_json::Value,
+    source_offset: FixedOffset,
+) -> Result<Vec<Reading>> {
+    let items = response_items(pm_station, json_response)?;
+
+    let mut readings = Vec::with_capacity(items.len());
+    for item in &items {
+        match parse_item(pm_station, item, source_offset) {
+            Ok(reading) => readings.push(reading),
+            Err(e) => warn!("{}", e.message),
+        }
+    }
+    Ok(readings)
+}
+
+// 시도별 응답의 items 를 측정소 이름별 측정값으로 변환
+pub fn parse_province_readings(
+    sido_name: &str,
+    json_response: &serde_json::Value,
+    source_offset: FixedOffset,
+) -> Result<ProvinceReadings> {
+    let items = response_items(sido_name, json_response)?;
+
+    Ok(items
+        .iter()
+        .filter_map(|item| {
+            let pm_station = item.get("stationName")?.as_str()?.trim();
+            Some((
+                pm_station.to_owned(),
+                parse_item(pm_station, item, source_offset),
+            ))
+        })
+        .collect())
+}
+
+// 응답 본문 중 분류에 필요한 부분 (response.header / response.body)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AirKoreaResponse {
+    // 최상위 response 객체 존재 여부
+    pub has_response: bool,
+    pub result_code: Option<String>,
+    pub result_msg: Option<String>,
+    pub total_count: Option<i64>,
+    // 배열이 아닐 수도 있으므로 원래 값 그대로 보관
+    pub items: Option<serde_json::Value>,
+}
+
+impl AirKoreaResponse {
+    pub fn from_json(json_response: &serde_json::Value) -> Self {
+        let Some(response) = json_response
+            .get("response")
+            .filter(|response| response.is_object())
+        else {
+            return AirKoreaResponse::default();
+        };
+
+        let header = &response["header"];
+        let body = &response["body"];
+        // totalCount 는 숫자로 오지만 문자열인 경우도 허용
+        let total_count = match &body["totalCount"] {
+            serde_json::Value::String(count) => count.trim().parse().ok(),
+            count => count.as_i64(),
+        };
+
+        AirKoreaResponse {
+            has_response: true,
+            result_code: header["resultCode"].as_str().map(str::to_owned),
+            result_msg: header["resultMsg"].as_str().map(str::to_owned),
+            total_count,
+            items: body.get("items").cloned(),
+        }
+    }
+
+    // 헤더가 없으면 정상으로 간주 (기존 동작 유지)
+    fn is_normal_header(&self) -> bool {
+        match (&self.result_code, &self.result_msg) {
+            (None, None) => true,
+            (code, msg) => code.as_deref() == Some("00") || msg.as_deref() == Some("NORMAL_CODE"),
+        }
+    }
+}
+
+// 본문 분류 결과 (정확히 하나)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Classified {
+    Ok(Vec<serde_json::Value>),
+    // 정상 응답이지만 측정값 없음
+    NoData,
+    // 헤더가 오류를 알림 (resultMsg 가 없으면 resultCode)
+    ApiError(String),
+    // 응답 구조를 해석할 수 없음
+    Malformed(String),
+}
+
+// 헤더 코드, totalCount, items 를 함께 보고 응답 분류
+// 우선순위 (위에서부터 먼저 해당하는 결과):
+//   1. response 객체가 없음                              -> Malformed
+//   2. resultMsg 가 서비스 키 오류                          -> ApiError (항목이 있어도 키 오류를 먼저 알림)
+//   3. items 가 배열이 아니거나 객체가 아닌 항목이 있음        -> Malformed
+//   4. items 가 비어 있지 않음                             -> Ok (헤더가 비정상이거나 totalCount 가 0 이어도 항목 사용)
+//   5. 헤더가 비정상                                      -> ApiError
+//   6. totalCount > 0 인데 items 가 없거나 비어 있음         -> Malformed
+//   7. 그 밖 (정상 헤더, totalCount 0 / 없음, items 없음/빈 배열) -> NoData
+pub fn classify_response(response: AirKoreaResponse) -> Classified {
+    if !response.has_response {
+        return Classified::Malformed("response object missing".to_owned());
+    }
+
+    if response.result_msg.as_deref() == Some(SERVICE_KEY_NOT_REGISTERED) {
+        return Classified::ApiError(SERVICE_KEY_NOT_REGISTERED.to_owned());
+    }
+
+    let is_normal_header = response.is_normal_header();
+    let header_error = response
+        .result_msg
+        .or(response.result_code)
+        .unwrap_or_default();
+
+    let items = match response.items {
+        None | Some(serde_json::Value::Null) => Vec::new(),
+        Some(serde_json::Value::Array(items)) => {
+            if !items.iter().all(|item| item.is_object()) {
+                return Classified::Malformed("items contains a non-object entry".to_owned());
+            }
+            items
+        }
+        Some(other) => {
+            return Classified::Malformed(format!("items is not an array: {}", other));
+        }
+    };
+
+    if !items.is_empty() {
+        if !is_normal_header {
+            warn!(
+                "API returned {} with usable items, using items",
+                header_error
+            );
+        }
+        return Classified::Ok(items);
+    }
+
+    if !is_normal_header {
+        return Classified::ApiError(header_error);
+    }
+
+    match response.total_count {
+        Some(total_count) if total_count > 0 => Classified::Malformed(format!(
+            "totalCount is {} but items are missing",
+            total_count
+        )),
+        _ => Classified::NoData,
+    }
+}
+
+// 분류 결과를 항목 목록 또는 FetchError 로 변환
+fn response_items(
+    label: &str,
+    json_response: &serde_json::Value,
+) -> Result<Vec<serde_json::Value>> {
+    match classify_response(AirKoreaResponse::from_json(json_response)) {
+        Classified::Ok(items) => Ok(items),
+        Classified::NoData => Err(no_data_error(label)),
+        Classified::ApiError(error_message) => {
+            let kind = if error_message == SERVICE_KEY_NOT_REGISTERED {
+                FailureKind::InvalidServiceKey
+            } else {
+                FailureKind::ApiError
+            };
+            Err(FetchError::new(
+                kind,
+                format!("{} : API returned an error: {}", label, error_message),
+            ))
+        }
+        Classified::Malformed(reason) => Err(FetchError::new(
+            FailureKind::Parse,
+            format!("{} : Malformed API response: {}", label, reason),
+        )),
+    }
+}
+
+fn no_data_error(label: &str) -> FetchError {
+    FetchError::new(
+        FailureKind::NoData,
+        format!("{} : No data available in API response.", label),
+    )
+}
+
+// items 의 측정값 하나 변환
+fn parse_item(
+    pm_station: &str,
+    item: &serde_json::Value,
+    source_offset: FixedOffset,
+) -> Result<Reading> {
+    let pm10 = item
+        .get("pm10Value")
+        .and_then(|v| v.as_str())
+        .filter(|&v| v != "-")
+        .and_then(|v| v.parse::<f64>().ok());
+
+    let pm25 = item
+        .get("pm25Value")
+        .and_then(|v| v.as_str())
+        .filter(|&v| v != "-")
+        .and_then(|v| v.parse::<f64>().ok());
+
+    let recorded_at = item.get("dataTime").and_then(|v| v.as_str()).unwrap_or("");
+
+    // 측정 시각 파싱 실패를 현재 시각으로 덮지 않고 파싱 오류로 기록
+    let recorded_at_datetime_utc = parse_source_data_time(recorded_at, source_offset)
+        .map(truncate_to_hour)
+        .map_err(|e| {
+            FetchError::new(
+                FailureKind::Parse,
+                format!("{} : Failed to parse dataTime: {}", pm_station, e),
+            )
+        })?;
+
+    // 유효 범위 밖의 값은 NULL 로 저장
+    Ok(valid_ranges().apply(
+        pm_station,
+        Reading {
+            pm10,
+            pm25,
+            recorded_at: recorded_at_datetime_utc,
+            server_time: None,
+            raw: None,
+            rejected_values: 0,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::MockApiClient;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    fn item() -> serde_json::Value {
+        json!({ "dataTime": "2024-05-01 13:00", "pm10Value": "42", "pm25Value": "20" })
+    }
+
+    fn response(
+        result_code: &str,
+        total_count: i64,
+        items: Option<Vec<serde_json::Value>>,
+    ) -> AirKoreaResponse {
+        AirKoreaResponse {
+            has_response: true,
+            result_code: Some(result_code.to_owned()),
+            result_msg: Some(if result_code == "00" {
+                "NORMAL_CODE".to_owned()
+            } else {
+                "SERVICE ERROR".to_owned()
+            }),
+            total_count: Some(total_count),
+            items: items.map(serde_json::Value::Array),
+        }
+    }
+
+    #[test]
+    fn extra_query_params_accept_only_json_objects() {
+        assert_eq!(
+            parse_extra_query_params(r#"{"ver": "1.3", "numOfRows": 24, "flag": true}"#),
+            vec![
+                ("flag".to_owned(), "true".to_owned()),
+                ("numOfRows".to_owned(), "24".to_owned()),
+                ("ver".to_owned(), "1.3".to_owned()),
+            ]
+        );
+        for invalid in [r#"["ver", "1.3"]"#, r#""ver=1.3""#, "ver=1.3", ""] {
+            assert!(parse_extra_query_params(invalid).is_empty(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn extra_query_params_override_defaults_but_not_key_or_station() {
+        let mut provider =
+            AirKoreaProvider::new(Arc::new(MockApiClient::new()), "real-key".to_owned());
+        provider.extra_query_params = parse_extra_query_params(
+            r#"{"ver": "1.3", "dataTerm": "MONTH", "serviceKey": "other", "stationName": "종로구", "extra": "1"}"#,
+        );
+
+        let params: HashMap<_, _> = provider.query_params("중구").into_iter().collect();
+        assert_eq!(params["ver"], "1.3");
+        assert_eq!(params["dataTerm"], "MONTH");
+        assert_eq!(params["extra"], "1");
+        assert_eq!(params["serviceKey"], "real-key");
+        assert_eq!(params["stationName"], "중구");
+        assert_eq!(params["returnType"], "json");
+        // 같은 이름을 덮어쓰므로 파라미터가 중복되지 않음
+        assert_eq!(provider.query_params("중구").len(), params.len());
+    }
+
+    // 헤더(정상/오류) x totalCount(0/1) x items(있음/없음) 8가지 조합
+    #[test]
+    fn classify_response_matrix() {
+        let cases = [
+            ("00", 1, Some(vec![item()]), Classified::Ok(vec![item()])),
+            ("00", 0, Some(vec![item()]), Classified::Ok(vec![item()])),
+            (
+                "00",
+                1,
+                Some(vec![]),
+                Classified::Malformed("totalCount is 1 but items are missing".to_owned()),
+            ),
+            ("00", 0, None, Classified::NoData),
+            ("22", 1, Some(vec![item()]), Classified::Ok(vec![item()])),
+            ("22", 0, Some(vec![item()]), Classified::Ok(vec![item()])),
+            (
+                "22",
+                1,
+                None,
+                Classified::ApiError("SERVICE ERROR".to_owned()),
+            ),
+            (
+                "22",
+                0,
+                Some(vec![]),
+                Classified::ApiError("SERVICE ERROR".to_owned()),
+            ),
+        ];
+
+        for (result_code, total_count, items, expected) in cases {
+            let input = response(result_code, total_count, items);
+            assert_eq!(
+                classify_response(input.clone()),
+                expected,
+                "input: {:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn classify_response_checks_structure_and_service_key_first() {
+        assert!(matches!(
+            classify_response(AirKoreaResponse::from_json(&json!({ "items": [] }))),
+            Classified::Malformed(_)
+        ));
+
+        let mut service_key = response("30", 1, Some(vec![item()]));
+        service_key.result_msg = Some(SERVICE_KEY_NOT_REGISTERED.to_owned());
+        assert_eq!(
+            classify_response(service_key),
+            Classified::ApiError(SERVICE_KEY_NOT_REGISTERED.to_owned())
+        );
+
+        let mut not_array = response("00", 1, None);
+        not_array.items = Some(json!({ "item": item() }));
+        assert!(matches!(
+            classify_response(not_array),
+            Classified::Malformed(_)
+        ));
+
+        let mut non_object = response("00", 1, None);
+        non_object.items = Some(json!([item(), "x"]));
+        assert!(matches!(
+            classify_response(non_object),
+            Classified::Malformed(_)
+        ));
+
+        // 헤더가 없으면 정상 헤더로 간주
+        let mut headerless = response("00", 0, None);
+        headerless.result_code = None;
+        headerless.result_msg = None;
+        assert_eq!(classify_response(headerless), Classified::NoData);
+    }
+
+    fn body(result_code: &str, items: serde_json::Value) -> String {
+        json!({
+            "response": {
+                "header": { "resultCode": result_code, "resultMsg": "MSG" },
+                "body": { "totalCount": items.as_array().map_or(0, Vec::len), "items": items },
+            }
+        })
+        .to_string()
+    }
+
+    // 상태 코드와 본문이 함께 정하는 분류 (서비스 키 오류 > 상태 코드 > JSON 파싱 > 본문 분류)
+    #[test]
+    fn classify_http_response_matrix() {
+        let valid = body("00", json!([item()]));
+        let cases = [
+            (StatusCode::OK, valid.clone(), "success", None),
+            (
+                StatusCode::OK,
+                body("00", json!([])),
+                "soft",
+                Some(FailureKind::NoData),
+            ),
+            (
+                StatusCode::OK,
+                body("22", json!([])),
+                "hard",
+                Some(FailureKind::ApiError),
+            ),
+            (
+                StatusCode::OK,
+                valid[..valid.len() / 2].to_owned(),
+                "retryable",
+                Some(FailureKind::TruncatedBody),
+            ),
+            (
+                StatusCode::OK,
+                "<html>maintenance</html>".to_owned(),
+                "hard",
+                Some(FailureKind::Parse),
+            ),
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                valid.clone(),
+                "retryable",
+                Some(FailureKind::HttpStatus),
+            ),
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                String::new(),
+                "retryable",
+                Some(FailureKind::RateLimited),
+            ),
+            (
+                StatusCode::BAD_REQUEST,
+                valid.clone(),
+                "hard",
+                Some(FailureKind::HttpStatus),
+            ),
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!(
+                    "<returnAuthMsg>{}</returnAuthMsg>",
+                    SERVICE_KEY_NOT_REGISTERED
+                ),
+                "hard",
+                Some(FailureKind::InvalidServiceKey),
+            ),
+        ];
+
+        for (status, body, expected_outcome, expected_kind) in cases {
+            let outcome =
+                classify_http_response("중구", status, &HeaderMap::new(), &body, KST_OFFSET);
+            let (label, kind) = match outcome {
+                ResponseOutcome::Success(_) => ("success", None),
+                ResponseOutcome::Retryable(e) => ("retryable", Some(e.kind)),
+                ResponseOutcome::SoftFail(e) => ("soft", Some(e.kind)),
+                ResponseOutcome::HardFail(e) => ("hard", Some(e.kind)),
+            };
+            assert_eq!(
+                (label, kind),
+                (expected_outcome, expected_kind),
+                "status {} body {}",
+                status,
+                body
+            );
+        }
+    }
+
+    fn provider(mock: MockApiClient) -> AirKoreaProvider {
+        AirKoreaProvider::new(Arc::new(mock), "test-key".to_owned())
+    }
+
+    #[tokio::test]
+    async fn fetch_success_keeps_server_time() {
+        let server_date = Utc.with_ymd_and_hms(2024, 5, 1, 4, 5, 0).unwrap();
+        let provider = provider(
+            MockApiClient::new().with_envelope(
+                "중구",
+                ApiEnvelope::new(StatusCode::OK, body("00", json!([item()])))
+                    .with_server_date(server_date),
+            ),
+        );
+
+        let reading = provider.fetch("중구").await.unwrap();
+        assert_eq!((reading.pm10, reading.pm25), (Some(42.0), Some(20.0)));
+        assert_eq!(
+            reading.recorded_at,
+            Utc.with_ymd_and_hms(2024, 5, 1, 4, 0, 0).unwrap()
+        );
+        assert_eq!(reading.server_time, Some(server_date));
+    }
+
+    #[tokio::test]
+    async fn fetch_maps_no_data_and_error_codes() {
+        let provider = provider(
+            MockApiClient::new()
+                .with_envelope(
+                    "빈응답",
+                    ApiEnvelope::new(StatusCode::OK, body("00", json!([]))),
+                )
+                .with_envelope(
+                    "오류코드",
+                    ApiEnvelope::new(StatusCode::OK, body("22", json!([]))),
+                )
+                .with_envelope(
+                    "점검",
+                    ApiEnvelope::new(StatusCode::BAD_GATEWAY, "bad gateway"),
+                ),
+        );
+
+        let no_data = provider.fetch("빈응답").await.unwrap_err();
+        assert_eq!(no_data.kind, FailureKind::NoData);
+        assert!(no_data.message.contains("No data"), "{}", no_data.message);
+
+        let api_error = provider.fetch("오류코드").await.unwrap_err();
+        assert_eq!(api_error.kind, FailureKind::ApiError);
+        assert!(api_error.message.contains("MSG"), "{}", api_error.message);
+
+        let retryable = provider.fetch("점검").await.unwrap_err();
+        assert_eq!(retryable.kind, FailureKind::HttpStatus);
+        assert!(retryable.kind.is_retriable());
+    }
+
+    #[tokio::test]
+    async fn raw_body_is_kept_for_every_success_at_full_rate_and_none_at_zero() {
+        let stations = ["중구", "종로구", "용산구"];
+        let mock = || {
+            stations.iter().fold(MockApiClient::new(), |mock, station| {
+                mock.with_envelope(
+                    station,
+                    ApiEnvelope::new(StatusCode::OK, body("00", json!([item()]))),
+                )
+            })
+        };
+
+        let always = provider(mock()).with_raw_sample_rate(1.0);
+        let never = provider(mock()).with_raw_sample_rate(0.0);
+        for station in stations {
+            let raw = always.fetch(station).await.unwrap().raw;
+            let expected: serde_json::Value =
+                serde_json::from_str(&body("00", json!([item()]))).unwrap();
+            assert_eq!(raw, Some(expected), "{}", station);
+            assert_eq!(never.fetch(station).await.unwrap().raw, None, "{}", station);
+        }
+    }
+
+    // 업스트림 장애 시의 수 MB 오류 페이지도 오류 메시지에는 잘라낸 본문만 남음
+    #[tokio::test]
+    async fn huge_error_pages_produce_bounded_messages() {
+        let html = format!("<html>{}</html>", "x".repeat(4 * 1024 * 1024));
+        let provider = provider(
+            MockApiClient::new()
+                .with_envelope(
+                    "점검",
+                    ApiEnvelope::new(StatusCode::BAD_GATEWAY, html.clone()),
+                )
+                .with_envelope("HTML", ApiEnvelope::new(StatusCode::OK, html.clone())),
+        );
+
+        for station in ["점검", "HTML"] {
+            let e = provider.fetch(station).await.unwrap_err();
+            assert!(
+                e.message.len() < 4 * 1024,
+                "{} : {}",
+                station,
+                e.message.len()
+            );
+            assert!(
+                e.message.contains(&format!("({} bytes total)", html.len())),
+                "{}",
+                station
+            );
+        }
+    }
+}
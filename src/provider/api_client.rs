@@ -0,0 +1,419 @@
+// src/provider/api_client.rs
+
+// 에어코리아 / 기상청 API HTTP 호출 추상화
+// AirKoreaProvider, 날씨 수집은 응답 분류/파싱만 담당하고 실제 요청은 ServerState 의 ApiClient 에 위임하여,
+// 실서버 없이 준비된 응답(MockApiClient)으로 조회 흐름을 확인할 수 있도록 함
+
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, DATE};
+use reqwest::{Client, StatusCode};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use tracing::warn;
+
+use super::airkorea::{AIRKOREA_API_URL, AIRKOREA_PROVINCE_API_URL};
+use super::rate_limit;
+use super::{FetchError, Result};
+use crate::diagnostics;
+use crate::failure::FailureKind;
+use crate::http_body::{is_truncated_json, read_text, verbose_errors};
+use crate::nearby_station::NEARBY_STATION_API_URL;
+use crate::scrub::scrub_text;
+use crate::weather::WEATHER_API_URL;
+
+// 끊긴 본문 재요청 기본 횟수
+const DEFAULT_TRUNCATED_BODY_RETRIES: u32 = 1;
+
+pub type ApiFuture<'a> = Pin<Box<dyn Future<Output = Result<ApiEnvelope>> + Send + 'a>>;
+
+// 응답 상태 코드, 헤더, 본문
+#[derive(Debug, Clone)]
+pub struct ApiEnvelope {
+    pub status: StatusCode,
+    // 오류 메시지에만 쓰이므로 verbose 모드가 아니면 비어 있음
+    pub headers: HeaderMap,
+    pub body: String,
+    // 응답 Date 헤더 (상위 서버 시각, verbose 모드와 무관하게 보관)
+    pub server_date: Option<DateTime<Utc>>,
+}
+
+impl ApiEnvelope {
+    pub fn new(status: StatusCode, body: impl Into<String>) -> Self {
+        ApiEnvelope {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+            server_date: None,
+        }
+    }
+
+    pub fn with_server_date(mut self, server_date: DateTime<Utc>) -> Self {
+        self.server_date = Some(server_date);
+        self
+    }
+}
+
+// Date 헤더 (HTTP-date, 예: "Wed, 21 Oct 2015 07:28:00 GMT")
+pub fn parse_date_header(headers: &HeaderMap) -> Option<DateTime<Utc>> {
+    let value = headers.get(DATE)?.to_str().ok()?;
+    DateTime::parse_from_rfc2822(value.trim())
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+// PM_TRUNCATED_BODY_RETRIES 환경 변수 (기본 1, 0 이면 재요청 없음)
+pub fn truncated_body_retries() -> u32 {
+    std::env::var("PM_TRUNCATED_BODY_RETRIES")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(DEFAULT_TRUNCATED_BODY_RETRIES)
+}
+
+// ServerState 에 Arc<dyn ApiClient> 로 보관하므로 Future 는 Box 로 반환
+pub trait ApiClient: Send + Sync {
+    // 측정소별 실시간 측정정보 조회 (params: serviceKey, stationName 등 쿼리 파라미터)
+    fn fetch_station<'a>(
+        &'a self,
+        pm_station: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a>;
+
+    // 시도별 실시간 측정정보 조회 (params: serviceKey, sidoName 등 쿼리 파라미터)
+    fn fetch_province<'a>(
+        &'a self,
+        sido_name: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a>;
+
+    // 기상청 초단기실황 조회 (grid: "nx,ny", params: serviceKey, base_date 등 쿼리 파라미터)
+    fn fetch_weather<'a>(&'a self, grid: &'a str, params: &'a [(String, String)]) -> ApiFuture<'a>;
+
+    // TM 기준좌표 근접측정소 목록 조회 (point: "tmX,tmY", params: serviceKey, tmX, tmY 등 쿼리 파라미터)
+    fn fetch_nearby_station<'a>(
+        &'a self,
+        point: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a>;
+}
+
+// reqwest 기반 구현 (실행 간 커넥션 재사용)
+#[derive(Clone, Default)]
+pub struct ReqwestApiClient {
+    http_client: Client,
+}
+
+impl ReqwestApiClient {
+    pub fn new(http_client: Client) -> Self {
+        ReqwestApiClient { http_client }
+    }
+
+    // url 호출 후 본문까지 읽기 (label 은 오류 메시지용)
+    // 성공 응답의 본문이 끊겼으면 (수신 실패 또는 JSON 이 끝나기 전에 종료) PM_TRUNCATED_BODY_RETRIES 번까지 다시 요청
+    async fn get(
+        &self,
+        url: &str,
+        params: &[(String, String)],
+        label: &str,
+    ) -> Result<ApiEnvelope> {
+        let max_retries = truncated_body_retries();
+        let mut attempt = 0;
+        loop {
+            let result = self.get_once(url, params, label).await;
+            let truncated = match &result {
+                Ok(envelope) => envelope.status.is_success() && is_truncated_json(&envelope.body),
+                Err(e) => e.kind == FailureKind::ReadBody,
+            };
+            if !truncated || attempt >= max_retries {
+                return result;
+            }
+            attempt += 1;
+            warn!(
+                "{} : Response body truncated, retrying ({}/{})",
+                label, attempt, max_retries
+            );
+        }
+    }
+
+    // 요청 한 번 (429 / 503 은 Retry-After 만큼 기다린 뒤 PM_RATE_LIMIT_RETRIES 번까지 다시 요청)
+    async fn get_once(
+        &self,
+        url: &str,
+        params: &[(String, String)],
+        label: &str,
+    ) -> Result<ApiEnvelope> {
+        let max_retries = rate_limit::rate_limit_retries();
+        let max_wait = rate_limit::retry_after_max();
+        let mut attempt = 0;
+        let res = loop {
+            // 응답을 받지 못한 요청도 한도를 쓰므로 보내기 전에 기록
+            rate_limit::record_request();
+            let res = self
+                .http_client
+                .get(url)
+                .query(params)
+                .send()
+                .await
+                .map_err(|e| {
+                    FetchError::new(
+                        FailureKind::Request,
+                        scrub_text(&format!("{} : Request failed: {:?}", label, e)),
+                    )
+                })?;
+
+            let status = res.status();
+            if status == StatusCode::TOO_MANY_REQUESTS {
+                rate_limit::record_rate_limited();
+            }
+            if !rate_limit::is_retry_after_status(status) || attempt >= max_retries {
+                break res;
+            }
+            let wait = rate_limit::retry_wait(res.headers(), Utc::now(), attempt, max_wait);
+            warn!(
+                "{} : Received {}, retrying in {:?} ({}/{})",
+                label,
+                status,
+                wait,
+                attempt + 1,
+                max_retries
+            );
+            drop(res);
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+        };
+
+        // 상태 코드와 무관하게 본문을 읽은 뒤 호출하는 쪽에서 결과 분류
+        let status = res.status();
+        let server_date = parse_date_header(res.headers());
+        // 헤더는 오류 메시지에만 쓰이므로 verbose 모드에서만 복사
+        let headers = if verbose_errors() {
+            res.headers().clone()
+        } else {
+            HeaderMap::new()
+        };
+        let body = match read_text(res).await {
+            Ok(text) => text,
+            Err(e) if status.is_success() => {
+                return Err(FetchError::new(
+                    e.kind(),
+                    format!("{} : Failed to read response text: {}", label, e),
+                ));
+            }
+            Err(_) => String::new(),
+        };
+        diagnostics::record_response(status.as_u16(), body.len());
+
+        Ok(ApiEnvelope {
+            status,
+            headers,
+            body,
+            server_date,
+        })
+    }
+}
+
+impl ApiClient for ReqwestApiClient {
+    fn fetch_station<'a>(
+        &'a self,
+        pm_station: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        Box::pin(self.get(AIRKOREA_API_URL, params, pm_station))
+    }
+
+    fn fetch_province<'a>(
+        &'a self,
+        sido_name: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        Box::pin(self.get(AIRKOREA_PROVINCE_API_URL, params, sido_name))
+    }
+
+    fn fetch_weather<'a>(&'a self, grid: &'a str, params: &'a [(String, String)]) -> ApiFuture<'a> {
+        Box::pin(self.get(WEATHER_API_URL, params, grid))
+    }
+
+    fn fetch_nearby_station<'a>(
+        &'a self,
+        point: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        Box::pin(self.get(NEARBY_STATION_API_URL, params, point))
+    }
+}
+
+// 측정소(시도) 이름별로 준비된 응답을 돌려주는 구현 (없는 이름은 요청 실패로 처리)
+#[derive(Clone, Default)]
+pub struct MockApiClient {
+    envelopes: HashMap<String, ApiEnvelope>,
+}
+
+impl MockApiClient {
+    pub fn new() -> Self {
+        MockApiClient::default()
+    }
+
+    pub fn with_envelope(mut self, name: &str, envelope: ApiEnvelope) -> Self {
+        self.envelopes.insert(name.to_owned(), envelope);
+        self
+    }
+
+    fn canned(&self, name: &str) -> Result<ApiEnvelope> {
+        self.envelopes.get(name).cloned().ok_or_else(|| {
+            FetchError::new(
+                FailureKind::Request,
+                format!("{} : No canned response", name),
+            )
+        })
+    }
+}
+
+impl ApiClient for MockApiClient {
+    fn fetch_station<'a>(
+        &'a self,
+        pm_station: &'a str,
+        _params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        Box::pin(async move { self.canned(pm_station) })
+    }
+
+    fn fetch_province<'a>(
+        &'a self,
+        sido_name: &'a str,
+        _params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        Box::pin(async move { self.canned(sido_name) })
+    }
+
+    fn fetch_weather<'a>(
+        &'a self,
+        grid: &'a str,
+        _params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        Box::pin(async move { self.canned(grid) })
+    }
+
+    fn fetch_nearby_station<'a>(
+        &'a self,
+        point: &'a str,
+        _params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        Box::pin(async move { self.canned(point) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // 준비된 응답을 연결마다 하나씩 돌려주는 로컬 서버, 주소 반환
+    async fn serve(responses: Vec<&'static str>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = [0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+                socket.write_all(response.as_bytes()).await.unwrap();
+                let _ = socket.shutdown().await;
+            }
+        });
+        format!("http://{}/", addr)
+    }
+
+    async fn counted_get(url: &str) -> (Result<ApiEnvelope>, u64) {
+        let budget =
+            rate_limit::ApiBudget::new(std::sync::Arc::new(tokio::sync::Semaphore::new(4)), 4);
+        let client = ReqwestApiClient::new(Client::new());
+        let result = rate_limit::scope(budget.clone(), client.get(url, &[], "test")).await;
+        (result, budget.requests())
+    }
+
+    #[tokio::test]
+    async fn counts_every_attempt_including_retries() {
+        let url = serve(vec![
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+        ])
+        .await;
+
+        let (result, requests) = counted_get(&url).await;
+        let envelope = result.unwrap();
+        assert_eq!(envelope.status, StatusCode::OK);
+        assert_eq!(envelope.body, "ok");
+        assert_eq!(requests, 2);
+    }
+
+    #[tokio::test]
+    async fn rerequests_a_truncated_success_body() {
+        let url = serve(vec![
+            "HTTP/1.1 200 OK\r\nContent-Length: 12\r\nConnection: close\r\n\r\n{\"response\":",
+            "HTTP/1.1 200 OK\r\nContent-Length: 15\r\nConnection: close\r\n\r\n{\"response\":{}}",
+        ])
+        .await;
+
+        let (result, requests) = counted_get(&url).await;
+        assert_eq!(result.unwrap().body, r#"{"response":{}}"#);
+        assert_eq!(requests, 2);
+    }
+
+    #[tokio::test]
+    async fn gives_up_on_a_body_that_stays_truncated() {
+        let truncated =
+            "HTTP/1.1 200 OK\r\nContent-Length: 12\r\nConnection: close\r\n\r\n{\"response\":";
+        let url = serve(vec![truncated, truncated]).await;
+
+        // 재요청 후에도 끊긴 본문은 그대로 돌려주고 제공처에서 TRUNCATED_BODY 로 분류
+        let (result, requests) = counted_get(&url).await;
+        assert_eq!(result.unwrap().body, r#"{"response":"#);
+        assert_eq!(requests, 2);
+    }
+
+    #[tokio::test]
+    async fn counts_requests_that_got_no_response() {
+        // 바로 닫은 리스너의 포트: 연결 거부
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        drop(listener);
+
+        let (result, requests) = counted_get(&url).await;
+        assert_eq!(result.unwrap_err().kind, FailureKind::Request);
+        assert_eq!(requests, 1);
+    }
+
+    #[test]
+    fn date_header_is_parsed_as_utc() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_date_header(&headers), None);
+        headers.insert(DATE, "Wed, 01 May 2024 04:20:00 GMT".parse().unwrap());
+        assert_eq!(
+            parse_date_header(&headers),
+            Some(
+                DateTime::parse_from_rfc3339("2024-05-01T04:20:00Z")
+                    .unwrap()
+                    .with_timezone(&Utc)
+            )
+        );
+        headers.insert(DATE, "yesterday".parse().unwrap());
+        assert_eq!(parse_date_header(&headers), None);
+    }
+
+    #[tokio::test]
+    async fn keeps_the_date_header_without_verbose_errors() {
+        let url = serve(vec![
+            "HTTP/1.1 200 OK\r\nDate: Wed, 01 May 2024 04:20:00 GMT\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+        ])
+        .await;
+
+        let (result, _) = counted_get(&url).await;
+        let envelope = result.unwrap();
+        assert!(envelope.headers.is_empty());
+        assert_eq!(
+            envelope.server_date.map(|date| date.to_rfc3339()),
+            Some("2024-05-01T04:20:00+00:00".to_owned())
+        );
+    }
+}
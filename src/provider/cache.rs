@@ -0,0 +1,91 @@
+// src/provider/cache.rs
+
+// 실행 단위 측정값 캐시: 여러 sub_region 이 같은 측정소를 가리키면 첫 조회 결과를 재사용하여 API 호출을 한 번으로 줄임
+// 실행마다 새로 만들고 실행이 끝나면 버리므로 이전 실행의 (오래된) 측정값은 재사용하지 않음
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
+
+use super::{average_readings, split_station_refs, PmProvider, Reading, Result};
+
+// (제공처, 측정소)
+type StationKey = (String, String);
+
+#[derive(Default)]
+pub struct ReadingCache {
+    // (제공처, 측정소) -> 조회 결과 (성공한 경우에만 채워짐)
+    entries: Mutex<HashMap<StationKey, Arc<OnceCell<Reading>>>>,
+    hits: AtomicUsize,
+}
+
+impl ReadingCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 같은 측정소를 동시에 조회하면 먼저 시작한 조회만 API 를 호출하고 나머지는 그 결과를 기다림
+    // 조회가 실패하면 캐시에 저장하지 않으므로 대기 중인 다음 태스크가 다시 조회
+    pub async fn get_or_fetch<P: PmProvider>(
+        &self,
+        provider: &P,
+        station_ref: &str,
+    ) -> Result<Reading> {
+        let cell = {
+            let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+            entries
+                .entry((provider.provider_key().to_owned(), station_ref.to_owned()))
+                .or_default()
+                .clone()
+        };
+
+        let mut fetched = false;
+        let reading = cell
+            .get_or_try_init(|| async {
+                fetched = true;
+                provider.fetch(station_ref).await
+            })
+            .await?;
+
+        if !fetched {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(reading.clone())
+    }
+
+    // 쉼표로 구분한 여러 측정소면 각각 조회 후 평균 (한 곳만 성공하면 그 값을 그대로 사용)
+    // 모두 실패하면 첫 번째 측정소의 오류 반환
+    pub async fn get_or_fetch_all<P: PmProvider>(
+        &self,
+        provider: &P,
+        station_ref: &str,
+    ) -> Result<Reading> {
+        let station_refs = split_station_refs(station_ref);
+        if station_refs.len() <= 1 {
+            return self.get_or_fetch(provider, station_ref.trim()).await;
+        }
+
+        let mut readings = Vec::new();
+        let mut first_error = None;
+        for station_ref in station_refs {
+            match self.get_or_fetch(provider, station_ref).await {
+                Ok(reading) => readings.push(reading),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        match (average_readings(&readings), first_error) {
+            (Some(reading), _) => Ok(reading),
+            (None, Some(e)) => Err(e),
+            (None, None) => unreachable!("at least two station refs"),
+        }
+    }
+
+    // 캐시로 API 호출을 생략한 횟수
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+}
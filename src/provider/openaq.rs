@@ -0,0 +1,240 @@
+// src/provider/openaq.rs
+
+// OpenAQ v3 API (https://docs.openaq.org)
+// /locations/{id} 로 센서별 측정 항목을 확인한 뒤 /locations/{id}/latest 의 최신값을 pm10/pm25 로 매핑
+
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::{FetchError, PmProvider, Reading, Result};
+use crate::clock::Clock;
+use crate::diagnostics;
+use crate::failure::FailureKind;
+use crate::http_body::{describe_json_error, read_text, truncate_body};
+use crate::timeutil::truncate_to_hour;
+use crate::validity::valid_ranges;
+
+pub const OPENAQ_PROVIDER_KEY: &str = "openaq";
+
+pub const OPENAQ_API_BASE_URL: &str = "https://api.openaq.org/v3";
+
+pub struct OpenAqProvider {
+    http_client: Client,
+    api_key: Option<String>,
+    // 응답에 측정 시각이 없을 때 쓰는 현재 시각 (ServerState.clock)
+    clock: Arc<dyn Clock>,
+}
+
+impl OpenAqProvider {
+    pub fn new(http_client: Client, api_key: Option<String>, clock: Arc<dyn Clock>) -> Self {
+        OpenAqProvider {
+            http_client,
+            api_key,
+            clock,
+        }
+    }
+
+    async fn get_json(&self, location_id: &str, url: &str) -> Result<serde_json::Value> {
+        let mut request = self.http_client.get(url);
+        if let Some(api_key) = &self.api_key {
+            request = request.header("X-API-Key", api_key);
+        }
+
+        let res = request.send().await.map_err(|e| {
+            FetchError::new(
+                FailureKind::Request,
+                format!("{} : Request failed: {:?}", location_id, e),
+            )
+        })?;
+
+        if !res.status().is_success() {
+            let res_status = res.status();
+            let res_text = read_text(res).await.unwrap_or_default();
+            diagnostics::record_response(res_status.as_u16(), res_text.len());
+            return Err(FetchError::new(
+                FailureKind::HttpStatus,
+                format!(
+                    "{} : Received non-success status code: {}\nResponse text: {}",
+                    location_id,
+                    res_status,
+                    truncate_body(&res_text)
+                ),
+            ));
+        }
+
+        let res_status = res.status();
+        let res_text = read_text(res).await.map_err(|e| {
+            FetchError::new(
+                e.kind(),
+                format!("{} : Failed to read response text: {}", location_id, e),
+            )
+        })?;
+        diagnostics::record_response(res_status.as_u16(), res_text.len());
+
+        serde_json::from_str(&res_text).map_err(|e| {
+            let (kind, error_message) = describe_json_error(location_id, &e, &res_text);
+            FetchError::new(kind, error_message)
+        })
+    }
+}
+
+impl PmProvider for OpenAqProvider {
+    fn provider_key(&self) -> &str {
+        OPENAQ_PROVIDER_KEY
+    }
+
+    async fn fetch(&self, location_id: &str) -> Result<Reading> {
+        let location = self
+            .get_json(
+                location_id,
+                &format!("{}/locations/{}", OPENAQ_API_BASE_URL, location_id),
+            )
+            .await?;
+        let latest = self
+            .get_json(
+                location_id,
+                &format!("{}/locations/{}/latest", OPENAQ_API_BASE_URL, location_id),
+            )
+            .await?;
+
+        parse_latest_reading(location_id, &location, &latest, self.clock.now())
+    }
+}
+
+// 센서 id -> 측정 항목 이름 (pm10, pm25 ...)
+fn sensor_parameters(location: &serde_json::Value) -> HashMap<i64, String> {
+    location
+        .get("results")
+        .and_then(|results| results.get(0))
+        .and_then(|location| location.get("sensors"))
+        .and_then(|sensors| sensors.as_array())
+        .map(|sensors| {
+            sensors
+                .iter()
+                .filter_map(|sensor| {
+                    let id = sensor.get("id")?.as_i64()?;
+                    let parameter = sensor.get("parameter")?.get("name")?.as_str()?;
+                    Some((id, parameter.to_owned()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// 측정 시각이 없는 항목만 있으면 now (정시 절삭) 를 측정 시각으로 사용
+pub fn parse_latest_reading(
+    location_id: &str,
+    location: &serde_json::Value,
+    latest: &serde_json::Value,
+    now: DateTime<Utc>,
+) -> Result<Reading> {
+    let parameters = sensor_parameters(location);
+
+    let results = latest
+        .get("results")
+        .and_then(|results| results.as_array())
+        .filter(|results| !results.is_empty())
+        .ok_or_else(|| {
+            FetchError::new(
+                FailureKind::NoData,
+                format!("{} : No data available in API response.", location_id),
+            )
+        })?;
+
+    let mut pm10 = None;
+    let mut pm25 = None;
+    let mut recorded_at: Option<DateTime<Utc>> = None;
+
+    for result in results {
+        let parameter = result
+            .get("sensorsId")
+            .and_then(|id| id.as_i64())
+            .and_then(|id| parameters.get(&id));
+        let value = result.get("value").and_then(|v| v.as_f64());
+
+        match parameter.map(|p| p.as_str()) {
+            Some("pm10") => pm10 = value,
+            Some("pm25") => pm25 = value,
+            _ => continue,
+        }
+
+        let datetime = result
+            .get("datetime")
+            .and_then(|dt| dt.get("utc"))
+            .and_then(|utc| utc.as_str())
+            .and_then(|utc| DateTime::parse_from_rfc3339(utc).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+        recorded_at = recorded_at.max(datetime);
+    }
+
+    if pm10.is_none() && pm25.is_none() {
+        return Err(FetchError::new(
+            FailureKind::NoData,
+            format!(
+                "{} : No pm10/pm25 sensor data in API response.",
+                location_id
+            ),
+        ));
+    }
+
+    let recorded_at = truncate_to_hour(recorded_at.unwrap_or(now));
+
+    // 유효 범위 밖의 값은 NULL 로 저장
+    Ok(valid_ranges().apply(
+        location_id,
+        Reading {
+            pm10,
+            pm25,
+            recorded_at,
+            server_time: None,
+            raw: None,
+            rejected_values: 0,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn location() -> serde_json::Value {
+        json!({ "results": [{ "sensors": [
+            { "id": 1, "parameter": { "name": "pm10" } },
+            { "id": 2, "parameter": { "name": "pm25" } },
+        ] }] })
+    }
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn maps_sensors_and_uses_the_latest_datetime() {
+        let latest = json!({ "results": [
+            { "sensorsId": 1, "value": 31.0, "datetime": { "utc": "2024-05-01T03:00:00Z" } },
+            { "sensorsId": 2, "value": 12.0, "datetime": { "utc": "2024-05-01T04:10:00Z" } },
+        ] });
+        let reading =
+            parse_latest_reading("42", &location(), &latest, utc("2030-01-01T00:00:00Z")).unwrap();
+        assert_eq!((reading.pm10, reading.pm25), (Some(31.0), Some(12.0)));
+        assert_eq!(reading.recorded_at, utc("2024-05-01T04:00:00Z"));
+    }
+
+    #[test]
+    fn missing_datetime_falls_back_to_the_injected_now() {
+        let latest = json!({ "results": [{ "sensorsId": 1, "value": 31.0 }] });
+        let reading =
+            parse_latest_reading("42", &location(), &latest, utc("2024-05-01T07:45:12Z")).unwrap();
+        assert_eq!(reading.recorded_at, utc("2024-05-01T07:00:00Z"));
+    }
+
+    #[test]
+    fn no_pm_sensor_is_no_data() {
+        let latest = json!({ "results": [{ "sensorsId": 9, "value": 1.0 }] });
+        let error = parse_latest_reading("42", &location(), &latest, Utc::now()).unwrap_err();
+        assert_eq!(error.kind, FailureKind::NoData);
+    }
+}
@@ -0,0 +1,222 @@
+// src/provider/rate_limit.rs
+
+// 상위 API 의 429 / 503 응답 처리
+// Retry-After (초 또는 HTTP-date) 만큼 기다린 뒤 PM_RATE_LIMIT_RETRIES 번까지 다시 요청하고 (대기는 PM_RETRY_AFTER_MAX_SECS 로 제한),
+// 재시도를 모두 쓴 429 는 RATE_LIMITED 로 분류
+// 실행 중 첫 429 를 받으면 남은 실행 동안 API 동시 호출 퍼밋을 절반으로 줄임 (실행마다 새 세마포어이므로 다음 실행은 원래대로)
+// 실행에서 보낸 요청 수(재시도 포함)도 함께 세어 일일 한도 추적(api_quota)에 사용
+
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tracing::warn;
+
+const DEFAULT_RATE_LIMIT_RETRIES: u32 = 2;
+const DEFAULT_RETRY_AFTER_MAX_SECS: u64 = 10;
+
+// Retry-After 가 없을 때의 첫 대기 시간 (재시도마다 두 배)
+const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+tokio::task_local! {
+    // 현재 측정소가 속한 실행의 API 호출 예산 (scope 범위 밖의 호출은 줄이지 않음)
+    static API_BUDGET: ApiBudget;
+}
+
+// 실행별 API 동시 호출 퍼밋
+#[derive(Clone)]
+pub struct ApiBudget {
+    semaphore: Arc<Semaphore>,
+    limit: usize,
+    reduced: Arc<AtomicBool>,
+    requests: Arc<AtomicU64>,
+}
+
+impl ApiBudget {
+    pub fn new(semaphore: Arc<Semaphore>, limit: usize) -> Self {
+        ApiBudget {
+            semaphore,
+            limit,
+            reduced: Arc::new(AtomicBool::new(false)),
+            requests: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    // 실행에서 보낸 요청 수 (재시도, 시도별 / 근접 측정소 조회, 응답을 받지 못한 요청 포함)
+    pub fn requests(&self) -> u64 {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    // 첫 429 에서만 퍼밋을 절반으로
+    // 남은 퍼밋은 바로 버리고, 모자라는 만큼은 사용 중인 퍼밋이 반납되는 대로 가져와 버림
+    // (대기 순서상 새 측정소보다 먼저 받으므로 목표치에 도달할 때까지 새 호출이 시작되지 않음)
+    fn reduce(&self) {
+        if self.reduced.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let target = self.limit / 2;
+        let forgotten = self.semaphore.forget_permits(target);
+        let pending = target - forgotten;
+        if pending > 0 {
+            let semaphore = self.semaphore.clone();
+            tokio::spawn(async move {
+                if let Ok(permits) = semaphore.acquire_many_owned(pending as u32).await {
+                    permits.forget();
+                }
+            });
+        }
+        warn!(
+            "Rate limited by upstream, API concurrency reduced by {} (limit {}, {} dropped as in-flight calls finish) for the rest of the run",
+            target, self.limit, pending
+        );
+    }
+}
+
+// fut 안의 API 호출이 429 를 받으면 budget 을 줄임
+pub async fn scope<F: Future>(budget: ApiBudget, fut: F) -> F::Output {
+    API_BUDGET.scope(budget, fut).await
+}
+
+// 요청 한 번 기록 (scope 범위 밖이면 아무것도 하지 않음)
+pub fn record_request() {
+    let _ = API_BUDGET.try_with(|budget| budget.requests.fetch_add(1, Ordering::Relaxed));
+}
+
+// 429 수신 기록 (scope 범위 밖이면 아무것도 하지 않음)
+pub fn record_rate_limited() {
+    let _ = API_BUDGET.try_with(ApiBudget::reduce);
+}
+
+// 기다린 뒤 다시 요청할 상태 코드
+pub fn is_retry_after_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE
+}
+
+// PM_RATE_LIMIT_RETRIES 환경 변수 (기본 2, 0 이면 재시도 없음)
+pub fn rate_limit_retries() -> u32 {
+    std::env::var("PM_RATE_LIMIT_RETRIES")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(DEFAULT_RATE_LIMIT_RETRIES)
+}
+
+// PM_RETRY_AFTER_MAX_SECS 환경 변수 (기본 10, 한 번의 대기 상한)
+pub fn retry_after_max() -> Duration {
+    let secs = std::env::var("PM_RETRY_AFTER_MAX_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_RETRY_AFTER_MAX_SECS);
+    Duration::from_secs(secs)
+}
+
+// Retry-After 헤더 (초 단위 정수 또는 HTTP-date, 이미 지난 시각이면 0)
+pub fn parse_retry_after(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((at.with_timezone(&Utc) - now).to_std().unwrap_or_default())
+}
+
+// 재시도 전 대기 시간 (Retry-After 가 없으면 지수 백오프, 어느 쪽이든 max 로 제한)
+pub fn retry_wait(
+    headers: &HeaderMap,
+    now: DateTime<Utc>,
+    attempt: u32,
+    max: Duration,
+) -> Duration {
+    parse_retry_after(headers, now)
+        .unwrap_or_else(|| DEFAULT_RETRY_BACKOFF.saturating_mul(2u32.saturating_pow(attempt)))
+        .min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reduce_drops_idle_permits_at_once() {
+        let semaphore = Arc::new(Semaphore::new(4));
+        let budget = ApiBudget::new(semaphore.clone(), 4);
+
+        scope(budget.clone(), async { record_rate_limited() }).await;
+        assert_eq!(semaphore.available_permits(), 2);
+
+        // 두 번째 429 는 더 줄이지 않음
+        scope(budget, async { record_rate_limited() }).await;
+        assert_eq!(semaphore.available_permits(), 2);
+    }
+
+    #[tokio::test]
+    async fn reduce_drops_in_flight_permits_as_they_return() {
+        let semaphore = Arc::new(Semaphore::new(4));
+        let budget = ApiBudget::new(semaphore.clone(), 4);
+        let in_flight = semaphore.clone().acquire_many_owned(4).await.unwrap();
+
+        scope(budget, async { record_rate_limited() }).await;
+        // 버릴 퍼밋을 기다리는 작업이 먼저 대기열에 들어가도록 양보
+        tokio::task::yield_now().await;
+        drop(in_flight);
+
+        // 반납된 퍼밋 중 절반은 버려지고, 이후 동시에 잡을 수 있는 퍼밋은 2개
+        let held = tokio::time::timeout(
+            Duration::from_secs(1),
+            semaphore.clone().acquire_many_owned(2),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        tokio::task::yield_now().await;
+        assert_eq!(semaphore.available_permits(), 0);
+        drop(held);
+        assert_eq!(semaphore.available_permits(), 2);
+    }
+
+    #[tokio::test]
+    async fn counts_requests_only_inside_scope() {
+        let budget = ApiBudget::new(Arc::new(Semaphore::new(1)), 1);
+        record_request();
+        scope(budget.clone(), async {
+            record_request();
+            record_request();
+        })
+        .await;
+        assert_eq!(budget.requests(), 2);
+    }
+
+    #[test]
+    fn retry_after_accepts_seconds_and_http_date() {
+        let now = "2024-03-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, "3".parse().unwrap());
+        assert_eq!(
+            parse_retry_after(&headers, now),
+            Some(Duration::from_secs(3))
+        );
+
+        headers.insert(
+            RETRY_AFTER,
+            "Fri, 01 Mar 2024 00:00:05 GMT".parse().unwrap(),
+        );
+        assert_eq!(
+            parse_retry_after(&headers, now),
+            Some(Duration::from_secs(5))
+        );
+
+        // 이미 지난 시각은 0, 없으면 지수 백오프를 max 로 제한
+        headers.insert(
+            RETRY_AFTER,
+            "Thu, 29 Feb 2024 23:59:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(parse_retry_after(&headers, now), Some(Duration::ZERO));
+        assert_eq!(
+            retry_wait(&HeaderMap::new(), now, 3, Duration::from_secs(2)),
+            Duration::from_secs(2)
+        );
+    }
+}
@@ -0,0 +1,88 @@
+// src/provider/raw_sample.rs
+
+// 파서 검증용 원문 샘플링 (PM_RAW_SAMPLE_RATE, 예: 0.05)
+// 성공한 응답 중 해당 비율만 원문 JSON 을 측정값에 보관하여 응답 data 항목의 raw 필드로 반환
+// 운영 중에도 전체를 저장하지 않고 파싱 결과를 원문과 비교해 볼 수 있도록 하기 위한 용도
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+// PM_RAW_SAMPLE_RATE 환경 변수 (0.0 ~ 1.0, 기본 0 = 샘플링 안 함, 범위 밖의 값은 범위 안으로 맞춤)
+pub fn raw_sample_rate() -> f64 {
+    std::env::var("PM_RAW_SAMPLE_RATE")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|rate| !rate.is_nan())
+        .map(|rate| rate.clamp(0.0, 1.0))
+        .unwrap_or(0.0)
+}
+
+// rate 비율로 true (1.0 이면 항상, 0.0 이면 항상 false)
+pub fn sample(rate: f64) -> bool {
+    sample_with(rate, random_unit)
+}
+
+// unit 이 돌려주는 [0, 1) 값으로 판정 (테스트에서는 고정 값을 넣어 결과를 재현)
+pub fn sample_with(rate: f64, unit: impl FnOnce() -> f64) -> bool {
+    if rate >= 1.0 {
+        return true;
+    }
+    if rate <= 0.0 || rate.is_nan() {
+        return false;
+    }
+    unit() < rate
+}
+
+// [0, 1) 범위의 난수 (RandomState 는 생성할 때마다 키가 달라지므로 별도 난수 crate 없이 사용)
+fn random_unit() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+// 샘플로 선택된 응답이면 원문 본문을 JSON 으로 반환 (JSON 이 아니면 None)
+pub fn sample_body(body: &str, rate: f64) -> Option<serde_json::Value> {
+    if !sample(rate) {
+        return None;
+    }
+    serde_json::from_str(body).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_and_zero_rates_do_not_draw() {
+        let never_called = || -> f64 { panic!("rate 0 / 1 must not draw a random value") };
+        assert!(sample_with(1.0, never_called));
+        assert!(sample_with(1.5, never_called));
+        assert!(!sample_with(0.0, never_called));
+        assert!(!sample_with(-0.1, never_called));
+        assert!(!sample_with(f64::NAN, never_called));
+    }
+
+    #[test]
+    fn rate_selects_that_share_of_evenly_spread_draws() {
+        let selected = (0..1000)
+            .filter(|i| sample_with(0.05, || *i as f64 / 1000.0))
+            .count();
+        assert_eq!(selected, 50);
+    }
+
+    #[test]
+    fn random_unit_stays_in_range() {
+        assert!((0..1000)
+            .map(|_| random_unit())
+            .all(|unit| (0.0..1.0).contains(&unit)));
+    }
+
+    #[test]
+    fn sampled_body_must_be_json() {
+        assert_eq!(
+            sample_body(r#"{"response":{}}"#, 1.0),
+            Some(serde_json::json!({ "response": {} }))
+        );
+        assert_eq!(sample_body("<xml/>", 1.0), None);
+        assert_eq!(sample_body(r#"{"response":{}}"#, 0.0), None);
+    }
+}
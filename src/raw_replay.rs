@@ -0,0 +1,194 @@
+// src/raw_replay.rs
+
+// 보관된 원문 응답 재처리 (mode: "replay_raw")
+// PM_RAW_RESPONSE_BUCKET 에 보관된 에어코리아 측정소 응답 원문을 실제 API 호출 없이 다시 파싱/저장하여,
+// 파서 수정 후 실패했던 응답 그대로 재처리
+// 예) {"mode": "replay_raw", "rawKey": "raw/2024-05-02T13/중구.json", "station": "중구"}
+// 해당 측정소를 pm_station 으로 쓰는 AirKorea sub_region 마다 저장 (저장된 값이 더 최신이면 overwrite: true 일 때만 덮어씀)
+
+use anyhow::{anyhow, Result};
+use aws_config::BehaviorVersion;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::sync::Arc;
+
+use crate::handler::{new_run_id, FetchOptions, IngestReport, StationResult, SubRegionInfo};
+use crate::inline_stations::StationListSource;
+use crate::phases::Phases;
+use crate::provider::airkorea::{classify_http_response, ResponseOutcome, AIRKOREA_PROVIDER_KEY};
+use crate::reprocess::store_as_of;
+use crate::state::ServerState;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawReplayOptions {
+    // 버킷 안의 원문 객체 키
+    pub raw_key: String,
+    // 원문을 조회한 측정소 이름 (측정소별 응답 항목에는 측정소 이름이 없음)
+    pub station: String,
+    pub overwrite: bool,
+}
+
+impl RawReplayOptions {
+    pub fn from_payload(payload: &serde_json::Value) -> Result<Self> {
+        let raw_key = payload
+            .get("rawKey")
+            .and_then(|v| v.as_str())
+            .filter(|key| !key.is_empty())
+            .ok_or_else(|| anyhow!("replay_raw 모드에는 rawKey 가 필요함"))?;
+        let station = payload
+            .get("station")
+            .and_then(|v| v.as_str())
+            .filter(|station| !station.is_empty())
+            .ok_or_else(|| anyhow!("replay_raw 모드에는 station 이 필요함"))?;
+        let overwrite = payload
+            .get("overwrite")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        Ok(RawReplayOptions {
+            raw_key: raw_key.to_owned(),
+            station: station.to_owned(),
+            overwrite,
+        })
+    }
+}
+
+// PM_RAW_RESPONSE_BUCKET 환경 변수
+pub fn raw_response_bucket_from_env() -> Result<String> {
+    std::env::var("PM_RAW_RESPONSE_BUCKET")
+        .ok()
+        .filter(|bucket| !bucket.is_empty())
+        .ok_or_else(|| anyhow!("replay_raw 모드에는 PM_RAW_RESPONSE_BUCKET 이 필요함"))
+}
+
+// S3 객체 본문 조회
+pub async fn fetch_raw_response(bucket: &str, key: &str) -> Result<String> {
+    let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+    let client = aws_sdk_s3::Client::new(&sdk_config);
+
+    let object = client
+        .get_object()
+        .bucket(bucket)
+        .key(key)
+        .send()
+        .await
+        .map_err(|e| anyhow!("s3://{}/{} 조회 실패: {:?}", bucket, key, e))?;
+    let bytes = object
+        .body
+        .collect()
+        .await
+        .map_err(|e| anyhow!("s3://{}/{} 읽기 실패: {:?}", bucket, key, e))?
+        .into_bytes();
+
+    String::from_utf8(bytes.to_vec()).map_err(|e| anyhow!("s3://{}/{} : {}", bucket, key, e))
+}
+
+// 버킷에서 원문을 읽어 재처리
+pub async fn run_raw_replay(
+    state: Arc<ServerState>,
+    options: &FetchOptions,
+    replay: &RawReplayOptions,
+) -> Result<IngestReport> {
+    let bucket = raw_response_bucket_from_env()?;
+    let body = fetch_raw_response(&bucket, &replay.raw_key).await?;
+
+    replay_raw_body(state, options, replay, &body).await
+}
+
+// 원문 본문을 실시간 수집과 같은 분류/파싱 경로로 처리한 뒤 측정소의 sub_region 마다 저장
+pub async fn replay_raw_body(
+    state: Arc<ServerState>,
+    options: &FetchOptions,
+    replay: &RawReplayOptions,
+    body: &str,
+) -> Result<IngestReport> {
+    let start = tokio::time::Instant::now();
+    let run_id = options.run_id.clone().unwrap_or_else(new_run_id);
+    let station = replay.station.as_str();
+
+    // 보관된 원문은 정상 응답(200)으로 받은 본문
+    let reading = match classify_http_response(
+        station,
+        StatusCode::OK,
+        &HeaderMap::new(),
+        body,
+        state.source_offset,
+    ) {
+        ResponseOutcome::Success(reading) => Ok(reading),
+        ResponseOutcome::Retryable(e)
+        | ResponseOutcome::SoftFail(e)
+        | ResponseOutcome::HardFail(e) => Err(e),
+    };
+
+    // 풀 오류는 PoolError 그대로 반환하여 handle_event 에서 503/500 으로 구분
+    let db_client = state.pool.get().await?;
+    let rows = state
+        .sub_region_queries
+        .fetch_rows(
+            &db_client,
+            options.sub_region_ids.as_ref(),
+            options.include_disabled,
+        )
+        .await?;
+    drop(db_client);
+
+    let sub_region_ids: Vec<i32> = rows
+        .iter()
+        .filter_map(|row| SubRegionInfo::try_from_row(row).ok())
+        .filter(|sub_region| {
+            sub_region.provider == AIRKOREA_PROVIDER_KEY
+                && sub_region.pm_station.as_deref() == Some(station)
+        })
+        .map(|sub_region| sub_region.sub_region_id)
+        .collect();
+    if sub_region_ids.is_empty() {
+        return Err(anyhow!(
+            "{} : 이 측정소를 pm_station 으로 쓰는 AirKorea sub_region 없음",
+            station
+        ));
+    }
+
+    let mut results = Vec::with_capacity(sub_region_ids.len());
+    for &sub_region_id in &sub_region_ids {
+        let result = match &reading {
+            Ok(reading) => {
+                store_as_of(
+                    &state,
+                    sub_region_id,
+                    station,
+                    reading,
+                    replay.overwrite,
+                    options.dry_run,
+                )
+                .await
+            }
+            Err(e) => StationResult::failed(
+                sub_region_id,
+                station,
+                e.kind,
+                format!("{} ({})", e.message, replay.raw_key),
+            ),
+        };
+        results.push(result);
+    }
+
+    Ok(IngestReport {
+        run_id,
+        results,
+        skipped_fresh: 0,
+        deferred: 0,
+        cache_hits: 0,
+        db_read_only: false,
+        no_sub_regions: false,
+        advanced: None,
+        data_frozen: false,
+        elapsed: start.elapsed(),
+        warnings: Vec::new(),
+        latest_cache_failures: 0,
+        disabled_sub_regions: None,
+        diagnostics: None,
+        phases: Phases::default(),
+        station_list_source: StationListSource::Db,
+        quota: None,
+    })
+}
@@ -0,0 +1,306 @@
+// src/rds_iam.rs
+
+// RDS IAM 인증: 고정 DB 비밀번호 대신 커넥션 생성 시마다 IAM 인증 토큰(15분 유효)을 발급하여 비밀번호로 사용
+// DB_IAM_AUTH=true 일 때 DB_CONN_URL 대신 DB_HOST / DB_PORT / DB_USER / DB_NAME 으로 접속하며 TLS 필수
+
+use anyhow::{anyhow, Result};
+use aws_config::{BehaviorVersion, SdkConfig};
+use aws_sdk_rds::auth_token::{AuthTokenGenerator, Config as AuthTokenConfig};
+use deadpool_postgres::{Connect, Manager, ManagerConfig, Pool, Runtime};
+use postgres_native_tls::MakeTlsConnector;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio_postgres::config::SslMode;
+use tokio_postgres::{Client as PgClient, Config as PgConfig, Error as PgError};
+use tracing::{error, info};
+
+use crate::state::ConnectionRecycle;
+
+const DEFAULT_DB_PORT: u16 = 5432;
+const DEFAULT_DB_NAME: &str = "postgres";
+
+// IAM 인증 토큰 발급 (테스트에서는 고정 토큰을 반환하는 signer 로 대체)
+pub trait AuthTokenSigner: Send + Sync {
+    fn sign(&self) -> impl Future<Output = Result<String>> + Send;
+}
+
+// aws-sdk 로 host/port/user 에 대해 서명한 RDS 인증 토큰 발급
+pub struct RdsAuthTokenSigner {
+    sdk_config: SdkConfig,
+    generator: AuthTokenGenerator,
+}
+
+impl RdsAuthTokenSigner {
+    pub async fn from_env(host: &str, port: u16, user: &str) -> Result<Self> {
+        let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+        let region = sdk_config
+            .region()
+            .cloned()
+            .ok_or_else(|| anyhow!("RDS IAM 인증: AWS region 설정 누락"))?;
+
+        let generator = AuthTokenGenerator::new(
+            AuthTokenConfig::builder()
+                .hostname(host)
+                .port(u64::from(port))
+                .username(user)
+                .region(region)
+                .build()
+                .map_err(|e| anyhow!("RDS IAM 인증 설정 실패: {:?}", e))?,
+        );
+
+        Ok(RdsAuthTokenSigner {
+            sdk_config,
+            generator,
+        })
+    }
+}
+
+impl AuthTokenSigner for RdsAuthTokenSigner {
+    async fn sign(&self) -> Result<String> {
+        let token = self
+            .generator
+            .auth_token(&self.sdk_config)
+            .await
+            .map_err(|e| anyhow!("RDS IAM 인증 토큰 발급 실패: {:?}", e))?;
+        Ok(token.as_str().to_owned())
+    }
+}
+
+// 새 커넥션을 만들 때마다 토큰을 다시 발급하여 비밀번호로 설정
+// (정적 URL 로는 15분 후 만료되므로 deadpool 의 Connect 훅 사용)
+pub struct IamConnect<S> {
+    signer: S,
+    tls: MakeTlsConnector,
+}
+
+impl<S: AuthTokenSigner> IamConnect<S> {
+    pub fn new(signer: S, tls: MakeTlsConnector) -> Self {
+        IamConnect { signer, tls }
+    }
+
+    async fn connect_with_token(
+        &self,
+        pg_config: &PgConfig,
+    ) -> Result<(PgClient, JoinHandle<()>), PgError> {
+        let mut pg_config = pg_config.clone();
+        // 토큰 발급 실패 시 비밀번호 없이 접속 시도 -> 서버의 인증 오류로 커넥션 생성 실패 처리
+        match self.signer.sign().await {
+            Ok(token) => {
+                pg_config.password(token);
+            }
+            Err(e) => error!("{:?}", e),
+        }
+
+        let (client, connection) = pg_config.connect(self.tls.clone()).await?;
+        let handle = tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                error!("Connection error: {:?}", e);
+            }
+        });
+        Ok((client, handle))
+    }
+}
+
+impl<S: AuthTokenSigner> Connect for IamConnect<S> {
+    fn connect(
+        &self,
+        pg_config: &PgConfig,
+    ) -> Pin<Box<dyn Future<Output = Result<(PgClient, JoinHandle<()>), PgError>> + Send + '_>>
+    {
+        let pg_config = pg_config.clone();
+        Box::pin(async move { self.connect_with_token(&pg_config).await })
+    }
+}
+
+// DB_IAM_AUTH=true 여부
+pub fn is_enabled() -> bool {
+    std::env::var("DB_IAM_AUTH")
+        .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+        .unwrap_or(false)
+}
+
+// DB_HOST / DB_PORT / DB_USER / DB_NAME 으로 tokio-postgres 설정 (TLS 필수)
+pub fn pg_config_from_env() -> Result<PgConfig> {
+    let host = std::env::var("DB_HOST").map_err(|e| anyhow!("DB_HOST 환경 변수 누락: {:?}", e))?;
+    let user = std::env::var("DB_USER").map_err(|e| anyhow!("DB_USER 환경 변수 누락: {:?}", e))?;
+    let port = std::env::var("DB_PORT")
+        .ok()
+        .and_then(|v| v.parse::<u16>().ok())
+        .unwrap_or(DEFAULT_DB_PORT);
+    let db_name = std::env::var("DB_NAME").unwrap_or_else(|_| DEFAULT_DB_NAME.to_owned());
+
+    let mut pg_config = PgConfig::new();
+    pg_config
+        .host(&host)
+        .port(port)
+        .user(&user)
+        .dbname(&db_name)
+        .ssl_mode(SslMode::Require);
+    Ok(pg_config)
+}
+
+// IAM 인증 커넥션 풀 생성 (signer 를 주입받아 토큰 발급과 풀 연결을 분리)
+pub fn create_pool<S: AuthTokenSigner + 'static>(
+    pg_config: PgConfig,
+    signer: S,
+    manager_config: ManagerConfig,
+    recycle: ConnectionRecycle,
+    wait_timeout: Duration,
+) -> Result<Pool> {
+    let tls =
+        native_tls::TlsConnector::new().map_err(|e| anyhow!("TLS 커넥터 생성 실패: {:?}", e))?;
+    let connect = IamConnect::new(signer, MakeTlsConnector::new(tls));
+    let manager = Manager::from_connect(pg_config, connect, manager_config);
+
+    let pool = Pool::builder(manager)
+        .runtime(Runtime::Tokio1)
+        .wait_timeout(Some(wait_timeout))
+        .create_timeout(Some(wait_timeout))
+        .pre_recycle(recycle.hook())
+        .build()
+        .map_err(|e| anyhow!("Pool 생성 실패: {:?}", e))?;
+    info!("Connection pool established (RDS IAM auth).");
+    Ok(pool)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::sync::mpsc;
+
+    // 발급할 때마다 번호가 바뀌는 토큰
+    #[derive(Default)]
+    struct SequenceSigner(AtomicUsize);
+
+    impl AuthTokenSigner for SequenceSigner {
+        async fn sign(&self) -> Result<String> {
+            Ok(format!(
+                "token-{}",
+                self.0.fetch_add(1, Ordering::SeqCst) + 1
+            ))
+        }
+    }
+
+    struct FailingSigner;
+
+    impl AuthTokenSigner for FailingSigner {
+        async fn sign(&self) -> Result<String> {
+            Err(anyhow!("no credentials"))
+        }
+    }
+
+    async fn read_message_body(stream: &mut TcpStream) -> Vec<u8> {
+        let len = stream.read_i32().await.unwrap() as usize;
+        let mut body = vec![0; len - 4];
+        stream.read_exact(&mut body).await.unwrap();
+        body
+    }
+
+    // 평문 비밀번호를 요청하고 받은 비밀번호를 보낸 뒤 인증 실패로 끊는 Postgres 서버 흉내
+    async fn password_capturing_server() -> (u16, mpsc::UnboundedReceiver<Option<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                read_message_body(&mut stream).await;
+                // AuthenticationCleartextPassword
+                stream
+                    .write_all(&[b'R', 0, 0, 0, 8, 0, 0, 0, 3])
+                    .await
+                    .unwrap();
+
+                let password = match stream.read_u8().await {
+                    Ok(b'p') => {
+                        let body = read_message_body(&mut stream).await;
+                        Some(String::from_utf8_lossy(&body[..body.len() - 1]).into_owned())
+                    }
+                    _ => None,
+                };
+                let fields = b"SFATAL\0C28P01\0Mpassword authentication failed\0\0";
+                let mut error = vec![b'E'];
+                error.extend_from_slice(&(fields.len() as i32 + 4).to_be_bytes());
+                error.extend_from_slice(fields);
+                let _ = stream.write_all(&error).await;
+                sender.send(password).unwrap();
+            }
+        });
+        (port, receiver)
+    }
+
+    fn plain_config(port: u16) -> PgConfig {
+        let mut pg_config = PgConfig::new();
+        pg_config
+            .host("127.0.0.1")
+            .port(port)
+            .user("lambda")
+            .dbname("postgres")
+            .ssl_mode(SslMode::Disable);
+        pg_config
+    }
+
+    fn tls() -> MakeTlsConnector {
+        MakeTlsConnector::new(native_tls::TlsConnector::new().unwrap())
+    }
+
+    #[tokio::test]
+    async fn every_new_connection_signs_a_fresh_token() {
+        let (port, mut passwords) = password_capturing_server().await;
+        let connect = IamConnect::new(SequenceSigner::default(), tls());
+
+        for expected in ["token-1", "token-2"] {
+            assert!(connect
+                .connect_with_token(&plain_config(port))
+                .await
+                .is_err());
+            assert_eq!(passwords.recv().await.unwrap().as_deref(), Some(expected));
+        }
+    }
+
+    #[tokio::test]
+    async fn signing_failure_fails_the_connection_without_a_password() {
+        let (port, mut passwords) = password_capturing_server().await;
+        let connect = IamConnect::new(FailingSigner, tls());
+
+        assert!(connect
+            .connect_with_token(&plain_config(port))
+            .await
+            .is_err());
+        assert_eq!(passwords.recv().await.unwrap(), None);
+    }
+
+    #[test]
+    fn pg_config_requires_host_and_user_and_forces_tls() {
+        for name in ["DB_HOST", "DB_PORT", "DB_USER", "DB_NAME"] {
+            std::env::remove_var(name);
+        }
+        std::env::set_var("DB_USER", "lambda");
+        assert!(pg_config_from_env()
+            .unwrap_err()
+            .to_string()
+            .contains("DB_HOST"));
+
+        std::env::set_var("DB_HOST", "db.example.internal");
+        let pg_config = pg_config_from_env().unwrap();
+        assert_eq!(pg_config.get_ports(), [DEFAULT_DB_PORT]);
+        assert_eq!(pg_config.get_dbname(), Some(DEFAULT_DB_NAME));
+        assert_eq!(pg_config.get_ssl_mode(), SslMode::Require);
+
+        std::env::set_var("DB_PORT", "6432");
+        std::env::set_var("DB_NAME", "environment");
+        let pg_config = pg_config_from_env().unwrap();
+        assert_eq!(pg_config.get_ports(), [6432]);
+        assert_eq!(pg_config.get_dbname(), Some("environment"));
+        assert_eq!(pg_config.get_user(), Some("lambda"));
+
+        for name in ["DB_HOST", "DB_PORT", "DB_USER", "DB_NAME"] {
+            std::env::remove_var(name);
+        }
+    }
+}
@@ -0,0 +1,464 @@
+// src/reprocess.rs
+
+// 지정 시각 재처리 (payload 의 asOf, source, overwrite)
+// 파싱 오류 수정 후 지난 시간을 다시 저장하기 위한 용도로, 최신 항목 대신 DAILY 응답에서
+// dataTime 이 asOf 와 같은 항목을 골라 upsert
+// 저장된 값이 더 최신이면 갱신하지 않음 (overwrite: true 면 무조건 덮어씀)
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use deadpool_postgres::Client as DbClient;
+use futures::StreamExt;
+use serde_json::json;
+use std::sync::Arc;
+use tokio_postgres::Row;
+
+use crate::db_error::{classify_db_error, describe_db_error};
+use crate::db_schema::sql;
+use crate::failure::FailureKind;
+use crate::handler::{
+    acquire_permit, api_concurrency, fetched_pm_json, new_run_id, per_station_timeout,
+    station_stream, upserted_pm_json, with_station_deadline, FetchOptions, IngestReport,
+    StationResult, SubRegionInfo, UPSERT_EXTERNAL_PM_QUERY,
+};
+use crate::inline_stations::StationListSource;
+use crate::phases::Phases;
+use crate::provider::airkorea::AIRKOREA_PROVIDER_KEY;
+use crate::provider::{AirKoreaProvider, Reading};
+use crate::state::{get_client_with_retry, ServerState};
+use crate::timeutil::truncate_to_hour;
+
+// 저장된 측정 시각이 같거나 이전일 때만 갱신 (같은 시각 재처리는 허용)
+// previous 는 UPSERT_EXTERNAL_PM_QUERY 와 같이 잠그지 않고 읽음 (FOR UPDATE 면 항상 NULL)
+pub const UPSERT_EXTERNAL_PM_IF_NOT_NEWER_QUERY: &str = r#"
+WITH previous AS (
+    SELECT pm10, pm25
+    FROM {schema}.external_pm
+    WHERE sub_region_id = $1
+)
+INSERT INTO {schema}.external_pm (sub_region_id, pm10, pm25, recorded_at)
+VALUES ($1, $2, $3, $4)
+ON CONFLICT (sub_region_id)
+DO UPDATE SET
+    pm10 = EXCLUDED.pm10,
+    pm25 = EXCLUDED.pm25,
+    recorded_at = EXCLUDED.recorded_at,
+    update_at = now()
+WHERE {schema}.external_pm.recorded_at <= EXCLUDED.recorded_at
+RETURNING {schema}.external_pm.*,
+    (SELECT pm10 FROM previous) AS prev_pm10,
+    (SELECT pm25 FROM previous) AS prev_pm25;
+"#;
+
+// DAILY 응답이 담고 있는 기간 (api 재처리 가능 범위)
+const API_DAILY_WINDOW_HOURS: i64 = 24;
+
+// asOf 형식 (예: "2024-05-02T13:00+09:00"), 초가 있는 RFC 3339 도 허용
+const AS_OF_FORMAT: &str = "%Y-%m-%dT%H:%M%:z";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReprocessSource {
+    // 에어코리아 DAILY 응답 (시간별 원문 보관본이 없으므로 유일한 출처)
+    Api,
+}
+
+impl ReprocessSource {
+    fn parse(source: &str) -> Result<Self> {
+        match source {
+            "api" => Ok(ReprocessSource::Api),
+            other => Err(anyhow!("source 는 \"api\" 만 가능: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReprocessOptions {
+    // 재처리할 정시 (UTC)
+    pub as_of: DateTime<Utc>,
+    pub source: ReprocessSource,
+    // 저장된 값이 더 최신이어도 덮어씀
+    pub overwrite: bool,
+}
+
+impl ReprocessOptions {
+    // payload 에 asOf 가 없으면 None, 값이 잘못되었으면 오류
+    // now 는 asOf 가 미래 / DAILY 범위 밖인지 판단하는 기준 시각
+    pub fn from_payload(payload: &serde_json::Value, now: DateTime<Utc>) -> Result<Option<Self>> {
+        let Some(as_of) = payload.get("asOf") else {
+            return Ok(None);
+        };
+        let as_of = as_of
+            .as_str()
+            .ok_or_else(|| anyhow!("asOf 는 문자열이어야 함"))?;
+        let as_of = parse_as_of(as_of)?;
+
+        let source = match payload.get("source").and_then(|v| v.as_str()) {
+            Some(source) => ReprocessSource::parse(source)?,
+            None => ReprocessSource::Api,
+        };
+        validate_as_of(as_of, now)?;
+
+        let overwrite = payload
+            .get("overwrite")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        Ok(Some(ReprocessOptions {
+            as_of,
+            source,
+            overwrite,
+        }))
+    }
+}
+
+// asOf 를 UTC 정시로 변환 (정시가 아니면 오류)
+pub fn parse_as_of(as_of: &str) -> Result<DateTime<Utc>> {
+    let parsed = DateTime::parse_from_str(as_of, AS_OF_FORMAT)
+        .or_else(|_| DateTime::parse_from_rfc3339(as_of))
+        .map_err(|e| anyhow!("잘못된 asOf {:?}: {}", as_of, e))?
+        .with_timezone(&Utc);
+
+    if truncate_to_hour(parsed) != parsed {
+        return Err(anyhow!("asOf 는 정시여야 함: {}", as_of));
+    }
+    Ok(parsed)
+}
+
+// 미래 시각, 보관 기간(DAILY 응답 범위)보다 오래된 시각은 거부
+pub fn validate_as_of(as_of: DateTime<Utc>, now: DateTime<Utc>) -> Result<()> {
+    if as_of > truncate_to_hour(now) {
+        return Err(anyhow!("asOf 가 미래 시각: {}", as_of));
+    }
+    let oldest = truncate_to_hour(now) - Duration::hours(API_DAILY_WINDOW_HOURS);
+    if as_of < oldest {
+        return Err(anyhow!(
+            "asOf 가 보관 기간({}시간)보다 오래됨: {} (가장 오래된 시각: {})",
+            API_DAILY_WINDOW_HOURS,
+            as_of,
+            oldest
+        ));
+    }
+    Ok(())
+}
+
+// DAILY 항목 중 측정 시각이 asOf 와 같은 항목
+pub fn select_as_of(readings: Vec<Reading>, as_of: DateTime<Utc>) -> Option<Reading> {
+    readings
+        .into_iter()
+        .find(|reading| reading.recorded_at == as_of)
+}
+
+// sub_region 목록 조회 후 측정소별 asOf 항목을 upsert
+pub async fn run_reprocess(
+    state: Arc<ServerState>,
+    options: &FetchOptions,
+    reprocess: &ReprocessOptions,
+) -> Result<IngestReport, anyhow::Error> {
+    let start = tokio::time::Instant::now();
+    let run_id = options.run_id.clone().unwrap_or_else(new_run_id);
+
+    // 풀 오류는 PoolError 그대로 반환하여 handle_event 에서 503/500 으로 구분
+    let db_client = state.pool.get().await?;
+
+    let rows = state
+        .sub_region_queries
+        .fetch_rows(
+            &db_client,
+            options.sub_region_ids.as_ref(),
+            options.include_disabled,
+        )
+        .await?;
+    drop(db_client);
+
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(api_concurrency()));
+    let airkorea = AirKoreaProvider::new(state.api_client.clone(), state.air_quality_api_key())
+        .with_source_offset(state.source_offset);
+    let per_station_timeout = per_station_timeout();
+    let dry_run = options.dry_run;
+
+    let mut stations = Vec::new();
+    let mut results = Vec::new();
+
+    for (index, row) in rows.iter().enumerate() {
+        let sub_region = match SubRegionInfo::try_from_row(row) {
+            Ok(sub_region) => sub_region,
+            Err(e) => {
+                let error_message = format!("sub_region row {} : {}", index, e.message);
+                results.push(StationResult::failed(
+                    e.sub_region_id.unwrap_or_default(),
+                    "",
+                    FailureKind::RowMapping,
+                    error_message,
+                ));
+                continue;
+            }
+        };
+
+        // DAILY 응답은 AirKorea 측정소 이름으로만 조회 가능
+        let sub_region_id = sub_region.sub_region_id;
+        let pm_station = match sub_region.pm_station {
+            Some(pm_station) if sub_region.provider == AIRKOREA_PROVIDER_KEY => pm_station,
+            _ => continue,
+        };
+
+        stations.push((sub_region_id, pm_station));
+    }
+
+    // 측정소 future 는 태스크로 만들지 않고 station_stream 에서 실행
+    let state = &state;
+    let airkorea = &airkorea;
+    let semaphore = &semaphore;
+    let stations = station_stream(stations.into_iter().map(|(sub_region_id, pm_station)| {
+        let task_label = pm_station.clone();
+
+        let station_task = async move {
+            let _permit = match acquire_permit(semaphore.clone(), sub_region_id, &pm_station).await
+            {
+                Ok(permit) => permit,
+                Err(result) => return result,
+            };
+
+            with_station_deadline(
+                sub_region_id,
+                &pm_station,
+                per_station_timeout,
+                reprocess_station(
+                    state,
+                    airkorea,
+                    sub_region_id,
+                    &pm_station,
+                    reprocess,
+                    dry_run,
+                ),
+            )
+            .await
+        };
+
+        (sub_region_id, task_label, station_task)
+    }));
+    results.extend(stations.collect::<Vec<_>>().await);
+
+    Ok(IngestReport {
+        run_id,
+        results,
+        skipped_fresh: 0,
+        deferred: 0,
+        cache_hits: 0,
+        db_read_only: false,
+        no_sub_regions: false,
+        advanced: None,
+        data_frozen: false,
+        elapsed: start.elapsed(),
+        warnings: Vec::new(),
+        latest_cache_failures: 0,
+        disabled_sub_regions: None,
+        diagnostics: None,
+        phases: Phases::default(),
+        station_list_source: StationListSource::Db,
+        quota: None,
+    })
+}
+
+// 단일 측정소의 asOf 항목 조회 및 upsert
+async fn reprocess_station(
+    state: &ServerState,
+    airkorea: &AirKoreaProvider,
+    sub_region_id: i32,
+    pm_station: &str,
+    reprocess: &ReprocessOptions,
+    dry_run: bool,
+) -> StationResult {
+    let readings = match airkorea.fetch_history(pm_station).await {
+        Ok(readings) => readings,
+        Err(e) => return StationResult::failed(sub_region_id, pm_station, e.kind, e.message),
+    };
+
+    let Some(reading) = select_as_of(readings, reprocess.as_of) else {
+        let error_message = format!(
+            "{} : No item for asOf {} in API response.",
+            pm_station, reprocess.as_of
+        );
+        return StationResult::failed(
+            sub_region_id,
+            pm_station,
+            FailureKind::NoData,
+            error_message,
+        );
+    };
+
+    store_as_of(
+        state,
+        sub_region_id,
+        pm_station,
+        &reading,
+        reprocess.overwrite,
+        dry_run,
+    )
+    .await
+}
+
+// 지정한 측정값 저장 (저장된 값이 더 최신이면 overwrite 일 때만 덮어씀), raw 재처리와 공통
+pub(crate) async fn store_as_of(
+    state: &ServerState,
+    sub_region_id: i32,
+    pm_station: &str,
+    reading: &Reading,
+    overwrite: bool,
+    dry_run: bool,
+) -> StationResult {
+    if dry_run {
+        return StationResult::success(
+            sub_region_id,
+            pm_station,
+            fetched_pm_json(reading, sub_region_id, pm_station, state.clock.now()),
+        );
+    }
+
+    let (db_client, checkout_retries) = match get_client_with_retry(&state.pool).await {
+        Ok(client) => client,
+        Err(e) => {
+            let error_message = format!("{} : Failed to get db client: {:?}", pm_station, e);
+            return StationResult::failed(
+                sub_region_id,
+                pm_station,
+                FailureKind::DbPool,
+                error_message,
+            );
+        }
+    };
+
+    let result = match upsert_as_of(&db_client, sub_region_id, reading, overwrite).await {
+        // 갱신된 행
+        Ok(Some(row)) => match upserted_pm_json(&row, pm_station) {
+            Ok(data) => StationResult::success(sub_region_id, pm_station, data),
+            Err(e) => {
+                let error_message =
+                    format!("{} : Failed to read upserted row: {:?}", pm_station, e);
+                StationResult::failed(
+                    sub_region_id,
+                    pm_station,
+                    FailureKind::RowMapping,
+                    error_message,
+                )
+            }
+        },
+        // 저장된 값이 더 최신이라 갱신하지 않음
+        Ok(None) => StationResult::success(
+            sub_region_id,
+            pm_station,
+            json!({
+                "subRegionId": sub_region_id,
+                "stationName": pm_station,
+                "dataTime": reading.recorded_at,
+                "updated": false,
+            }),
+        ),
+        Err(e) => {
+            let error_message = format!(
+                "{} : Reprocess upsert failed: {}",
+                pm_station,
+                describe_db_error(&e)
+            );
+            StationResult::failed(
+                sub_region_id,
+                pm_station,
+                classify_db_error(&e),
+                error_message,
+            )
+        }
+    };
+
+    result.with_checkout_retries(checkout_retries)
+}
+
+// overwrite 가 아니면 저장된 값이 더 최신일 때 갱신하지 않음 (None)
+pub async fn upsert_as_of(
+    client: &DbClient,
+    sub_region_id: i32,
+    reading: &Reading,
+    overwrite: bool,
+) -> Result<Option<Row>, tokio_postgres::Error> {
+    let query = if overwrite {
+        sql(UPSERT_EXTERNAL_PM_QUERY)
+    } else {
+        sql(UPSERT_EXTERNAL_PM_IF_NOT_NEWER_QUERY)
+    };
+
+    client
+        .query_opt(
+            query.as_str(),
+            &[
+                &sub_region_id,
+                &reading.pm10,
+                &reading.pm25,
+                &reading.recorded_at,
+            ],
+        )
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn reading(hour: u32, pm10: f64) -> Reading {
+        Reading {
+            pm10: Some(pm10),
+            pm25: None,
+            recorded_at: Utc.with_ymd_and_hms(2024, 5, 2, hour, 0, 0).unwrap(),
+            server_time: None,
+            raw: None,
+            rejected_values: 0,
+        }
+    }
+
+    #[test]
+    fn select_as_of_picks_matching_hour_instead_of_newest() {
+        let readings = vec![reading(6, 30.0), reading(5, 20.0), reading(4, 10.0)];
+        let as_of = parse_as_of("2024-05-02T14:00+09:00").unwrap();
+
+        assert_eq!(
+            select_as_of(readings.clone(), as_of),
+            Some(reading(5, 20.0))
+        );
+        let missing = parse_as_of("2024-05-02T20:00+09:00").unwrap();
+        assert_eq!(select_as_of(readings, missing), None);
+    }
+
+    #[test]
+    fn parse_as_of_rejects_non_hour_values() {
+        assert!(parse_as_of("2024-05-02T13:30+09:00").is_err());
+        assert!(parse_as_of("yesterday").is_err());
+    }
+
+    #[test]
+    fn from_payload_reads_overwrite_and_rejects_s3_source() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 2, 10, 5, 0).unwrap();
+        let payload = json!({ "asOf": "2024-05-02T13:00+09:00", "overwrite": true });
+        let options = ReprocessOptions::from_payload(&payload, now)
+            .unwrap()
+            .unwrap();
+        assert_eq!(options.source, ReprocessSource::Api);
+        assert!(options.overwrite);
+
+        let s3 = json!({ "asOf": "2024-05-02T13:00+09:00", "source": "s3" });
+        assert!(ReprocessOptions::from_payload(&s3, now).is_err());
+        assert_eq!(
+            ReprocessOptions::from_payload(&json!({}), now).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn validate_as_of_rejects_future_and_expired_hours() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 2, 10, 5, 0).unwrap();
+        let hour = |h: i64| truncate_to_hour(now) + Duration::hours(h);
+
+        assert!(validate_as_of(hour(0), now).is_ok());
+        assert!(validate_as_of(hour(-API_DAILY_WINDOW_HOURS), now).is_ok());
+        assert!(validate_as_of(hour(1), now).is_err());
+        assert!(validate_as_of(hour(-API_DAILY_WINDOW_HOURS - 1), now).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+// src/response_detail.rs
+
+// 응답 상세 수준 (payload 의 responseDetail)
+// Step Functions 상태 크기 제한 때문에 측정소별 항목(~100KB)을 응답에서 뺄 수 있도록 함
+// 수집/저장/멱등성 요약은 항상 전체 결과로 처리하고 직렬화 직전에만 적용
+
+use serde_json::Value;
+use std::time::Duration;
+
+use crate::handler::RunSummary;
+
+// summary 에서 제거하는 측정소별 목록
+const PER_STATION_META_FIELDS: [&str; 5] = [
+    "errorList",
+    "errors",
+    "unconfiguredSubRegions",
+    "unmappedStations",
+    "warnings",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResponseDetail {
+    // data + meta 전체 (기본값)
+    #[default]
+    Full,
+    // meta 의 건수/소요 시간만
+    Summary,
+    // meta + 실패/건너뛴 측정소 항목 (data 제외)
+    ErrorsOnly,
+}
+
+impl ResponseDetail {
+    // 알 수 없는 값은 호환성을 위해 full 로 처리
+    pub fn from_payload(payload: &Value) -> Self {
+        match payload.get("responseDetail").and_then(|v| v.as_str()) {
+            Some("summary") => ResponseDetail::Summary,
+            Some("errorsOnly") => ResponseDetail::ErrorsOnly,
+            _ => ResponseDetail::Full,
+        }
+    }
+
+    pub fn apply(self, mut response: Value, summary: &RunSummary, elapsed: Duration) -> Value {
+        if self == ResponseDetail::Full {
+            return response;
+        }
+
+        if let Some(body) = response.as_object_mut() {
+            body.remove("data");
+        }
+
+        if self == ResponseDetail::Summary {
+            if let Some(meta) = response.get_mut("meta").and_then(|m| m.as_object_mut()) {
+                for field in PER_STATION_META_FIELDS {
+                    meta.remove(field);
+                }
+                meta.insert("succeeded".to_owned(), summary.succeeded.into());
+                meta.insert("failed".to_owned(), summary.failed.into());
+                meta.insert("durationMs".to_owned(), (elapsed.as_millis() as u64).into());
+            }
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // 측정소 1건 성공, 1건 실패, 설정 누락/빈 측정소 이름 각 1건인 전체 응답
+    fn full_response() -> Value {
+        json!({
+            "data": [{ "subRegionId": 1, "stationName": "중구", "pm10Value": 42.0 }],
+            "meta": {
+                "runId": "run-1",
+                "outcome": "OK",
+                "message": "SUCCESS: 1",
+                "errorList": ["종로구 : No data available in API response."],
+                "errors": [{
+                    "subRegionId": 2,
+                    "stationName": "종로구",
+                    "kind": "NO_DATA",
+                    "message": "종로구 : No data available in API response.",
+                }],
+                "unconfiguredSubRegions": [4],
+                "unmappedStations": [5],
+                "skippedFresh": 0,
+                "warnings": ["sns : throttled"],
+            }
+        })
+    }
+
+    fn apply(detail: ResponseDetail) -> Value {
+        let response = full_response();
+        let summary = RunSummary::from_response("run-1", &response);
+        detail.apply(response, &summary, Duration::from_millis(1234))
+    }
+
+    #[test]
+    fn detail_is_read_from_the_payload() {
+        let detail = |payload: Value| ResponseDetail::from_payload(&payload);
+        assert_eq!(detail(json!({})), ResponseDetail::Full);
+        assert_eq!(
+            detail(json!({ "responseDetail": "summary" })),
+            ResponseDetail::Summary
+        );
+        assert_eq!(
+            detail(json!({ "responseDetail": "errorsOnly" })),
+            ResponseDetail::ErrorsOnly
+        );
+        // 알 수 없는 값 / 문자열이 아닌 값은 full
+        assert_eq!(
+            detail(json!({ "responseDetail": "minimal" })),
+            ResponseDetail::Full
+        );
+        assert_eq!(detail(json!({ "responseDetail": 1 })), ResponseDetail::Full);
+    }
+
+    #[test]
+    fn full_returns_the_response_unchanged() {
+        assert_eq!(apply(ResponseDetail::Full), full_response());
+    }
+
+    // 저장된 응답 본문과 비교 (summary 는 측정소별 목록 대신 건수/소요 시간만)
+    #[test]
+    fn summary_matches_the_golden_body() {
+        let golden: Value =
+            serde_json::from_str(include_str!("../tests/golden/response_summary.json")).unwrap();
+        assert_eq!(apply(ResponseDetail::Summary), golden);
+    }
+
+    // errorsOnly 는 data 만 빼고 실패/건너뛴 측정소 항목은 유지
+    #[test]
+    fn errors_only_matches_the_golden_body() {
+        let golden: Value =
+            serde_json::from_str(include_str!("../tests/golden/response_errors_only.json"))
+                .unwrap();
+        assert_eq!(apply(ResponseDetail::ErrorsOnly), golden);
+    }
+}
@@ -0,0 +1,159 @@
+// src/run_lock.rs
+
+// 실행 중복 방지: 수동 실행과 정기 실행이 겹치면 같은 측정소를 두 번 조회하고 upsert 가 경합하므로
+// Postgres advisory lock(세션 단위)으로 한 번에 하나의 수집만 실행
+// 락을 잡은 세션의 application_name 에 run_id 를 기록하여 다른 컨테이너에서도 실행 중인 run_id 를 확인
+
+use anyhow::Result;
+use deadpool_postgres::Client as DbClient;
+use tracing::{error, warn};
+
+// 수집 실행 락 키 ("PMIN")
+pub const RUN_LOCK_KEY: i64 = 0x504d_494e;
+
+// 락을 잡은 세션의 application_name 접두사
+const LOCK_HOLDER_PREFIX: &str = "pm_ingest_run:";
+
+pub const TRY_RUN_LOCK_QUERY: &str = r#"
+SELECT pg_try_advisory_lock($1) AS locked;
+"#;
+
+pub const RELEASE_RUN_LOCK_QUERY: &str = r#"
+SELECT pg_advisory_unlock($1) AS released;
+"#;
+
+pub const SET_LOCK_HOLDER_QUERY: &str = r#"
+SELECT set_config('application_name', $1, false);
+"#;
+
+pub const RESET_LOCK_HOLDER_QUERY: &str = r#"
+RESET application_name;
+"#;
+
+// 64비트 키는 classid(상위 32비트) / objid(하위 32비트) 로 나뉘어 기록됨
+// $1 을 bigint 로 명시 (그렇지 않으면 int4 로 추론되어 i64 바인딩이 실패)
+pub const GET_RUN_LOCK_HOLDER_QUERY: &str = r#"
+SELECT a.application_name
+FROM pg_locks l
+JOIN pg_stat_activity a ON a.pid = l.pid
+WHERE l.locktype = 'advisory'
+  AND l.granted
+  AND l.classid::bigint = ($1::bigint >> 32)
+  AND l.objid::bigint = ($1::bigint & 4294967295)
+  AND l.objsubid = 1
+LIMIT 1;
+"#;
+
+// 다른 실행이 락을 잡고 있음 (ALREADY_RUNNING)
+#[derive(Debug)]
+pub struct AlreadyRunningError {
+    // 락을 잡은 실행의 run_id (확인할 수 없으면 None)
+    pub holder_run_id: Option<String>,
+}
+
+impl std::fmt::Display for AlreadyRunningError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.holder_run_id {
+            Some(holder_run_id) => {
+                write!(f, "another ingest run is in progress: {}", holder_run_id)
+            }
+            None => write!(f, "another ingest run is in progress"),
+        }
+    }
+}
+
+impl std::error::Error for AlreadyRunningError {}
+
+// 락을 보유한 커넥션 (release 없이 drop 되면 커넥션을 풀에서 분리하여 닫음 -> 세션 락 해제)
+// 실행이 취소(시간 초과, 종료 신호)되어도 락을 잡은 커넥션이 풀로 돌아가 락이 남지 않도록 함
+pub struct RunLockGuard {
+    db_client: Option<DbClient>,
+}
+
+impl RunLockGuard {
+    pub fn client(&self) -> &DbClient {
+        self.db_client
+            .as_ref()
+            .expect("RunLockGuard client is only taken on release/drop")
+    }
+
+    // 락 해제 후 커넥션을 풀로 반환
+    pub async fn release(mut self) {
+        if let Some(db_client) = self.db_client.take() {
+            release(db_client).await;
+        }
+    }
+}
+
+impl Drop for RunLockGuard {
+    fn drop(&mut self) {
+        if let Some(db_client) = self.db_client.take() {
+            warn!("실행 락을 해제하지 못한 채 취소됨, 커넥션을 닫아 락 해제");
+            drop(DbClient::take(db_client));
+        }
+    }
+}
+
+// 락 획득 (다른 실행이 보유 중이면 AlreadyRunningError)
+pub async fn acquire(db_client: DbClient, run_id: &str) -> Result<RunLockGuard> {
+    if !try_lock(&db_client, run_id).await? {
+        let holder_run_id = holder_run_id(&db_client).await;
+        return Err(anyhow::Error::new(AlreadyRunningError { holder_run_id }));
+    }
+    Ok(RunLockGuard {
+        db_client: Some(db_client),
+    })
+}
+
+// 락 획득 시도 (획득하면 application_name 에 run_id 기록)
+pub async fn try_lock(db_client: &DbClient, run_id: &str) -> Result<bool> {
+    let locked: bool = db_client
+        .query_one(TRY_RUN_LOCK_QUERY, &[&RUN_LOCK_KEY])
+        .await?
+        .try_get("locked")?;
+
+    if locked {
+        let holder = format!("{}{}", LOCK_HOLDER_PREFIX, run_id);
+        if let Err(e) = db_client.query(SET_LOCK_HOLDER_QUERY, &[&holder]).await {
+            warn!("실행 락 보유자 기록 실패: {:?}", e);
+        }
+    }
+    Ok(locked)
+}
+
+// 락을 잡은 실행의 run_id (조회 실패 시 None)
+pub async fn holder_run_id(db_client: &DbClient) -> Option<String> {
+    let row = match db_client
+        .query_opt(GET_RUN_LOCK_HOLDER_QUERY, &[&RUN_LOCK_KEY])
+        .await
+    {
+        Ok(row) => row?,
+        Err(e) => {
+            warn!("실행 락 보유자 조회 실패: {:?}", e);
+            return None;
+        }
+    };
+
+    row.try_get::<_, Option<String>>("application_name")
+        .ok()
+        .flatten()?
+        .strip_prefix(LOCK_HOLDER_PREFIX)
+        .map(str::to_owned)
+}
+
+// 락 해제 (커넥션은 풀로 돌아가므로 반드시 해제)
+// 해제에 실패하면 락을 잡은 채 재사용되지 않도록 커넥션을 풀에서 제거하여 닫음
+pub async fn release(db_client: DbClient) {
+    let released = async {
+        db_client
+            .query(RELEASE_RUN_LOCK_QUERY, &[&RUN_LOCK_KEY])
+            .await?;
+        db_client.batch_execute(RESET_LOCK_HOLDER_QUERY).await
+    }
+    .await;
+
+    if let Err(e) = released {
+        error!("실행 락 해제 실패, 커넥션 폐기: {:?}", e);
+        drop(DbClient::take(db_client));
+    }
+}
@@ -0,0 +1,433 @@
+// src/schema_check.rs
+
+// 대상 DB 스키마 확인 (payload 의 verify: "schema", CLI --verify schema)
+// 배포 전에 수집 코드가 읽고 쓰는 테이블/컬럼이 PM_DB_SCHEMA 스키마에 있는지, 타입이 맞는지 information_schema 로 확인
+// 데이터는 조회/저장하지 않음
+// sub_region 쿼리를 덮어쓴 경우(SUB_REGION_QUERY 등)에는 sub_region 테이블 대신 해당 쿼리를 prepare 하여 확인
+
+use deadpool_postgres::Client as DbClient;
+use serde_json::json;
+use std::collections::HashMap;
+
+use crate::db_schema::db_schema;
+use crate::sub_region_query::SubRegionQueries;
+
+pub const SCHEMA_COLUMNS_QUERY: &str = r#"
+SELECT table_name, column_name, data_type
+FROM information_schema.columns
+WHERE table_schema = $1 AND table_name = ANY($2);
+"#;
+
+// 검사 결과 코드 (meta.outcome)
+pub const SCHEMA_OK_OUTCOME: &str = "OK";
+pub const SCHEMA_MISMATCH_OUTCOME: &str = "SCHEMA_MISMATCH";
+
+// information_schema.columns.data_type 기준 허용 타입
+const INTEGER: &[&str] = &["integer"];
+const FLOAT8: &[&str] = &["double precision"];
+const TEXT: &[&str] = &["text", "character varying"];
+const TIMESTAMPTZ: &[&str] = &["timestamp with time zone"];
+const BOOLEAN: &[&str] = &["boolean"];
+const DATE: &[&str] = &["date"];
+const BIGINT: &[&str] = &["bigint"];
+
+struct ExpectedColumn {
+    name: &'static str,
+    data_types: &'static [&'static str],
+    // false 면 없어도 기본값으로 동작 (있으면 타입은 확인)
+    required: bool,
+}
+
+struct ExpectedTable {
+    name: &'static str,
+    // false 면 해당 기능(backfill, weather, NO_DATA 추적, API 한도 추적)을 쓸 때만 필요
+    required: bool,
+    columns: &'static [ExpectedColumn],
+}
+
+const fn column(
+    name: &'static str,
+    data_types: &'static [&'static str],
+    required: bool,
+) -> ExpectedColumn {
+    ExpectedColumn {
+        name,
+        data_types,
+        required,
+    }
+}
+
+const SUB_REGION_TABLE: ExpectedTable = ExpectedTable {
+    name: "sub_region",
+    required: true,
+    columns: &[
+        column("sub_region_id", INTEGER, true),
+        column("pm_station", TEXT, true),
+        column("tm_x", FLOAT8, false),
+        column("tm_y", FLOAT8, false),
+        column("provider", TEXT, false),
+        column("nx", INTEGER, false),
+        column("ny", INTEGER, false),
+        column("is_active", BOOLEAN, false),
+        column("ingest_enabled", BOOLEAN, false),
+    ],
+};
+
+const DATA_TABLES: [ExpectedTable; 5] = [
+    ExpectedTable {
+        name: "external_pm",
+        required: true,
+        columns: &[
+            column("sub_region_id", INTEGER, true),
+            column("pm10", FLOAT8, true),
+            column("pm25", FLOAT8, true),
+            column("recorded_at", TIMESTAMPTZ, true),
+            column("update_at", TIMESTAMPTZ, true),
+        ],
+    },
+    ExpectedTable {
+        name: "external_pm_history",
+        required: false,
+        columns: &[
+            column("sub_region_id", INTEGER, true),
+            column("pm10", FLOAT8, true),
+            column("pm25", FLOAT8, true),
+            column("recorded_at", TIMESTAMPTZ, true),
+        ],
+    },
+    ExpectedTable {
+        name: "external_weather",
+        required: false,
+        columns: &[
+            column("sub_region_id", INTEGER, true),
+            column("temperature", FLOAT8, true),
+            column("humidity", FLOAT8, true),
+            column("wind_speed", FLOAT8, true),
+            column("recorded_at", TIMESTAMPTZ, true),
+            column("update_at", TIMESTAMPTZ, true),
+        ],
+    },
+    ExpectedTable {
+        name: "pm_nodata_counter",
+        required: false,
+        columns: &[
+            column("sub_region_id", INTEGER, true),
+            column("consecutive_nodata", INTEGER, true),
+            column("updated_at", TIMESTAMPTZ, true),
+        ],
+    },
+    ExpectedTable {
+        name: "api_quota_usage",
+        required: false,
+        columns: &[
+            column("key_hash", TEXT, true),
+            column("usage_date", DATE, true),
+            column("request_count", BIGINT, true),
+            column("updated_at", TIMESTAMPTZ, true),
+        ],
+    },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Ok,
+    // 선택 테이블/컬럼이 없음 (실패 아님)
+    Absent,
+    TableMissing,
+    ColumnMissing,
+    TypeMismatch,
+    // sub_region 쿼리 덮어쓰기 확인 실패
+    QueryInvalid,
+}
+
+impl CheckStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckStatus::Ok => "OK",
+            CheckStatus::Absent => "ABSENT",
+            CheckStatus::TableMissing => "TABLE_MISSING",
+            CheckStatus::ColumnMissing => "COLUMN_MISSING",
+            CheckStatus::TypeMismatch => "TYPE_MISMATCH",
+            CheckStatus::QueryInvalid => "QUERY_INVALID",
+        }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        !matches!(self, CheckStatus::Ok | CheckStatus::Absent)
+    }
+}
+
+// 테이블 또는 컬럼 하나의 확인 결과
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaCheck {
+    pub table: String,
+    // 테이블 단위 결과면 None
+    pub column: Option<String>,
+    pub status: CheckStatus,
+    pub expected: Vec<&'static str>,
+    pub actual: Option<String>,
+    pub detail: Option<String>,
+}
+
+impl SchemaCheck {
+    fn table(table: &str, status: CheckStatus) -> Self {
+        SchemaCheck {
+            table: table.to_owned(),
+            column: None,
+            status,
+            expected: Vec::new(),
+            actual: None,
+            detail: None,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "table": self.table,
+            "column": self.column,
+            "status": self.status.as_str(),
+            "expected": self.expected,
+            "actual": self.actual,
+            "detail": self.detail,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaReport {
+    pub schema: String,
+    pub checks: Vec<SchemaCheck>,
+}
+
+impl SchemaReport {
+    pub fn passed(&self) -> bool {
+        !self.checks.iter().any(|check| check.status.is_failure())
+    }
+
+    // data: 확인 항목 전체, meta: 결과 요약과 실패 항목
+    pub fn to_json(&self, run_id: &str) -> serde_json::Value {
+        let failures: Vec<serde_json::Value> = self
+            .checks
+            .iter()
+            .filter(|check| check.status.is_failure())
+            .map(SchemaCheck::to_json)
+            .collect();
+        json!({
+            "data": self.checks.iter().map(SchemaCheck::to_json).collect::<Vec<_>>(),
+            "meta": {
+                "runId": run_id,
+                "verify": "schema",
+                "schema": self.schema,
+                "outcome": if self.passed() { SCHEMA_OK_OUTCOME } else { SCHEMA_MISMATCH_OUTCOME },
+                "checked": self.checks.len(),
+                "failures": failures,
+            }
+        })
+    }
+}
+
+// 기대 테이블/컬럼과 DB 의 실제 컬럼 비교
+pub async fn verify_schema(
+    client: &DbClient,
+    sub_region_queries: &SubRegionQueries,
+) -> Result<SchemaReport, tokio_postgres::Error> {
+    let schema = db_schema();
+    let custom_sub_region_query = *sub_region_queries != SubRegionQueries::default();
+
+    let mut tables: Vec<&ExpectedTable> = DATA_TABLES.iter().collect();
+    if !custom_sub_region_query {
+        tables.insert(0, &SUB_REGION_TABLE);
+    }
+    let table_names: Vec<&str> = tables.iter().map(|table| table.name).collect();
+
+    // (table, column) -> data_type
+    let actual: HashMap<(String, String), String> = client
+        .query(SCHEMA_COLUMNS_QUERY, &[&schema, &table_names])
+        .await?
+        .iter()
+        .map(|row| {
+            Ok((
+                (row.try_get("table_name")?, row.try_get("column_name")?),
+                row.try_get("data_type")?,
+            ))
+        })
+        .collect::<Result<_, tokio_postgres::Error>>()?;
+
+    let mut checks = Vec::new();
+    if custom_sub_region_query {
+        let (status, detail) = match sub_region_queries.validate(client).await {
+            Ok(()) => (CheckStatus::Ok, None),
+            Err(e) => (CheckStatus::QueryInvalid, Some(e.to_string())),
+        };
+        checks.push(SchemaCheck {
+            detail: detail.or_else(|| Some("sub_region query override".to_owned())),
+            ..SchemaCheck::table("sub_region", status)
+        });
+    }
+    for table in tables {
+        checks.extend(check_table(table, &actual));
+    }
+
+    Ok(SchemaReport {
+        schema: schema.to_owned(),
+        checks,
+    })
+}
+
+fn check_table(
+    table: &ExpectedTable,
+    actual: &HashMap<(String, String), String>,
+) -> Vec<SchemaCheck> {
+    let exists = actual.keys().any(|(name, _)| name == table.name);
+    if !exists {
+        let status = if table.required {
+            CheckStatus::TableMissing
+        } else {
+            CheckStatus::Absent
+        };
+        return vec![SchemaCheck::table(table.name, status)];
+    }
+
+    table
+        .columns
+        .iter()
+        .map(|expected| {
+            let data_type = actual.get(&(table.name.to_owned(), expected.name.to_owned()));
+            let status = match data_type {
+                Some(data_type) if expected.data_types.contains(&data_type.as_str()) => {
+                    CheckStatus::Ok
+                }
+                Some(_) => CheckStatus::TypeMismatch,
+                None if expected.required => CheckStatus::ColumnMissing,
+                None => CheckStatus::Absent,
+            };
+            let detail = (status == CheckStatus::TypeMismatch).then(|| {
+                format!(
+                    "{}.{} : expected {}, found {}",
+                    table.name,
+                    expected.name,
+                    expected.data_types.join(" or "),
+                    data_type.map(String::as_str).unwrap_or_default()
+                )
+            });
+            SchemaCheck {
+                table: table.name.to_owned(),
+                column: Some(expected.name.to_owned()),
+                status,
+                expected: expected.data_types.to_vec(),
+                actual: data_type.cloned(),
+                detail,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(table: &str, columns: &[(&str, &str)]) -> HashMap<(String, String), String> {
+        columns
+            .iter()
+            .map(|(column, data_type)| {
+                (
+                    (table.to_owned(), (*column).to_owned()),
+                    (*data_type).to_owned(),
+                )
+            })
+            .collect()
+    }
+
+    fn statuses(checks: &[SchemaCheck]) -> Vec<(Option<&str>, &'static str)> {
+        checks
+            .iter()
+            .map(|check| (check.column.as_deref(), check.status.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn missing_tables_fail_only_when_required() {
+        let actual = HashMap::new();
+        assert_eq!(
+            statuses(&check_table(&DATA_TABLES[0], &actual)),
+            [(None, "TABLE_MISSING")]
+        );
+        assert_eq!(
+            statuses(&check_table(&DATA_TABLES[1], &actual)),
+            [(None, "ABSENT")]
+        );
+    }
+
+    #[test]
+    fn columns_are_checked_by_presence_and_type() {
+        let actual = columns(
+            "sub_region",
+            &[
+                ("sub_region_id", "integer"),
+                ("pm_station", "character varying"),
+                ("tm_x", "numeric"),
+                ("provider", "text"),
+            ],
+        );
+        let checks = check_table(&SUB_REGION_TABLE, &actual);
+        assert_eq!(
+            statuses(&checks)[..4],
+            [
+                (Some("sub_region_id"), "OK"),
+                (Some("pm_station"), "OK"),
+                (Some("tm_x"), "TYPE_MISMATCH"),
+                (Some("tm_y"), "ABSENT"),
+            ]
+        );
+        assert_eq!(
+            checks[2].detail.as_deref(),
+            Some("sub_region.tm_x : expected double precision, found numeric")
+        );
+        assert_eq!(checks[2].actual.as_deref(), Some("numeric"));
+
+        // 필수 컬럼이 없으면 실패
+        let actual = columns("sub_region", &[("sub_region_id", "integer")]);
+        let checks = check_table(&SUB_REGION_TABLE, &actual);
+        assert_eq!(checks[1].status, CheckStatus::ColumnMissing);
+        assert_eq!(checks[1].expected, ["text", "character varying"]);
+    }
+
+    #[test]
+    fn report_lists_failures_in_meta() {
+        let report = SchemaReport {
+            schema: "v3".to_owned(),
+            checks: vec![
+                SchemaCheck::table("external_pm_history", CheckStatus::Absent),
+                SchemaCheck::table("external_pm", CheckStatus::TableMissing),
+            ],
+        };
+        assert!(!report.passed());
+        let json = report.to_json("run-1");
+        assert_eq!(json["meta"]["outcome"], SCHEMA_MISMATCH_OUTCOME);
+        assert_eq!(json["meta"]["schema"], "v3");
+        assert_eq!(json["meta"]["checked"], 2);
+        assert_eq!(
+            json["meta"]["failures"],
+            json!([{
+                "table": "external_pm",
+                "column": null,
+                "status": "TABLE_MISSING",
+                "expected": [],
+                "actual": null,
+                "detail": null,
+            }])
+        );
+        assert_eq!(json["data"][0]["status"], "ABSENT");
+
+        // 선택 테이블이 없는 것만으로는 통과
+        let report = SchemaReport {
+            checks: report.checks[..1].to_vec(),
+            ..report
+        };
+        assert!(report.passed());
+        assert_eq!(
+            report.to_json("run-2")["meta"]["outcome"],
+            SCHEMA_OK_OUTCOME
+        );
+    }
+}
@@ -0,0 +1,180 @@
+// src/scrub.rs
+
+// 이벤트 로그용 민감 정보 마스킹: Function URL 헤더의 x-trigger-secret / authorization, API 키 등이
+// 로그에 그대로 남지 않도록 키 이름에 key / secret / token / authorization 이 포함된 값을 재귀적으로 가림
+// 문자열 안의 URL 쿼리(serviceKey=...) 와 Bearer 토큰도 가림 (reqwest 오류 메시지에는 요청 URL 이 포함됨)
+
+use serde_json::Value;
+
+const MASK: &str = "***";
+const SENSITIVE_KEY_PARTS: [&str; 4] = ["key", "secret", "token", "authorization"];
+
+// 키 이름이 민감 정보인지 여부 (대소문자 무시)
+fn is_sensitive_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SENSITIVE_KEY_PARTS.iter().any(|part| key.contains(part))
+}
+
+// 중첩 객체 / 배열을 모두 따라가며 민감한 키의 값을 마스킹한 사본 반환
+pub fn scrub_secrets(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| {
+                    let v = if is_sensitive_key(k) {
+                        Value::String(MASK.to_owned())
+                    } else {
+                        scrub_secrets(v)
+                    };
+                    (k.clone(), v)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(scrub_secrets).collect()),
+        Value::String(text) => Value::String(scrub_text(text)),
+        other => other.clone(),
+    }
+}
+
+// 쿼리 파라미터 값이 끝나는 문자
+fn is_value_end(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '&' | '"' | '\'' | ')' | ',' | '}' | ']' | '#' | ';')
+}
+
+// 문자열의 민감한 쿼리 파라미터 값(name=value)과 Bearer 토큰 마스킹
+pub fn scrub_text(text: &str) -> String {
+    let mut scrubbed = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(eq) = rest.find('=') {
+        let (before, after) = (&rest[..eq], &rest[eq + 1..]);
+        let name_start = before
+            .char_indices()
+            .rev()
+            .find(|(_, c)| !(c.is_ascii_alphanumeric() || *c == '_' || *c == '-'))
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let name = &before[name_start..];
+        scrubbed.push_str(before);
+        scrubbed.push('=');
+
+        if name.is_empty() || !is_sensitive_key(name) {
+            rest = after;
+            continue;
+        }
+        let value_end = after.find(is_value_end).unwrap_or(after.len());
+        if value_end > 0 {
+            scrubbed.push_str(MASK);
+        }
+        rest = &after[value_end..];
+    }
+    scrubbed.push_str(rest);
+
+    mask_bearer_tokens(&scrubbed)
+}
+
+fn mask_bearer_tokens(text: &str) -> String {
+    const BEARER: &str = "bearer ";
+    let lower = text.to_ascii_lowercase();
+    let mut masked = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut search_from = 0;
+    while let Some(found) = lower[search_from..].find(BEARER) {
+        let token_start = search_from + found + BEARER.len();
+        let token_end = text[token_start..]
+            .find(is_value_end)
+            .map_or(text.len(), |end| token_start + end);
+        masked.push_str(&text[last..token_start]);
+        if token_end > token_start {
+            masked.push_str(MASK);
+        }
+        last = token_end;
+        search_from = token_end;
+    }
+    masked.push_str(&text[last..]);
+    masked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn masks_sensitive_keys_in_nested_objects_and_arrays() {
+        let payload = json!({
+            "mode": "realtime",
+            "headers": {
+                "X-Trigger-Secret": "s3cr3t",
+                "Authorization": "Bearer abc",
+                "content-type": "application/json",
+            },
+            "targets": [
+                { "apiKey": "k1", "stationName": "중구" },
+                { "nested": [{ "accessToken": "t1" }] },
+            ],
+        });
+
+        assert_eq!(
+            scrub_secrets(&payload),
+            json!({
+                "mode": "realtime",
+                "headers": {
+                    "X-Trigger-Secret": "***",
+                    "Authorization": "***",
+                    "content-type": "application/json",
+                },
+                "targets": [
+                    { "apiKey": "***", "stationName": "중구" },
+                    { "nested": [{ "accessToken": "***" }] },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn masks_service_key_in_urls() {
+        let url =
+            "http://apis.data.go.kr/B552584/ArpltnInforInqireSvc/getMsrstnAcctoRltmMesureDnsty\
+                   ?serviceKey=abc%2Bdef%3D%3D&returnType=json&stationName=중구";
+        let scrubbed = scrub_text(url);
+        assert!(!scrubbed.contains("abc%2Bdef"), "{}", scrubbed);
+        assert!(scrubbed.contains("serviceKey=***&returnType=json&stationName=중구"));
+
+        // 문자열 값 안의 URL 도 마스킹
+        assert_eq!(
+            scrub_secrets(&json!({ "url": "https://example.com/?token=t1&page=2" })),
+            json!({ "url": "https://example.com/?token=***&page=2" })
+        );
+    }
+
+    #[test]
+    fn masks_secrets_in_error_strings() {
+        let error = r#"중구 : Request failed: reqwest::Error { kind: Request, url: Url { scheme: "http", host: Some(Domain("apis.data.go.kr")), query: Some("serviceKey=SECRETKEY&stationName=%EC%A4%91%EA%B5%AC") }, source: TimedOut }"#;
+        let scrubbed = scrub_text(error);
+        assert!(!scrubbed.contains("SECRETKEY"), "{}", scrubbed);
+        assert!(
+            scrubbed.contains(r#"query: Some("serviceKey=***&stationName=%EC%A4%91%EA%B5%AC")"#)
+        );
+
+        assert_eq!(
+            scrub_text("upstream said: Authorization: Bearer eyJhbGciOi.abc, retry later"),
+            "upstream said: Authorization: Bearer ***, retry later"
+        );
+        assert_eq!(
+            scrub_text("client_secret=xyz; expires=3600"),
+            "client_secret=***; expires=3600"
+        );
+    }
+
+    #[test]
+    fn leaves_text_without_secrets_unchanged() {
+        for text in [
+            "pm10=42&pm25=20",
+            "sub_region 3 : 측정소 = 중구",
+            "a == b",
+            "=leading",
+            "",
+        ] {
+            assert_eq!(scrub_text(text), text);
+        }
+    }
+}
@@ -0,0 +1,238 @@
+// src/secrets.rs
+
+// AWS Secrets Manager 에서 서비스 키 조회
+// AIR_QUALITY_API_KEY_SECRET_ARN 설정 시 평문 환경 변수 대신 사용하며, warm 컨테이너에서는 캐시된 값을 재사용
+// 키가 교체되어 인증에 실패하면 refetch_air_quality_api_key 로 캐시를 무시하고 다시 조회
+
+use anyhow::{anyhow, Result};
+use aws_config::BehaviorVersion;
+use std::future::Future;
+use tokio::sync::Mutex;
+
+// 시크릿 문자열 조회 (테스트에서는 고정 값을 반환하는 구현으로 대체)
+pub trait SecretSource: Send + Sync {
+    fn secret_string(&self, secret_id: &str) -> impl Future<Output = Result<String>> + Send;
+}
+
+impl SecretSource for aws_sdk_secretsmanager::Client {
+    async fn secret_string(&self, secret_id: &str) -> Result<String> {
+        let output = self
+            .get_secret_value()
+            .secret_id(secret_id)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Secrets Manager 조회 실패: {:?}", e))?;
+
+        output
+            .secret_string()
+            .map(|secret| secret.to_owned())
+            .ok_or_else(|| anyhow!("{} : SecretString 이 비어 있음", secret_id))
+    }
+}
+
+// 시크릿 값에서 키 추출
+// 평문 문자열이면 그대로, JSON 객체면 key_name 필드 사용 (콘솔의 key/value 형식)
+pub fn extract_key(secret: &str, key_name: &str) -> Result<String> {
+    let key = match serde_json::from_str::<serde_json::Value>(secret) {
+        Ok(serde_json::Value::Object(fields)) => fields
+            .get(key_name)
+            .and_then(|v| v.as_str())
+            .map(|v| v.to_owned())
+            .ok_or_else(|| anyhow!("시크릿에 {} 필드 없음", key_name))?,
+        _ => secret.trim().to_owned(),
+    };
+
+    if key.is_empty() {
+        return Err(anyhow!("시크릿의 {} 값이 비어 있음", key_name));
+    }
+    Ok(key)
+}
+
+// source 로 시크릿을 조회하여 key_name 값 반환
+pub async fn fetch_key<S: SecretSource>(
+    source: &S,
+    secret_id: &str,
+    key_name: &str,
+) -> Result<String> {
+    let secret = source.secret_string(secret_id).await?;
+    extract_key(&secret, key_name)
+}
+
+// 조회한 키 캐시 (fetch 는 캐시가 비어 있을 때만 실행)
+pub struct SecretKeyCache {
+    key: Mutex<Option<String>>,
+}
+
+impl SecretKeyCache {
+    pub const fn new() -> Self {
+        SecretKeyCache {
+            key: Mutex::const_new(None),
+        }
+    }
+
+    pub async fn get_or_fetch(
+        &self,
+        fetch: impl Future<Output = Result<String>>,
+    ) -> Result<String> {
+        let mut key = self.key.lock().await;
+        if let Some(key) = key.as_ref() {
+            return Ok(key.clone());
+        }
+        let fetched = fetch.await?;
+        *key = Some(fetched.clone());
+        Ok(fetched)
+    }
+
+    // 캐시를 무시하고 다시 조회 (실패하면 기존 값 유지)
+    pub async fn refetch(&self, fetch: impl Future<Output = Result<String>>) -> Result<String> {
+        let mut key = self.key.lock().await;
+        let fetched = fetch.await?;
+        *key = Some(fetched.clone());
+        Ok(fetched)
+    }
+}
+
+impl Default for SecretKeyCache {
+    fn default() -> Self {
+        SecretKeyCache::new()
+    }
+}
+
+static AIR_QUALITY_API_KEY: SecretKeyCache = SecretKeyCache::new();
+
+fn air_quality_api_key_secret_arn() -> Option<String> {
+    std::env::var("AIR_QUALITY_API_KEY_SECRET_ARN")
+        .ok()
+        .filter(|arn| !arn.is_empty())
+}
+
+async fn fetch_from_secrets_manager(secret_arn: &str) -> Result<String> {
+    let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+    let client = aws_sdk_secretsmanager::Client::new(&sdk_config);
+    fetch_key(&client, secret_arn, "AIR_QUALITY_API_KEY").await
+}
+
+// AIR_QUALITY_API_KEY: AIR_QUALITY_API_KEY_SECRET_ARN 이 있으면 Secrets Manager (컨테이너당 1회 조회), 없으면 환경 변수
+pub async fn air_quality_api_key() -> Result<String> {
+    let Some(secret_arn) = air_quality_api_key_secret_arn() else {
+        return std::env::var("AIR_QUALITY_API_KEY")
+            .map_err(|e| anyhow!("AIR_QUALITY_API_KEY 환경 변수 누락: {:?}", e));
+    };
+
+    AIR_QUALITY_API_KEY
+        .get_or_fetch(fetch_from_secrets_manager(&secret_arn))
+        .await
+}
+
+// 서비스 키 인증 실패 후 Secrets Manager 에서 다시 조회 (환경 변수 키는 바뀌지 않으므로 None)
+pub async fn refetch_air_quality_api_key() -> Result<Option<String>> {
+    let Some(secret_arn) = air_quality_api_key_secret_arn() else {
+        return Ok(None);
+    };
+
+    AIR_QUALITY_API_KEY
+        .refetch(fetch_from_secrets_manager(&secret_arn))
+        .await
+        .map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // 호출할 때마다 준비된 시크릿을 순서대로 반환 (교체된 키 시뮬레이션)
+    struct RotatingSource {
+        secrets: Vec<&'static str>,
+        calls: AtomicUsize,
+    }
+
+    impl RotatingSource {
+        fn new(secrets: Vec<&'static str>) -> Self {
+            RotatingSource {
+                secrets,
+                calls: AtomicUsize::new(0),
+            }
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    impl SecretSource for RotatingSource {
+        async fn secret_string(&self, secret_id: &str) -> Result<String> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            self.secrets
+                .get(call)
+                .map(|secret| secret.to_string())
+                .ok_or_else(|| anyhow!("{} : 조회 실패", secret_id))
+        }
+    }
+
+    #[test]
+    fn extract_key_reads_plain_and_json_secrets() {
+        assert_eq!(extract_key(" plain-key \n", "K").unwrap(), "plain-key");
+        assert_eq!(
+            extract_key(r#"{"K": "json-key"}"#, "K").unwrap(),
+            "json-key"
+        );
+        assert!(extract_key(r#"{"OTHER": "x"}"#, "K").is_err());
+        assert!(extract_key(r#"{"K": ""}"#, "K").is_err());
+    }
+
+    #[tokio::test]
+    async fn cached_key_is_reused_until_refetched_after_rotation() {
+        let source = RotatingSource::new(vec![
+            r#"{"AIR_QUALITY_API_KEY": "old-key"}"#,
+            r#"{"AIR_QUALITY_API_KEY": "rotated-key"}"#,
+        ]);
+        let cache = SecretKeyCache::new();
+        let fetch = || fetch_key(&source, "arn:secret", "AIR_QUALITY_API_KEY");
+
+        assert_eq!(cache.get_or_fetch(fetch()).await.unwrap(), "old-key");
+        assert_eq!(cache.get_or_fetch(fetch()).await.unwrap(), "old-key");
+        assert_eq!(source.calls(), 1);
+
+        // 인증 실패 후 다시 조회하면 교체된 키를 받고, 이후에는 그 키를 재사용
+        assert_eq!(cache.refetch(fetch()).await.unwrap(), "rotated-key");
+        assert_eq!(cache.get_or_fetch(fetch()).await.unwrap(), "rotated-key");
+        assert_eq!(source.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn failed_refetch_keeps_the_previous_key() {
+        let source = RotatingSource::new(vec!["only-key"]);
+        let cache = SecretKeyCache::new();
+        let fetch = || fetch_key(&source, "arn:secret", "AIR_QUALITY_API_KEY");
+
+        assert_eq!(cache.get_or_fetch(fetch()).await.unwrap(), "only-key");
+        assert!(cache.refetch(fetch()).await.is_err());
+        assert_eq!(cache.get_or_fetch(fetch()).await.unwrap(), "only-key");
+    }
+
+    #[tokio::test]
+    async fn concurrent_cold_starts_fetch_the_secret_once() {
+        let source = RotatingSource::new(vec!["first-key", "second-key"]);
+        let cache = SecretKeyCache::new();
+        let fetch = || fetch_key(&source, "arn:secret", "AIR_QUALITY_API_KEY");
+
+        let keys = futures::future::join_all((0..5).map(|_| cache.get_or_fetch(fetch()))).await;
+        for key in keys {
+            assert_eq!(key.unwrap(), "first-key");
+        }
+        assert_eq!(source.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_first_fetch_is_not_cached() {
+        let source = RotatingSource::new(vec![r#"{"OTHER": "x"}"#, "retried-key"]);
+        let cache = SecretKeyCache::new();
+        let fetch = || fetch_key(&source, "arn:secret", "AIR_QUALITY_API_KEY");
+
+        // 필드가 없는 시크릿은 오류로 끝나고 다음 호출에서 다시 조회
+        assert!(cache.get_or_fetch(fetch()).await.is_err());
+        assert_eq!(cache.get_or_fetch(fetch()).await.unwrap(), "retried-key");
+        assert_eq!(source.calls(), 2);
+    }
+}
@@ -0,0 +1,198 @@
+// src/sink.rs
+
+// 분석 파이프라인용 출력 (PM_SNS_TOPIC_ARN 설정 시 SNS, FIREHOSE_STREAM_NAME 설정 시 Firehose)
+// DB 저장이 기본이며, 발행 실패는 errorList 가 아닌 warnings 로 기록
+
+pub mod firehose;
+
+use anyhow::{anyhow, Result};
+use aws_config::BehaviorVersion;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tracing::warn;
+
+use crate::handler::{IngestReport, StationStatus, MAX_CONCURRENT_REQUESTS};
+
+pub use firehose::{FirehoseClient, FirehoseSink};
+
+// 실행 결과 출력 (출력 방식과 무관하게 실패는 경고 메시지로 반환)
+pub trait ReadingSink: Send + Sync {
+    fn deliver(&self, report: &IngestReport) -> impl Future<Output = Vec<String>> + Send;
+}
+
+// 메시지 발행 (테스트에서는 발행된 메시지를 기록하는 구현으로 대체)
+pub trait ReadingPublisher: Send + Sync {
+    fn publish(&self, message: String) -> impl Future<Output = Result<()>> + Send;
+}
+
+pub struct SnsPublisher {
+    client: aws_sdk_sns::Client,
+    topic_arn: String,
+}
+
+impl SnsPublisher {
+    // PM_SNS_TOPIC_ARN 이 없으면 None
+    pub async fn from_env() -> Option<Self> {
+        let topic_arn = std::env::var("PM_SNS_TOPIC_ARN")
+            .ok()
+            .filter(|arn| !arn.is_empty())?;
+        let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+
+        Some(SnsPublisher {
+            client: aws_sdk_sns::Client::new(&sdk_config),
+            topic_arn,
+        })
+    }
+}
+
+impl ReadingPublisher for SnsPublisher {
+    async fn publish(&self, message: String) -> Result<()> {
+        self.client
+            .publish()
+            .topic_arn(&self.topic_arn)
+            .message(message)
+            .send()
+            .await
+            .map_err(|e| anyhow!("SNS publish failed: {:?}", e))?;
+        Ok(())
+    }
+}
+
+// 성공한 측정소마다 메시지 1건 발행 (runId 포함), 실패한 발행은 경고 메시지로 반환
+pub async fn publish_readings<P: ReadingPublisher + 'static>(
+    publisher: Arc<P>,
+    report: &IngestReport,
+) -> Vec<String> {
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS));
+    let mut tasks = Vec::new();
+
+    for result in &report.results {
+        let StationStatus::Success(data) = &result.status else {
+            continue;
+        };
+
+        let publisher = publisher.clone();
+        let semaphore = semaphore.clone();
+        let pm_station = result.pm_station.clone();
+        let mut message = data.clone();
+        message["runId"] = serde_json::json!(report.run_id);
+        let message = message.to_string();
+
+        tasks.push(tokio::spawn(async move {
+            let _permit = semaphore.acquire_owned().await.ok();
+            publisher
+                .publish(message)
+                .await
+                .map_err(|e| format!("{} : {}", pm_station, e))
+        }));
+    }
+
+    let mut warnings = Vec::new();
+    for task in tasks {
+        let warning = match task.await {
+            Ok(Ok(())) => continue,
+            Ok(Err(warning)) => warning,
+            Err(e) => format!("Publish task failed: {:?}", e),
+        };
+        warn!("{}", warning);
+        warnings.push(warning);
+    }
+
+    warnings
+}
+
+// 메시지 단위 발행(SNS 등)을 ReadingSink 로 사용
+pub struct PublisherSink<P: ReadingPublisher + 'static>(pub Arc<P>);
+
+impl<P: ReadingPublisher + 'static> ReadingSink for PublisherSink<P> {
+    async fn deliver(&self, report: &IngestReport) -> Vec<String> {
+        publish_readings(self.0.clone(), report).await
+    }
+}
+
+// 설정된 출력으로 성공 결과 전달 (PM_SNS_TOPIC_ARN / FIREHOSE_STREAM_NAME 모두 미설정이면 아무것도 하지 않음)
+pub async fn publish_from_env(report: &IngestReport) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if let Some(publisher) = SnsPublisher::from_env().await {
+        let sink = PublisherSink(Arc::new(publisher));
+        warnings.extend(sink.deliver(report).await);
+    }
+    if let Some(sink) = FirehoseSink::from_env().await {
+        warnings.extend(sink.deliver(report).await);
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::failure::FailureKind;
+    use crate::handler::StationResult;
+    use crate::inline_stations::StationListSource;
+    use crate::phases::Phases;
+    use serde_json::json;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    // 발행된 메시지를 기록, fail_on 측정소가 들어간 메시지는 실패
+    struct RecordingPublisher {
+        messages: Mutex<Vec<serde_json::Value>>,
+        fail_on: &'static str,
+    }
+
+    impl ReadingPublisher for RecordingPublisher {
+        async fn publish(&self, message: String) -> Result<()> {
+            let message: serde_json::Value = serde_json::from_str(&message)?;
+            if message["stationName"] == self.fail_on {
+                return Err(anyhow!("throttled"));
+            }
+            self.messages.lock().unwrap().push(message);
+            Ok(())
+        }
+    }
+
+    fn report(results: Vec<StationResult>) -> IngestReport {
+        IngestReport {
+            run_id: "0192a1b2-0000-7000-8000-000000000001".to_owned(),
+            results,
+            skipped_fresh: 0,
+            deferred: 0,
+            cache_hits: 0,
+            db_read_only: false,
+            no_sub_regions: false,
+            advanced: None,
+            data_frozen: false,
+            elapsed: Duration::ZERO,
+            warnings: Vec::new(),
+            latest_cache_failures: 0,
+            disabled_sub_regions: None,
+            diagnostics: None,
+            phases: Phases::default(),
+            station_list_source: StationListSource::Db,
+            quota: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn publishes_successes_with_run_id_and_reports_failed_publishes() {
+        let publisher = Arc::new(RecordingPublisher {
+            messages: Mutex::new(Vec::new()),
+            fail_on: "종로구",
+        });
+        let report = report(vec![
+            StationResult::success(1, "중구", json!({ "stationName": "중구" })),
+            StationResult::success(2, "종로구", json!({ "stationName": "종로구" })),
+            StationResult::failed(3, "용산구", FailureKind::Timeout, "timeout".to_owned()),
+        ]);
+
+        let warnings = PublisherSink(publisher.clone()).deliver(&report).await;
+
+        // 실패한 측정소는 발행하지 않고, 발행 실패는 측정소 이름과 함께 경고로 반환
+        let messages = publisher.messages.lock().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["stationName"], "중구");
+        assert_eq!(messages[0]["runId"], report.run_id);
+        assert_eq!(warnings, vec!["종로구 : throttled".to_owned()]);
+    }
+}
@@ -0,0 +1,411 @@
+// src/sink/firehose.rs
+
+// 데이터 레이크용 Kinesis Firehose 출력 (FIREHOSE_STREAM_NAME 설정 시)
+// 성공한 측정값을 NDJSON 레코드로 만들어 PutRecordBatch (배치당 최대 500건 / 4 MB) 로 전송하고,
+// 일부 레코드만 실패하면 실패한 레코드만 최대 2번 재전송
+
+use anyhow::{anyhow, Result};
+use aws_config::BehaviorVersion;
+use aws_sdk_firehose::primitives::Blob;
+use aws_sdk_firehose::types::Record;
+use serde_json::json;
+use std::future::Future;
+use tracing::warn;
+
+use super::ReadingSink;
+use crate::handler::{IngestReport, StationStatus};
+
+// PutRecordBatch 제한
+pub const FIREHOSE_MAX_BATCH_RECORDS: usize = 500;
+pub const FIREHOSE_MAX_BATCH_BYTES: usize = 4 * 1024 * 1024;
+
+// 일부 실패한 레코드 재전송 횟수
+const FIREHOSE_MAX_RETRIES: usize = 2;
+
+// 레코드 배치 전송 (테스트에서는 MockFirehoseClient 로 대체)
+pub trait FirehoseClient: Send + Sync {
+    // 실패한 레코드의 인덱스 반환 (요청 자체가 실패하면 Err)
+    fn put_record_batch(
+        &self,
+        records: &[Vec<u8>],
+    ) -> impl Future<Output = Result<Vec<usize>>> + Send;
+}
+
+pub struct AwsFirehoseClient {
+    client: aws_sdk_firehose::Client,
+    stream_name: String,
+}
+
+impl FirehoseClient for AwsFirehoseClient {
+    async fn put_record_batch(&self, records: &[Vec<u8>]) -> Result<Vec<usize>> {
+        let records = records
+            .iter()
+            .map(|data| Record::builder().data(Blob::new(data.clone())).build())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| anyhow!("Firehose record build failed: {:?}", e))?;
+
+        let output = self
+            .client
+            .put_record_batch()
+            .delivery_stream_name(&self.stream_name)
+            .set_records(Some(records))
+            .send()
+            .await
+            .map_err(|e| anyhow!("Firehose PutRecordBatch failed: {:?}", e))?;
+
+        Ok(output
+            .request_responses()
+            .iter()
+            .enumerate()
+            .filter(|(_, response)| response.error_code().is_some())
+            .map(|(index, _)| index)
+            .collect())
+    }
+}
+
+pub struct FirehoseSink<C: FirehoseClient> {
+    client: C,
+}
+
+impl FirehoseSink<AwsFirehoseClient> {
+    // FIREHOSE_STREAM_NAME 이 없으면 None
+    pub async fn from_env() -> Option<Self> {
+        let stream_name = std::env::var("FIREHOSE_STREAM_NAME")
+            .ok()
+            .filter(|name| !name.is_empty())?;
+        let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+
+        Some(FirehoseSink::new(AwsFirehoseClient {
+            client: aws_sdk_firehose::Client::new(&sdk_config),
+            stream_name,
+        }))
+    }
+}
+
+impl<C: FirehoseClient> FirehoseSink<C> {
+    pub fn new(client: C) -> Self {
+        FirehoseSink { client }
+    }
+
+    // 배치 전송, 실패한 레코드만 재전송 (재시도 후에도 남으면 경고 메시지 반환)
+    async fn put_batch(&self, mut records: Vec<Vec<u8>>) -> Option<String> {
+        let mut last_error = None;
+        for attempt in 0..=FIREHOSE_MAX_RETRIES {
+            if attempt > 0 {
+                warn!(
+                    "Firehose 재전송 {}/{}: {} 건",
+                    attempt,
+                    FIREHOSE_MAX_RETRIES,
+                    records.len()
+                );
+            }
+
+            match self.client.put_record_batch(&records).await {
+                Ok(failed) if failed.is_empty() => return None,
+                Ok(failed) => {
+                    records = records
+                        .into_iter()
+                        .enumerate()
+                        .filter(|(index, _)| failed.contains(index))
+                        .map(|(_, record)| record)
+                        .collect();
+                    last_error = None;
+                }
+                // 요청 자체가 실패하면 배치 전체를 다시 전송
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Some(match last_error {
+            Some(e) => format!("Firehose : {} records not delivered: {}", records.len(), e),
+            None => format!(
+                "Firehose : {} records failed after {} retries",
+                records.len(),
+                FIREHOSE_MAX_RETRIES
+            ),
+        })
+    }
+}
+
+impl<C: FirehoseClient> ReadingSink for FirehoseSink<C> {
+    async fn deliver(&self, report: &IngestReport) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for batch in batch_records(firehose_records(report)) {
+            if let Some(warning) = self.put_batch(batch).await {
+                warn!("{}", warning);
+                warnings.push(warning);
+            }
+        }
+        warnings
+    }
+}
+
+// 성공한 측정소마다 NDJSON 레코드 1건
+pub fn firehose_records(report: &IngestReport) -> Vec<Vec<u8>> {
+    report
+        .results
+        .iter()
+        .filter_map(|result| {
+            let StationStatus::Success(data) = &result.status else {
+                return None;
+            };
+            let mut line = json!({
+                "sub_region_id": result.sub_region_id,
+                "station": result.pm_station,
+                "pm10": data["pm10Value"],
+                "pm25": data["pm25Value"],
+                "recorded_at": data["dataTime"],
+                "run_id": report.run_id,
+            })
+            .to_string()
+            .into_bytes();
+            line.push(b'\n');
+            Some(line)
+        })
+        .collect()
+}
+
+// 레코드 수 / 전체 크기 제한에 맞춰 배치 분할 (순서 유지)
+pub fn batch_records(records: Vec<Vec<u8>>) -> Vec<Vec<Vec<u8>>> {
+    let mut batches = Vec::new();
+    let mut batch: Vec<Vec<u8>> = Vec::new();
+    let mut batch_bytes = 0;
+
+    for record in records {
+        let full = batch.len() >= FIREHOSE_MAX_BATCH_RECORDS
+            || batch_bytes + record.len() > FIREHOSE_MAX_BATCH_BYTES;
+        if full && !batch.is_empty() {
+            batches.push(std::mem::take(&mut batch));
+            batch_bytes = 0;
+        }
+        batch_bytes += record.len();
+        batch.push(record);
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+
+    batches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::failure::FailureKind;
+    use crate::handler::StationResult;
+    use crate::inline_stations::StationListSource;
+    use crate::phases::Phases;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    // 호출마다 준비된 실패 인덱스를 돌려주고 전송된 배치를 기록하는 구현
+    #[derive(Default)]
+    struct MockFirehoseClient {
+        failures: Mutex<VecDeque<Vec<usize>>>,
+        sent: Mutex<Vec<Vec<Vec<u8>>>>,
+    }
+
+    impl MockFirehoseClient {
+        fn new() -> Self {
+            MockFirehoseClient::default()
+        }
+
+        // 다음 호출에서 실패로 응답할 레코드 인덱스 (준비된 응답이 없으면 모두 성공)
+        fn with_failures(self, failed: Vec<usize>) -> Self {
+            self.failures
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push_back(failed);
+            self
+        }
+
+        fn sent(&self) -> Vec<Vec<Vec<u8>>> {
+            self.sent.lock().unwrap_or_else(|e| e.into_inner()).clone()
+        }
+    }
+
+    impl FirehoseClient for MockFirehoseClient {
+        async fn put_record_batch(&self, records: &[Vec<u8>]) -> Result<Vec<usize>> {
+            self.sent
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(records.to_vec());
+            let failed = self
+                .failures
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .pop_front()
+                .unwrap_or_default();
+            Ok(failed
+                .into_iter()
+                .filter(|index| *index < records.len())
+                .collect())
+        }
+    }
+
+    // 한 번 호출에 요청 자체가 실패하는 구현
+    struct FailingFirehoseClient;
+
+    impl FirehoseClient for FailingFirehoseClient {
+        async fn put_record_batch(&self, _records: &[Vec<u8>]) -> Result<Vec<usize>> {
+            Err(anyhow!("connection reset"))
+        }
+    }
+
+    fn records(count: usize, size: usize) -> Vec<Vec<u8>> {
+        (0..count).map(|index| vec![index as u8; size]).collect()
+    }
+
+    #[test]
+    fn batches_split_at_the_record_limit() {
+        let batches = batch_records(records(1201, 10));
+        let sizes: Vec<usize> = batches.iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![500, 500, 201]);
+        // 순서 유지
+        assert_eq!(batches[1][0], vec![(500 % 256) as u8; 10]);
+    }
+
+    #[test]
+    fn batches_split_at_the_byte_limit() {
+        // 1 MB 레코드 5건 → 4 MB 를 넘지 않도록 4건 / 1건
+        let batches = batch_records(records(5, 1024 * 1024));
+        let sizes: Vec<usize> = batches.iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![4, 1]);
+        assert!(batches
+            .iter()
+            .all(|batch| batch.iter().map(Vec::len).sum::<usize>() <= FIREHOSE_MAX_BATCH_BYTES));
+    }
+
+    #[test]
+    fn oversized_record_gets_its_own_batch() {
+        let mut input = records(2, 10);
+        input.insert(1, vec![0; FIREHOSE_MAX_BATCH_BYTES + 1]);
+        let sizes: Vec<usize> = batch_records(input).iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![1, 1, 1]);
+        assert!(batch_records(Vec::new()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn only_failed_records_are_resent() {
+        let sink = FirehoseSink::new(MockFirehoseClient::new().with_failures(vec![1, 3]));
+
+        assert_eq!(sink.put_batch(records(4, 1)).await, None);
+        let sent = sink.client.sent();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0], records(4, 1));
+        assert_eq!(sent[1], vec![vec![1u8], vec![3u8]]);
+    }
+
+    #[tokio::test]
+    async fn remaining_failures_become_a_warning() {
+        let sink = FirehoseSink::new(
+            MockFirehoseClient::new()
+                .with_failures(vec![0, 1])
+                .with_failures(vec![1])
+                .with_failures(vec![0]),
+        );
+
+        let warning = sink.put_batch(records(3, 1)).await.unwrap();
+        assert_eq!(warning, "Firehose : 1 records failed after 2 retries");
+        // 최초 1번 + 재전송 2번
+        let sent = sink.client.sent();
+        assert_eq!(sent.iter().map(Vec::len).collect::<Vec<_>>(), vec![3, 2, 1]);
+        assert_eq!(sent[2], vec![vec![1u8]]);
+    }
+
+    #[tokio::test]
+    async fn request_failure_becomes_a_warning() {
+        let sink = FirehoseSink::new(FailingFirehoseClient);
+        let warning = sink.put_batch(records(2, 1)).await.unwrap();
+        assert_eq!(
+            warning,
+            "Firehose : 2 records not delivered: connection reset"
+        );
+    }
+
+    fn report(results: Vec<StationResult>) -> IngestReport {
+        IngestReport {
+            run_id: "run-1".to_owned(),
+            results,
+            skipped_fresh: 0,
+            deferred: 0,
+            cache_hits: 0,
+            db_read_only: false,
+            no_sub_regions: false,
+            advanced: None,
+            data_frozen: false,
+            elapsed: Duration::ZERO,
+            warnings: Vec::new(),
+            latest_cache_failures: 0,
+            disabled_sub_regions: None,
+            diagnostics: None,
+            phases: Phases::default(),
+            station_list_source: StationListSource::Db,
+            quota: None,
+        }
+    }
+
+    fn success(sub_region_id: i32) -> StationResult {
+        StationResult::success(
+            sub_region_id,
+            "중구",
+            json!({
+                "pm10Value": 42.0,
+                "pm25Value": null,
+                "dataTime": "2024-05-01T04:00:00Z",
+                "stationName": "중구",
+            }),
+        )
+    }
+
+    #[test]
+    fn records_are_ndjson_lines_for_successful_stations() {
+        let records = firehose_records(&report(vec![
+            success(1),
+            StationResult::failed(2, "용산구", FailureKind::Timeout, "timeout".to_owned()),
+        ]));
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].last(), Some(&b'\n'));
+        let line: serde_json::Value = serde_json::from_slice(&records[0]).unwrap();
+        assert_eq!(
+            line,
+            json!({
+                "sub_region_id": 1,
+                "station": "중구",
+                "pm10": 42.0,
+                "pm25": null,
+                "recorded_at": "2024-05-01T04:00:00Z",
+                "run_id": "run-1",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn deliver_sends_every_batch_and_collects_warnings() {
+        // 첫 배치(500건)는 모두 성공, 두 번째 배치는 재전송 후에도 1건 실패
+        let sink = FirehoseSink::new(
+            MockFirehoseClient::new()
+                .with_failures(vec![])
+                .with_failures(vec![0])
+                .with_failures(vec![0])
+                .with_failures(vec![0]),
+        );
+
+        let warnings = sink
+            .deliver(&report((1..=501).map(success).collect()))
+            .await;
+        assert_eq!(
+            warnings,
+            vec!["Firehose : 1 records failed after 2 retries".to_owned()]
+        );
+        let sizes: Vec<usize> = sink.client.sent().iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![500, 1, 1, 1]);
+
+        // 성공한 측정소가 없으면 전송하지 않음
+        let sink = FirehoseSink::new(MockFirehoseClient::new());
+        assert!(sink.deliver(&report(Vec::new())).await.is_empty());
+        assert!(sink.client.sent().is_empty());
+    }
+}
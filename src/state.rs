@@ -1,37 +1,609 @@
 // src/state.rs
 
 use anyhow::{anyhow, Result};
-use deadpool_postgres::{Config, ManagerConfig, Pool, RecyclingMethod, Runtime};
+use chrono::FixedOffset;
+use deadpool_postgres::{
+    Config, Hook, HookError, ManagerConfig, Metrics, Object, Pool, PoolError, RecyclingMethod,
+    Runtime,
+};
+use reqwest::Client;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::OnceCell;
+use tokio_postgres::config::Host;
 use tokio_postgres::NoTls;
-use tracing::info;
+use tracing::{debug, info, warn};
+
+use crate::clock::{Clock, SystemClock};
+use crate::db_conn::DbConnConfig;
+use crate::db_schema;
+use crate::fallback::FallbackSink;
+use crate::handler::{api_concurrency, db_write_concurrency};
+use crate::init_timing::{log_phase, timed};
+use crate::latest_cache::LatestCache;
+use crate::provider::{ApiClient, ReqwestApiClient};
+use crate::rds_iam::{self, AuthTokenSigner, RdsAuthTokenSigner};
+use crate::secrets;
+use crate::sub_region_query::SubRegionQueries;
+use crate::timeutil::{self, KST_OFFSET};
+
+// Postgres 커넥션 설정 기본값
+const DEFAULT_KEEPALIVES_IDLE_SECS: u64 = 30;
+const DEFAULT_STATEMENT_TIMEOUT_MS: u64 = 10_000;
+const APPLICATION_NAME: &str = "pm_ingest_lambda";
+
+// 커넥션 획득 재시도 간격 (첫 시도 포함 최대 3번 시도)
+const POOL_CHECKOUT_RETRY_DELAYS_MS: [u64; 2] = [100, 300];
+
+// 초기화 단계에서 미리 여는 커넥션 수 기본값
+const DEFAULT_POOL_WARM_CONNECTIONS: usize = 2;
+
+// 커넥션 대기 / 생성 제한 시간 기본값 (없으면 풀이 고갈될 때 pool.get() 이 무한 대기)
+const DEFAULT_POOL_WAIT_TIMEOUT_MS: u64 = 5_000;
 
 pub struct ServerState {
     pub pool: Pool,
-    pub air_quality_api_key: String,
+    // 서비스 키 (Secrets Manager 의 키가 교체되면 replace_air_quality_api_key 로 갱신)
+    pub air_quality_api_key: RwLock<String>,
+    // 기상청 초단기실황 API 키 (weather 모드에서만 필요)
+    pub weather_api_key: Option<String>,
+    // OpenAQ API 키 (provider = 'openaq' 인 sub_region 에서 사용)
+    pub openaq_api_key: Option<String>,
+    // sub_region 조회 쿼리 (SUB_REGION_QUERY 등으로 덮어쓰기 가능)
+    pub sub_region_queries: SubRegionQueries,
+    // 공용 HTTP 클라이언트 (기본 ApiClient, 웹훅 알림이 커넥션 풀 공유)
+    pub http_client: Client,
+    // 에어코리아 API 호출 클라이언트 (기본 reqwest, 테스트에서는 MockApiClient 로 교체)
+    pub api_client: Arc<dyn ApiClient>,
+    // 최신 측정값 Redis 캐시 (REDIS_URL 미설정 시 비활성)
+    pub latest_cache: LatestCache,
+    // DB 장애로 저장하지 못한 측정값 보관 (PM_FALLBACK_SINK 미설정 시 None)
+    pub fallback: Option<FallbackSink>,
+    // 현재 시각 (기본 시스템 시계)
+    pub clock: Arc<dyn Clock>,
+    // 제공처 시간대 (SOURCE_TZ_OFFSET_HOURS, 초기화 단계에서 검증)
+    pub source_offset: FixedOffset,
+    // 초기화 단계의 커넥션 warm-up 소요 시간 (warm-up 을 하지 않았으면 None)
+    pub pool_warmup: Option<Duration>,
 }
 
 impl ServerState {
-    pub fn new(pool: Pool, air_quality_api_key: String) -> Self {
+    pub fn new(
+        pool: Pool,
+        air_quality_api_key: String,
+        weather_api_key: Option<String>,
+        openaq_api_key: Option<String>,
+    ) -> Self {
+        let http_client = Client::new();
         ServerState {
             pool,
-            air_quality_api_key,
+            air_quality_api_key: RwLock::new(air_quality_api_key),
+            weather_api_key,
+            openaq_api_key,
+            sub_region_queries: SubRegionQueries::default(),
+            api_client: Arc::new(ReqwestApiClient::new(http_client.clone())),
+            http_client,
+            latest_cache: LatestCache::default(),
+            fallback: None,
+            clock: Arc::new(SystemClock),
+            source_offset: KST_OFFSET,
+            pool_warmup: None,
         }
     }
+
+    pub fn with_api_client(mut self, api_client: Arc<dyn ApiClient>) -> Self {
+        self.api_client = api_client;
+        self
+    }
+
+    pub fn with_latest_cache(mut self, latest_cache: LatestCache) -> Self {
+        self.latest_cache = latest_cache;
+        self
+    }
+
+    pub fn with_fallback(mut self, fallback: Option<FallbackSink>) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    pub fn with_sub_region_queries(mut self, sub_region_queries: SubRegionQueries) -> Self {
+        self.sub_region_queries = sub_region_queries;
+        self
+    }
+
+    pub fn with_pool_warmup(mut self, pool_warmup: Option<Duration>) -> Self {
+        self.pool_warmup = pool_warmup;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn with_source_offset(mut self, source_offset: FixedOffset) -> Self {
+        self.source_offset = source_offset;
+        self
+    }
+
+    pub fn air_quality_api_key(&self) -> String {
+        self.air_quality_api_key
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn replace_air_quality_api_key(&self, air_quality_api_key: String) {
+        *self
+            .air_quality_api_key
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = air_quality_api_key;
+    }
+}
+
+// Lambda 실행 환경에서 재사용하는 ServerState (첫 호출에서만 초기화, 커넥션 풀 / HTTP 클라이언트 유지)
+static SHARED_STATE: OnceCell<Arc<ServerState>> = OnceCell::const_new();
+
+// 재사용 ServerState 와 이번 호출에서 초기화했는지(콜드 스타트) 여부
+// 초기화에 실패하면 다음 호출에서 다시 시도
+pub async fn shared_state_from_env() -> Result<(Arc<ServerState>, bool)> {
+    let mut cold_start = false;
+    let state = SHARED_STATE
+        .get_or_try_init(|| async {
+            cold_start = true;
+            initialize_state_from_env().await.map(Arc::new)
+        })
+        .await?;
+    Ok((state.clone(), cold_start))
+}
+
+// 환경 변수로 ServerState 초기화 (Lambda, CLI 공통)
+pub async fn initialize_state_from_env() -> Result<ServerState> {
+    let init_start = tokio::time::Instant::now();
+    let state = initialize_state_from_env_inner().await;
+    log_phase("total", init_start.elapsed());
+    state
+}
+
+async fn initialize_state_from_env_inner() -> Result<ServerState> {
+    // 환경 변수 로드 (AIR_QUALITY_API_KEY_SECRET_ARN 이면 Secrets Manager 조회 포함)
+    let air_quality_api_key = timed("secrets", secrets::air_quality_api_key()).await?;
+    let weather_api_key = std::env::var("WEATHER_API_KEY").ok();
+    let openaq_api_key = std::env::var("OPENAQ_API_KEY").ok();
+
+    // DB_IAM_AUTH=true 면 DB_CONN_URL 대신 IAM 인증 토큰으로 접속
+    if rds_iam::is_enabled() {
+        return initialize_iam_state(&air_quality_api_key, weather_api_key, openaq_api_key)
+            .await
+            .map_err(|e| anyhow!("ServerState 초기화 실패: {:?}", e));
+    }
+
+    // DB_CONN_URL, 없으면 PGHOST / PGPORT / PGUSER / PGPASSWORD / PGDATABASE
+    let db_conn = DbConnConfig::from_env()?;
+    info!("DB connection source: {}", db_conn.source());
+
+    // ServerState 초기화
+    initialize_state(
+        &db_conn,
+        &air_quality_api_key,
+        weather_api_key,
+        openaq_api_key,
+    )
+    .await
+    .map_err(|e| anyhow!("ServerState 초기화 실패: {:?}", e))
 }
 
 // ServerState 초기화 함수
-pub async fn initialize_state(db_conn_url: &str, air_quality_api_key: &str) -> Result<ServerState> {
+pub async fn initialize_state(
+    db_conn: &DbConnConfig,
+    air_quality_api_key: &str,
+    weather_api_key: Option<String>,
+    openaq_api_key: Option<String>,
+) -> Result<ServerState> {
     // 데이터베이스 풀 설정
     let mut cfg = Config::new();
-    cfg.url = Some(db_conn_url.to_owned());
-    cfg.manager = Some(ManagerConfig {
-        recycling_method: RecyclingMethod::Fast,
-    });
+    db_conn.apply(&mut cfg);
+
+    // NAT 뒤에서 조용히 끊긴 커넥션을 감지하기 위한 TCP keepalive
+    cfg.keepalives = Some(true);
+    cfg.keepalives_idle = Some(Duration::from_secs(env_u64(
+        "DB_KEEPALIVES_IDLE_SECS",
+        DEFAULT_KEEPALIVES_IDLE_SECS,
+    )));
+
+    // 좀비 커넥션에서 쿼리가 무한정 대기하지 않도록 statement_timeout 설정
+    cfg.options = Some(connection_options());
+
+    cfg.manager = Some(manager_config());
 
+    let pool_start = tokio::time::Instant::now();
     let pool = cfg
-        .create_pool(Some(Runtime::Tokio1), NoTls)
+        .builder(NoTls)
+        .map_err(|e| anyhow!("Pool 생성 실패: {:?}", e))?
+        .runtime(Runtime::Tokio1)
+        .wait_timeout(Some(pool_wait_timeout()))
+        .create_timeout(Some(pool_wait_timeout()))
+        .pre_recycle(ConnectionRecycle::from_env().hook())
+        .build()
         .map_err(|e| anyhow!("Pool 생성 실패: {:?}", e))?;
+    log_phase("pool_create", pool_start.elapsed());
     info!("Connection pool established.");
 
-    Ok(ServerState::new(pool, air_quality_api_key.to_owned()))
+    finish_state(pool, air_quality_api_key, weather_api_key, openaq_api_key).await
+}
+
+// RDS IAM 인증 ServerState 초기화 (DB_HOST / DB_PORT / DB_USER / DB_NAME, TLS 필수)
+async fn initialize_iam_state(
+    air_quality_api_key: &str,
+    weather_api_key: Option<String>,
+    openaq_api_key: Option<String>,
+) -> Result<ServerState> {
+    let mut pg_config = rds_iam::pg_config_from_env()?;
+
+    // URL 방식과 동일한 keepalive / statement_timeout 설정
+    pg_config
+        .keepalives(true)
+        .keepalives_idle(Duration::from_secs(env_u64(
+            "DB_KEEPALIVES_IDLE_SECS",
+            DEFAULT_KEEPALIVES_IDLE_SECS,
+        )))
+        .options(connection_options());
+
+    let host = match pg_config.get_hosts().first() {
+        Some(Host::Tcp(host)) => host.clone(),
+        _ => return Err(anyhow!("DB_HOST 환경 변수 누락")),
+    };
+    let port = pg_config.get_ports().first().copied().unwrap_or(5432);
+    let user = pg_config.get_user().unwrap_or_default().to_owned();
+
+    let signer = RdsAuthTokenSigner::from_env(&host, port, &user).await?;
+    // 설정 오류(리전, 자격 증명)는 첫 커넥션 전에 바로 드러나도록 미리 한 번 발급
+    timed("iam_token", signer.sign()).await?;
+
+    let pool_start = tokio::time::Instant::now();
+    let pool = rds_iam::create_pool(
+        pg_config,
+        signer,
+        manager_config(),
+        ConnectionRecycle::from_env(),
+        pool_wait_timeout(),
+    )?;
+    log_phase("pool_create", pool_start.elapsed());
+
+    finish_state(pool, air_quality_api_key, weather_api_key, openaq_api_key).await
+}
+
+// statement_timeout / application_name 접속 옵션
+fn connection_options() -> String {
+    format!(
+        "-c statement_timeout={} -c application_name={}",
+        env_u64("DB_STATEMENT_TIMEOUT_MS", DEFAULT_STATEMENT_TIMEOUT_MS),
+        APPLICATION_NAME
+    )
+}
+
+// DB_RECYCLE_VERIFIED=true 면 체크아웃 시 쿼리로 커넥션 상태 확인
+fn manager_config() -> ManagerConfig {
+    let recycle_verified = std::env::var("DB_RECYCLE_VERIFIED")
+        .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+        .unwrap_or(false);
+    ManagerConfig {
+        recycling_method: if recycle_verified {
+            RecyclingMethod::Verified
+        } else {
+            RecyclingMethod::Fast
+        },
+    }
+}
+
+// 커넥션 수명 / 유휴 시간 제한 (DB_CONN_MAX_LIFETIME_SECS / DB_CONN_IDLE_TIMEOUT_SECS, 미설정 또는 0 이면 제한 없음)
+// 오래 살아 있는 warm 컨테이너가 RDS 쪽에서 이미 닫은 커넥션을 쓰지 않도록 체크아웃 시점에 확인하여 버리고 새로 연결
+// (deadpool 은 백그라운드 정리 작업이 없으므로 다음 체크아웃에서 적용)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConnectionRecycle {
+    pub max_lifetime: Option<Duration>,
+    pub idle_timeout: Option<Duration>,
+}
+
+impl ConnectionRecycle {
+    pub fn from_env() -> Self {
+        let secs = |name: &str| {
+            Some(env_u64(name, 0))
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs)
+        };
+        ConnectionRecycle {
+            max_lifetime: secs("DB_CONN_MAX_LIFETIME_SECS"),
+            idle_timeout: secs("DB_CONN_IDLE_TIMEOUT_SECS"),
+        }
+    }
+
+    // 재사용할 수 없는 커넥션이면 이유 반환
+    pub fn check(&self, metrics: &Metrics) -> Result<(), String> {
+        if let Some(max_lifetime) = self.max_lifetime {
+            if metrics.age() > max_lifetime {
+                return Err(format!(
+                    "connection age {:?} exceeds max lifetime {:?}",
+                    metrics.age(),
+                    max_lifetime
+                ));
+            }
+        }
+        if let Some(idle_timeout) = self.idle_timeout {
+            if metrics.last_used() > idle_timeout {
+                return Err(format!(
+                    "connection idle {:?} exceeds idle timeout {:?}",
+                    metrics.last_used(),
+                    idle_timeout
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // 풀의 pre_recycle hook (실패한 커넥션은 풀에서 버려지고 다른 커넥션 / 새 커넥션으로 대체)
+    pub fn hook(self) -> Hook {
+        Hook::sync_fn(move |_, metrics| {
+            self.check(metrics).map_err(|reason| {
+                debug!("Recycling pooled connection: {}", reason);
+                HookError::message(reason)
+            })
+        })
+    }
+}
+
+// 풀 pre-warm, sub_region 쿼리 확인 후 ServerState 생성
+async fn finish_state(
+    pool: Pool,
+    air_quality_api_key: &str,
+    weather_api_key: Option<String>,
+    openaq_api_key: Option<String>,
+) -> Result<ServerState> {
+    // 스키마 이름은 SQL 에 그대로 들어가므로 DB 접근 전에 검증
+    let schema = db_schema::init_from_env()?;
+    info!("Using DB schema: {}", schema);
+
+    // 제공처 시간대도 DB 접근 전에 검증
+    let source_offset = timeutil::source_offset_from_env()?;
+    info!("Using source time zone offset: {}", source_offset);
+
+    // DB_POOL_MAX_SIZE 로 풀 최대 크기 지정 (미설정 시 deadpool 기본값)
+    if let Some(max_size) = pool_max_size_from_env() {
+        pool.resize(max_size);
+    }
+
+    // DB 쓰기 동시 수가 풀 최대 크기를 넘지 않는지 초기화 단계에서 확인
+    let db_write_concurrency = db_write_concurrency(pool.status().max_size)?;
+    info!(
+        "Concurrency limits: api={}, db_write={}, pool_max_size={}",
+        api_concurrency(),
+        db_write_concurrency,
+        pool.status().max_size
+    );
+
+    // 콜드 스타트 시 커넥션 생성 비용을 측정소 동시 수집 전 초기화 단계로 당겨옴
+    let warm_connections = pool_warm_connections();
+    let pool_warmup = if warm_connections > 0 {
+        let warmup_start = tokio::time::Instant::now();
+        timed("prewarm", prewarm_pool(&pool, warm_connections)).await;
+        Some(warmup_start.elapsed())
+    } else {
+        None
+    };
+
+    let state = ServerState::new(
+        pool,
+        air_quality_api_key.to_owned(),
+        weather_api_key,
+        openaq_api_key,
+    )
+    .with_latest_cache(LatestCache::from_env())
+    .with_fallback(FallbackSink::from_env()?)
+    .with_pool_warmup(pool_warmup)
+    .with_source_offset(source_offset);
+
+    // sub_region 쿼리를 덮어쓴 경우 수집 전에 반환 컬럼 확인
+    match SubRegionQueries::from_env()? {
+        Some(sub_region_queries) => {
+            timed("schema_check", async {
+                let client = state.pool.get().await?;
+                sub_region_queries.validate(&client).await
+            })
+            .await?;
+            info!("Using sub_region query override.");
+            Ok(state.with_sub_region_queries(sub_region_queries))
+        }
+        None => Ok(state),
+    }
+}
+
+// DB_POOL_MAX_SIZE 환경 변수 (풀 최대 커넥션 수)
+pub(crate) fn pool_max_size_from_env() -> Option<usize> {
+    std::env::var("DB_POOL_MAX_SIZE")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|max_size| *max_size > 0)
+}
+
+// DB_POOL_WAIT_TIMEOUT_MS 환경 변수 (기본 5000, 커넥션 대기와 새 커넥션 생성에 각각 적용)
+// 초과하면 PoolError::Timeout 으로 끝나 get_client_with_retry 가 재시도
+pub fn pool_wait_timeout() -> Duration {
+    Duration::from_millis(env_u64("DB_POOL_WAIT_TIMEOUT_MS", DEFAULT_POOL_WAIT_TIMEOUT_MS).max(1))
+}
+
+// DB_POOL_WARM_CONNECTIONS 환경 변수 (기본 2, 0 이면 warm-up 없음)
+// 미설정 시 이전 이름인 DB_POOL_MIN_IDLE 사용
+pub(crate) fn pool_warm_connections() -> usize {
+    ["DB_POOL_WARM_CONNECTIONS", "DB_POOL_MIN_IDLE"]
+        .into_iter()
+        .find_map(|name| std::env::var(name).ok())
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_POOL_WARM_CONNECTIONS)
+}
+
+// count 개의 커넥션을 하나씩 획득 후 한 번에 반환하여 풀에 유휴 커넥션을 채움
+// (획득할 때마다 반환하면 같은 커넥션이 재사용되므로 모두 잡은 뒤 반환)
+// 실패는 경고만 남기고 초기화는 계속 진행하며, 첫 실패 이후의 획득은 시도하지 않음 (DB 장애 시 초기화 지연 방지)
+async fn prewarm_pool(pool: &Pool, count: usize) {
+    let count = count.min(pool.status().max_size);
+    let mut clients: Vec<Object> = Vec::with_capacity(count);
+    for _ in 0..count {
+        match pool.get().await {
+            Ok(client) => clients.push(client),
+            Err(e) => {
+                warn!("Pool pre-warm 실패: {:?}", e);
+                break;
+            }
+        }
+    }
+    let warmed = clients.len();
+    drop(clients);
+
+    info!(
+        "Connection pool pre-warmed: {}/{} (available: {})",
+        warmed,
+        count,
+        pool.status().available
+    );
+}
+
+// 일시적인 풀 고갈/커넥션 끊김에만 재시도 (설정 오류, 인증 실패 등은 즉시 실패)
+pub fn is_retriable_pool_error(e: &PoolError) -> bool {
+    match e {
+        PoolError::Timeout(_) => true,
+        PoolError::Backend(e) => e.is_closed() || is_connection_io_error(e),
+        _ => false,
+    }
+}
+
+// 커넥션을 여는 중 소켓이 끊기거나 시간이 초과된 경우 (인증 실패, 설정 오류 등은 재시도하지 않음)
+fn is_connection_io_error(e: &tokio_postgres::Error) -> bool {
+    std::error::Error::source(e)
+        .and_then(|source| source.downcast_ref::<std::io::Error>())
+        .is_some_and(|io_error| {
+            matches!(
+                io_error.kind(),
+                std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::ConnectionRefused
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::UnexpectedEof
+            )
+        })
+}
+
+// 커넥션 획득 (재시도 가능한 오류면 100ms, 300ms 간격으로 재시도, 최대 3번 시도)
+// 반환값: (클라이언트, 재시도 횟수)
+pub async fn get_client_with_retry(pool: &Pool) -> Result<(Object, u32), PoolError> {
+    let mut retries = 0;
+    loop {
+        match pool.get().await {
+            Ok(client) => return Ok((client, retries)),
+            Err(e) => {
+                let delay = match POOL_CHECKOUT_RETRY_DELAYS_MS.get(retries as usize) {
+                    Some(delay) if is_retriable_pool_error(&e) => *delay,
+                    _ => return Err(e),
+                };
+                warn!("Failed to get db client, retrying in {}ms: {:?}", delay, e);
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                retries += 1;
+            }
+        }
+    }
+}
+
+fn env_u64(name: &str, default: u64) -> u64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deadpool_postgres::TimeoutType;
+
+    #[test]
+    fn pool_timeouts_are_retriable() {
+        assert!(is_retriable_pool_error(&PoolError::Timeout(
+            TimeoutType::Wait
+        )));
+        assert!(is_retriable_pool_error(&PoolError::Timeout(
+            TimeoutType::Create
+        )));
+        assert!(!is_retriable_pool_error(&PoolError::Closed));
+        assert!(!is_retriable_pool_error(&PoolError::NoRuntimeSpecified));
+    }
+
+    #[tokio::test]
+    async fn refused_connection_is_retriable() {
+        // 열려 있지 않은 포트: 소켓 오류(ConnectionRefused)
+        let e = tokio_postgres::connect("host=127.0.0.1 port=1 user=postgres", NoTls)
+            .await
+            .map(|_| ())
+            .unwrap_err();
+        assert!(is_retriable_pool_error(&PoolError::Backend(e)));
+    }
+
+    #[tokio::test]
+    async fn configuration_error_is_not_retriable() {
+        // 잘못된 설정은 소켓을 열기 전에 실패하므로 재시도해도 같은 결과
+        let e = tokio_postgres::connect("host=127.0.0.1 port=1 sslmode=bogus", NoTls)
+            .await
+            .map(|_| ())
+            .unwrap_err();
+        assert!(!is_retriable_pool_error(&PoolError::Backend(e)));
+    }
+
+    fn pool_for(url: &str, max_size: usize) -> Pool {
+        let pool = deadpool_postgres::Config {
+            url: Some(url.to_owned()),
+            ..Default::default()
+        }
+        .create_pool(Some(Runtime::Tokio1), NoTls)
+        .unwrap();
+        pool.resize(max_size);
+        pool
+    }
+
+    #[test]
+    fn warm_connections_fall_back_to_the_old_name() {
+        std::env::remove_var("DB_POOL_WARM_CONNECTIONS");
+        std::env::remove_var("DB_POOL_MIN_IDLE");
+        assert_eq!(pool_warm_connections(), DEFAULT_POOL_WARM_CONNECTIONS);
+
+        std::env::set_var("DB_POOL_MIN_IDLE", "4");
+        assert_eq!(pool_warm_connections(), 4);
+        // 새 이름이 우선
+        std::env::set_var("DB_POOL_WARM_CONNECTIONS", "0");
+        assert_eq!(pool_warm_connections(), 0);
+
+        std::env::remove_var("DB_POOL_WARM_CONNECTIONS");
+        std::env::remove_var("DB_POOL_MIN_IDLE");
+    }
+
+    #[tokio::test]
+    async fn prewarm_leaves_idle_connections_up_to_the_pool_size() {
+        let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+            return;
+        };
+        let pool = pool_for(&url, 3);
+        prewarm_pool(&pool, 2).await;
+        assert_eq!((pool.status().size, pool.status().available), (2, 2));
+
+        // 풀 크기보다 많이 요청해도 max_size 까지만 채움
+        let pool = pool_for(&url, 3);
+        prewarm_pool(&pool, 10).await;
+        assert_eq!((pool.status().size, pool.status().available), (3, 3));
+    }
+
+    #[tokio::test]
+    async fn prewarm_stops_at_the_first_failure() {
+        // 연결할 수 없는 DB: 첫 획득 실패 후 나머지는 시도하지 않고 빈 풀로 계속 진행
+        let pool = pool_for("postgres://postgres@127.0.0.1:1/postgres", 5);
+        prewarm_pool(&pool, 5).await;
+        assert_eq!((pool.status().size, pool.status().available), (0, 0));
+    }
 }
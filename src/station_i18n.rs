@@ -0,0 +1,158 @@
+// src/station_i18n.rs
+
+// 측정소 이름 영문 표기 (payload 의 locale: "en")
+// data.go.kr 의 stationName 은 한글이므로 {schema}.station_i18n (station_name -> station_name_en) 으로 응답의 stationName 만 바꿈
+// DB 키(pm_station)와 SNS / Firehose / Redis 출력은 한글 이름 그대로 두고, 매핑이 없으면 한글 이름 유지
+
+use deadpool_postgres::Client as DbClient;
+use std::collections::HashMap;
+use tokio_postgres::error::SqlState;
+use tracing::warn;
+
+use crate::db_schema::sql;
+use crate::handler::{IngestReport, StationStatus};
+use crate::provider::split_station_refs;
+
+pub const GET_STATION_NAMES_EN_QUERY: &str = r#"
+SELECT station_name, station_name_en
+FROM {schema}.station_i18n
+WHERE station_name_en IS NOT NULL;
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    Ko,
+    En,
+}
+
+impl Locale {
+    // payload 의 locale (없거나 알 수 없는 값이면 ko)
+    pub fn from_payload(payload: &serde_json::Value) -> Self {
+        match payload.get("locale").and_then(|v| v.as_str()) {
+            None | Some("ko") => Locale::Ko,
+            Some("en") => Locale::En,
+            Some(other) => {
+                warn!("지원하지 않는 locale, ko 사용: {}", other);
+                Locale::Ko
+            }
+        }
+    }
+}
+
+// 한글 측정소 이름 -> 영문 이름 (테이블이 없으면 빈 목록)
+pub async fn load_station_names_en(client: &DbClient) -> HashMap<String, String> {
+    match client
+        .query(sql(GET_STATION_NAMES_EN_QUERY).as_str(), &[])
+        .await
+    {
+        Ok(rows) => rows
+            .iter()
+            .filter_map(|row| {
+                let station_name: String = row.try_get("station_name").ok()?;
+                let station_name_en: String = row.try_get("station_name_en").ok()?;
+                Some((station_name, station_name_en))
+            })
+            .collect(),
+        Err(e) if e.code() == Some(&SqlState::UNDEFINED_TABLE) => {
+            warn!("station_i18n 테이블 없음, 측정소 이름은 한글로 응답");
+            HashMap::new()
+        }
+        Err(e) => {
+            warn!("측정소 영문 이름 조회 실패, 한글로 응답: {:?}", e);
+            HashMap::new()
+        }
+    }
+}
+
+// 영문 이름 (여러 측정소 "A,B" 는 측정소별로 바꿔 다시 연결, 매핑이 없는 측정소는 한글 유지)
+pub fn station_name_en(station_name: &str, names_en: &HashMap<String, String>) -> String {
+    split_station_refs(station_name)
+        .into_iter()
+        .map(|station| names_en.get(station).map(String::as_str).unwrap_or(station))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// 성공한 측정소 응답의 stationName 을 영문으로 교체
+pub fn localize_station_names(report: &mut IngestReport, names_en: &HashMap<String, String>) {
+    for result in &mut report.results {
+        if let StationStatus::Success(data) = &mut result.status {
+            if let Some(station_name) = data["stationName"].as_str() {
+                data["stationName"] = station_name_en(station_name, names_en).into();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::failure::FailureKind;
+    use crate::handler::StationResult;
+    use crate::inline_stations::StationListSource;
+    use crate::phases::Phases;
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn names_en() -> HashMap<String, String> {
+        HashMap::from([
+            ("중구".to_owned(), "Jung-gu".to_owned()),
+            ("종로구".to_owned(), "Jongno-gu".to_owned()),
+        ])
+    }
+
+    #[test]
+    fn locale_defaults_to_korean() {
+        assert_eq!(Locale::from_payload(&json!({})), Locale::Ko);
+        assert_eq!(Locale::from_payload(&json!({ "locale": "ko" })), Locale::Ko);
+        assert_eq!(Locale::from_payload(&json!({ "locale": "en" })), Locale::En);
+        assert_eq!(Locale::from_payload(&json!({ "locale": "ja" })), Locale::Ko);
+    }
+
+    #[test]
+    fn station_name_en_maps_each_station_and_keeps_unmapped_korean() {
+        assert_eq!(station_name_en("중구", &names_en()), "Jung-gu");
+        assert_eq!(
+            station_name_en("중구,종로구", &names_en()),
+            "Jung-gu,Jongno-gu"
+        );
+        assert_eq!(
+            station_name_en("중구,강남구", &names_en()),
+            "Jung-gu,강남구"
+        );
+        assert_eq!(station_name_en("강남구", &HashMap::new()), "강남구");
+    }
+
+    #[test]
+    fn localize_changes_only_successful_station_names() {
+        let mut report = IngestReport {
+            run_id: "run-1".to_owned(),
+            results: vec![
+                StationResult::success(1, "중구", json!({ "stationName": "중구" })),
+                StationResult::failed(2, "종로구", FailureKind::NoData, "no data".to_owned()),
+            ],
+            skipped_fresh: 0,
+            deferred: 0,
+            cache_hits: 0,
+            db_read_only: false,
+            no_sub_regions: false,
+            advanced: None,
+            data_frozen: false,
+            elapsed: Duration::ZERO,
+            warnings: Vec::new(),
+            latest_cache_failures: 0,
+            disabled_sub_regions: None,
+            diagnostics: None,
+            phases: Phases::default(),
+            station_list_source: StationListSource::Db,
+            quota: None,
+        };
+        localize_station_names(&mut report, &names_en());
+
+        assert_eq!(report.results[0].to_json()["stationName"], "Jung-gu");
+        // DB 키와 실패 항목의 측정소 이름은 한글 그대로
+        assert_eq!(report.results[0].pm_station, "중구");
+        assert_eq!(report.results[1].pm_station, "종로구");
+    }
+}
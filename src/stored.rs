@@ -0,0 +1,85 @@
+// src/stored.rs
+
+// 저장된 최신 측정값 조회 (payload 의 read: true)
+// 외부 API 를 호출하지 않고 {schema}.external_pm 의 현재 행을 측정소 이름과 함께 반환 (DB 쓰기 없음)
+// 예) {"read": true, "subRegionIds": [101, 102]}
+// 측정소 이름은 수집과 같은 sub_region 쿼리(SUB_REGION_QUERY 등 재정의 포함)로 조회
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use std::collections::HashMap;
+
+use crate::db_schema::sql;
+use crate::handler::SubRegionInfo;
+use crate::state::ServerState;
+
+pub const GET_STORED_PM_QUERY: &str = r#"
+SELECT sub_region_id, pm10, pm25, recorded_at, update_at
+FROM {schema}.external_pm
+ORDER BY sub_region_id;
+"#;
+
+pub const GET_STORED_PM_BY_IDS_QUERY: &str = r#"
+SELECT sub_region_id, pm10, pm25, recorded_at, update_at
+FROM {schema}.external_pm
+WHERE sub_region_id = ANY($1)
+ORDER BY sub_region_id;
+"#;
+
+// 저장된 행 조회 후 data / meta 응답 본문 구성
+pub async fn read_stored(
+    state: &ServerState,
+    run_id: &str,
+    sub_region_ids: Option<&Vec<i32>>,
+) -> Result<serde_json::Value> {
+    let db_client = state.pool.get().await?;
+
+    let rows = match sub_region_ids {
+        Some(sub_region_ids) => {
+            db_client
+                .query(sql(GET_STORED_PM_BY_IDS_QUERY).as_str(), &[sub_region_ids])
+                .await?
+        }
+        None => {
+            db_client
+                .query(sql(GET_STORED_PM_QUERY).as_str(), &[])
+                .await?
+        }
+    };
+
+    // 수집 중지된 sub_region 의 저장 값도 이름을 표시
+    let station_names: HashMap<i32, String> = state
+        .sub_region_queries
+        .fetch_rows(&db_client, sub_region_ids, true)
+        .await?
+        .iter()
+        .filter_map(|row| SubRegionInfo::try_from_row(row).ok())
+        .filter_map(|sub_region| Some((sub_region.sub_region_id, sub_region.pm_station?)))
+        .collect();
+    drop(db_client);
+
+    let data = rows
+        .iter()
+        .map(|row| {
+            let sub_region_id: i32 = row.try_get("sub_region_id")?;
+            Ok(json!({
+                "subRegionId": sub_region_id,
+                "pm10Value": row.try_get::<_, Option<f64>>("pm10")?,
+                "pm25Value": row.try_get::<_, Option<f64>>("pm25")?,
+                "dataTime": row.try_get::<_, DateTime<Utc>>("recorded_at")?,
+                "requestedTime": row.try_get::<_, DateTime<Utc>>("update_at")?,
+                "stationName": station_names.get(&sub_region_id),
+            }))
+        })
+        .collect::<Result<Vec<_>, tokio_postgres::Error>>()?;
+
+    Ok(json!({
+        "data": data,
+        "meta": {
+            "runId": run_id,
+            "outcome": "OK",
+            "count": data.len(),
+        }
+    }))
+}
@@ -0,0 +1,145 @@
+// src/stream.rs
+
+// 측정소별 결과 스트리밍 (상시 실행 dev 서버용)
+// 전체 수집이 끝날 때까지 기다리지 않고 측정소 처리가 끝날 때마다 한 줄짜리 JSON (NDJSON) 으로 전달
+
+use std::sync::Arc;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tokio::task::JoinHandle;
+
+use crate::handler::{run_ingest, FetchOptions, IngestReport, StationResult};
+use crate::state::ServerState;
+
+// 측정소 결과 한 줄 (개행 포함)
+pub fn ndjson_line(result: &StationResult) -> String {
+    format!("{}\n", result.to_json())
+}
+
+// 수집을 백그라운드로 시작하고 완료 순서대로 NDJSON 줄을 받을 채널 반환
+// 모든 측정소가 끝나면 채널이 닫히고, 최종 결과는 반환된 핸들로 확인
+pub fn stream_ingest(
+    state: Arc<ServerState>,
+    options: FetchOptions,
+) -> (
+    JoinHandle<anyhow::Result<IngestReport>>,
+    UnboundedReceiver<String>,
+) {
+    let (result_tx, mut result_rx) = mpsc::unbounded_channel::<StationResult>();
+    let (line_tx, line_rx) = mpsc::unbounded_channel::<String>();
+
+    let options = FetchOptions {
+        progress: Some(result_tx),
+        ..options
+    };
+    let handle = tokio::spawn(async move { run_ingest(state, &options).await });
+
+    tokio::spawn(async move {
+        while let Some(result) = result_rx.recv().await {
+            if line_tx.send(ndjson_line(&result)).is_err() {
+                break;
+            }
+        }
+    });
+
+    (handle, line_rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::failure::FailureKind;
+    use crate::inline_stations::InlineStation;
+    use crate::provider::{ApiEnvelope, MockApiClient};
+    use crate::timeutil::KST_OFFSET;
+    use serde_json::json;
+
+    fn station_body() -> String {
+        let data_time = chrono::Utc::now()
+            .with_timezone(&KST_OFFSET)
+            .format("%Y-%m-%d %H:00")
+            .to_string();
+        json!({
+            "response": {
+                "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+                "body": {
+                    "totalCount": 1,
+                    "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+                },
+            }
+        })
+        .to_string()
+    }
+
+    // 접속하지 않는 풀 (dry-run + payload 측정소 목록은 DB 에 접근하지 않음)
+    fn unused_pool() -> deadpool_postgres::Pool {
+        deadpool_postgres::Config {
+            url: Some("postgres://unused@127.0.0.1:1/unused".to_owned()),
+            ..Default::default()
+        }
+        .create_pool(
+            Some(deadpool_postgres::Runtime::Tokio1),
+            tokio_postgres::NoTls,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn each_result_is_one_json_line() {
+        let failed = StationResult::failed(2, "종로구", FailureKind::NoData, "no data".to_owned());
+        let line = ndjson_line(&failed);
+        assert!(line.ends_with('\n'));
+        assert_eq!(line.matches('\n').count(), 1);
+        let value: serde_json::Value = serde_json::from_str(line.trim_end()).unwrap();
+        assert_eq!(
+            value,
+            json!({ "subRegionId": 2, "stationName": "종로구", "error": "no data" })
+        );
+    }
+
+    #[tokio::test]
+    async fn streams_one_line_per_station_and_closes_when_done() {
+        // 종로구는 준비된 응답이 없으므로 실패 줄로 전달
+        let mock = MockApiClient::new().with_envelope(
+            "중구",
+            ApiEnvelope::new(reqwest::StatusCode::OK, station_body()),
+        );
+        let state = Arc::new(
+            ServerState::new(unused_pool(), "test-key".to_owned(), None, None)
+                .with_api_client(Arc::new(mock)),
+        );
+        let options = FetchOptions {
+            dry_run: true,
+            inline_stations: Some(vec![
+                InlineStation {
+                    sub_region_id: 1,
+                    pm_station: "중구".to_owned(),
+                },
+                InlineStation {
+                    sub_region_id: 2,
+                    pm_station: "종로구".to_owned(),
+                },
+            ]),
+            ..Default::default()
+        };
+
+        let (handle, mut lines) = stream_ingest(state, options);
+        let mut streamed = Vec::new();
+        // 모든 측정소가 끝나면 채널이 닫혀 루프가 끝남
+        while let Some(line) = lines.recv().await {
+            assert!(line.ends_with('\n'));
+            streamed.push(serde_json::from_str::<serde_json::Value>(line.trim_end()).unwrap());
+        }
+        let report = handle.await.unwrap().unwrap();
+
+        assert_eq!(streamed.len(), 2);
+        assert_eq!(report.results.len(), 2);
+        // 스트리밍한 줄은 최종 결과의 측정소별 JSON 과 같음
+        for result in &report.results {
+            assert!(streamed.contains(&result.to_json()), "{:?}", result);
+        }
+        assert!(streamed.iter().any(|line| line["pm10Value"] == 42.0));
+        assert!(streamed
+            .iter()
+            .any(|line| line["subRegionId"] == 2 && line["error"].is_string()));
+    }
+}
@@ -0,0 +1,310 @@
+// src/sub_region_query.rs
+
+// sub_region 조회 쿼리 설정
+// 배포 환경마다 테이블/컬럼 이름이 다를 수 있으므로 환경 변수로 덮어쓸 수 있도록 하고, 기본값은 {PM_DB_SCHEMA}.sub_region 상수 쿼리
+//   SUB_REGION_QUERY: 전체 조회 쿼리 (sub_region_id, pm_station 컬럼은 필수, tm_x, tm_y, provider, nx, ny, is_active 는 선택)
+//   SUB_REGION_TABLE / SUB_REGION_ID_COLUMN / SUB_REGION_PM_STATION_COLUMN: 테이블/컬럼 이름만 변경
+// 기본 쿼리만 ingest_enabled = false 인 sub_region 을 제외 (사용자 정의 쿼리는 그대로 실행)
+
+use anyhow::{anyhow, Result};
+use deadpool_postgres::Client as DbClient;
+use tokio_postgres::Row;
+use tracing::{info, warn};
+
+use crate::db_error::is_undefined_column_error;
+use crate::db_schema::{db_schema, sql};
+use crate::handler::{
+    COUNT_DISABLED_SUB_REGIONS_QUERY, GET_ALL_SUB_REGION_ID_AND_PM_STATION_QUERY,
+    GET_ALL_SUB_REGION_INCLUDING_DISABLED_QUERY, GET_SUB_REGION_BY_IDS_INCLUDING_DISABLED_QUERY,
+    GET_SUB_REGION_ID_AND_PM_STATION_BY_IDS_QUERY,
+};
+
+const DEFAULT_TABLE: &str = "sub_region";
+const DEFAULT_ID_COLUMN: &str = "sub_region_id";
+const DEFAULT_PM_STATION_COLUMN: &str = "pm_station";
+
+// SubRegionInfo::try_from_row 가 반드시 읽는 컬럼
+pub const REQUIRED_COLUMNS: [&str; 2] = ["sub_region_id", "pm_station"];
+
+// 결과에 없으면 None (provider 는 'airkorea') 으로 처리하는 컬럼
+pub const OPTIONAL_COLUMNS: [&str; 6] = ["tm_x", "tm_y", "provider", "nx", "ny", "is_active"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubRegionQueries {
+    // 전체 sub_region 조회
+    pub all: String,
+    // sub_region_id = ANY($1) 조회
+    pub by_ids: String,
+    // 수집 중지된 sub_region 포함 조회 (필터가 없는 쿼리는 all / by_ids 와 같음)
+    pub all_including_disabled: String,
+    pub by_ids_including_disabled: String,
+    // 수집 중지된 sub_region 수 조회 (사용자 정의 쿼리는 None)
+    pub count_disabled: Option<String>,
+}
+
+impl Default for SubRegionQueries {
+    fn default() -> Self {
+        SubRegionQueries {
+            all: sql(GET_ALL_SUB_REGION_ID_AND_PM_STATION_QUERY),
+            by_ids: sql(GET_SUB_REGION_ID_AND_PM_STATION_BY_IDS_QUERY),
+            all_including_disabled: sql(GET_ALL_SUB_REGION_INCLUDING_DISABLED_QUERY),
+            by_ids_including_disabled: sql(GET_SUB_REGION_BY_IDS_INCLUDING_DISABLED_QUERY),
+            count_disabled: Some(sql(COUNT_DISABLED_SUB_REGIONS_QUERY)),
+        }
+    }
+}
+
+impl SubRegionQueries {
+    // 환경 변수로 쿼리 구성 (설정이 없으면 기본 상수 쿼리)
+    pub fn from_env() -> Result<Option<Self>> {
+        if let Ok(query) = std::env::var("SUB_REGION_QUERY") {
+            return Ok(Some(Self::from_query(&query)));
+        }
+
+        let table = std::env::var("SUB_REGION_TABLE").ok();
+        let id_column = std::env::var("SUB_REGION_ID_COLUMN").ok();
+        let pm_station_column = std::env::var("SUB_REGION_PM_STATION_COLUMN").ok();
+        if table.is_none() && id_column.is_none() && pm_station_column.is_none() {
+            return Ok(None);
+        }
+
+        // 스키마 없이 테이블 이름만 지정하면 PM_DB_SCHEMA 스키마의 테이블
+        let table = match table {
+            Some(table) if table.contains('.') => table,
+            Some(table) => format!("{}.{}", db_schema(), table),
+            None => format!("{}.{}", db_schema(), DEFAULT_TABLE),
+        };
+
+        Self::from_columns(
+            &table,
+            id_column.as_deref().unwrap_or(DEFAULT_ID_COLUMN),
+            pm_station_column
+                .as_deref()
+                .unwrap_or(DEFAULT_PM_STATION_COLUMN),
+        )
+        .map(Some)
+    }
+
+    // 전체 조회 쿼리를 서브쿼리로 감싸 sub_region_id 조건 조회 쿼리 생성
+    pub fn from_query(query: &str) -> Self {
+        let query = query.trim().trim_end_matches(';').trim_end();
+        let by_ids = format!(
+            "SELECT * FROM ({}) AS sub_region WHERE sub_region_id = ANY($1)",
+            query
+        );
+        SubRegionQueries {
+            all: query.to_owned(),
+            all_including_disabled: query.to_owned(),
+            by_ids_including_disabled: by_ids.clone(),
+            by_ids,
+            count_disabled: None,
+        }
+    }
+
+    // 테이블/컬럼 이름으로 쿼리 생성 (결과 컬럼은 기존 이름으로 alias)
+    pub fn from_columns(table: &str, id_column: &str, pm_station_column: &str) -> Result<Self> {
+        for identifier in [table, id_column, pm_station_column] {
+            validate_identifier(identifier)?;
+        }
+
+        let select = format!(
+            "SELECT {id} AS sub_region_id, {pm} AS pm_station, tm_x, tm_y, COALESCE(provider, 'airkorea') AS provider\nFROM {table}",
+            id = id_column,
+            pm = pm_station_column,
+            table = table
+        );
+        let by_ids = format!("{}\nWHERE {} = ANY($1)", select, id_column);
+        Ok(SubRegionQueries {
+            all: select.clone(),
+            all_including_disabled: select,
+            by_ids_including_disabled: by_ids.clone(),
+            by_ids,
+            count_disabled: None,
+        })
+    }
+
+    // sub_region 목록 조회 (sub_region_ids 가 None 이면 전체)
+    // ingest_enabled 컬럼이 없는 이전 스키마면 필터 없는 쿼리로 다시 조회
+    pub async fn fetch_rows(
+        &self,
+        client: &DbClient,
+        sub_region_ids: Option<&Vec<i32>>,
+        include_disabled: bool,
+    ) -> Result<Vec<Row>, tokio_postgres::Error> {
+        let (filtered, unfiltered) = match sub_region_ids {
+            Some(_) => (&self.by_ids, &self.by_ids_including_disabled),
+            None => (&self.all, &self.all_including_disabled),
+        };
+        if include_disabled {
+            return query_sub_regions(client, unfiltered, sub_region_ids).await;
+        }
+
+        match query_sub_regions(client, filtered, sub_region_ids).await {
+            Err(e) if is_undefined_column_error(&e) && filtered != unfiltered => {
+                info!("sub_region.ingest_enabled 컬럼 없음, 필터 없이 조회");
+                query_sub_regions(client, unfiltered, sub_region_ids).await
+            }
+            result => result,
+        }
+    }
+
+    // 수집 중지된 sub_region 수 (사용자 정의 쿼리이거나 ingest_enabled 컬럼이 없으면 None)
+    pub async fn count_disabled(&self, client: &DbClient) -> Option<usize> {
+        let query = self.count_disabled.as_ref()?;
+        match client.query_one(query.as_str(), &[]).await {
+            Ok(row) => row
+                .try_get::<_, i64>(0)
+                .ok()
+                .map(|count| count.max(0) as usize),
+            Err(e) if is_undefined_column_error(&e) => None,
+            Err(e) => {
+                warn!("수집 중지된 sub_region 수 조회 실패: {:?}", e);
+                None
+            }
+        }
+    }
+
+    // 쿼리를 prepare 하여 필요한 컬럼을 모두 반환하는지 확인 (실행 전 설정 오류 감지)
+    pub async fn validate(&self, client: &DbClient) -> Result<()> {
+        let statement = client
+            .prepare(&self.all)
+            .await
+            .map_err(|e| anyhow!("sub_region 쿼리 확인 실패: {:?}", e))?;
+        let columns: Vec<&str> = statement.columns().iter().map(|c| c.name()).collect();
+        check_columns(&columns)?;
+
+        let absent: Vec<&str> = OPTIONAL_COLUMNS
+            .iter()
+            .copied()
+            .filter(|optional| !columns.contains(optional))
+            .collect();
+        if !absent.is_empty() {
+            info!(
+                "sub_region 쿼리에 없는 선택 컬럼은 기본값 사용: {}",
+                absent.join(", ")
+            );
+        }
+
+        client
+            .prepare(&self.by_ids)
+            .await
+            .map_err(|e| anyhow!("sub_region id 조회 쿼리 확인 실패: {:?}", e))?;
+        Ok(())
+    }
+}
+
+async fn query_sub_regions(
+    client: &DbClient,
+    query: &str,
+    sub_region_ids: Option<&Vec<i32>>,
+) -> Result<Vec<Row>, tokio_postgres::Error> {
+    match sub_region_ids {
+        Some(sub_region_ids) => client.query(query, &[sub_region_ids]).await,
+        None => client.query(query, &[]).await,
+    }
+}
+
+// 쿼리 결과 컬럼에 필요한 컬럼이 모두 있는지 확인
+pub fn check_columns(columns: &[&str]) -> Result<()> {
+    let missing: Vec<&str> = REQUIRED_COLUMNS
+        .iter()
+        .copied()
+        .filter(|required| !columns.contains(required))
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "sub_region 쿼리에 필요한 컬럼 누락: {} (반환 컬럼: {})",
+            missing.join(", "),
+            columns.join(", ")
+        ))
+    }
+}
+
+// SQL 식별자 (schema.table 포함) 는 영문/숫자/밑줄만 허용
+fn validate_identifier(identifier: &str) -> Result<()> {
+    let valid = !identifier.is_empty()
+        && identifier.split('.').all(|part| {
+            !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow!("잘못된 SQL 식별자: {}", identifier))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 기본 쿼리만 수집 중지 필터와 수집 중지 수 조회를 가짐
+    #[test]
+    fn only_default_queries_filter_disabled_sub_regions() {
+        let queries = SubRegionQueries::default();
+        for filtered in [&queries.all, &queries.by_ids] {
+            assert!(
+                filtered.contains("COALESCE(ingest_enabled, true)"),
+                "{}",
+                filtered
+            );
+        }
+        for unfiltered in [
+            &queries.all_including_disabled,
+            &queries.by_ids_including_disabled,
+        ] {
+            assert!(!unfiltered.contains("ingest_enabled"), "{}", unfiltered);
+        }
+        assert!(queries.by_ids_including_disabled.contains("ANY($1)"));
+        assert!(queries.count_disabled.is_some());
+    }
+
+    #[test]
+    fn custom_query_runs_as_is_with_or_without_disabled() {
+        let queries = SubRegionQueries::from_query(
+            "SELECT id AS sub_region_id, station AS pm_station FROM public.regions ; ",
+        );
+        assert_eq!(
+            queries.all,
+            "SELECT id AS sub_region_id, station AS pm_station FROM public.regions"
+        );
+        assert_eq!(
+            queries.by_ids,
+            "SELECT * FROM (SELECT id AS sub_region_id, station AS pm_station FROM public.regions) AS sub_region WHERE sub_region_id = ANY($1)"
+        );
+        assert_eq!(queries.all_including_disabled, queries.all);
+        assert_eq!(queries.by_ids_including_disabled, queries.by_ids);
+        assert_eq!(queries.count_disabled, None);
+    }
+
+    #[test]
+    fn renamed_columns_are_aliased_and_validated() {
+        let queries =
+            SubRegionQueries::from_columns("geo.regions", "region_id", "station").unwrap();
+        assert!(queries
+            .all
+            .starts_with("SELECT region_id AS sub_region_id, station AS pm_station"));
+        assert!(queries.by_ids.ends_with("WHERE region_id = ANY($1)"));
+        assert_eq!(queries.all_including_disabled, queries.all);
+        assert_eq!(queries.count_disabled, None);
+
+        for table in ["", "geo.", "regions; DROP TABLE x", "geo.re-gions"] {
+            assert!(
+                SubRegionQueries::from_columns(table, "id", "station").is_err(),
+                "{}",
+                table
+            );
+        }
+    }
+
+    #[test]
+    fn required_columns_are_checked() {
+        assert!(check_columns(&["sub_region_id", "pm_station", "tm_x"]).is_ok());
+        let e = check_columns(&["sub_region_id", "station"]).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "sub_region 쿼리에 필요한 컬럼 누락: pm_station (반환 컬럼: sub_region_id, station)"
+        );
+    }
+}
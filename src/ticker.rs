@@ -0,0 +1,169 @@
+// src/ticker.rs
+
+// 상시 실행(axum) 배포용 내부 스케줄러
+// 외부 cron 이 /external-pm 을 호출하는 대신 서버 부팅 시 INGEST_INTERVAL_SECS 간격으로 run_ingest 를 실행
+// 겹치는 실행은 run_ingest 의 advisory lock 으로 막고 (ALREADY_RUNNING 은 경고만 남김),
+// 마지막 실행 결과는 /ingest/status 에서 조회할 수 있도록 메모리에 보관
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{watch, RwLock};
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info, warn};
+
+use crate::handler::{build_response_body, run_ingest, FetchOptions, IngestReport};
+use crate::run_lock::AlreadyRunningError;
+use crate::state::ServerState;
+
+pub type LastReport = Arc<RwLock<Option<IngestReport>>>;
+
+// INGEST_INTERVAL_SECS 환경 변수 (미설정 / 0 이면 내부 스케줄러 사용 안 함)
+pub fn interval_from_env() -> Option<Duration> {
+    std::env::var("INGEST_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+}
+
+// 종료 시 진행 중인 실행을 기다리는 최대 시간 기본값
+const DEFAULT_DRAIN_TIMEOUT_SECS: u64 = 25;
+
+// INGEST_DRAIN_TIMEOUT_SECS 환경 변수 (기본 25초, 배포 시 SIGKILL 전 유예 시간보다 짧게 설정)
+pub fn drain_timeout_from_env() -> Duration {
+    let secs = std::env::var("INGEST_DRAIN_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_DRAIN_TIMEOUT_SECS);
+    Duration::from_secs(secs)
+}
+
+pub struct IngestTicker {
+    last_report: LastReport,
+    shutdown: watch::Sender<bool>,
+    handle: JoinHandle<()>,
+}
+
+impl IngestTicker {
+    // 첫 실행은 즉시, 이후 interval 마다 실행 (이전 실행이 길어지면 밀린 틱은 건너뜀)
+    pub fn spawn(state: Arc<ServerState>, interval: Duration, drain_timeout: Duration) -> Self {
+        let last_report: LastReport = Arc::new(RwLock::new(None));
+        let (shutdown, mut shutdown_rx) = watch::channel(false);
+
+        let report_slot = last_report.clone();
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = shutdown_rx.changed() => break,
+                }
+
+                let options = FetchOptions {
+                    run_lock: true,
+                    ..Default::default()
+                };
+                let run = run_ingest(state.clone(), &options);
+                tokio::pin!(run);
+
+                // 종료 신호가 오면 진행 중인 실행은 drain_timeout 까지 기다린 뒤 중단
+                // (중단된 실행의 advisory lock 은 RunLockGuard 가 커넥션을 닫아 해제)
+                tokio::select! {
+                    result = &mut run => record_result(&report_slot, result).await,
+                    _ = shutdown_rx.changed() => {
+                        info!("Shutdown requested, draining in-flight ingest (up to {:?})", drain_timeout);
+                        match tokio::time::timeout(drain_timeout, &mut run).await {
+                            Ok(result) => record_result(&report_slot, result).await,
+                            Err(_) => warn!("Drain timeout exceeded, cancelling in-flight ingest"),
+                        }
+                        break;
+                    }
+                }
+            }
+            info!("Ingest ticker stopped.");
+        });
+
+        IngestTicker {
+            last_report,
+            shutdown,
+            handle,
+        }
+    }
+
+    // /ingest/status 핸들러와 공유할 마지막 실행 결과
+    pub fn last_report(&self) -> LastReport {
+        self.last_report.clone()
+    }
+
+    // 스케줄러 종료 (진행 중인 실행은 drain_timeout 까지 기다림)
+    pub async fn shutdown(self) {
+        let _ = self.shutdown.send(true);
+        if let Err(e) = self.handle.await {
+            error!("Ingest ticker task failed: {:?}", e);
+        }
+    }
+}
+
+async fn record_result(report_slot: &LastReport, result: anyhow::Result<IngestReport>) {
+    match result {
+        Ok(report) => {
+            info!(
+                "{} : scheduled ingest finished ({} stations)",
+                report.run_id,
+                report.results.len()
+            );
+            *report_slot.write().await = Some(report);
+        }
+        Err(e) if e.downcast_ref::<AlreadyRunningError>().is_some() => {
+            warn!("scheduled ingest skipped: {}", e);
+        }
+        Err(e) => error!("scheduled ingest failed: {:?}", e),
+    }
+}
+
+// 서버 종료 후 정리: 스케줄러 drain/중단 후 커넥션 풀을 명시적으로 닫음
+pub async fn shutdown_and_close(ticker: Option<IngestTicker>, state: &ServerState) {
+    if let Some(ticker) = ticker {
+        ticker.shutdown().await;
+    }
+    state.pool.close();
+    info!("Connection pool closed.");
+}
+
+// /ingest/status 응답 본문 (아직 실행 전이면 null)
+pub async fn status_body(last_report: &LastReport) -> serde_json::Value {
+    match last_report.read().await.clone() {
+        Some(report) => build_response_body(report),
+        None => serde_json::Value::Null,
+    }
+}
+
+// SIGTERM / Ctrl+C 대기 (axum 의 with_graceful_shutdown 에 전달)
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            error!("Ctrl+C handler failed: {:?}", e);
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => error!("SIGTERM handler failed: {:?}", e),
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
@@ -0,0 +1,209 @@
+// src/timeutil.rs
+
+// 제공처 현지 시각(기본 KST) <-> UTC 변환 및 정시 절삭
+// 제공처 시간대는 SOURCE_TZ_OFFSET_HOURS 환경 변수로 재정의 (기본 9, 서머타임 없는 고정 오프셋만 지원)
+// 초기화 단계(finish_state)에서 한 번 검증하여 ServerState.source_offset 에 보관하고 파싱할 때마다 전달, 잘못된 값이면 수집 전에 실패
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc};
+
+use crate::clock::Clock;
+
+const DEFAULT_SOURCE_TZ_OFFSET_HOURS: i32 = 9;
+
+// 기본 제공처 시간대 (KST, 상수 오프셋이므로 컴파일 시 확인)
+pub const KST_OFFSET: FixedOffset =
+    match FixedOffset::east_opt(DEFAULT_SOURCE_TZ_OFFSET_HOURS * 3600) {
+        Some(offset) => offset,
+        None => panic!("invalid KST offset"),
+    };
+
+// 허용 범위 (UTC-12 ~ UTC+14)
+const SOURCE_TZ_OFFSET_HOURS_RANGE: std::ops::RangeInclusive<i32> = -12..=14;
+
+// 에어코리아 dataTime 형식 (예: "2024-10-25 14:00")
+pub const KST_DATA_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+// 일부 측정소가 반환하는 dataTime 형식 (초 포함, ISO-8601 의 T 구분자), 앞에서부터 순서대로 시도
+pub const DATA_TIME_FORMATS: [&str; 4] = [
+    KST_DATA_TIME_FORMAT,
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+];
+
+// "24:00" 표기 (다음 날 00:00)
+const END_OF_DAY_SUFFIXES: [&str; 4] = [" 24:00", " 24:00:00", "T24:00", "T24:00:00"];
+
+// SOURCE_TZ_OFFSET_HOURS 환경 변수 (미설정 시 KST, 정수가 아니거나 범위를 벗어나면 오류)
+pub fn source_offset_from_env() -> Result<FixedOffset> {
+    let Ok(value) = std::env::var("SOURCE_TZ_OFFSET_HOURS") else {
+        return Ok(KST_OFFSET);
+    };
+    value
+        .trim()
+        .parse::<i32>()
+        .ok()
+        .filter(|hours| SOURCE_TZ_OFFSET_HOURS_RANGE.contains(hours))
+        .and_then(|hours| FixedOffset::east_opt(hours * 3600))
+        .ok_or_else(|| {
+            anyhow!(
+                "잘못된 SOURCE_TZ_OFFSET_HOURS: {:?} ({} ~ {} 사이의 정수 시간)",
+                value,
+                SOURCE_TZ_OFFSET_HOURS_RANGE.start(),
+                SOURCE_TZ_OFFSET_HOURS_RANGE.end()
+            )
+        })
+}
+
+// 제공처 시간대 기준 시각 (시간대는 ServerState.source_offset 또는 KST_OFFSET)
+pub fn to_source_time(datetime: DateTime<Utc>, offset: FixedOffset) -> DateTime<FixedOffset> {
+    datetime.with_timezone(&offset)
+}
+
+// 현재 KST 시각 (현재 시각은 ServerState.clock 에서 받음)
+pub fn kst_now(clock: &dyn Clock) -> DateTime<FixedOffset> {
+    to_source_time(clock.now(), KST_OFFSET)
+}
+
+// 정시 단위로 절삭 (분/초/나노초 = 0)
+pub fn truncate_to_hour(datetime: DateTime<Utc>) -> DateTime<Utc> {
+    datetime
+        - Duration::minutes(i64::from(datetime.minute()))
+        - Duration::seconds(i64::from(datetime.second()))
+        - Duration::nanoseconds(i64::from(datetime.nanosecond()))
+}
+
+// 에어코리아 dataTime (제공처 현지 시각, offset 은 ServerState.source_offset) 을 UTC 로 변환
+// "24:00" 은 다음 날 00:00 으로 처리, 오프셋이 붙은 RFC 3339 값은 그 오프셋 기준으로 변환
+pub fn parse_source_data_time(data_time: &str, offset: FixedOffset) -> Result<DateTime<Utc>> {
+    let data_time = data_time.trim();
+    if let Some(date) = END_OF_DAY_SUFFIXES
+        .iter()
+        .find_map(|suffix| data_time.strip_suffix(suffix))
+    {
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|e| anyhow!("Invalid dataTime {:?}: {}", data_time, e))?;
+        let next_day = date
+            .succ_opt()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .ok_or_else(|| anyhow!("Invalid dataTime {:?}", data_time))?;
+        return local_to_utc(next_day, offset);
+    }
+
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(data_time) {
+        return Ok(datetime.with_timezone(&Utc));
+    }
+
+    DATA_TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(data_time, format).ok())
+        .ok_or_else(|| {
+            anyhow!(
+                "Invalid dataTime {:?} (formats {:?})",
+                data_time,
+                DATA_TIME_FORMATS
+            )
+        })
+        .and_then(|naive| local_to_utc(naive, offset))
+}
+
+// 형식이 지정된 KST 시각 문자열을 UTC 로 변환 (기상청처럼 항상 KST 로 발표하는 제공처)
+pub fn parse_kst_time(value: &str, format: &str) -> Result<DateTime<Utc>> {
+    let naive = NaiveDateTime::parse_from_str(value, format)
+        .map_err(|e| anyhow!("Invalid time {:?} (format {}): {}", value, format, e))?;
+    local_to_utc(naive, KST_OFFSET)
+}
+
+fn local_to_utc(naive: NaiveDateTime, offset: FixedOffset) -> Result<DateTime<Utc>> {
+    offset
+        .from_local_datetime(&naive)
+        .single()
+        .map(|datetime| datetime.with_timezone(&Utc))
+        .ok_or_else(|| anyhow!("Ambiguous local time {}", naive))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use proptest::prelude::*;
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    fn offset(hours: i32) -> FixedOffset {
+        FixedOffset::east_opt(hours * 3600).unwrap()
+    }
+
+    // 2000-01-01 ~ 2099-12-31 (초 단위 epoch)
+    const EPOCH_RANGE: std::ops::Range<i64> = 946_684_800..4_102_444_800;
+
+    proptest! {
+        #[test]
+        fn truncation_is_idempotent(secs in EPOCH_RANGE, nanos in 0u32..1_000_000_000) {
+            let datetime = DateTime::from_timestamp(secs, nanos).unwrap();
+            let truncated = truncate_to_hour(datetime);
+            prop_assert_eq!(truncate_to_hour(truncated), truncated);
+            prop_assert!(truncated <= datetime);
+            prop_assert!(datetime - truncated < Duration::hours(1));
+            prop_assert_eq!((truncated.minute(), truncated.second(), truncated.nanosecond()), (0, 0, 0));
+        }
+
+        #[test]
+        fn data_time_round_trips_in_any_offset(hours in EPOCH_RANGE.start / 3600..EPOCH_RANGE.end / 3600, offset_hours in -12i32..=14) {
+            let datetime = DateTime::from_timestamp(hours * 3600, 0).unwrap();
+            let offset = offset(offset_hours);
+            for format in DATA_TIME_FORMATS {
+                let local = to_source_time(datetime, offset).format(format).to_string();
+                prop_assert_eq!(parse_source_data_time(&local, offset).unwrap(), datetime);
+            }
+        }
+    }
+
+    #[test]
+    fn end_of_day_rolls_over_year_and_month() {
+        let kst = KST_OFFSET;
+        assert_eq!(
+            parse_source_data_time("2023-12-31 24:00", kst).unwrap(),
+            utc("2023-12-31T15:00:00Z")
+        );
+        assert_eq!(
+            parse_source_data_time("2024-02-29 24:00", kst).unwrap(),
+            utc("2024-02-29T15:00:00Z")
+        );
+        assert_eq!(
+            parse_source_data_time("2023-02-28T24:00:00", kst).unwrap(),
+            utc("2023-02-28T15:00:00Z")
+        );
+        // 음수 오프셋은 UTC 기준 다음 해로 넘어감
+        assert_eq!(
+            parse_source_data_time("2024-12-31 23:00", offset(-5)).unwrap(),
+            utc("2025-01-01T04:00:00Z")
+        );
+    }
+
+    #[test]
+    fn rfc3339_keeps_its_own_offset() {
+        assert_eq!(
+            parse_source_data_time("2024-05-01T13:00:00+02:00", KST_OFFSET).unwrap(),
+            utc("2024-05-01T11:00:00Z")
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_formats() {
+        assert!(parse_source_data_time("2024/05/01 13:00", KST_OFFSET).is_err());
+        assert!(parse_source_data_time("2024-13-01 24:00", KST_OFFSET).is_err());
+    }
+
+    #[test]
+    fn kst_now_reads_the_injected_clock() {
+        let clock = FixedClock(utc("2024-12-31T15:30:00Z"));
+        assert_eq!(
+            kst_now(&clock).format("%Y-%m-%d %H:%M").to_string(),
+            "2025-01-01 00:30"
+        );
+    }
+}
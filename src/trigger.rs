@@ -0,0 +1,204 @@
+// src/trigger.rs
+
+// Lambda 이벤트 트리거 구분
+// payload 형태로 트리거를 판별하여 Function URL / SQS / EventBridge 예약 실행 / 직접 호출로 나누고,
+// 형태가 정해진 트리거(SQS 레코드, EventBridge 예약 이벤트)는 serde 로 역직렬화
+// EventBridge 예약 이벤트는 detail 의 값을 수집 옵션으로 사용 (detail 이 비어 있으면 기본 실시간 수집)
+// Records 가 있는데 SQS 이벤트로 읽을 수 없으면 직접 호출(전체 수집)로 넘기지 않고 오류 반환
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+// EventBridge 예약 실행 규칙의 source / detail-type
+pub const SCHEDULED_EVENT_SOURCE: &str = "aws.events";
+pub const SCHEDULED_EVENT_DETAIL_TYPE: &str = "Scheduled Event";
+
+// SQS 레코드의 eventSource
+pub const SQS_EVENT_SOURCE: &str = "aws:sqs";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trigger {
+    // Function URL / API Gateway v2 (requestContext.http)
+    Http,
+    Sqs(Vec<SqsRecord>),
+    Scheduled(ScheduledEvent),
+    // 수동 실행 / 상수 입력을 지정한 EventBridge 규칙 / CLI (payload 전체가 수집 옵션)
+    Direct,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SqsEvent {
+    #[serde(rename = "Records")]
+    pub records: Vec<SqsRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SqsRecord {
+    #[serde(rename = "messageId")]
+    pub message_id: String,
+    #[serde(default)]
+    pub body: String,
+    #[serde(rename = "eventSource")]
+    pub event_source: String,
+}
+
+// SQS 메시지 본문: {"sub_region_ids": [1, 2, 3]}
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SqsBody {
+    pub sub_region_ids: Vec<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ScheduledEvent {
+    pub id: String,
+    #[serde(rename = "detail-type")]
+    pub detail_type: String,
+    pub source: String,
+    pub time: DateTime<Utc>,
+    #[serde(default)]
+    pub resources: Vec<String>,
+    #[serde(default)]
+    pub detail: serde_json::Value,
+}
+
+impl Trigger {
+    pub fn from_payload(payload: &serde_json::Value) -> Result<Self> {
+        if payload
+            .get("requestContext")
+            .and_then(|ctx| ctx.get("http"))
+            .is_some()
+        {
+            return Ok(Trigger::Http);
+        }
+
+        if payload.get("Records").is_some() {
+            let event = SqsEvent::deserialize(payload)
+                .map_err(|e| anyhow!("Malformed Records payload: {}", e))?;
+            if event.records.is_empty() {
+                return Err(anyhow!("Malformed Records payload: no records"));
+            }
+            if let Some(record) = event
+                .records
+                .iter()
+                .find(|record| record.event_source != SQS_EVENT_SOURCE)
+            {
+                return Err(anyhow!(
+                    "Unsupported Records payload: eventSource {} (message {})",
+                    record.event_source,
+                    record.message_id
+                ));
+            }
+            return Ok(Trigger::Sqs(event.records));
+        }
+
+        if payload.get("detail-type").and_then(|v| v.as_str()) == Some(SCHEDULED_EVENT_DETAIL_TYPE)
+        {
+            if let Ok(event) = ScheduledEvent::deserialize(payload) {
+                if event.source == SCHEDULED_EVENT_SOURCE {
+                    return Ok(Trigger::Scheduled(event));
+                }
+            }
+        }
+
+        Ok(Trigger::Direct)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Trigger::Http => "http",
+            Trigger::Sqs(_) => "sqs",
+            Trigger::Scheduled(_) => "scheduled",
+            Trigger::Direct => "direct",
+        }
+    }
+}
+
+impl SqsRecord {
+    pub fn parse_body(&self) -> Result<SqsBody, serde_json::Error> {
+        serde_json::from_str(&self.body)
+    }
+}
+
+impl ScheduledEvent {
+    // 수집 옵션으로 쓸 payload (detail 객체, 중복 실행 방지 키로 이벤트 id 유지)
+    pub fn options_payload(&self) -> serde_json::Value {
+        let mut options = match &self.detail {
+            serde_json::Value::Object(detail) => detail.clone(),
+            _ => serde_json::Map::new(),
+        };
+        options
+            .entry("id")
+            .or_insert_with(|| serde_json::json!(self.id));
+        serde_json::Value::Object(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sqs_record(message_id: &str, body: &str) -> serde_json::Value {
+        json!({ "messageId": message_id, "body": body, "eventSource": SQS_EVENT_SOURCE })
+    }
+
+    #[test]
+    fn classifies_function_url_request() {
+        let payload = json!({ "requestContext": { "http": { "method": "POST" } }, "body": "{}" });
+        assert_eq!(Trigger::from_payload(&payload).unwrap(), Trigger::Http);
+    }
+
+    #[test]
+    fn classifies_sqs_records() {
+        let payload = json!({ "Records": [sqs_record("m-1", r#"{"sub_region_ids": [1, 2]}"#)] });
+        let Trigger::Sqs(records) = Trigger::from_payload(&payload).unwrap() else {
+            panic!("expected sqs trigger");
+        };
+        assert_eq!(records[0].message_id, "m-1");
+        assert_eq!(records[0].parse_body().unwrap().sub_region_ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn malformed_records_fail_instead_of_running_direct() {
+        let missing_message_id = json!({ "Records": [{ "body": "{}", "eventSource": "aws:sqs" }] });
+        let empty = json!({ "Records": [] });
+        let other_source = json!({ "Records": [{ "messageId": "m-1", "eventSource": "aws:s3" }] });
+        let not_a_list = json!({ "Records": "oops" });
+
+        for payload in [missing_message_id, empty, other_source, not_a_list] {
+            assert!(Trigger::from_payload(&payload).is_err(), "{}", payload);
+        }
+    }
+
+    #[test]
+    fn classifies_scheduled_event_and_uses_detail_as_options() {
+        let payload = json!({
+            "id": "evt-1",
+            "detail-type": SCHEDULED_EVENT_DETAIL_TYPE,
+            "source": SCHEDULED_EVENT_SOURCE,
+            "time": "2024-03-01T00:00:00Z",
+            "resources": ["arn:aws:events:ap-northeast-2:1:rule/pm"],
+            "detail": { "mode": "weather" },
+        });
+        let Trigger::Scheduled(event) = Trigger::from_payload(&payload).unwrap() else {
+            panic!("expected scheduled trigger");
+        };
+        assert_eq!(
+            event.options_payload(),
+            json!({ "mode": "weather", "id": "evt-1" })
+        );
+    }
+
+    #[test]
+    fn anything_else_is_direct() {
+        for payload in [
+            json!({}),
+            json!({ "mode": "backfill" }),
+            // 다른 source 의 Scheduled Event 형태는 상수 입력과 같이 직접 호출로 처리
+            json!({ "detail-type": SCHEDULED_EVENT_DETAIL_TYPE, "source": "custom" }),
+        ] {
+            assert_eq!(Trigger::from_payload(&payload).unwrap(), Trigger::Direct);
+        }
+    }
+}
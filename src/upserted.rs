@@ -0,0 +1,313 @@
+// src/upserted.rs
+
+// upsert RETURNING 행 변환
+// batch upsert 의 RETURNING 행은 순서가 보장되지 않고, 저장하지 않은(더 오래된 값) 측정소의 행은 돌아오지 않으므로
+// 저장 요청과 sub_region_id 로 맞춰 측정소별 결과로 변환 (reconcile_batch_result)
+// Row 에서 값을 꺼내는 부분(UpsertedRow::from_row)과 대조를 나누어 대조는 꺼낸 값만으로 동작하고,
+// 행 하나의 컬럼을 읽지 못해도 batch 전체가 아닌 해당 측정소만 오류로 기록
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use tokio_postgres::Row;
+
+use crate::handler::{with_reading_fields, StagedWrite};
+
+// RETURNING 행에서 꺼낸 값
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpsertedRow {
+    pub sub_region_id: i32,
+    pub pm10: Option<f64>,
+    pub pm25: Option<f64>,
+    pub recorded_at: DateTime<Utc>,
+    pub update_at: DateTime<Utc>,
+    // 갱신 전 값 (처음 저장한 행이면 None)
+    pub prev_pm10: Option<f64>,
+    pub prev_pm25: Option<f64>,
+}
+
+// 컬럼을 읽지 못한 행 (sub_region_id 도 읽지 못했으면 None)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowError {
+    pub sub_region_id: Option<i32>,
+    pub message: String,
+}
+
+impl UpsertedRow {
+    pub fn from_row(row: &Row) -> Result<Self, tokio_postgres::Error> {
+        Ok(UpsertedRow {
+            sub_region_id: row.try_get("sub_region_id")?,
+            pm10: row.try_get("pm10")?,
+            pm25: row.try_get("pm25")?,
+            recorded_at: row.try_get("recorded_at")?,
+            update_at: row.try_get("update_at")?,
+            prev_pm10: row.try_get("prev_pm10")?,
+            prev_pm25: row.try_get("prev_pm25")?,
+        })
+    }
+
+    // batch 의 각 행을 따로 읽음 (실패한 행만 RowError)
+    pub fn from_rows(rows: &[Row]) -> Vec<Result<Self, RowError>> {
+        rows.iter()
+            .map(|row| {
+                UpsertedRow::from_row(row).map_err(|e| RowError {
+                    sub_region_id: row.try_get("sub_region_id").ok(),
+                    message: format!("Failed to read upserted row: {:?}", e),
+                })
+            })
+            .collect()
+    }
+
+    // pm10Delta / pm25Delta 는 갱신 전 값 대비 변화량 (처음 저장했거나 이전 / 현재 값 중 하나가 NULL 이면 null)
+    pub fn to_json(&self, pm_station: &str) -> serde_json::Value {
+        json!({
+            "subRegionId": self.sub_region_id,
+            "pm10Value": self.pm10,
+            "pm25Value": self.pm25,
+            "pm10Delta": delta(self.pm10, self.prev_pm10),
+            "pm25Delta": delta(self.pm25, self.prev_pm25),
+            "dataTime": self.recorded_at,
+            "requestedTime": self.update_at,
+            "stationName": pm_station,
+        })
+    }
+}
+
+fn delta(current: Option<f64>, previous: Option<f64>) -> Option<f64> {
+    Some(current? - previous?)
+}
+
+// 저장 요청 하나(또는 요청에 없는 행 하나)의 결과
+#[derive(Debug, Clone, PartialEq)]
+pub enum StationWriteOutcome {
+    Written {
+        sub_region_id: i32,
+        data: serde_json::Value,
+    },
+    // 돌아온 행이 없음 (저장된 값이 더 새로워 갱신하지 않음)
+    SkippedStale {
+        sub_region_id: i32,
+    },
+    // 행은 돌아왔지만 컬럼을 읽지 못함
+    RowError {
+        sub_region_id: i32,
+        message: String,
+    },
+    // 요청에 없는 sub_region_id 의 행, 같은 sub_region_id 의 중복 행, sub_region_id 를 읽지 못한 행
+    Unexpected {
+        sub_region_id: Option<i32>,
+        message: String,
+    },
+}
+
+// RETURNING 행을 저장 요청과 sub_region_id 로 대조 (요청 순서대로, 예상하지 못한 행은 끝에)
+// sub_region_id 를 읽지 못한 행이 있으면 행이 없는 요청을 SkippedStale 로 단정할 수 없으므로 RowError 로 기록
+pub(crate) fn reconcile_batch_result(
+    inputs: &[StagedWrite],
+    rows: Vec<Result<UpsertedRow, RowError>>,
+) -> Vec<StationWriteOutcome> {
+    let requested: HashSet<i32> = inputs.iter().map(|write| write.sub_region_id).collect();
+    let mut returned: HashMap<i32, Result<UpsertedRow, String>> = HashMap::new();
+    let mut unexpected = Vec::new();
+    let mut unidentified_rows = 0;
+
+    for row in rows {
+        let (sub_region_id, row) = match row {
+            Ok(row) => (row.sub_region_id, Ok(row)),
+            Err(RowError {
+                sub_region_id: Some(sub_region_id),
+                message,
+            }) => (sub_region_id, Err(message)),
+            Err(RowError {
+                sub_region_id: None,
+                message,
+            }) => {
+                unidentified_rows += 1;
+                unexpected.push(StationWriteOutcome::Unexpected {
+                    sub_region_id: None,
+                    message,
+                });
+                continue;
+            }
+        };
+        if !requested.contains(&sub_region_id) {
+            unexpected.push(StationWriteOutcome::Unexpected {
+                sub_region_id: Some(sub_region_id),
+                message: "Row returned for a sub_region that was not in the batch".to_owned(),
+            });
+        } else if returned.insert(sub_region_id, row).is_some() {
+            unexpected.push(StationWriteOutcome::Unexpected {
+                sub_region_id: Some(sub_region_id),
+                message: "More than one row returned for the sub_region".to_owned(),
+            });
+        }
+    }
+
+    let mut outcomes: Vec<StationWriteOutcome> = inputs
+        .iter()
+        .map(|write| match returned.remove(&write.sub_region_id) {
+            Some(Ok(row)) => StationWriteOutcome::Written {
+                sub_region_id: write.sub_region_id,
+                data: with_reading_fields(row.to_json(&write.pm_station), &write.reading),
+            },
+            Some(Err(message)) => StationWriteOutcome::RowError {
+                sub_region_id: write.sub_region_id,
+                message,
+            },
+            None if unidentified_rows > 0 => StationWriteOutcome::RowError {
+                sub_region_id: write.sub_region_id,
+                message: format!(
+                    "No readable row returned ({} rows without a readable sub_region_id)",
+                    unidentified_rows
+                ),
+            },
+            None => StationWriteOutcome::SkippedStale {
+                sub_region_id: write.sub_region_id,
+            },
+        })
+        .collect();
+    outcomes.extend(unexpected);
+    outcomes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Reading;
+    use chrono::TimeZone;
+
+    fn recorded_at() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 5, 1, 4, 0, 0).unwrap()
+    }
+
+    fn write(sub_region_id: i32, pm_station: &str) -> StagedWrite {
+        StagedWrite {
+            sub_region_id,
+            pm_station: pm_station.to_owned(),
+            reading: Reading {
+                pm10: Some(42.0),
+                pm25: Some(20.0),
+                recorded_at: recorded_at(),
+                server_time: None,
+                raw: None,
+                rejected_values: 0,
+            },
+        }
+    }
+
+    fn row(sub_region_id: i32, prev_pm10: Option<f64>) -> Result<UpsertedRow, RowError> {
+        Ok(UpsertedRow {
+            sub_region_id,
+            pm10: Some(42.0),
+            pm25: Some(20.0),
+            recorded_at: recorded_at(),
+            update_at: recorded_at(),
+            prev_pm10,
+            prev_pm25: None,
+        })
+    }
+
+    fn written_data(outcome: &StationWriteOutcome) -> &serde_json::Value {
+        match outcome {
+            StationWriteOutcome::Written { data, .. } => data,
+            other => panic!("expected Written, got {:?}", other),
+        }
+    }
+
+    // 새로 저장(inserted) / 갱신(updated) / 더 오래된 값이라 그대로(unchanged)
+    #[test]
+    fn classifies_inserted_updated_and_unchanged_in_request_order() {
+        let inputs = [write(1, "중구"), write(2, "종로구"), write(3, "강남구")];
+        // RETURNING 순서는 요청 순서와 다를 수 있음
+        let outcomes = reconcile_batch_result(&inputs, vec![row(2, Some(40.0)), row(1, None)]);
+
+        assert_eq!(outcomes.len(), 3);
+        let inserted = written_data(&outcomes[0]);
+        assert_eq!(inserted["subRegionId"], 1);
+        assert_eq!(inserted["stationName"], "중구");
+        assert!(inserted["pm10Delta"].is_null());
+        let updated = written_data(&outcomes[1]);
+        assert_eq!(updated["stationName"], "종로구");
+        assert_eq!(updated["pm10Delta"], 2.0);
+        assert_eq!(
+            outcomes[2],
+            StationWriteOutcome::SkippedStale { sub_region_id: 3 }
+        );
+    }
+
+    #[test]
+    fn extra_and_duplicate_rows_are_reported_as_unexpected() {
+        let inputs = [write(1, "중구")];
+        let outcomes =
+            reconcile_batch_result(&inputs, vec![row(1, None), row(1, None), row(9, None)]);
+
+        // 요청 1개에 행 3개: 요청 결과 뒤에 예상하지 못한 행 2개
+        assert_eq!(outcomes.len(), 3);
+        written_data(&outcomes[0]);
+        assert!(matches!(
+            outcomes[1],
+            StationWriteOutcome::Unexpected {
+                sub_region_id: Some(1),
+                ..
+            }
+        ));
+        assert!(matches!(
+            outcomes[2],
+            StationWriteOutcome::Unexpected {
+                sub_region_id: Some(9),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn corrupted_rows_fail_only_their_station() {
+        let inputs = [write(1, "중구"), write(2, "종로구")];
+        let outcomes = reconcile_batch_result(
+            &inputs,
+            vec![
+                row(1, None),
+                Err(RowError {
+                    sub_region_id: Some(2),
+                    message: "bad pm10".to_owned(),
+                }),
+            ],
+        );
+        written_data(&outcomes[0]);
+        assert_eq!(
+            outcomes[1],
+            StationWriteOutcome::RowError {
+                sub_region_id: 2,
+                message: "bad pm10".to_owned(),
+            }
+        );
+
+        // sub_region_id 도 읽지 못한 행이 있으면 행이 없는 요청을 SkippedStale 로 단정하지 않음
+        let outcomes = reconcile_batch_result(
+            &inputs,
+            vec![
+                row(1, None),
+                Err(RowError {
+                    sub_region_id: None,
+                    message: "bad row".to_owned(),
+                }),
+            ],
+        );
+        assert_eq!(outcomes.len(), 3);
+        written_data(&outcomes[0]);
+        assert!(matches!(
+            outcomes[1],
+            StationWriteOutcome::RowError {
+                sub_region_id: 2,
+                ..
+            }
+        ));
+        assert!(matches!(
+            outcomes[2],
+            StationWriteOutcome::Unexpected {
+                sub_region_id: None,
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,210 @@
+// src/validity.rs
+
+// 항목별 유효 범위 확인 (PM_VALID_RANGES, 기본 pm10 0-1000 / pm25 0-500)
+// 9999 나 음수 같은 비정상 값이 차트를 망치지 않도록 범위 밖의 값은 NULL 로 저장하고 경고 로그를 남김
+// 예) PM_VALID_RANGES={"pm10": [0, 1000], "pm25": [0, 500]}  (지정하지 않은 항목은 기본값)
+
+use serde_json::json;
+use std::sync::OnceLock;
+use tracing::warn;
+
+use crate::provider::Reading;
+
+static VALID_RANGES: OnceLock<ValidRanges> = OnceLock::new();
+
+// 양 끝을 포함하는 범위
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValidRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl ValidRange {
+    pub const fn new(min: f64, max: f64) -> Self {
+        ValidRange { min, max }
+    }
+
+    pub fn contains(&self, value: f64) -> bool {
+        self.min <= value && value <= self.max
+    }
+
+    // [min, max] 배열 (min > max 이거나 숫자가 아니면 None)
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        match value.as_array().map(Vec::as_slice) {
+            Some([min, max]) => {
+                let range = ValidRange::new(min.as_f64()?, max.as_f64()?);
+                (range.min <= range.max).then_some(range)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValidRanges {
+    pub pm10: ValidRange,
+    pub pm25: ValidRange,
+}
+
+impl Default for ValidRanges {
+    fn default() -> Self {
+        ValidRanges {
+            pm10: ValidRange::new(0.0, 1000.0),
+            pm25: ValidRange::new(0.0, 500.0),
+        }
+    }
+}
+
+impl ValidRanges {
+    // PM_VALID_RANGES 파싱 (잘못된 항목은 무시하고 기본값 사용)
+    pub fn parse(value: &str) -> Self {
+        let mut ranges = ValidRanges::default();
+        let fields = match serde_json::from_str::<serde_json::Value>(value) {
+            Ok(serde_json::Value::Object(fields)) => fields,
+            _ => {
+                warn!("PM_VALID_RANGES 는 JSON 객체여야 함, 무시: {}", value);
+                return ranges;
+            }
+        };
+
+        for (pollutant, range) in fields {
+            let target = match pollutant.as_str() {
+                "pm10" => &mut ranges.pm10,
+                "pm25" => &mut ranges.pm25,
+                _ => {
+                    warn!("PM_VALID_RANGES : 알 수 없는 항목 {}, 무시", pollutant);
+                    continue;
+                }
+            };
+            match ValidRange::from_json(&range) {
+                Some(parsed) => *target = parsed,
+                None => warn!(
+                    "PM_VALID_RANGES : {} 범위는 [min, max] 여야 함, 무시: {}",
+                    pollutant, range
+                ),
+            }
+        }
+        ranges
+    }
+
+    pub fn from_env() -> Self {
+        match std::env::var("PM_VALID_RANGES") {
+            Ok(value) => ValidRanges::parse(&value),
+            Err(_) => ValidRanges::default(),
+        }
+    }
+
+    // 범위 밖의 값은 None 으로 바꾸고 측정소 이름과 버린 값을 경고로 기록 (버린 수는 rejected_values 에 누적)
+    pub fn apply(&self, pm_station: &str, reading: Reading) -> Reading {
+        let pm10 = check_value(pm_station, "pm10", reading.pm10, self.pm10);
+        let pm25 = check_value(pm_station, "pm25", reading.pm25, self.pm25);
+        let rejected = (pm10.is_none() && reading.pm10.is_some()) as u32
+            + (pm25.is_none() && reading.pm25.is_some()) as u32;
+        Reading {
+            pm10,
+            pm25,
+            recorded_at: reading.recorded_at,
+            server_time: reading.server_time,
+            raw: reading.raw,
+            rejected_values: reading.rejected_values + rejected,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "pm10": [self.pm10.min, self.pm10.max],
+            "pm25": [self.pm25.min, self.pm25.max],
+        })
+    }
+}
+
+fn check_value(
+    pm_station: &str,
+    pollutant: &str,
+    value: Option<f64>,
+    range: ValidRange,
+) -> Option<f64> {
+    let value = value?;
+    if range.contains(value) {
+        return Some(value);
+    }
+    warn!(
+        "{} : {} 값 {} 이 유효 범위({}-{}) 밖, NULL 로 저장",
+        pm_station, pollutant, value, range.min, range.max
+    );
+    None
+}
+
+// 현재 유효 범위 (처음 사용할 때 환경 변수에서 한 번 읽음)
+pub fn valid_ranges() -> &'static ValidRanges {
+    VALID_RANGES.get_or_init(ValidRanges::from_env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn reading(pm10: Option<f64>, pm25: Option<f64>) -> Reading {
+        Reading {
+            pm10,
+            pm25,
+            recorded_at: Utc.with_ymd_and_hms(2024, 5, 1, 4, 0, 0).unwrap(),
+            server_time: None,
+            raw: None,
+            rejected_values: 0,
+        }
+    }
+
+    // 항목별 경계값: 양 끝은 포함, 바로 바깥과 음수는 NULL
+    #[test]
+    fn boundaries_per_pollutant() {
+        let ranges = ValidRanges::default();
+        let pm10_cases = [
+            (0.0, Some(0.0)),
+            (1000.0, Some(1000.0)),
+            (1000.1, None),
+            (9999.0, None),
+            (-0.1, None),
+        ];
+        for (value, expected) in pm10_cases {
+            let checked = ranges.apply("중구", reading(Some(value), None));
+            assert_eq!(checked.pm10, expected, "pm10 {}", value);
+            assert_eq!(checked.rejected_values, expected.is_none() as u32);
+        }
+
+        let pm25_cases = [
+            (0.0, Some(0.0)),
+            (500.0, Some(500.0)),
+            (500.5, None),
+            (-1.0, None),
+        ];
+        for (value, expected) in pm25_cases {
+            let checked = ranges.apply("중구", reading(None, Some(value)));
+            assert_eq!(checked.pm25, expected, "pm25 {}", value);
+            assert_eq!(checked.rejected_values, expected.is_none() as u32);
+        }
+    }
+
+    #[test]
+    fn missing_values_are_not_counted_as_rejected() {
+        let checked = ValidRanges::default().apply("중구", reading(None, None));
+        assert_eq!((checked.pm10, checked.pm25), (None, None));
+        assert_eq!(checked.rejected_values, 0);
+
+        let checked = ValidRanges::default().apply("중구", reading(Some(-5.0), Some(800.0)));
+        assert_eq!(checked.rejected_values, 2);
+    }
+
+    #[test]
+    fn parse_overrides_only_valid_entries() {
+        let ranges = ValidRanges::parse(r#"{"pm10": [5, 600], "pm25": [10, 1], "o3": [0, 1]}"#);
+        assert_eq!(ranges.pm10, ValidRange::new(5.0, 600.0));
+        // min > max 는 무시하고 기본값 유지
+        assert_eq!(ranges.pm25, ValidRanges::default().pm25);
+        assert_eq!(ValidRanges::parse("not json"), ValidRanges::default());
+
+        let checked = ranges.apply("중구", reading(Some(4.0), Some(4.0)));
+        assert_eq!((checked.pm10, checked.pm25), (None, Some(4.0)));
+    }
+}
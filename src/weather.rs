@@ -0,0 +1,557 @@
+// src/weather.rs
+
+// [기상청] 단기예보 조회서비스 - 초단기실황 조회 (getUltraSrtNcst) 연동
+// sub_region 의 격자 좌표(nx, ny)로 기온(T1H), 습도(REH), 풍속(WSD) 을 조회하여 {schema}.external_weather 에 upsert
+// PM 수집과 같이 ingest_enabled = false 인 sub_region 은 건너뛰고 (includeDisabled: true 면 포함), 실행 마감 시각을 지킴
+// 요청은 PM 조회와 같은 ApiClient / 동시성 제한 / 429 처리를 거치고, dryRun 이면 저장하지 않음
+
+use anyhow::anyhow;
+use chrono::{DateTime, Duration, FixedOffset, Timelike, Utc};
+use deadpool_postgres::Client as DbClient;
+use futures::StreamExt;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::Arc;
+use tokio_postgres::Row;
+use tracing::{info, warn};
+
+use crate::db_error::{classify_db_error, describe_db_error, is_undefined_column_error};
+use crate::db_schema::sql;
+use crate::failure::FailureKind;
+use crate::handler::{
+    acquire_permit, api_concurrency, new_run_id, per_station_timeout, station_stream,
+    with_station_deadline, FetchOptions, IngestReport, StationResult,
+};
+use crate::http_body::{describe_json_error, truncate_body};
+use crate::inline_stations::StationListSource;
+use crate::params::{to_query_pairs, UltraSrtNcstParams};
+use crate::phases::Phases;
+use crate::provider::rate_limit::{self, ApiBudget};
+use crate::state::{get_client_with_retry, ServerState};
+use crate::timeutil::{kst_now, parse_kst_time, to_source_time, KST_OFFSET};
+
+pub const WEATHER_API_URL: &str =
+    "http://apis.data.go.kr/1360000/VilageFcstInfoService_2.0/getUltraSrtNcst";
+
+pub const GET_ALL_SUB_REGION_ID_AND_GRID_QUERY: &str = r#"
+SELECT sub_region_id, nx, ny
+FROM {schema}.sub_region
+WHERE COALESCE(ingest_enabled, true);
+"#;
+
+pub const GET_SUB_REGION_ID_AND_GRID_BY_IDS_QUERY: &str = r#"
+SELECT sub_region_id, nx, ny
+FROM {schema}.sub_region
+WHERE sub_region_id = ANY($1) AND COALESCE(ingest_enabled, true);
+"#;
+
+// 수집 중지 여부와 무관한 조회 (includeDisabled: true, ingest_enabled 컬럼이 없는 이전 스키마)
+pub const GET_ALL_SUB_REGION_GRID_INCLUDING_DISABLED_QUERY: &str = r#"
+SELECT sub_region_id, nx, ny
+FROM {schema}.sub_region;
+"#;
+
+pub const GET_SUB_REGION_GRID_BY_IDS_INCLUDING_DISABLED_QUERY: &str = r#"
+SELECT sub_region_id, nx, ny
+FROM {schema}.sub_region
+WHERE sub_region_id = ANY($1);
+"#;
+
+pub const UPSERT_EXTERNAL_WEATHER_QUERY: &str = r#"
+INSERT INTO {schema}.external_weather (sub_region_id, temperature, humidity, wind_speed, recorded_at)
+VALUES ($1, $2, $3, $4, $5)
+ON CONFLICT (sub_region_id)
+DO UPDATE SET
+    temperature = EXCLUDED.temperature,
+    humidity = EXCLUDED.humidity,
+    wind_speed = EXCLUDED.wind_speed,
+    recorded_at = EXCLUDED.recorded_at,
+    update_at = now()
+RETURNING *;
+"#;
+
+// 초단기실황은 매시 정각 관측분이 40분 이후에 제공됨 (KST)
+const KMA_PUBLISH_MINUTE: u32 = 40;
+
+// 관측값
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WeatherObservation {
+    pub temperature: Option<f64>,
+    pub humidity: Option<f64>,
+    pub wind_speed: Option<f64>,
+}
+
+// 현재 시각 기준 조회 가능한 최신 base_date, base_time
+// 기상청 발표 시각은 항상 KST 이므로 에어코리아용 SOURCE_TZ_OFFSET_HOURS 와 무관하게 KST 로 계산
+pub fn kma_base_at(now: DateTime<Utc>) -> (String, String) {
+    kma_base_date_time(to_source_time(now, KST_OFFSET))
+}
+
+// 현재 KST 시각 기준 조회 가능한 최신 base_date(YYYYMMDD), base_time(HH00)
+// 40분 이전이면 아직 이번 시각 관측값이 제공되지 않으므로 이전 시각 사용
+pub fn kma_base_date_time(now_kst: DateTime<FixedOffset>) -> (String, String) {
+    let base = if now_kst.minute() < KMA_PUBLISH_MINUTE {
+        now_kst - Duration::hours(1)
+    } else {
+        now_kst
+    };
+
+    (
+        base.format("%Y%m%d").to_string(),
+        base.format("%H00").to_string(),
+    )
+}
+
+// 응답의 category 별 obsrValue 추출 (T1H: 기온, REH: 습도, WSD: 풍속)
+pub fn parse_observation(json_response: &serde_json::Value) -> Option<WeatherObservation> {
+    let items = json_response
+        .get("response")
+        .and_then(|res| res.get("body"))
+        .and_then(|body| body.get("items"))
+        .and_then(|items| items.get("item"))
+        .and_then(|item| item.as_array())
+        .filter(|items| !items.is_empty())?;
+
+    let mut observation = WeatherObservation::default();
+    for item in items {
+        let value = item.get("obsrValue").and_then(|v| match v {
+            serde_json::Value::String(v) => v.parse::<f64>().ok(),
+            v => v.as_f64(),
+        });
+
+        match item.get("category").and_then(|v| v.as_str()) {
+            Some("T1H") => observation.temperature = value,
+            Some("REH") => observation.humidity = value,
+            Some("WSD") => observation.wind_speed = value,
+            _ => {}
+        }
+    }
+
+    Some(observation)
+}
+
+// 격자 좌표 목록 조회 후 sub_region 별 초단기실황 조회 및 upsert 실행
+pub async fn run_weather_ingest(
+    state: Arc<ServerState>,
+    options: &FetchOptions,
+) -> Result<IngestReport, anyhow::Error> {
+    let start = tokio::time::Instant::now();
+    let run_id = options.run_id.clone().unwrap_or_else(new_run_id);
+
+    let weather_api_key = state
+        .weather_api_key
+        .clone()
+        .ok_or_else(|| anyhow!("WEATHER_API_KEY 환경 변수 누락"))?;
+
+    let db_client = state.pool.get().await?;
+
+    let rows = fetch_grid_rows(
+        &db_client,
+        options.sub_region_ids.as_ref(),
+        options.include_disabled,
+    )
+    .await?;
+    drop(db_client);
+
+    // 전체 수집에서 sub_region 이 없으면 설정 오류로 구분
+    let no_sub_regions = rows.is_empty() && options.sub_region_ids.is_none();
+    if no_sub_regions {
+        warn!("sub_region 목록이 비어 있음: 수집할 격자 없음");
+    }
+
+    let (base_date, base_time) = kma_base_date_time(kst_now(state.clock.as_ref()));
+
+    // PM 수집과 동일한 동시성 제한 (복합 모드에서는 PM 수집과 공유)
+    let semaphore = options.semaphore();
+    // 상위 API 가 429 를 보내면 남은 실행 동안 퍼밋을 줄이고, 실행에서 보낸 요청 수를 셈
+    let api_budget = ApiBudget::new(semaphore.clone(), api_concurrency());
+    let per_station_timeout = per_station_timeout();
+
+    let mut grids = Vec::new();
+    let mut results = Vec::new();
+
+    for (index, row) in rows.iter().enumerate() {
+        // 잘못된 타입/NULL 컬럼은 패닉 대신 해당 행만 건너뛰고 오류로 기록
+        let (sub_region_id, nx, ny) = match read_grid_row(row) {
+            Ok(grid_row) => grid_row,
+            Err(e) => {
+                let error_message = format!("sub_region row {} : {:?}", index, e);
+                results.push(StationResult::failed(
+                    row.try_get::<_, i32>("sub_region_id").unwrap_or_default(),
+                    "",
+                    FailureKind::RowMapping,
+                    error_message,
+                ));
+                continue;
+            }
+        };
+
+        let (nx, ny) = match (nx, ny) {
+            (Some(nx), Some(ny)) => (nx, ny),
+            _ => {
+                let error_message =
+                    format!("sub_region {} : nx/ny are not configured", sub_region_id);
+                results.push(StationResult::failed(
+                    sub_region_id,
+                    "",
+                    FailureKind::StationConfig,
+                    error_message,
+                ));
+                continue;
+            }
+        };
+
+        grids.push((sub_region_id, nx, ny));
+    }
+
+    // 격자별 future 는 태스크로 만들지 않고 station_stream 에서 실행
+    let state = &state;
+    let api_budget = &api_budget;
+    let weather_api_key = &weather_api_key;
+    let base_date = &base_date;
+    let base_time = &base_time;
+    let semaphore = &semaphore;
+    let stations = station_stream(grids.into_iter().map(|(sub_region_id, nx, ny)| {
+        let grid = format!("{},{}", nx, ny);
+        let task_label = grid.clone();
+
+        let station_task = async move {
+            let _permit = match acquire_permit(semaphore.clone(), sub_region_id, &grid).await {
+                Ok(permit) => permit,
+                Err(result) => return result,
+            };
+
+            // 측정소별 제한 시간 (복합 모드 / Lambda 마감 시각이 더 가까우면 남은 시간까지만)
+            with_station_deadline(
+                sub_region_id,
+                &grid,
+                options.station_timeout(per_station_timeout),
+                process_weather_station(
+                    state,
+                    weather_api_key,
+                    options.dry_run,
+                    sub_region_id,
+                    nx,
+                    ny,
+                    base_date,
+                    base_time,
+                ),
+            )
+            .await
+        };
+
+        (
+            sub_region_id,
+            task_label,
+            rate_limit::scope(api_budget.clone(), station_task),
+        )
+    }));
+    results.extend(stations.collect::<Vec<_>>().await);
+
+    Ok(IngestReport {
+        run_id,
+        results,
+        skipped_fresh: 0,
+        deferred: 0,
+        cache_hits: 0,
+        db_read_only: false,
+        no_sub_regions,
+        advanced: None,
+        data_frozen: false,
+        elapsed: start.elapsed(),
+        warnings: Vec::new(),
+        latest_cache_failures: 0,
+        disabled_sub_regions: None,
+        diagnostics: None,
+        phases: Phases::default(),
+        station_list_source: StationListSource::Db,
+        quota: None,
+    })
+}
+
+// 격자 좌표 목록 조회 (sub_region_ids 가 None 이면 전체)
+// ingest_enabled 컬럼이 없는 이전 스키마면 필터 없는 쿼리로 다시 조회
+async fn fetch_grid_rows(
+    client: &DbClient,
+    sub_region_ids: Option<&Vec<i32>>,
+    include_disabled: bool,
+) -> Result<Vec<Row>, tokio_postgres::Error> {
+    let query = |filtered: bool| {
+        let query = match (sub_region_ids.is_some(), filtered) {
+            (true, true) => GET_SUB_REGION_ID_AND_GRID_BY_IDS_QUERY,
+            (true, false) => GET_SUB_REGION_GRID_BY_IDS_INCLUDING_DISABLED_QUERY,
+            (false, true) => GET_ALL_SUB_REGION_ID_AND_GRID_QUERY,
+            (false, false) => GET_ALL_SUB_REGION_GRID_INCLUDING_DISABLED_QUERY,
+        };
+        sql(query)
+    };
+    let run = |query: String| async move {
+        match sub_region_ids {
+            Some(sub_region_ids) => client.query(query.as_str(), &[sub_region_ids]).await,
+            None => client.query(query.as_str(), &[]).await,
+        }
+    };
+
+    if include_disabled {
+        return run(query(false)).await;
+    }
+    match run(query(true)).await {
+        Err(e) if is_undefined_column_error(&e) => {
+            info!("sub_region.ingest_enabled 컬럼 없음, 필터 없이 조회");
+            run(query(false)).await
+        }
+        result => result,
+    }
+}
+
+// 격자 좌표 행 변환 (sub_region_id, nx, ny)
+fn read_grid_row(row: &Row) -> Result<(i32, Option<i32>, Option<i32>), tokio_postgres::Error> {
+    Ok((
+        row.try_get("sub_region_id")?,
+        row.try_get("nx")?,
+        row.try_get("ny")?,
+    ))
+}
+
+// upsert RETURNING 행을 응답 JSON 으로 변환
+fn upserted_weather_json(
+    row: &Row,
+    sub_region_id: i32,
+) -> Result<serde_json::Value, tokio_postgres::Error> {
+    Ok(json!({
+        "subRegionId": sub_region_id,
+        "temperature": row.try_get::<_, Option<f64>>("temperature")?,
+        "humidity": row.try_get::<_, Option<f64>>("humidity")?,
+        "windSpeed": row.try_get::<_, Option<f64>>("wind_speed")?,
+        "dataTime": row.try_get::<_, DateTime<Utc>>("recorded_at")?,
+        "requestedTime": row.try_get::<_, DateTime<Utc>>("update_at")?,
+    }))
+}
+
+// 조회만 한 관측값을 응답 JSON 으로 변환 (dryRun, 저장 시각 대신 조회 시각)
+fn fetched_weather_json(
+    observation: &WeatherObservation,
+    sub_region_id: i32,
+    recorded_at: DateTime<Utc>,
+    requested_at: DateTime<Utc>,
+) -> serde_json::Value {
+    json!({
+        "subRegionId": sub_region_id,
+        "temperature": observation.temperature,
+        "humidity": observation.humidity,
+        "windSpeed": observation.wind_speed,
+        "dataTime": recorded_at,
+        "requestedTime": requested_at,
+    })
+}
+
+// 단일 격자에 대한 초단기실황 조회 및 upsert (dry_run 이면 조회만)
+#[allow(clippy::too_many_arguments)]
+async fn process_weather_station(
+    state: &ServerState,
+    weather_api_key: &str,
+    dry_run: bool,
+    sub_region_id: i32,
+    nx: i32,
+    ny: i32,
+    base_date: &str,
+    base_time: &str,
+) -> StationResult {
+    let grid = format!("{},{}", nx, ny);
+    let params = to_query_pairs(&UltraSrtNcstParams::new(
+        weather_api_key,
+        base_date,
+        base_time,
+        nx,
+        ny,
+    ));
+
+    // PM 조회와 같은 ApiClient 사용 (429 / 503 Retry-After 재시도, 끊긴 본문 재요청, 실행 요청 수 기록)
+    let envelope = match state.api_client.fetch_weather(&grid, &params).await {
+        Ok(envelope) => envelope,
+        Err(e) => return StationResult::failed(sub_region_id, &grid, e.kind, e.message),
+    };
+
+    if !envelope.status.is_success() {
+        let kind = if envelope.status == StatusCode::TOO_MANY_REQUESTS {
+            FailureKind::RateLimited
+        } else {
+            FailureKind::HttpStatus
+        };
+        let error_message = format!(
+            "{} : Received non-success status code: {}\nResponse text: {}",
+            grid,
+            envelope.status,
+            truncate_body(&envelope.body)
+        );
+        return StationResult::failed(sub_region_id, &grid, kind, error_message);
+    }
+
+    let json_response: serde_json::Value = match serde_json::from_str(&envelope.body) {
+        Ok(json) => json,
+        Err(e) => {
+            let (kind, error_message) = describe_json_error(&grid, &e, &envelope.body);
+            return StationResult::failed(sub_region_id, &grid, kind, error_message);
+        }
+    };
+
+    // 파싱이 끝나면 원문은 바로 해제
+    drop(envelope);
+
+    // 기상청 API 는 resultCode "00" 이 정상
+    if let Some(result_code) = json_response
+        .get("response")
+        .and_then(|res| res.get("header"))
+        .and_then(|header| header.get("resultCode"))
+        .and_then(|code| code.as_str())
+    {
+        if result_code != "00" {
+            let result_msg = json_response["response"]["header"]["resultMsg"]
+                .as_str()
+                .unwrap_or_default();
+            let error_message = format!(
+                "{} : API returned an error: {} {}",
+                grid, result_code, result_msg
+            );
+            return StationResult::failed(
+                sub_region_id,
+                &grid,
+                FailureKind::ApiError,
+                error_message,
+            );
+        }
+    }
+
+    let observation = match parse_observation(&json_response) {
+        Some(observation) => observation,
+        None => {
+            let error_message = format!("{} : No data available in API response.", grid);
+            return StationResult::failed(sub_region_id, &grid, FailureKind::NoData, error_message);
+        }
+    };
+
+    // 관측 시각 (base_date + base_time, KST) 을 UTC 로 변환
+    let recorded_at_datetime_utc =
+        match parse_kst_time(&format!("{}{}", base_date, base_time), "%Y%m%d%H%M") {
+            Ok(datetime) => datetime,
+            Err(e) => {
+                let error_message = format!("{} : Failed to parse base time: {}", grid, e);
+                return StationResult::failed(
+                    sub_region_id,
+                    &grid,
+                    FailureKind::Parse,
+                    error_message,
+                );
+            }
+        };
+
+    // dryRun: 조회만 하고 저장하지 않음
+    if dry_run {
+        let data = fetched_weather_json(
+            &observation,
+            sub_region_id,
+            recorded_at_datetime_utc,
+            state.clock.now(),
+        );
+        return StationResult::success(sub_region_id, &grid, data);
+    }
+
+    // 응답을 모두 해석한 뒤에 커넥션 획득 (외부 API 를 기다리는 동안 풀을 점유하지 않음)
+    let (db_client, checkout_retries) = match get_client_with_retry(&state.pool).await {
+        Ok(client) => client,
+        Err(e) => {
+            let error_message = format!("{} : Failed to get db client: {:?}", grid, e);
+            return StationResult::failed(sub_region_id, &grid, FailureKind::DbPool, error_message);
+        }
+    };
+
+    let result = match db_client
+        .query_one(
+            sql(UPSERT_EXTERNAL_WEATHER_QUERY).as_str(),
+            &[
+                &sub_region_id,
+                &observation.temperature,
+                &observation.humidity,
+                &observation.wind_speed,
+                &recorded_at_datetime_utc,
+            ],
+        )
+        .await
+    {
+        Ok(row) => match upserted_weather_json(&row, sub_region_id) {
+            Ok(data) => StationResult::success(sub_region_id, &grid, data),
+            Err(e) => {
+                let error_message = format!("{} : Failed to read upserted row: {:?}", grid, e);
+                StationResult::failed(sub_region_id, &grid, FailureKind::RowMapping, error_message)
+            }
+        },
+        Err(e) => {
+            let error_message = format!(
+                "{} : Database query failed: {}",
+                grid,
+                describe_db_error(&e)
+            );
+            StationResult::failed(sub_region_id, &grid, classify_db_error(&e), error_message)
+        }
+    };
+
+    // 조회/upsert 결과에 커넥션 획득 재시도 횟수 기록
+    result.with_checkout_retries(checkout_retries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timeutil::parse_kst_time;
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn base_time_is_the_latest_published_kst_hour() {
+        // 05:50Z = 14:50 KST: 14시 관측분 제공됨
+        assert_eq!(
+            kma_base_at(utc("2024-03-01T05:50:00Z")),
+            ("20240301".to_owned(), "1400".to_owned())
+        );
+        // 05:30Z = 14:30 KST: 아직 40분 전이므로 13시
+        assert_eq!(
+            kma_base_at(utc("2024-03-01T05:30:00Z")),
+            ("20240301".to_owned(), "1300".to_owned())
+        );
+        // 15:10Z = 다음 날 00:10 KST: 전날 23시
+        assert_eq!(
+            kma_base_at(utc("2024-03-01T15:10:00Z")),
+            ("20240301".to_owned(), "2300".to_owned())
+        );
+    }
+
+    #[test]
+    fn base_time_round_trips_as_kst() {
+        let (base_date, base_time) = kma_base_at(utc("2024-03-01T05:50:00Z"));
+        assert_eq!(
+            parse_kst_time(&format!("{}{}", base_date, base_time), "%Y%m%d%H%M").unwrap(),
+            utc("2024-03-01T05:00:00Z")
+        );
+    }
+
+    #[test]
+    fn parses_observation_categories() {
+        let response = json!({ "response": { "body": { "items": { "item": [
+            { "category": "T1H", "obsrValue": "3.5" },
+            { "category": "REH", "obsrValue": 61 },
+            { "category": "WSD", "obsrValue": "1.2" },
+            { "category": "PTY", "obsrValue": "0" },
+        ] } } } });
+        assert_eq!(
+            parse_observation(&response),
+            Some(WeatherObservation {
+                temperature: Some(3.5),
+                humidity: Some(61.0),
+                wind_speed: Some(1.2),
+            })
+        );
+        assert_eq!(parse_observation(&json!({ "response": {} })), None);
+    }
+}
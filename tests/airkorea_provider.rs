@@ -0,0 +1,131 @@
+// tests/airkorea_provider.rs
+
+// 준비된 응답(MockApiClient)으로 AirKoreaProvider 의 조회/분류 흐름 확인 (실서버 호출 없음)
+
+use chrono::{FixedOffset, TimeZone, Utc};
+use environment_lambda::failure::FailureKind;
+use environment_lambda::provider::airkorea::SERVICE_KEY_NOT_REGISTERED;
+use environment_lambda::provider::{AirKoreaProvider, ApiEnvelope, MockApiClient, PmProvider};
+use reqwest::StatusCode;
+use std::sync::Arc;
+
+fn response_body(items: serde_json::Value) -> String {
+    serde_json::json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": { "totalCount": items.as_array().map_or(0, Vec::len), "items": items },
+        }
+    })
+    .to_string()
+}
+
+fn provider(mock: MockApiClient) -> AirKoreaProvider {
+    AirKoreaProvider::new(Arc::new(mock), "test-key".to_owned())
+}
+
+#[tokio::test]
+async fn fetch_returns_latest_reading_with_server_time() {
+    let server_date = Utc.with_ymd_and_hms(2024, 5, 1, 4, 20, 0).unwrap();
+    let body = response_body(serde_json::json!([
+        { "dataTime": "2024-05-01 13:00", "pm10Value": "42", "pm25Value": "-" },
+        { "dataTime": "2024-05-01 12:00", "pm10Value": "40", "pm25Value": "20" },
+    ]));
+    let provider = provider(MockApiClient::new().with_envelope(
+        "중구",
+        ApiEnvelope::new(StatusCode::OK, body).with_server_date(server_date),
+    ));
+
+    let reading = provider.fetch("중구").await.unwrap();
+    assert_eq!(reading.pm10, Some(42.0));
+    assert_eq!(reading.pm25, None);
+    assert_eq!(
+        reading.recorded_at,
+        Utc.with_ymd_and_hms(2024, 5, 1, 4, 0, 0).unwrap()
+    );
+    assert_eq!(reading.upstream_lag_seconds(), Some(20 * 60));
+}
+
+#[tokio::test]
+async fn fetch_classifies_failures() {
+    let provider = provider(
+        MockApiClient::new()
+            .with_envelope(
+                "키오류",
+                ApiEnvelope::new(
+                    StatusCode::OK,
+                    format!(
+                        "<returnAuthMsg>{}</returnAuthMsg>",
+                        SERVICE_KEY_NOT_REGISTERED
+                    ),
+                ),
+            )
+            .with_envelope(
+                "빈응답",
+                ApiEnvelope::new(StatusCode::OK, response_body(serde_json::json!([]))),
+            )
+            .with_envelope(
+                "서버오류",
+                ApiEnvelope::new(StatusCode::SERVICE_UNAVAILABLE, "unavailable"),
+            ),
+    );
+
+    let kind = |result: environment_lambda::provider::Result<_>| result.unwrap_err().kind;
+    assert_eq!(
+        kind(provider.fetch("키오류").await),
+        FailureKind::InvalidServiceKey
+    );
+    assert_eq!(kind(provider.fetch("빈응답").await), FailureKind::NoData);
+    assert_eq!(
+        kind(provider.fetch("서버오류").await),
+        FailureKind::HttpStatus
+    );
+    // 준비된 응답이 없는 측정소는 요청 실패
+    assert_eq!(
+        kind(provider.fetch("없는측정소").await),
+        FailureKind::Request
+    );
+}
+
+#[tokio::test]
+async fn fetch_province_serves_station_readings() {
+    let body = response_body(serde_json::json!([
+        { "stationName": "중구", "dataTime": "2024-05-01 13:00", "pm10Value": "30", "pm25Value": "12" },
+        { "stationName": "종로구", "dataTime": "잘못된 시각", "pm10Value": "31", "pm25Value": "13" },
+    ]));
+    let provider = provider(
+        MockApiClient::new().with_envelope("서울", ApiEnvelope::new(StatusCode::OK, body)),
+    );
+
+    let province_readings = provider.fetch_province("서울").await.unwrap();
+    assert_eq!(province_readings.len(), 2);
+    let provider = provider.with_province_readings(province_readings);
+
+    // 시도별 응답에서 꺼내므로 측정소별 응답을 준비하지 않아도 됨
+    let reading = provider.fetch("중구").await.unwrap();
+    assert_eq!((reading.pm10, reading.pm25), (Some(30.0), Some(12.0)));
+    assert_eq!(
+        provider.fetch("종로구").await.unwrap_err().kind,
+        FailureKind::Parse
+    );
+    assert_eq!(
+        provider.fetch("강남구").await.unwrap_err().kind,
+        FailureKind::NoData
+    );
+}
+
+#[tokio::test]
+async fn fetch_reads_data_time_in_the_given_source_offset() {
+    let body = response_body(serde_json::json!([
+        { "dataTime": "2024-05-01 13:00", "pm10Value": "42", "pm25Value": "20" },
+    ]));
+    let provider = provider(
+        MockApiClient::new().with_envelope("Berlin", ApiEnvelope::new(StatusCode::OK, body)),
+    )
+    .with_source_offset(FixedOffset::east_opt(2 * 3600).unwrap());
+
+    let reading = provider.fetch("Berlin").await.unwrap();
+    assert_eq!(
+        reading.recorded_at,
+        Utc.with_ymd_and_hms(2024, 5, 1, 11, 0, 0).unwrap()
+    );
+}
@@ -0,0 +1,106 @@
+// tests/atomic_rollback.rs
+
+// atomic: true 실행에서 두 번째 batch 의 DB 오류가 먼저 실행된 batch 까지 모두 롤백하는지 확인 (TEST_DATABASE_URL 필요)
+// sql() 의 전역 스키마 / PM_UPSERT_BATCH_SIZE 를 쓰므로 파일을 분리
+
+mod common;
+
+use chrono::Utc;
+use environment_lambda::db_schema;
+use environment_lambda::failure::FailureKind;
+use environment_lambda::handler::{run_ingest, FetchOptions, StationStatus};
+use environment_lambda::inline_stations::InlineStation;
+use environment_lambda::provider::{ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::Arc;
+
+const SCHEMA: &str = "test_atomic_rollback";
+
+fn station_body() -> String {
+    let data_time = Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+            },
+        }
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn mid_run_db_error_rolls_back_every_write() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    // batch 2개: [1, 2] 는 성공, [3] 은 외래 키 위반
+    std::env::set_var("PM_UPSERT_BATCH_SIZE", "2");
+    db_schema::init_from_env().unwrap();
+    common::fresh_schema(&pool, SCHEMA).await;
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "CREATE TABLE {SCHEMA}.sub_region (sub_region_id integer PRIMARY KEY);
+             INSERT INTO {SCHEMA}.sub_region VALUES (1), (2);
+             ALTER TABLE {SCHEMA}.external_pm ADD FOREIGN KEY (sub_region_id)
+                 REFERENCES {SCHEMA}.sub_region (sub_region_id);"
+        ))
+        .await
+        .unwrap();
+
+    let stations = ["중구", "종로구", "용산구"];
+    let mock = stations.iter().fold(MockApiClient::new(), |mock, station| {
+        mock.with_envelope(station, ApiEnvelope::new(StatusCode::OK, station_body()))
+    });
+    let state = Arc::new(
+        ServerState::new(pool.clone(), "test-key".to_owned(), None, None)
+            .with_api_client(Arc::new(mock)),
+    );
+    let options = FetchOptions {
+        atomic: true,
+        inline_stations: Some(
+            stations
+                .iter()
+                .enumerate()
+                .map(|(index, station)| InlineStation {
+                    sub_region_id: index as i32 + 1,
+                    pm_station: station.to_string(),
+                })
+                .collect(),
+        ),
+        ..Default::default()
+    };
+
+    let report = run_ingest(state, &options).await.unwrap();
+
+    assert_eq!(report.results.len(), 3);
+    for result in &report.results {
+        let StationStatus::Failed { kind, message } = &result.status else {
+            panic!("{} should have been rolled back", result.pm_station);
+        };
+        assert_eq!(*kind, FailureKind::DbForeignKey, "{}", message);
+        assert!(message.contains("rolled back"), "{}", message);
+        assert!(message.contains("batch 2/2"), "{}", message);
+    }
+
+    // 먼저 실행된 batch [1, 2] 도 남지 않음
+    let stored: i64 = pool
+        .get()
+        .await
+        .unwrap()
+        .query_one(&format!("SELECT count(*) FROM {SCHEMA}.external_pm"), &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(stored, 0);
+}
@@ -0,0 +1,98 @@
+// tests/backfill_history.rs
+
+// backfill 모드: DAILY 응답의 24개 시간별 항목이 external_pm_history 에 24행으로 저장되고,
+// 다시 실행하면 이미 저장된 시간은 건너뛰는지 확인 (TEST_DATABASE_URL 필요)
+// sql() 의 전역 스키마를 쓰므로 파일을 분리
+
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use environment_lambda::backfill::insert_history;
+use environment_lambda::db_schema;
+use environment_lambda::provider::{AirKoreaProvider, ApiEnvelope, MockApiClient};
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::Arc;
+
+const SCHEMA: &str = "test_backfill_history";
+
+// 2024-05-01 00:00 ~ 23:00 (KST) 의 24개 항목, 최신 항목이 먼저
+fn daily_body() -> String {
+    let items: Vec<serde_json::Value> = (0..24)
+        .rev()
+        .map(|hour| {
+            json!({
+                "dataTime": format!("2024-05-01 {:02}:00", hour),
+                "pm10Value": (30 + hour).to_string(),
+                "pm25Value": if hour == 5 { "-".to_owned() } else { (10 + hour).to_string() },
+            })
+        })
+        .collect();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": { "totalCount": items.len(), "items": items },
+        }
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn daily_response_inserts_24_history_rows_once() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+    common::fresh_schema(&pool, SCHEMA).await;
+    let client = pool.get().await.unwrap();
+    client
+        .batch_execute(&format!(
+            "CREATE TABLE {SCHEMA}.external_pm_history (
+                 sub_region_id integer NOT NULL,
+                 pm10 double precision,
+                 pm25 double precision,
+                 recorded_at timestamptz NOT NULL,
+                 UNIQUE (sub_region_id, recorded_at)
+             );"
+        ))
+        .await
+        .unwrap();
+
+    let provider = AirKoreaProvider::new(
+        Arc::new(
+            MockApiClient::new()
+                .with_envelope("중구", ApiEnvelope::new(StatusCode::OK, daily_body())),
+        ),
+        "test-key".to_owned(),
+    );
+    let readings = provider.fetch_history("중구").await.unwrap();
+    assert_eq!(readings.len(), 24);
+
+    assert_eq!(insert_history(&client, 7, &readings).await.unwrap(), 24);
+    // 같은 응답을 다시 저장하면 모두 건너뜀
+    assert_eq!(insert_history(&client, 7, &readings).await.unwrap(), 0);
+
+    let rows = client
+        .query(
+            &format!(
+                "SELECT pm10, pm25, recorded_at FROM {SCHEMA}.external_pm_history
+                 WHERE sub_region_id = 7 ORDER BY recorded_at"
+            ),
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 24);
+
+    // KST 00:00 = 전날 15:00 UTC 부터 한 시간 간격
+    let first_hour = Utc.with_ymd_and_hms(2024, 4, 30, 15, 0, 0).unwrap();
+    for (hour, row) in rows.iter().enumerate() {
+        let recorded_at: chrono::DateTime<Utc> = row.get("recorded_at");
+        assert_eq!(recorded_at, first_hour + Duration::hours(hour as i64));
+        assert_eq!(row.get::<_, Option<f64>>("pm10"), Some(30.0 + hour as f64));
+    }
+    // 결측 값은 NULL 로 저장
+    assert_eq!(rows[5].get::<_, Option<f64>>("pm25"), None);
+    assert_eq!(rows[6].get::<_, Option<f64>>("pm25"), Some(16.0));
+}
@@ -0,0 +1,80 @@
+// tests/batch_upsert.rs
+
+// atomic 실행의 UNNEST batch upsert: 모든 행(첫 행 포함)의 이전 값 반환 확인 (TEST_DATABASE_URL 필요)
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use environment_lambda::db_schema::apply_schema;
+use environment_lambda::handler::BATCH_UPSERT_EXTERNAL_PM_QUERY;
+use environment_lambda::upserted::UpsertedRow;
+
+#[tokio::test]
+async fn batch_upsert_returns_previous_values_for_every_row() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    let schema = common::fresh_schema(&pool, "test_batch_upsert").await;
+    let client = pool.get().await.unwrap();
+    let query = apply_schema(BATCH_UPSERT_EXTERNAL_PM_QUERY, &schema);
+
+    let ids = vec![1, 2, 3];
+    let recorded_at: DateTime<Utc> = Utc::now() - Duration::hours(1);
+    let first_pm10: Vec<Option<f64>> = vec![Some(10.0), Some(20.0), Some(30.0)];
+    let first_pm25: Vec<Option<f64>> = vec![Some(1.0), Some(2.0), None];
+    client
+        .query(
+            &query,
+            &[&ids, &first_pm10, &first_pm25, &vec![recorded_at; 3]],
+        )
+        .await
+        .unwrap();
+
+    // 실제 실행처럼 트랜잭션 안에서 다시 저장
+    client.batch_execute("BEGIN").await.unwrap();
+    let second_pm10: Vec<Option<f64>> = vec![Some(15.0), Some(18.0), Some(33.0)];
+    let second_pm25: Vec<Option<f64>> = vec![Some(4.0), Some(2.0), Some(3.0)];
+    let rows = client
+        .query(
+            &query,
+            &[
+                &ids,
+                &second_pm10,
+                &second_pm25,
+                &vec![recorded_at + Duration::hours(1); 3],
+            ],
+        )
+        .await
+        .unwrap();
+    client.batch_execute("COMMIT").await.unwrap();
+
+    let mut rows: Vec<UpsertedRow> = UpsertedRow::from_rows(&rows)
+        .into_iter()
+        .map(Result::unwrap)
+        .collect();
+    rows.sort_by_key(|row| row.sub_region_id);
+    assert_eq!(rows.len(), 3);
+    for (index, row) in rows.iter().enumerate() {
+        assert_eq!(
+            row.prev_pm10, first_pm10[index],
+            "row {}",
+            row.sub_region_id
+        );
+        assert_eq!(
+            row.prev_pm25, first_pm25[index],
+            "row {}",
+            row.sub_region_id
+        );
+    }
+
+    let deltas: Vec<serde_json::Value> = rows
+        .iter()
+        .map(|row| row.to_json("중구")["pm10Delta"].clone())
+        .collect();
+    assert_eq!(deltas, vec![5.0, -2.0, 3.0]);
+    // 이전 pm25 가 NULL 이면 변화량도 null
+    assert_eq!(
+        rows[2].to_json("중구")["pm25Delta"],
+        serde_json::Value::Null
+    );
+}
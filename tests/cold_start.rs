@@ -0,0 +1,94 @@
+// tests/cold_start.rs
+
+// 같은 실행 환경의 두 번째 호출이 초기화한 상태를 재사용하고 meta.coldStart / meta.phases 를 남기는지
+// handle_event 로 확인 (TEST_DATABASE_URL 필요, shared_state_from_env 의 전역 상태 / 환경 변수를 쓰므로 파일을 분리)
+
+mod common;
+
+use environment_lambda::handler::handle_event;
+use environment_lambda::invocation::InvocationInfo;
+use serde_json::json;
+
+const SCHEMA: &str = "test_cold_start";
+
+// 디버그 빌드에서는 handle_event 의 poll 호출 깊이가 테스트 스레드 기본 스택(2 MiB)을 넘으므로 별도 스레드에서 실행
+fn run_with_large_stack<F>(future: F) -> F::Output
+where
+    F: std::future::Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    std::thread::Builder::new()
+        .stack_size(16 * 1024 * 1024)
+        .spawn(move || {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(future)
+        })
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+// 수집 마감이 이미 지난 dry-run 호출 (외부 API 를 부르지 않음)
+fn invoke(run_id: &str) -> serde_json::Value {
+    let invocation = InvocationInfo {
+        aws_request_id: "aws-1".to_owned(),
+        invoked_function_arn: String::new(),
+        function_version: "$LATEST".to_owned(),
+        deadline_millis: 0,
+        remaining: Some(std::time::Duration::from_secs(1)),
+        deadline: Some(tokio::time::Instant::now()),
+    };
+    run_with_large_stack(handle_event(
+        run_id.to_owned(),
+        "req-1".to_owned(),
+        json!({
+            "force": true,
+            "dryRun": true,
+            "stations": [{ "subRegionId": 1, "pmStation": "중구" }],
+        }),
+        invocation,
+    ))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn second_invocation_reuses_the_state() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    common::fresh_schema(&pool, SCHEMA).await;
+    std::env::set_var("DB_CONN_URL", std::env::var("TEST_DATABASE_URL").unwrap());
+    std::env::set_var("AIR_QUALITY_API_KEY", "test-key");
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+
+    // 첫 호출: 상태 초기화 (payload 측정소 목록이므로 stationListQuery 는 없음)
+    let first = invoke("run-1");
+    assert_eq!(first["statusCode"], 200);
+    let meta = &first["body"]["meta"];
+    assert_eq!(meta["coldStart"], true);
+    let phases = meta["phases"].as_object().unwrap();
+    let mut names: Vec<_> = phases.keys().map(String::as_str).collect();
+    names.sort_unstable();
+    assert_eq!(
+        names,
+        [
+            "fetchPhase",
+            "poolWarmup",
+            "reportPhase",
+            "stateInit",
+            "writePhase"
+        ]
+    );
+    assert!(phases.values().all(|v| v.is_u64()));
+
+    // 두 번째 호출: 같은 상태를 재사용하므로 poolWarmup 없음
+    let second = invoke("run-2");
+    assert_eq!(second["statusCode"], 200);
+    let meta = &second["body"]["meta"];
+    assert_eq!(meta["coldStart"], false);
+    assert!(meta["phases"]["stateInit"].is_u64());
+    assert!(meta["phases"].get("poolWarmup").is_none());
+}
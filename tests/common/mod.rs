@@ -0,0 +1,53 @@
+// tests/common/mod.rs
+
+// DB 연동 테스트 공통 준비
+// TEST_DATABASE_URL (예: postgres://postgres@localhost:5432/postgres) 이 없으면 DB 테스트는 건너뜀
+// 테스트마다 전용 스키마를 새로 만들어 서로 간섭하지 않도록 함
+
+#![allow(dead_code)]
+
+use deadpool_postgres::{Config, Pool, Runtime};
+use environment_lambda::db_schema::apply_schema;
+use tokio_postgres::NoTls;
+
+const EXTERNAL_PM_TABLE: &str = r#"
+CREATE TABLE {schema}.external_pm (
+    sub_region_id integer PRIMARY KEY,
+    pm10 double precision,
+    pm25 double precision,
+    recorded_at timestamptz NOT NULL,
+    update_at timestamptz NOT NULL DEFAULT now()
+);
+"#;
+
+// TEST_DATABASE_URL 로 만든 풀 (미설정이면 None)
+pub fn test_pool() -> Option<Pool> {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL 미설정: DB 테스트 건너뜀");
+        return None;
+    };
+    let cfg = Config {
+        url: Some(url),
+        ..Default::default()
+    };
+    Some(
+        cfg.create_pool(Some(Runtime::Tokio1), NoTls)
+            .expect("test pool"),
+    )
+}
+
+// 스키마를 새로 만들고 external_pm 테이블 생성 후 스키마 이름 반환
+pub async fn fresh_schema(pool: &Pool, schema: &str) -> String {
+    let client = pool.get().await.expect("test client");
+    client
+        .batch_execute(&format!(
+            "DROP SCHEMA IF EXISTS {schema} CASCADE; CREATE SCHEMA {schema};"
+        ))
+        .await
+        .expect("create schema");
+    client
+        .batch_execute(&apply_schema(EXTERNAL_PM_TABLE, schema))
+        .await
+        .expect("create external_pm");
+    schema.to_owned()
+}
@@ -0,0 +1,217 @@
+// tests/concurrency_limits.rs
+
+// API 동시 호출 수(API_CONCURRENCY)와 DB 쓰기 동시 수(DB_WRITE_CONCURRENCY)가 따로 적용되는지 확인
+// - 설정값 해석과 풀 최대 크기(DB_POOL_MAX_SIZE) 초과 검사
+// - 조회를 마친 측정소는 API 퍼밋을 반납하므로 upsert 가 막혀도 다음 측정소 조회가 진행됨 (TEST_DATABASE_URL 필요)
+// 환경 변수와 sql() 의 전역 스키마를 쓰므로 파일을 분리
+
+mod common;
+
+use deadpool_postgres::{Config, PoolConfig, Runtime};
+use environment_lambda::db_conn::DbConnConfig;
+use environment_lambda::db_schema;
+use environment_lambda::effective_config::effective_config;
+use environment_lambda::handler::{run_ingest, FetchOptions, StationStatus};
+use environment_lambda::inline_stations::InlineStation;
+use environment_lambda::provider::api_client::ApiFuture;
+use environment_lambda::provider::{ApiClient, ApiEnvelope, MockApiClient};
+use environment_lambda::state::{initialize_state, ServerState};
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio_postgres::NoTls;
+
+const SCHEMA: &str = "test_concurrency_limits";
+
+// 측정소 조회 횟수를 세는 ApiClient
+struct CountingApiClient {
+    inner: MockApiClient,
+    station_requests: AtomicUsize,
+}
+
+impl ApiClient for CountingApiClient {
+    fn fetch_station<'a>(
+        &'a self,
+        pm_station: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.station_requests.fetch_add(1, Ordering::SeqCst);
+        self.inner.fetch_station(pm_station, params)
+    }
+
+    fn fetch_province<'a>(
+        &'a self,
+        sido_name: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.inner.fetch_province(sido_name, params)
+    }
+
+    fn fetch_weather<'a>(&'a self, grid: &'a str, params: &'a [(String, String)]) -> ApiFuture<'a> {
+        self.inner.fetch_weather(grid, params)
+    }
+
+    fn fetch_nearby_station<'a>(
+        &'a self,
+        point: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.inner.fetch_nearby_station(point, params)
+    }
+}
+
+fn station_body() -> String {
+    let data_time = chrono::Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+            },
+        }
+    })
+    .to_string()
+}
+
+// 설정값은 한 테스트에서 순서대로 확인 (DB_WRITE_CONCURRENCY 는 아래 실행 테스트의 풀 크기 4 이하로만 설정)
+#[tokio::test]
+async fn limits_are_read_from_env_and_checked_against_the_pool() {
+    std::env::remove_var("API_CONCURRENCY");
+    std::env::remove_var("DB_WRITE_CONCURRENCY");
+    std::env::remove_var("DB_POOL_MAX_SIZE");
+    let resolved = &effective_config()["resolved"];
+    assert_eq!(resolved["apiConcurrency"], 10);
+    assert_eq!(resolved["dbPoolMaxSize"], serde_json::Value::Null);
+
+    // 쓰기 동시 수 기본값은 API 동시 호출 수와 풀 크기 중 작은 값
+    std::env::set_var("API_CONCURRENCY", "3");
+    std::env::set_var("DB_POOL_MAX_SIZE", "2");
+    let resolved = &effective_config()["resolved"];
+    assert_eq!(resolved["apiConcurrency"], 3);
+    assert_eq!(resolved["dbPoolMaxSize"], 2);
+    assert_eq!(resolved["dbWriteConcurrency"], 2);
+
+    std::env::set_var("DB_WRITE_CONCURRENCY", "1");
+    assert_eq!(effective_config()["resolved"]["dbWriteConcurrency"], 1);
+
+    // 0 이나 숫자가 아닌 값은 기본값
+    std::env::set_var("API_CONCURRENCY", "0");
+    std::env::set_var("DB_WRITE_CONCURRENCY", "many");
+    let resolved = &effective_config()["resolved"];
+    assert_eq!(resolved["apiConcurrency"], 10);
+    assert_eq!(resolved["dbWriteConcurrency"], 2);
+
+    // 풀보다 큰 쓰기 동시 수는 설정 오류 (DB 에 접속하기 전에 초기화 실패)
+    std::env::set_var("DB_WRITE_CONCURRENCY", "3");
+    assert_eq!(
+        effective_config()["resolved"]["dbWriteConcurrency"],
+        serde_json::Value::Null
+    );
+    let e = initialize_state(
+        &DbConnConfig::Url("postgres://unused@127.0.0.1:1/unused".to_owned()),
+        "test-key",
+        None,
+        None,
+    )
+    .await
+    .err()
+    .expect("DB_WRITE_CONCURRENCY above the pool size");
+    assert!(e.to_string().contains("DB_WRITE_CONCURRENCY(3)"), "{}", e);
+
+    std::env::remove_var("API_CONCURRENCY");
+    std::env::remove_var("DB_WRITE_CONCURRENCY");
+    std::env::remove_var("DB_POOL_MAX_SIZE");
+}
+
+#[tokio::test]
+async fn a_blocked_upsert_does_not_hold_the_api_permit() {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL 미설정: DB 테스트 건너뜀");
+        return;
+    };
+    let pool = Config {
+        url: Some(url),
+        pool: Some(PoolConfig::new(4)),
+        ..Default::default()
+    }
+    .create_pool(Some(Runtime::Tokio1), NoTls)
+    .unwrap();
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+    common::fresh_schema(&pool, SCHEMA).await;
+
+    // 1 번 행을 트랜잭션으로 잠가 해당 upsert 를 막음
+    let mut locker = pool.get().await.unwrap();
+    locker
+        .batch_execute(&format!(
+            "INSERT INTO {SCHEMA}.external_pm (sub_region_id, pm10, pm25, recorded_at)
+             VALUES (1, 10, 5, now() - interval '1 hour');"
+        ))
+        .await
+        .unwrap();
+    let lock = locker.transaction().await.unwrap();
+    lock.batch_execute(&format!(
+        "SELECT * FROM {SCHEMA}.external_pm WHERE sub_region_id = 1 FOR UPDATE;"
+    ))
+    .await
+    .unwrap();
+
+    let stations = ["중구", "종로구", "용산구"];
+    let client = Arc::new(CountingApiClient {
+        inner: stations.iter().fold(MockApiClient::new(), |mock, station| {
+            mock.with_envelope(station, ApiEnvelope::new(StatusCode::OK, station_body()))
+        }),
+        station_requests: AtomicUsize::new(0),
+    });
+    let state = Arc::new(
+        ServerState::new(pool.clone(), "test-key".to_owned(), None, None)
+            .with_api_client(client.clone()),
+    );
+    // API 동시 호출 1개
+    let options = FetchOptions {
+        semaphore: Some(Arc::new(Semaphore::new(1))),
+        inline_stations: Some(
+            stations
+                .iter()
+                .enumerate()
+                .map(|(index, station)| InlineStation {
+                    sub_region_id: index as i32 + 1,
+                    pm_station: station.to_string(),
+                })
+                .collect(),
+        ),
+        ..Default::default()
+    };
+    let run = tokio::spawn(async move { run_ingest(state, &options).await });
+
+    // 1 번 upsert 가 막혀 있는 동안에도 세 측정소 모두 조회됨
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+    while client.station_requests.load(Ordering::SeqCst) < stations.len() {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "조회 {} 건에서 멈춤",
+            client.station_requests.load(Ordering::SeqCst)
+        );
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(!run.is_finished());
+
+    lock.rollback().await.unwrap();
+    let report = run.await.unwrap().unwrap();
+    assert_eq!(report.results.len(), stations.len());
+    for result in &report.results {
+        assert!(
+            matches!(result.status, StationStatus::Success(_)),
+            "{:?}",
+            result.status
+        );
+    }
+}
@@ -0,0 +1,119 @@
+// tests/connection_recycle.rs
+
+// DB_CONN_MAX_LIFETIME_SECS / DB_CONN_IDLE_TIMEOUT_SECS 설정과 pre_recycle hook 이 오래된 커넥션을 새 커넥션으로 바꾸는지 확인
+// (커넥션 재사용 여부는 백엔드 pid 로 판별, TEST_DATABASE_URL 필요)
+// 환경 변수를 바꾸므로 파일을 분리
+
+use deadpool_postgres::{Config, Pool, Runtime};
+use environment_lambda::db_conn::DbConnConfig;
+use environment_lambda::state::{initialize_state, ConnectionRecycle};
+use std::time::Duration;
+use tokio_postgres::NoTls;
+
+fn recycling_pool(recycle: ConnectionRecycle) -> Option<Pool> {
+    let url = std::env::var("TEST_DATABASE_URL").ok()?;
+    let cfg = Config {
+        url: Some(url),
+        ..Default::default()
+    };
+    Some(
+        cfg.builder(NoTls)
+            .unwrap()
+            .runtime(Runtime::Tokio1)
+            .max_size(1)
+            .pre_recycle(recycle.hook())
+            .build()
+            .unwrap(),
+    )
+}
+
+async fn backend_pid(pool: &Pool) -> i32 {
+    pool.get()
+        .await
+        .unwrap()
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get(0)
+}
+
+#[tokio::test]
+async fn initialize_state_applies_the_configured_lifetime() {
+    std::env::set_var("DB_CONN_MAX_LIFETIME_SECS", "300");
+    std::env::set_var("DB_CONN_IDLE_TIMEOUT_SECS", "0");
+    assert_eq!(
+        ConnectionRecycle::from_env(),
+        ConnectionRecycle {
+            max_lifetime: Some(Duration::from_secs(300)),
+            idle_timeout: None,
+        }
+    );
+
+    // initialize_state 로 만든 풀에도 같은 설정이 hook 으로 걸림
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        return;
+    };
+    std::env::set_var("DB_CONN_MAX_LIFETIME_SECS", "1");
+    // warm-up 커넥션이 여럿이면 체크아웃마다 다른 커넥션이 나오므로 하나만 사용
+    std::env::set_var("DB_POOL_WARM_CONNECTIONS", "0");
+    let state = initialize_state(&DbConnConfig::Url(url), "test-key", None, None)
+        .await
+        .unwrap();
+
+    let first = backend_pid(&state.pool).await;
+    assert_eq!(backend_pid(&state.pool).await, first);
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert_ne!(backend_pid(&state.pool).await, first);
+    std::env::remove_var("DB_CONN_MAX_LIFETIME_SECS");
+    std::env::remove_var("DB_CONN_IDLE_TIMEOUT_SECS");
+}
+
+#[tokio::test]
+async fn connection_past_max_lifetime_is_replaced() {
+    let Some(pool) = recycling_pool(ConnectionRecycle {
+        max_lifetime: Some(Duration::from_millis(300)),
+        idle_timeout: None,
+    }) else {
+        return;
+    };
+
+    let first = backend_pid(&pool).await;
+    // 수명 안에서는 같은 커넥션 재사용
+    assert_eq!(backend_pid(&pool).await, first);
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    let replaced = backend_pid(&pool).await;
+    assert_ne!(replaced, first);
+    assert_eq!(pool.status().size, 1);
+}
+
+#[tokio::test]
+async fn connection_idle_past_timeout_is_replaced() {
+    let Some(pool) = recycling_pool(ConnectionRecycle {
+        max_lifetime: None,
+        idle_timeout: Some(Duration::from_millis(300)),
+    }) else {
+        return;
+    };
+
+    // 유휴 시간보다 짧은 간격으로 계속 쓰면 수명과 무관하게 유지
+    let first = backend_pid(&pool).await;
+    for _ in 0..3 {
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(backend_pid(&pool).await, first);
+    }
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert_ne!(backend_pid(&pool).await, first);
+}
+
+#[tokio::test]
+async fn unlimited_recycle_keeps_the_connection() {
+    let Some(pool) = recycling_pool(ConnectionRecycle::default()) else {
+        return;
+    };
+
+    let first = backend_pid(&pool).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(backend_pid(&pool).await, first);
+}
@@ -0,0 +1,88 @@
+// tests/connection_settings.rs
+
+// initialize_state 로 만든 풀의 커넥션에 statement_timeout / application_name 이 걸리고
+// DB_RECYCLE_VERIFIED=true 면 서버가 끊은 커넥션 대신 새 커넥션을 받는지 확인 (TEST_DATABASE_URL 필요)
+// 환경 변수를 바꾸므로 파일을 분리
+
+use environment_lambda::db_conn::DbConnConfig;
+use environment_lambda::state::initialize_state;
+use tokio_postgres::error::SqlState;
+
+#[tokio::test]
+async fn pool_connections_carry_session_settings() {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        return;
+    };
+    std::env::set_var("DB_STATEMENT_TIMEOUT_MS", "200");
+    std::env::set_var("DB_RECYCLE_VERIFIED", "true");
+    std::env::set_var("DB_POOL_WARM_CONNECTIONS", "0");
+    std::env::set_var("DB_POOL_MAX_SIZE", "1");
+    let state = initialize_state(&DbConnConfig::Url(url), "test-key", None, None)
+        .await
+        .unwrap();
+    let client = state.pool.get().await.unwrap();
+    let show = |name: &'static str| {
+        let client = &client;
+        async move {
+            client
+                .query_one(&format!("SHOW {}", name), &[])
+                .await
+                .unwrap()
+                .get::<_, String>(0)
+        }
+    };
+    assert_eq!(show("statement_timeout").await, "200ms");
+    assert_eq!(show("application_name").await, "pm_ingest_lambda");
+
+    // 제한 시간을 넘는 쿼리는 서버에서 취소 (57014: query_canceled)
+    let e = client
+        .query_one("SELECT pg_sleep(1)", &[])
+        .await
+        .unwrap_err();
+    assert_eq!(e.code(), Some(&SqlState::QUERY_CANCELED));
+
+    // 서버가 끊은 커넥션: 체크아웃 시 확인 쿼리로 걸러 내고 새로 연결
+    let pid: i32 = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get(0);
+    drop(client);
+    let (admin, connection) = tokio_postgres::connect(
+        &std::env::var("TEST_DATABASE_URL").unwrap(),
+        tokio_postgres::NoTls,
+    )
+    .await
+    .unwrap();
+    tokio::spawn(connection);
+    admin
+        .execute("SELECT pg_terminate_backend($1)", &[&pid])
+        .await
+        .unwrap();
+    // 종료 신호는 비동기로 처리되므로 백엔드가 사라질 때까지 대기
+    while admin
+        .query_opt("SELECT 1 FROM pg_stat_activity WHERE pid = $1", &[&pid])
+        .await
+        .unwrap()
+        .is_some()
+    {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+
+    let client = state.pool.get().await.unwrap();
+    let replaced: i32 = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_ne!(replaced, pid);
+
+    for name in [
+        "DB_STATEMENT_TIMEOUT_MS",
+        "DB_RECYCLE_VERIFIED",
+        "DB_POOL_WARM_CONNECTIONS",
+        "DB_POOL_MAX_SIZE",
+    ] {
+        std::env::remove_var(name);
+    }
+}
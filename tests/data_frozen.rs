@@ -0,0 +1,162 @@
+// tests/data_frozen.rs
+
+// 전체 수집에서 측정 시각이 갱신된 측정소 수(meta.advancedCount)를 세고, 하나도 갱신되지 않으면
+// meta.dataFrozen 과 경고가 붙는지 PM_LAST_SEEN_STORE (db / memory / off) 별로 확인 (TEST_DATABASE_URL 필요)
+// sql() 의 전역 스키마와 PM_LAST_SEEN_STORE 환경 변수를 쓰므로 파일을 분리
+
+mod common;
+
+use chrono::{Timelike, Utc};
+use environment_lambda::db_schema;
+use environment_lambda::handler::{
+    build_response_body, run_ingest, run_realtime_ingest, FetchOptions, DATA_FROZEN_WARNING,
+};
+use environment_lambda::last_seen::LastSeenStore;
+use environment_lambda::provider::{ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::Arc;
+
+const SCHEMA: &str = "test_data_frozen";
+
+// 이번 정시 (KST) 측정값 응답
+fn station_body(data_time: &str) -> String {
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+            },
+        }
+    })
+    .to_string()
+}
+
+#[test]
+fn last_seen_store_reads_env() {
+    std::env::remove_var("PM_LAST_SEEN_STORE");
+    assert_eq!(LastSeenStore::from_env(), LastSeenStore::Db);
+    std::env::set_var("PM_LAST_SEEN_STORE", "memory");
+    assert_eq!(LastSeenStore::from_env(), LastSeenStore::Memory);
+    std::env::set_var("PM_LAST_SEEN_STORE", "off");
+    assert_eq!(LastSeenStore::from_env(), LastSeenStore::Off);
+    // 알 수 없는 값은 db
+    std::env::set_var("PM_LAST_SEEN_STORE", "redis");
+    assert_eq!(LastSeenStore::from_env(), LastSeenStore::Db);
+    std::env::remove_var("PM_LAST_SEEN_STORE");
+}
+
+// 환경 변수를 바꾸며 순서대로 실행해야 하므로 하나의 테스트로 구성
+#[tokio::test]
+async fn unchanged_recorded_at_in_a_full_sweep_flags_frozen_data() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+    common::fresh_schema(&pool, SCHEMA).await;
+    let data_time = Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .with_minute(0)
+        .unwrap()
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    // 두 측정소 모두 이번 정시 측정값이 이미 저장되어 있음
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "CREATE TABLE {SCHEMA}.sub_region (
+                 sub_region_id integer PRIMARY KEY,
+                 pm_station text,
+                 tm_x double precision,
+                 tm_y double precision,
+                 provider text,
+                 nx integer,
+                 ny integer,
+                 ingest_enabled boolean
+             );
+             INSERT INTO {SCHEMA}.sub_region (sub_region_id, pm_station) VALUES (1, '중구'), (2, '종로구');
+             INSERT INTO {SCHEMA}.external_pm (sub_region_id, pm10, pm25, recorded_at) VALUES
+                 (1, 42, 20, '{data_time}:00+09'),
+                 (2, 42, 20, '{data_time}:00+09');"
+        ))
+        .await
+        .unwrap();
+
+    let mock = ["중구", "종로구"]
+        .iter()
+        .fold(MockApiClient::new(), |mock, station| {
+            mock.with_envelope(
+                station,
+                ApiEnvelope::new(StatusCode::OK, station_body(&data_time)),
+            )
+        });
+    let state = Arc::new(
+        ServerState::new(pool.clone(), "test-key".to_owned(), None, None)
+            .with_api_client(Arc::new(mock)),
+    );
+
+    // db (기본): 저장된 측정 시각과 같으므로 갱신 0, 경고 추가
+    std::env::remove_var("PM_LAST_SEEN_STORE");
+    let report = run_realtime_ingest(state.clone(), &FetchOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(report.advanced, Some(0));
+    assert!(report.data_frozen);
+    assert!(report.warnings.contains(&DATA_FROZEN_WARNING.to_owned()));
+    let body = build_response_body(report);
+    assert_eq!(body["meta"]["advancedCount"], 0);
+    assert_eq!(body["meta"]["dataFrozen"], true);
+
+    // 지정한 측정소만 수집하면 갱신이 없어도 정지로 보지 않음
+    let options = FetchOptions {
+        sub_region_ids: Some(vec![1, 2]),
+        ..Default::default()
+    };
+    let report = run_ingest(state.clone(), &options).await.unwrap();
+    assert_eq!(report.advanced, Some(0));
+    assert!(!report.data_frozen);
+
+    // 한 측정소라도 갱신되면 정지 아님
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "UPDATE {SCHEMA}.external_pm SET recorded_at = recorded_at - interval '1 hour'
+             WHERE sub_region_id = 1;"
+        ))
+        .await
+        .unwrap();
+    let report = run_ingest(state.clone(), &FetchOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(report.advanced, Some(1));
+    assert!(!report.data_frozen);
+
+    // memory: 첫 실행은 이전 값이 없어 모두 갱신, 같은 컨테이너의 다음 실행은 갱신 0
+    std::env::set_var("PM_LAST_SEEN_STORE", "memory");
+    let report = run_ingest(state.clone(), &FetchOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(report.advanced, Some(2));
+    assert!(!report.data_frozen);
+    let report = run_ingest(state.clone(), &FetchOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(report.advanced, Some(0));
+    assert!(report.data_frozen);
+
+    // off: 집계하지 않음
+    std::env::set_var("PM_LAST_SEEN_STORE", "off");
+    let report = run_ingest(state, &FetchOptions::default()).await.unwrap();
+    assert_eq!(report.advanced, None);
+    assert!(!report.data_frozen);
+    let body = build_response_body(report);
+    assert_eq!(body["meta"]["advancedCount"], serde_json::Value::Null);
+    assert_eq!(body["meta"]["dataFrozen"], false);
+    std::env::remove_var("PM_LAST_SEEN_STORE");
+}
@@ -0,0 +1,126 @@
+// tests/db_conn_env.rs
+
+// DB_CONN_URL 이 없을 때 PGHOST / PGPORT / PGUSER / PGPASSWORD / PGDATABASE 로 접속 정보를 만드는지,
+// 둘 다 있으면 DB_CONN_URL 이 우선하는지 확인 (접속 확인은 TEST_DATABASE_URL 필요)
+// 환경 변수를 바꾸므로 파일을 분리
+
+use environment_lambda::db_conn::DbConnConfig;
+use environment_lambda::effective_config::effective_config;
+use environment_lambda::state::initialize_state;
+
+const PG_ENV_VARS: [&str; 5] = ["PGHOST", "PGPORT", "PGUSER", "PGPASSWORD", "PGDATABASE"];
+
+fn clear_db_env() {
+    std::env::remove_var("DB_CONN_URL");
+    std::env::remove_var("DB_IAM_AUTH");
+    for name in PG_ENV_VARS {
+        std::env::remove_var(name);
+    }
+}
+
+fn set_pg_env() {
+    std::env::set_var("PGHOST", "db.internal");
+    std::env::set_var("PGUSER", "ingest");
+    std::env::set_var("PGPASSWORD", "pg-secret");
+    std::env::set_var("PGDATABASE", "environment");
+}
+
+// 환경 변수를 바꾸며 순서대로 실행해야 하므로 하나의 테스트로 구성
+#[tokio::test]
+async fn pg_env_vars_are_used_without_db_conn_url() {
+    // 둘 다 없으면 누락된 변수를 모두 나열 (빈 값도 누락)
+    clear_db_env();
+    std::env::set_var("PGUSER", "");
+    let e = DbConnConfig::from_env().unwrap_err().to_string();
+    assert!(
+        e.ends_with("(누락: PGHOST, PGUSER, PGPASSWORD, PGDATABASE)"),
+        "{}",
+        e
+    );
+    assert_eq!(effective_config()["resolved"]["dbAuth"], "missing");
+
+    // 일부만 있으면 남은 변수만 나열
+    std::env::set_var("PGHOST", "db.internal");
+    std::env::set_var("PGUSER", "ingest");
+    let e = DbConnConfig::from_env().unwrap_err().to_string();
+    assert!(e.ends_with("(누락: PGPASSWORD, PGDATABASE)"), "{}", e);
+
+    // PGPORT 생략 시 5432
+    set_pg_env();
+    match DbConnConfig::from_env().unwrap() {
+        DbConnConfig::Discrete {
+            host,
+            port,
+            user,
+            password,
+            dbname,
+        } => {
+            assert_eq!(host, "db.internal");
+            assert_eq!(port, 5432);
+            assert_eq!(user, "ingest");
+            assert_eq!(password, "pg-secret");
+            assert_eq!(dbname, "environment");
+        }
+        other => panic!("{:?}", other),
+    }
+    let config = effective_config();
+    assert_eq!(config["resolved"]["dbAuth"], "pg_env");
+    assert_eq!(config["env"]["PGHOST"], "db.internal");
+    assert_eq!(config["env"]["PGPASSWORD"], "***");
+
+    std::env::set_var("PGPORT", "6432");
+    assert!(matches!(
+        DbConnConfig::from_env().unwrap(),
+        DbConnConfig::Discrete { port: 6432, .. }
+    ));
+    std::env::set_var("PGPORT", "postgres");
+    let e = DbConnConfig::from_env().unwrap_err().to_string();
+    assert_eq!(e, "PGPORT 값이 올바른 포트 번호가 아님: postgres");
+
+    // DB_CONN_URL 이 있으면 PG* 변수보다 우선 (잘못된 PGPORT 도 보지 않음)
+    std::env::set_var("DB_CONN_URL", "postgres://u:p@h/d");
+    match DbConnConfig::from_env().unwrap() {
+        DbConnConfig::Url(url) => assert_eq!(url, "postgres://u:p@h/d"),
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(effective_config()["resolved"]["dbAuth"], "url");
+    std::env::set_var("DB_CONN_URL", "");
+    assert_eq!(DbConnConfig::from_env().unwrap_err().to_string(), e);
+
+    // PG* 변수로 만든 설정으로 실제 접속
+    clear_db_env();
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        return;
+    };
+    let parsed: tokio_postgres::Config = url.parse().unwrap();
+    let tokio_postgres::config::Host::Tcp(host) = &parsed.get_hosts()[0] else {
+        panic!("TCP 호스트가 아님: {}", url);
+    };
+    std::env::set_var("PGHOST", host);
+    std::env::set_var("PGPORT", parsed.get_ports()[0].to_string());
+    std::env::set_var("PGUSER", parsed.get_user().unwrap());
+    let password = parsed
+        .get_password()
+        .map(|p| String::from_utf8(p.to_vec()).unwrap())
+        .unwrap_or_else(|| "unused".to_owned());
+    std::env::set_var("PGPASSWORD", password);
+    std::env::set_var("PGDATABASE", parsed.get_dbname().unwrap_or("postgres"));
+    std::env::set_var("DB_POOL_WARM_CONNECTIONS", "0");
+
+    let db_conn = DbConnConfig::from_env().unwrap();
+    assert_eq!(db_conn.source(), "pg_env");
+    let state = initialize_state(&db_conn, "test-key", None, None)
+        .await
+        .unwrap();
+    let one: i32 = state
+        .pool
+        .get()
+        .await
+        .unwrap()
+        .query_one("SELECT 1", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(one, 1);
+    clear_db_env();
+}
@@ -0,0 +1,112 @@
+// tests/db_read_only.rs
+
+// upsert 가 읽기 전용 DB 오류(SQLSTATE 25006)로 실패하면 실행이 조회 전용으로 바뀌어
+// 측정소별 오류 없이 조회 결과를 반환하고 meta.outcome 이 DB_READ_ONLY 가 되는지 확인 (TEST_DATABASE_URL 필요)
+// sql() 의 전역 스키마를 쓰므로 파일을 분리
+
+mod common;
+
+use chrono::Utc;
+use environment_lambda::db_schema;
+use environment_lambda::handler::{
+    build_response_body, run_ingest, FetchOptions, StationStatus, OUTCOME_DB_READ_ONLY,
+};
+use environment_lambda::inline_stations::InlineStation;
+use environment_lambda::provider::{ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::Arc;
+
+const SCHEMA: &str = "test_db_read_only";
+
+fn station_body() -> String {
+    let data_time = Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+            },
+        }
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn read_only_database_switches_the_run_to_fetch_only() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+    common::fresh_schema(&pool, SCHEMA).await;
+    // 페일오버 중 읽기 전용 엔드포인트에 접속된 상황: 모든 upsert 가 25006 으로 실패
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "CREATE FUNCTION {SCHEMA}.read_only() RETURNS trigger AS $$
+             BEGIN
+                 RAISE EXCEPTION 'cannot execute INSERT in a read-only transaction'
+                     USING ERRCODE = '25006';
+             END $$ LANGUAGE plpgsql;
+             CREATE TRIGGER read_only BEFORE INSERT ON {SCHEMA}.external_pm
+                 FOR EACH ROW EXECUTE FUNCTION {SCHEMA}.read_only();"
+        ))
+        .await
+        .unwrap();
+
+    let stations = ["중구", "종로구", "용산구"];
+    let mock = stations.iter().fold(MockApiClient::new(), |mock, station| {
+        mock.with_envelope(station, ApiEnvelope::new(StatusCode::OK, station_body()))
+    });
+    let state = Arc::new(
+        ServerState::new(pool.clone(), "test-key".to_owned(), None, None)
+            .with_api_client(Arc::new(mock)),
+    );
+    let options = FetchOptions {
+        inline_stations: Some(
+            stations
+                .iter()
+                .enumerate()
+                .map(|(index, station)| InlineStation {
+                    sub_region_id: index as i32 + 1,
+                    pm_station: station.to_string(),
+                })
+                .collect(),
+        ),
+        ..Default::default()
+    };
+
+    let report = run_ingest(state, &options).await.unwrap();
+    assert!(report.db_read_only);
+    // 저장하지 못해도 측정소별 실패가 아닌 조회 결과로 반환
+    for result in &report.results {
+        let StationStatus::Success(data) = &result.status else {
+            panic!("{:?}", result.status);
+        };
+        assert_eq!(data["pm10Value"], 42.0);
+        assert_eq!(data["subRegionId"], result.sub_region_id);
+    }
+
+    let body = build_response_body(report);
+    assert_eq!(body["meta"]["outcome"], OUTCOME_DB_READ_ONLY);
+    assert_eq!(body["data"].as_array().unwrap().len(), 3);
+    assert_eq!(body["meta"]["errors"], json!([]));
+
+    let stored: i64 = pool
+        .get()
+        .await
+        .unwrap()
+        .query_one(&format!("SELECT count(*) FROM {SCHEMA}.external_pm"), &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(stored, 0);
+}
@@ -0,0 +1,22 @@
+// tests/db_schema_env.rs
+
+// PM_DB_SCHEMA 로 지정한 스키마가 sql() 로 만든 쿼리에 들어가는지 확인
+// sql() 의 스키마는 프로세스 전역 값이므로 파일을 분리
+
+use environment_lambda::db_schema::{self, sql};
+use environment_lambda::handler::GET_ALL_SUB_REGION_ID_AND_PM_STATION_QUERY;
+
+#[test]
+fn sql_uses_the_schema_from_pm_db_schema() {
+    std::env::set_var("PM_DB_SCHEMA", "v3_staging");
+    assert_eq!(db_schema::init_from_env().unwrap(), "v3_staging");
+
+    let query = sql(GET_ALL_SUB_REGION_ID_AND_PM_STATION_QUERY);
+    assert!(query.contains("FROM v3_staging.sub_region"), "{}", query);
+    assert!(!query.contains("{schema}"));
+    assert!(!query.contains("v3.sub_region"));
+
+    // 초기화 후에는 환경 변수가 바뀌어도 고정된 스키마 사용
+    std::env::set_var("PM_DB_SCHEMA", "v3");
+    assert_eq!(db_schema::db_schema(), "v3_staging");
+}
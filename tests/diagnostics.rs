@@ -0,0 +1,158 @@
+// tests/diagnostics.rs
+
+// diagnostics 실행에서 측정소별 상위 API 소요 시간 / 상태 코드 / 본문 크기가 느린 순서로 응답에 포함되는지 확인
+// (dry-run + payload 측정소 목록이므로 DB 불필요, PM_EMF_NAMESPACE 환경 변수를 쓰므로 파일을 분리)
+
+use environment_lambda::diagnostics::{self, emf_namespace};
+use environment_lambda::handler::{build_response_body, run_ingest, FetchOptions};
+use environment_lambda::inline_stations::InlineStation;
+use environment_lambda::provider::api_client::ApiFuture;
+use environment_lambda::provider::{ApiClient, ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+// ReqwestApiClient 처럼 응답을 diagnostics 에 기록하고, 측정소마다 정해진 시간만큼 늦게 응답하는 ApiClient
+struct SlowApiClient {
+    inner: MockApiClient,
+}
+
+impl SlowApiClient {
+    fn delay(pm_station: &str) -> Duration {
+        match pm_station {
+            "종로구" => Duration::from_millis(200),
+            "용산구" => Duration::from_millis(100),
+            _ => Duration::ZERO,
+        }
+    }
+}
+
+impl ApiClient for SlowApiClient {
+    fn fetch_station<'a>(
+        &'a self,
+        pm_station: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        Box::pin(async move {
+            tokio::time::sleep(Self::delay(pm_station)).await;
+            let envelope = self.inner.fetch_station(pm_station, params).await?;
+            diagnostics::record_response(envelope.status.as_u16(), envelope.body.len());
+            Ok(envelope)
+        })
+    }
+
+    fn fetch_province<'a>(
+        &'a self,
+        sido_name: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.inner.fetch_province(sido_name, params)
+    }
+
+    fn fetch_weather<'a>(&'a self, grid: &'a str, params: &'a [(String, String)]) -> ApiFuture<'a> {
+        self.inner.fetch_weather(grid, params)
+    }
+
+    fn fetch_nearby_station<'a>(
+        &'a self,
+        point: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.inner.fetch_nearby_station(point, params)
+    }
+}
+
+fn station_body() -> String {
+    let data_time = chrono::Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+            },
+        }
+    })
+    .to_string()
+}
+
+// 접속하지 않는 풀 (dry-run + payload 측정소 목록은 DB 에 접근하지 않음)
+fn unused_pool() -> deadpool_postgres::Pool {
+    deadpool_postgres::Config {
+        url: Some("postgres://unused@127.0.0.1:1/unused".to_owned()),
+        ..Default::default()
+    }
+    .create_pool(
+        Some(deadpool_postgres::Runtime::Tokio1),
+        tokio_postgres::NoTls,
+    )
+    .unwrap()
+}
+
+fn options(diagnostics: bool) -> FetchOptions {
+    FetchOptions {
+        dry_run: true,
+        diagnostics,
+        inline_stations: Some(
+            ["중구", "종로구", "용산구"]
+                .iter()
+                .enumerate()
+                .map(|(index, station)| InlineStation {
+                    sub_region_id: index as i32 + 1,
+                    pm_station: station.to_string(),
+                })
+                .collect(),
+        ),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn emf_namespace_reads_env() {
+    std::env::remove_var("PM_EMF_NAMESPACE");
+    assert_eq!(emf_namespace(), "ExternalPm");
+    std::env::set_var("PM_EMF_NAMESPACE", "Staging/ExternalPm");
+    assert_eq!(emf_namespace(), "Staging/ExternalPm");
+    std::env::set_var("PM_EMF_NAMESPACE", "");
+    assert_eq!(emf_namespace(), "ExternalPm");
+    std::env::remove_var("PM_EMF_NAMESPACE");
+}
+
+#[tokio::test]
+async fn diagnostics_list_stations_slowest_first() {
+    let body = station_body();
+    let mock = ["중구", "종로구", "용산구"]
+        .iter()
+        .fold(MockApiClient::new(), |mock, station| {
+            mock.with_envelope(station, ApiEnvelope::new(StatusCode::OK, body.clone()))
+        });
+    let state = Arc::new(
+        ServerState::new(unused_pool(), "test-key".to_owned(), None, None)
+            .with_api_client(Arc::new(SlowApiClient { inner: mock })),
+    );
+
+    let report = run_ingest(state.clone(), &options(true)).await.unwrap();
+    let response = build_response_body(report);
+    let diagnostics = response["diagnostics"].as_array().unwrap();
+    let order: Vec<&str> = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic["stationName"].as_str().unwrap())
+        .collect();
+    assert_eq!(order, vec!["종로구", "용산구", "중구"]);
+    assert!(diagnostics[0]["elapsedMs"].as_u64().unwrap() >= 200);
+    for diagnostic in diagnostics {
+        assert_eq!(diagnostic["status"], 200);
+        assert_eq!(diagnostic["contentLength"], body.len());
+    }
+
+    // diagnostics 가 아니면 응답에 포함하지 않음
+    let report = run_ingest(state, &options(false)).await.unwrap();
+    assert!(report.diagnostics.is_none());
+    assert!(build_response_body(report).get("diagnostics").is_none());
+}
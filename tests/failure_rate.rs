@@ -0,0 +1,148 @@
+// tests/failure_rate.rs
+
+// FAIL_RUN_ABOVE_FAILURE_RATE 를 넘는 실행이 200 응답 대신 호출 실패(Err)로 끝나고
+// 그 실행만 WEBHOOK_URL 로 알림이 가는지 handle_event 로 확인
+// (TEST_DATABASE_URL 필요, shared_state_from_env 의 전역 상태 / 환경 변수를 쓰므로 파일을 분리)
+
+mod common;
+
+use environment_lambda::handler::handle_event;
+use environment_lambda::invocation::InvocationInfo;
+use serde_json::json;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::sync::{Arc, Mutex};
+
+const SCHEMA: &str = "test_failure_rate";
+
+// 디버그 빌드에서는 handle_event 의 poll 호출 깊이가 테스트 스레드 기본 스택(2 MiB)을 넘으므로 별도 스레드에서 실행
+fn run_with_large_stack<F>(future: F) -> F::Output
+where
+    F: std::future::Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    std::thread::Builder::new()
+        .stack_size(16 * 1024 * 1024)
+        .spawn(move || {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(future)
+        })
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+// 받은 요청 본문을 기록하고 200 으로 응답하는 웹훅 서버
+// (handle_event 가 테스트 스레드를 막고 실행되므로 tokio 가 아닌 별도 스레드에서 처리)
+fn webhook_server() -> (String, Arc<Mutex<Vec<serde_json::Value>>>) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let bodies = Arc::new(Mutex::new(Vec::new()));
+    let recorded = bodies.clone();
+    std::thread::spawn(move || {
+        for socket in listener.incoming() {
+            let mut socket = socket.unwrap();
+            let mut reader = BufReader::new(socket.try_clone().unwrap());
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0u8; content_length];
+            reader.read_exact(&mut body).unwrap();
+            recorded
+                .lock()
+                .unwrap()
+                .push(serde_json::from_slice(&body).unwrap());
+            let _ = socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        }
+    });
+    (url, bodies)
+}
+
+// 수집 마감이 이미 지난 호출: 외부 API 없이 두 측정소 모두 TIMEOUT 실패 (실패율 1.0)
+fn invoke_all_failing() -> Result<serde_json::Value, environment_lambda::handler::Error> {
+    let invocation = InvocationInfo {
+        aws_request_id: "aws-1".to_owned(),
+        invoked_function_arn: String::new(),
+        function_version: "$LATEST".to_owned(),
+        deadline_millis: 0,
+        remaining: Some(std::time::Duration::from_secs(1)),
+        deadline: Some(tokio::time::Instant::now()),
+    };
+    run_with_large_stack(handle_event(
+        "run-1".to_owned(),
+        "req-1".to_owned(),
+        json!({
+            "force": true,
+            "dryRun": true,
+            "stations": [
+                { "subRegionId": 1, "pmStation": "중구" },
+                { "subRegionId": 2, "pmStation": "종로구" },
+            ],
+        }),
+        invocation,
+    ))
+}
+
+#[tokio::test]
+async fn run_fails_only_above_the_threshold() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    common::fresh_schema(&pool, SCHEMA).await;
+    std::env::set_var("DB_CONN_URL", std::env::var("TEST_DATABASE_URL").unwrap());
+    std::env::set_var("AIR_QUALITY_API_KEY", "test-key");
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    let (webhook_url, webhook_bodies) = webhook_server();
+    std::env::set_var("WEBHOOK_URL", webhook_url);
+
+    // 미설정: 실패율과 무관하게 200
+    std::env::remove_var("FAIL_RUN_ABOVE_FAILURE_RATE");
+    let response = invoke_all_failing().unwrap();
+    assert_eq!(response["statusCode"], 200);
+
+    // 임계값과 같으면 초과가 아니므로 200
+    std::env::set_var("FAIL_RUN_ABOVE_FAILURE_RATE", "1.0");
+    let response = invoke_all_failing().unwrap();
+    assert_eq!(response["statusCode"], 200);
+
+    std::env::set_var("FAIL_RUN_ABOVE_FAILURE_RATE", "0.5");
+    let e = invoke_all_failing().unwrap_err();
+    let message = e.to_string();
+    assert!(
+        message.starts_with("req-1 : Failure rate above threshold (0.5)"),
+        "{}",
+        message
+    );
+    assert!(message.contains("succeeded 0, failed 2"), "{}", message);
+
+    // 200 으로 끝난 실행은 알리지 않고, 실패율 초과 실행만 한 번 알림
+    let bodies = webhook_bodies.lock().unwrap().clone();
+    assert_eq!(bodies.len(), 1);
+    assert_eq!(
+        bodies[0]["text"],
+        "PM ingest FAILURE_RATE_EXCEEDED : failure rate 1.000 above threshold 0.5"
+    );
+    assert_eq!(bodies[0]["blocks"][1]["fields"][1]["text"], "*Failed*\n2");
+    assert_eq!(
+        bodies[0]["blocks"][1]["fields"][2]["text"],
+        "*Top errors*\nTIMEOUT (2)"
+    );
+    assert_eq!(
+        bodies[0]["blocks"][1]["fields"][3]["text"],
+        "*Run*\nrun-1 / req-1"
+    );
+
+    std::env::remove_var("FAIL_RUN_ABOVE_FAILURE_RATE");
+    std::env::remove_var("WEBHOOK_URL");
+}
@@ -0,0 +1,241 @@
+// tests/fallback_replay.rs
+
+// PM_FALLBACK_SINK=file: 재시도 후에도 저장하지 못한 측정값이 파일에 남고, mode "replay" 로 다시 저장되는지 확인
+// - 일시적인 DB 오류만 보관하고 데이터 오류(제약 조건 위반)는 보관하지 않음
+// - replay 는 저장된 값이 더 최신이면 갱신하지 않고, 다시 실패한 줄만 파일에 남김 (TEST_DATABASE_URL 필요)
+// sql() 의 전역 스키마와 PM_FALLBACK_* 환경 변수를 쓰므로 파일을 분리
+
+mod common;
+
+use chrono::Utc;
+use environment_lambda::db_schema;
+use environment_lambda::failure::FailureKind;
+use environment_lambda::fallback::{run_replay, FallbackRecord, FallbackSink};
+use environment_lambda::handler::{run_ingest, FetchOptions, StationStatus};
+use environment_lambda::inline_stations::InlineStation;
+use environment_lambda::provider::{ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::Arc;
+
+const SCHEMA: &str = "test_fallback_replay";
+
+fn station_body() -> String {
+    let data_time = Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+            },
+        }
+    })
+    .to_string()
+}
+
+#[test]
+fn fallback_sink_reads_env() {
+    std::env::remove_var("PM_FALLBACK_SINK");
+    std::env::remove_var("PM_FALLBACK_FILE");
+    std::env::remove_var("PM_FALLBACK_QUEUE_URL");
+    assert!(FallbackSink::from_env().unwrap().is_none());
+
+    std::env::set_var("PM_FALLBACK_SINK", "file");
+    assert!(matches!(
+        FallbackSink::from_env().unwrap(),
+        Some(FallbackSink::File { path, .. }) if path.to_str() == Some("/tmp/pm_unwritten.ndjson")
+    ));
+    std::env::set_var("PM_FALLBACK_FILE", "/tmp/other.ndjson");
+    assert!(matches!(
+        FallbackSink::from_env().unwrap(),
+        Some(FallbackSink::File { path, .. }) if path.to_str() == Some("/tmp/other.ndjson")
+    ));
+
+    // sqs 는 큐 URL 필수
+    std::env::set_var("PM_FALLBACK_SINK", "sqs");
+    assert!(FallbackSink::from_env().is_err());
+    std::env::set_var("PM_FALLBACK_QUEUE_URL", "https://sqs.example/queue");
+    assert!(matches!(
+        FallbackSink::from_env().unwrap(),
+        Some(FallbackSink::Sqs { queue_url, .. }) if queue_url == "https://sqs.example/queue"
+    ));
+
+    std::env::set_var("PM_FALLBACK_SINK", "s3");
+    assert!(FallbackSink::from_env().is_err());
+
+    std::env::remove_var("PM_FALLBACK_SINK");
+    std::env::remove_var("PM_FALLBACK_FILE");
+    std::env::remove_var("PM_FALLBACK_QUEUE_URL");
+}
+
+// 보관 → 재저장 → 재실패 순서로 같은 파일을 쓰므로 하나의 테스트로 구성
+#[tokio::test]
+async fn unwritten_readings_are_buffered_and_replayed() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+    common::fresh_schema(&pool, SCHEMA).await;
+    let path = std::env::temp_dir().join("test_fallback_replay.ndjson");
+    let _ = std::fs::remove_file(&path);
+
+    // 1 은 일시적인 DB 오류(P0001), 2 는 제약 조건 위반(23505)으로 저장 실패
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "CREATE FUNCTION {SCHEMA}.reject() RETURNS trigger AS $$
+             BEGIN
+                 IF NEW.sub_region_id = 1 THEN
+                     RAISE EXCEPTION 'temporarily unavailable';
+                 ELSIF NEW.sub_region_id = 2 THEN
+                     RAISE EXCEPTION 'duplicate' USING ERRCODE = '23505';
+                 END IF;
+                 RETURN NEW;
+             END $$ LANGUAGE plpgsql;
+             CREATE TRIGGER reject BEFORE INSERT ON {SCHEMA}.external_pm
+                 FOR EACH ROW EXECUTE FUNCTION {SCHEMA}.reject();"
+        ))
+        .await
+        .unwrap();
+
+    let stations = ["중구", "종로구", "용산구"];
+    let mock = stations.iter().fold(MockApiClient::new(), |mock, station| {
+        mock.with_envelope(station, ApiEnvelope::new(StatusCode::OK, station_body()))
+    });
+    let state = Arc::new(
+        ServerState::new(pool.clone(), "test-key".to_owned(), None, None)
+            .with_api_client(Arc::new(mock))
+            .with_fallback(Some(FallbackSink::file(&path))),
+    );
+    let options = FetchOptions {
+        inline_stations: Some(
+            stations
+                .iter()
+                .enumerate()
+                .map(|(index, station)| InlineStation {
+                    sub_region_id: index as i32 + 1,
+                    pm_station: station.to_string(),
+                })
+                .collect(),
+        ),
+        ..Default::default()
+    };
+
+    let report = run_ingest(state.clone(), &options).await.unwrap();
+    let mut outcomes: Vec<_> = report
+        .results
+        .iter()
+        .map(|result| (result.sub_region_id, result.failure_kind(), result.buffered))
+        .collect();
+    outcomes.sort_by_key(|(sub_region_id, _, _)| *sub_region_id);
+    assert_eq!(
+        outcomes,
+        vec![
+            (1, Some(FailureKind::DbQuery), true),
+            (2, Some(FailureKind::DbUniqueViolation), false),
+            (3, None, false),
+        ]
+    );
+
+    // 보관된 것은 1 뿐
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let records: Vec<FallbackRecord> = contents
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].sub_region_id, 1);
+    assert_eq!(records[0].pm10, Some(42.0));
+    assert!(records[0].reason.contains("temporarily unavailable"));
+
+    // 3 의 과거 측정값과 읽을 수 없는 줄도 추가
+    let stale = FallbackRecord {
+        sub_region_id: 3,
+        pm_station: "용산구".to_owned(),
+        pm10: Some(1.0),
+        pm25: Some(1.0),
+        recorded_at: Utc::now() - chrono::Duration::days(1),
+        reason: "pool timeout".to_owned(),
+    };
+    std::fs::write(
+        &path,
+        format!(
+            "{}{}\nnot json\n",
+            contents,
+            serde_json::to_string(&stale).unwrap()
+        ),
+    )
+    .unwrap();
+
+    // DB 복구 후 replay
+    let db_client = pool.get().await.unwrap();
+    db_client
+        .batch_execute(&format!("DROP TRIGGER reject ON {SCHEMA}.external_pm;"))
+        .await
+        .unwrap();
+    let report = run_replay(state.clone()).await.unwrap();
+    let mut replayed: Vec<_> = report
+        .results
+        .iter()
+        .map(|result| match &result.status {
+            StationStatus::Success(data) => (result.sub_region_id, data["updated"].clone()),
+            StationStatus::Failed { kind, .. } => (result.sub_region_id, json!(kind.as_str())),
+        })
+        .collect();
+    replayed.sort_by_key(|(sub_region_id, _)| *sub_region_id);
+    // 3 은 저장된 값이 더 최신이라 갱신하지 않음, 읽을 수 없는 줄은 PARSE 실패로 버림
+    assert_eq!(
+        replayed,
+        vec![(0, json!("PARSE")), (1, json!(true)), (3, json!(false))]
+    );
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+    let stored: Vec<(i32, f64)> = db_client
+        .query(
+            &format!("SELECT sub_region_id, pm10 FROM {SCHEMA}.external_pm ORDER BY 1"),
+            &[],
+        )
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect();
+    assert_eq!(stored, vec![(1, 42.0), (3, 42.0)]);
+
+    // 다시 실패한 레코드는 다음 replay 를 위해 파일에 남음
+    db_client
+        .batch_execute(&format!(
+            "CREATE TRIGGER reject BEFORE INSERT ON {SCHEMA}.external_pm
+                 FOR EACH ROW EXECUTE FUNCTION {SCHEMA}.reject();"
+        ))
+        .await
+        .unwrap();
+    drop(db_client);
+    let line = serde_json::to_string(&FallbackRecord {
+        sub_region_id: 2,
+        ..stale
+    })
+    .unwrap();
+    std::fs::write(&path, format!("{}\n", line)).unwrap();
+    let report = run_replay(state).await.unwrap();
+    assert_eq!(
+        report.results[0].failure_kind(),
+        Some(FailureKind::DbUniqueViolation)
+    );
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        format!("{}\n", line)
+    );
+
+    // 파일 보관을 쓰지 않으면 replay 할 수 없음
+    let state = Arc::new(ServerState::new(pool, "test-key".to_owned(), None, None));
+    assert!(run_replay(state).await.is_err());
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,76 @@
+// tests/handler_idempotency.rs
+
+// 잘못된 payload(400)가 멱등성 키를 잡아 두지 않는지 handle_event 로 확인 (TEST_DATABASE_URL 필요)
+// shared_state_from_env 의 전역 상태 / sql() 의 전역 스키마를 쓰므로 파일을 분리
+
+mod common;
+
+use environment_lambda::handler::handle_event;
+use environment_lambda::idempotency::{self, Claim};
+use environment_lambda::invocation::InvocationInfo;
+use serde_json::json;
+use std::time::Duration;
+
+const SCHEMA: &str = "test_handler_idempotency";
+
+#[tokio::test]
+async fn rejected_event_is_not_claimed() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    std::env::set_var("DB_CONN_URL", std::env::var("TEST_DATABASE_URL").unwrap());
+    std::env::set_var("AIR_QUALITY_API_KEY", "test-key");
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "DROP SCHEMA IF EXISTS {SCHEMA} CASCADE; CREATE SCHEMA {SCHEMA};
+             CREATE TABLE {SCHEMA}.ingest_idempotency (
+                 event_id text PRIMARY KEY,
+                 processed_at timestamptz NOT NULL,
+                 summary jsonb
+             );"
+        ))
+        .await
+        .unwrap();
+
+    let invocation = InvocationInfo {
+        aws_request_id: "aws-1".to_owned(),
+        invoked_function_arn: String::new(),
+        function_version: "$LATEST".to_owned(),
+        deadline_millis: 0,
+        remaining: None,
+        deadline: None,
+    };
+    let response = handle_event(
+        "run-1".to_owned(),
+        "req-1".to_owned(),
+        json!({ "id": "evt-bad", "resetNodata": "all" }),
+        invocation,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response["statusCode"], 400);
+    assert_eq!(response["body"]["meta"]["requestId"], "req-1");
+
+    // 키가 남지 않았으므로 고친 재전송은 중복이 아니라 새 이벤트로 처리
+    let stored: i64 = pool
+        .get()
+        .await
+        .unwrap()
+        .query_one(
+            &format!("SELECT count(*) FROM {SCHEMA}.ingest_idempotency"),
+            &[],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(stored, 0);
+    assert!(matches!(
+        idempotency::claim(&pool, "evt-bad", Duration::from_secs(300))
+            .await
+            .unwrap(),
+        Claim::New
+    ));
+}
@@ -0,0 +1,97 @@
+// tests/http_trigger.rs
+
+// Function URL / EventBridge 예약 / 직접 호출 payload 가 handle_event 에서 각자의 경로로 처리되는지 확인
+// TRIGGER_SECRET / DB_CONN_URL 등 프로세스 전역 환경 변수를 바꾸므로 파일을 분리
+
+mod common;
+
+use environment_lambda::handler::handle_event;
+use environment_lambda::invocation::InvocationInfo;
+use environment_lambda::trigger::{SCHEDULED_EVENT_DETAIL_TYPE, SCHEDULED_EVENT_SOURCE};
+use serde_json::json;
+
+fn invocation() -> InvocationInfo {
+    InvocationInfo {
+        aws_request_id: "aws-1".to_owned(),
+        invoked_function_arn: String::new(),
+        function_version: "$LATEST".to_owned(),
+        deadline_millis: 0,
+        remaining: None,
+        deadline: None,
+    }
+}
+
+fn function_url_event(secret: Option<&str>, query: serde_json::Value) -> serde_json::Value {
+    let headers = match secret {
+        Some(secret) => json!({ "x-trigger-secret": secret }),
+        None => json!({}),
+    };
+    json!({
+        "requestContext": { "http": { "method": "POST" } },
+        "headers": headers,
+        "queryStringParameters": query,
+    })
+}
+
+async fn invoke(payload: serde_json::Value) -> serde_json::Value {
+    handle_event(
+        "run-1".to_owned(),
+        "req-1".to_owned(),
+        payload,
+        invocation(),
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn each_payload_shape_takes_its_own_path() {
+    // DB 설정이 없으므로 DB 에 접근하는 경로로 들어가면 호출 자체가 실패함
+    std::env::remove_var("DB_CONN_URL");
+    std::env::set_var("TRIGGER_SECRET", "s3cret");
+
+    // Function URL: 인증 헤더가 없거나 틀리면 DB/API 접근 없이 401 (프록시 통합 응답 형식)
+    for secret in [None, Some("wrong"), Some("s3cret-but-longer")] {
+        let response = invoke(function_url_event(secret, json!({ "sub_region_id": "1" }))).await;
+        assert_eq!(response["statusCode"], 401, "{:?}", secret);
+        assert_eq!(response["headers"]["content-type"], "application/json");
+        assert!(response["body"].is_string());
+    }
+
+    // EventBridge 예약 이벤트: detail 이 수집 옵션이므로 config: show 가 그대로 적용됨
+    let scheduled = json!({
+        "id": "evt-1",
+        "detail-type": SCHEDULED_EVENT_DETAIL_TYPE,
+        "source": SCHEDULED_EVENT_SOURCE,
+        "time": "2024-03-01T00:00:00Z",
+        "detail": { "config": "show" },
+    });
+    let response = invoke(scheduled).await;
+    assert_eq!(response["statusCode"], 200);
+    assert_eq!(response["body"]["env"]["TRIGGER_SECRET"], "***");
+
+    // 직접 호출: payload 전체가 수집 옵션
+    let response = invoke(json!({ "config": "show" })).await;
+    assert_eq!(response["statusCode"], 200);
+
+    // TRIGGER_SECRET 미설정이면 모든 Function URL 요청 거부
+    std::env::remove_var("TRIGGER_SECRET");
+    let response = invoke(function_url_event(
+        Some(""),
+        json!({ "sub_region_id": "1" }),
+    ))
+    .await;
+    assert_eq!(response["statusCode"], 401);
+
+    // 인증을 통과하면 쿼리 스트링을 확인 (sub_region_id 가 없거나 숫자가 아니면 400)
+    let Some(_pool) = common::test_pool() else {
+        return;
+    };
+    std::env::set_var("TRIGGER_SECRET", "s3cret");
+    std::env::set_var("DB_CONN_URL", std::env::var("TEST_DATABASE_URL").unwrap());
+    std::env::set_var("AIR_QUALITY_API_KEY", "test-key");
+    for query in [json!(null), json!({ "sub_region_id": "abc" })] {
+        let response = invoke(function_url_event(Some("s3cret"), query.clone())).await;
+        assert_eq!(response["statusCode"], 400, "{}", query);
+    }
+}
@@ -0,0 +1,131 @@
+// tests/idempotency_replay.rs
+
+// 같은 이벤트가 TTL 안에 다시 오면 저장된 실행 요약을 그대로 돌려주는지 확인 (TEST_DATABASE_URL 필요)
+// sql() 이 쓰는 전역 스키마를 이 테스트 전용 스키마로 고정하므로 파일을 분리
+
+mod common;
+
+use deadpool_postgres::Pool;
+use environment_lambda::db_schema;
+use environment_lambda::idempotency::{self, Claim};
+use futures::future::join_all;
+use serde_json::json;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+const SCHEMA: &str = "test_idempotency";
+
+#[test]
+fn payload_key_is_stable_sha256() {
+    let payload = json!({ "mode": "realtime", "subRegionIds": [1, 2] });
+    let key = idempotency::idempotency_key(&payload);
+    assert_eq!(key, idempotency::idempotency_key(&payload.clone()));
+    assert_eq!(key.len(), "payload:".len() + 64);
+    assert!(key.starts_with("payload:"));
+
+    assert_eq!(
+        idempotency::idempotency_key(&json!({ "id": "evt-1", "mode": "realtime" })),
+        "evt-1"
+    );
+}
+
+// 두 DB 테스트가 같은 스키마를 다시 만들므로 순서대로 실행
+static SCHEMA_LOCK: Mutex<()> = Mutex::const_new(());
+
+async fn reset_schema(pool: &Pool) {
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "DROP SCHEMA IF EXISTS {SCHEMA} CASCADE; CREATE SCHEMA {SCHEMA};
+             CREATE TABLE {SCHEMA}.ingest_idempotency (
+                 event_id text PRIMARY KEY,
+                 processed_at timestamptz NOT NULL,
+                 summary jsonb
+             );"
+        ))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn duplicate_event_replays_saved_summary() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    let _guard = SCHEMA_LOCK.lock().await;
+    reset_schema(&pool).await;
+    let client = pool.get().await.unwrap();
+
+    let key = idempotency::idempotency_key(&json!({ "id": "evt-replay" }));
+    let ttl = Duration::from_secs(300);
+    assert!(matches!(
+        idempotency::claim(&pool, &key, ttl).await.unwrap(),
+        Claim::New
+    ));
+
+    let summary = json!({ "runId": "run-1", "succeeded": 3 });
+    idempotency::save_summary(&pool, &key, &summary)
+        .await
+        .unwrap();
+    match idempotency::claim(&pool, &key, ttl).await.unwrap() {
+        Claim::Duplicate(replayed) => assert_eq!(replayed, Some(summary.clone())),
+        Claim::New => panic!("duplicate event must not be claimed again"),
+    }
+
+    // 요약은 테이블에도 남아 다른 컨테이너가 받은 중복 이벤트에도 반환됨
+    let stored: String = client
+        .query_one(
+            &format!("SELECT summary::text FROM {SCHEMA}.ingest_idempotency WHERE event_id = $1"),
+            &[&key],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&stored).unwrap(),
+        summary
+    );
+
+    // 실패한 실행이 키를 반환하면 재시도는 새 이벤트로 처리
+    idempotency::release(&pool, &key).await.unwrap();
+    assert!(matches!(
+        idempotency::claim(&pool, &key, ttl).await.unwrap(),
+        Claim::New
+    ));
+}
+
+#[tokio::test]
+async fn concurrent_claims_have_exactly_one_winner() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    let _guard = SCHEMA_LOCK.lock().await;
+    reset_schema(&pool).await;
+    let ttl = Duration::from_secs(300);
+
+    // 메모리 캐시는 DB 선점 후에만 채워지므로 동시에 보낸 요청은 모두 ON CONFLICT 로 판정
+    let claims = join_all((0..8).map(|_| idempotency::claim(&pool, "evt-concurrent", ttl))).await;
+    let new_claims = claims
+        .into_iter()
+        .filter(|claim| matches!(claim.as_ref().unwrap(), Claim::New))
+        .count();
+    assert_eq!(new_claims, 1);
+
+    // TTL 이 지난 행은 다른 컨테이너가 남긴 것이어도 다시 선점
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "INSERT INTO {SCHEMA}.ingest_idempotency (event_id, processed_at)
+             VALUES ('evt-expired', now() - interval '1 hour');"
+        ))
+        .await
+        .unwrap();
+    assert!(matches!(
+        idempotency::claim(&pool, "evt-expired", ttl).await.unwrap(),
+        Claim::New
+    ));
+}
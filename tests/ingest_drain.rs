@@ -0,0 +1,172 @@
+// tests/ingest_drain.rs
+
+// 내부 스케줄러 종료: 진행 중인 실행은 drain_timeout 안에 끝나면 결과를 남기고,
+// 넘기면 중단되며 실행 락도 풀리는지 확인 (TEST_DATABASE_URL 필요)
+// sql() 의 전역 스키마와 실행 락을 쓰므로 파일을 분리 (락 키가 하나뿐이라 한 테스트 안에서 순서대로 확인)
+
+mod common;
+
+use chrono::Utc;
+use environment_lambda::db_schema;
+use environment_lambda::provider::api_client::ApiFuture;
+use environment_lambda::provider::{ApiClient, ApiEnvelope, MockApiClient};
+use environment_lambda::run_lock;
+use environment_lambda::state::ServerState;
+use environment_lambda::ticker::{self, IngestTicker};
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const SCHEMA: &str = "test_ingest_drain";
+
+// delay 만큼 늦게 응답하는 ApiClient (조회가 시작되면 started 표시)
+struct SlowApiClient {
+    inner: MockApiClient,
+    delay: Duration,
+    started: AtomicBool,
+}
+
+impl ApiClient for SlowApiClient {
+    fn fetch_station<'a>(
+        &'a self,
+        pm_station: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.started.store(true, Ordering::SeqCst);
+        Box::pin(async move {
+            tokio::time::sleep(self.delay).await;
+            self.inner.fetch_station(pm_station, params).await
+        })
+    }
+
+    fn fetch_province<'a>(
+        &'a self,
+        sido_name: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.inner.fetch_province(sido_name, params)
+    }
+
+    fn fetch_weather<'a>(&'a self, grid: &'a str, params: &'a [(String, String)]) -> ApiFuture<'a> {
+        self.inner.fetch_weather(grid, params)
+    }
+
+    fn fetch_nearby_station<'a>(
+        &'a self,
+        point: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.inner.fetch_nearby_station(point, params)
+    }
+}
+
+fn station_body() -> String {
+    let data_time = Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+            },
+        }
+    })
+    .to_string()
+}
+
+// 첫 틱(즉시)의 조회가 시작된 뒤 종료를 요청하고, 종료까지 걸린 시간과 남은 결과를 반환
+async fn shutdown_mid_run(
+    pool: &deadpool_postgres::Pool,
+    delay: Duration,
+    drain_timeout: Duration,
+) -> (Duration, serde_json::Value) {
+    let client = Arc::new(SlowApiClient {
+        inner: MockApiClient::new()
+            .with_envelope("중구", ApiEnvelope::new(StatusCode::OK, station_body())),
+        delay,
+        started: AtomicBool::new(false),
+    });
+    let state = Arc::new(
+        ServerState::new(pool.clone(), "test-key".to_owned(), None, None)
+            .with_api_client(client.clone()),
+    );
+    let ingest_ticker = IngestTicker::spawn(state, Duration::from_secs(3600), drain_timeout);
+    let last_report = ingest_ticker.last_report();
+
+    let mut waited = 0;
+    while !client.started.load(Ordering::SeqCst) {
+        assert!(waited < 100, "ticker did not start a run");
+        waited += 1;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    let start = tokio::time::Instant::now();
+    ingest_ticker.shutdown().await;
+    (start.elapsed(), ticker::status_body(&last_report).await)
+}
+
+#[tokio::test]
+async fn shutdown_drains_or_cancels_the_in_flight_run() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+    common::fresh_schema(&pool, SCHEMA).await;
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "CREATE TABLE {SCHEMA}.sub_region (
+                 sub_region_id integer PRIMARY KEY,
+                 pm_station text,
+                 tm_x double precision,
+                 tm_y double precision,
+                 provider text,
+                 nx integer,
+                 ny integer,
+                 ingest_enabled boolean
+             );
+             INSERT INTO {SCHEMA}.sub_region (sub_region_id, pm_station) VALUES (1, '중구');"
+        ))
+        .await
+        .unwrap();
+
+    // drain_timeout 안에 끝나는 실행은 기다려서 결과를 남김
+    let (elapsed, body) =
+        shutdown_mid_run(&pool, Duration::from_millis(300), Duration::from_secs(10)).await;
+    assert_eq!(body["data"][0]["stationName"], "중구", "{}", body);
+    assert!(elapsed < Duration::from_secs(5), "{:?}", elapsed);
+
+    // drain_timeout 을 넘기면 실행을 중단하고 결과를 남기지 않음
+    let (elapsed, body) =
+        shutdown_mid_run(&pool, Duration::from_secs(30), Duration::from_millis(200)).await;
+    assert_eq!(body, json!(null));
+    assert!(elapsed < Duration::from_secs(5), "{:?}", elapsed);
+
+    // 중단된 실행의 락은 guard 가 커넥션을 닫아 해제 (같은 세션이 섞이지 않도록 별도 풀로 확인)
+    let other_pool = common::test_pool().unwrap();
+    let mut retried = 0;
+    let lock = loop {
+        match run_lock::acquire(other_pool.get().await.unwrap(), "run-after-drain").await {
+            Ok(lock) => break lock,
+            Err(e) if retried < 50 => {
+                assert!(
+                    e.downcast_ref::<run_lock::AlreadyRunningError>().is_some(),
+                    "{:?}",
+                    e
+                );
+                retried += 1;
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+            Err(e) => panic!("lock is still held after the drain timeout: {:?}", e),
+        }
+    };
+    lock.release().await;
+}
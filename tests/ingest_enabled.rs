@@ -0,0 +1,144 @@
+// tests/ingest_enabled.rs
+
+// sub_region.ingest_enabled = false 인 행은 수집에서 빠지고 meta.disabledSubRegions 로 집계되며,
+// includeDisabled 이면 함께 수집하고, ingest_enabled 컬럼이 없는 이전 스키마에서는 모두 수집하는지 확인
+// (TEST_DATABASE_URL 필요, sql() 의 전역 스키마를 쓰므로 파일을 분리)
+
+mod common;
+
+use chrono::Utc;
+use environment_lambda::db_schema;
+use environment_lambda::handler::{build_response_body, run_ingest, FetchOptions};
+use environment_lambda::provider::{ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use environment_lambda::sub_region_query::SubRegionQueries;
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::Arc;
+
+const SCHEMA: &str = "test_ingest_enabled";
+
+fn station_body() -> String {
+    let data_time = Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+            },
+        }
+    })
+    .to_string()
+}
+
+fn collected(report: &environment_lambda::handler::IngestReport) -> Vec<i32> {
+    let mut sub_region_ids: Vec<i32> = report
+        .results
+        .iter()
+        .map(|result| result.sub_region_id)
+        .collect();
+    sub_region_ids.sort();
+    sub_region_ids
+}
+
+// 컬럼이 있는 스키마 → 컬럼을 지운 이전 스키마 순서로 확인하므로 하나의 테스트로 구성
+#[tokio::test]
+async fn disabled_sub_regions_are_skipped_on_both_schema_versions() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+    common::fresh_schema(&pool, SCHEMA).await;
+    // 1 은 수집, 2 는 수집 중지, 3 은 미설정(NULL, 수집)
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "CREATE TABLE {SCHEMA}.sub_region (
+                 sub_region_id integer PRIMARY KEY,
+                 pm_station text,
+                 tm_x double precision,
+                 tm_y double precision,
+                 provider text,
+                 nx integer,
+                 ny integer,
+                 ingest_enabled boolean
+             );
+             INSERT INTO {SCHEMA}.sub_region (sub_region_id, pm_station, ingest_enabled) VALUES
+                 (1, '중구', true), (2, '종로구', false), (3, '용산구', NULL);"
+        ))
+        .await
+        .unwrap();
+
+    let mock = ["중구", "종로구", "용산구"]
+        .iter()
+        .fold(MockApiClient::new(), |mock, station| {
+            mock.with_envelope(station, ApiEnvelope::new(StatusCode::OK, station_body()))
+        });
+    let state = Arc::new(
+        ServerState::new(pool.clone(), "test-key".to_owned(), None, None)
+            .with_api_client(Arc::new(mock)),
+    );
+
+    let report = run_ingest(state.clone(), &FetchOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(collected(&report), vec![1, 3]);
+    assert_eq!(report.disabled_sub_regions, Some(1));
+    assert_eq!(build_response_body(report)["meta"]["disabledSubRegions"], 1);
+
+    // sub_region_id 로 지정해도 수집 중지된 행은 제외
+    let options = FetchOptions {
+        sub_region_ids: Some(vec![1, 2]),
+        ..Default::default()
+    };
+    let report = run_ingest(state.clone(), &options).await.unwrap();
+    assert_eq!(collected(&report), vec![1]);
+
+    // includeDisabled: 모두 수집하고 수집 중지 수는 확인하지 않음
+    let options = FetchOptions {
+        include_disabled: true,
+        ..Default::default()
+    };
+    let report = run_ingest(state.clone(), &options).await.unwrap();
+    assert_eq!(collected(&report), vec![1, 2, 3]);
+    assert_eq!(report.disabled_sub_regions, None);
+
+    // ingest_enabled 컬럼이 없는 이전 스키마: 필터 없이 모두 수집
+    let db_client = pool.get().await.unwrap();
+    db_client
+        .batch_execute(&format!(
+            "ALTER TABLE {SCHEMA}.sub_region DROP COLUMN ingest_enabled;"
+        ))
+        .await
+        .unwrap();
+    let queries = SubRegionQueries::default();
+    assert_eq!(
+        queries
+            .fetch_rows(&db_client, None, false)
+            .await
+            .unwrap()
+            .len(),
+        3
+    );
+    assert_eq!(
+        queries
+            .fetch_rows(&db_client, Some(&vec![2]), false)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(queries.count_disabled(&db_client).await, None);
+    drop(db_client);
+
+    let report = run_ingest(state, &FetchOptions::default()).await.unwrap();
+    assert_eq!(collected(&report), vec![1, 2, 3]);
+    assert_eq!(report.disabled_sub_regions, None);
+}
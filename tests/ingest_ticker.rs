@@ -0,0 +1,127 @@
+// tests/ingest_ticker.rs
+
+// 상시 실행(axum) 배포의 내부 스케줄러: 다른 실행이 락을 잡고 있으면 틱을 건너뛰고,
+// 락이 풀린 뒤의 틱 결과가 /ingest/status 본문으로 나오며, 종료 시 풀을 닫는지 확인 (TEST_DATABASE_URL 필요)
+// sql() 의 전역 스키마, 실행 락, INGEST_* 환경 변수를 쓰므로 파일을 분리
+
+mod common;
+
+use chrono::Utc;
+use environment_lambda::db_schema;
+use environment_lambda::provider::{ApiEnvelope, MockApiClient};
+use environment_lambda::run_lock;
+use environment_lambda::state::ServerState;
+use environment_lambda::ticker::{self, IngestTicker};
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+const SCHEMA: &str = "test_ingest_ticker";
+
+fn station_body() -> String {
+    let data_time = Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+            },
+        }
+    })
+    .to_string()
+}
+
+#[test]
+fn interval_and_drain_timeout_read_from_env() {
+    std::env::remove_var("INGEST_INTERVAL_SECS");
+    assert_eq!(ticker::interval_from_env(), None);
+    // 0 이면 내부 스케줄러 사용 안 함
+    std::env::set_var("INGEST_INTERVAL_SECS", "0");
+    assert_eq!(ticker::interval_from_env(), None);
+    std::env::set_var("INGEST_INTERVAL_SECS", "300");
+    assert_eq!(ticker::interval_from_env(), Some(Duration::from_secs(300)));
+    std::env::remove_var("INGEST_INTERVAL_SECS");
+
+    std::env::remove_var("INGEST_DRAIN_TIMEOUT_SECS");
+    assert_eq!(ticker::drain_timeout_from_env(), Duration::from_secs(25));
+    std::env::set_var("INGEST_DRAIN_TIMEOUT_SECS", "5");
+    assert_eq!(ticker::drain_timeout_from_env(), Duration::from_secs(5));
+    std::env::remove_var("INGEST_DRAIN_TIMEOUT_SECS");
+}
+
+#[tokio::test]
+async fn ticker_skips_locked_ticks_and_keeps_the_last_report() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+    common::fresh_schema(&pool, SCHEMA).await;
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "CREATE TABLE {SCHEMA}.sub_region (
+                 sub_region_id integer PRIMARY KEY,
+                 pm_station text,
+                 tm_x double precision,
+                 tm_y double precision,
+                 provider text,
+                 nx integer,
+                 ny integer,
+                 ingest_enabled boolean
+             );
+             INSERT INTO {SCHEMA}.sub_region (sub_region_id, pm_station) VALUES (1, '중구');"
+        ))
+        .await
+        .unwrap();
+
+    // 수동 실행이 락을 잡고 있는 상태에서 시작 (같은 세션은 락을 다시 잡을 수 있으므로 별도 풀 사용)
+    let manual_pool = common::test_pool().unwrap();
+    let manual = run_lock::acquire(manual_pool.get().await.unwrap(), "run-manual")
+        .await
+        .unwrap();
+
+    let mock = MockApiClient::new()
+        .with_envelope("중구", ApiEnvelope::new(StatusCode::OK, station_body()));
+    let state = Arc::new(
+        ServerState::new(pool.clone(), "test-key".to_owned(), None, None)
+            .with_api_client(Arc::new(mock)),
+    );
+    let ingest_ticker = IngestTicker::spawn(
+        state.clone(),
+        Duration::from_millis(200),
+        Duration::from_secs(5),
+    );
+    let last_report = ingest_ticker.last_report();
+
+    // 첫 틱은 ALREADY_RUNNING 으로 건너뛰므로 아직 결과 없음
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(ticker::status_body(&last_report).await, json!(null));
+
+    // 락이 풀리면 다음 틱에서 수집
+    manual.release().await;
+    let mut waited = 0;
+    let body = loop {
+        let body = ticker::status_body(&last_report).await;
+        if !body.is_null() {
+            break body;
+        }
+        assert!(waited < 50, "ticker did not record a report");
+        waited += 1;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    };
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+    assert_eq!(body["data"][0]["stationName"], "중구");
+    assert!(body["meta"]["runId"].is_string());
+
+    // 종료: 스케줄러를 멈춘 뒤 풀을 닫음
+    ticker::shutdown_and_close(Some(ingest_ticker), &state).await;
+    assert!(state.pool.is_closed());
+}
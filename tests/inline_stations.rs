@@ -0,0 +1,150 @@
+// tests/inline_stations.rs
+
+// payload 의 stations 목록으로 sub_region 행이 없는 DB 에 수집/저장하는지, 잘못된 목록은 수집 없이 400 인지 확인
+// (TEST_DATABASE_URL 필요, sql() 의 전역 스키마 / shared_state_from_env 의 전역 상태를 쓰므로 파일을 분리)
+
+mod common;
+
+use environment_lambda::db_schema;
+use environment_lambda::handler::{
+    build_response_body, handle_event, run_ingest, FetchOptions, StationStatus,
+};
+use environment_lambda::inline_stations::InlineStation;
+use environment_lambda::invocation::InvocationInfo;
+use environment_lambda::provider::{ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::Arc;
+
+const SCHEMA: &str = "test_inline_stations";
+
+// 디버그 빌드에서는 handle_event 의 poll 호출 깊이가 테스트 스레드 기본 스택(2 MiB)을 넘으므로 별도 스레드에서 실행
+fn run_with_large_stack<F>(future: F) -> F::Output
+where
+    F: std::future::Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    std::thread::Builder::new()
+        .stack_size(16 * 1024 * 1024)
+        .spawn(move || {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(future)
+        })
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+fn station_body() -> String {
+    let data_time = chrono::Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+            },
+        }
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn payload_stations_are_stored_without_sub_region_rows() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    common::fresh_schema(&pool, SCHEMA).await;
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "CREATE TABLE {SCHEMA}.sub_region (
+                 sub_region_id integer PRIMARY KEY,
+                 pm_station text NOT NULL,
+                 tm_x double precision,
+                 tm_y double precision,
+                 provider text,
+                 nx integer,
+                 ny integer,
+                 ingest_enabled boolean NOT NULL DEFAULT true
+             );"
+        ))
+        .await
+        .unwrap();
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+
+    let mock = MockApiClient::new()
+        .with_envelope("중구", ApiEnvelope::new(StatusCode::OK, station_body()));
+    let state = Arc::new(
+        ServerState::new(pool.clone(), "test-key".to_owned(), None, None)
+            .with_api_client(Arc::new(mock)),
+    );
+    let options = FetchOptions {
+        inline_stations: Some(vec![InlineStation {
+            sub_region_id: 12,
+            pm_station: "중구".to_owned(),
+        }]),
+        ..Default::default()
+    };
+    let report = run_ingest(state, &options).await.unwrap();
+    assert!(matches!(
+        report.results[0].status,
+        StationStatus::Success(_)
+    ));
+
+    let body = build_response_body(report);
+    assert_eq!(body["meta"]["stationListSource"], "payload");
+    // payload 목록은 sub_region 을 조회하지 않으므로 수집 중지 수도 확인하지 않음
+    assert_eq!(body["meta"]["disabledSubRegions"], json!(null));
+    assert!(body["meta"]["phases"].get("stationListQuery").is_none());
+
+    let stored: Vec<(i32, f64)> = pool
+        .get()
+        .await
+        .unwrap()
+        .query(
+            &format!("SELECT sub_region_id, pm10 FROM {SCHEMA}.external_pm"),
+            &[],
+        )
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect();
+    assert_eq!(stored, [(12, 42.0)]);
+
+    // 잘못된 목록: 수집 없이 400, 원인은 detail
+    std::env::set_var("DB_CONN_URL", std::env::var("TEST_DATABASE_URL").unwrap());
+    std::env::set_var("AIR_QUALITY_API_KEY", "test-key");
+    let invocation = InvocationInfo {
+        aws_request_id: "aws-1".to_owned(),
+        invoked_function_arn: String::new(),
+        function_version: "$LATEST".to_owned(),
+        deadline_millis: 0,
+        remaining: None,
+        deadline: None,
+    };
+    let response = run_with_large_stack(handle_event(
+        "run-1".to_owned(),
+        "req-1".to_owned(),
+        json!({ "stations": [{ "subRegionId": 12, "pmStation": "" }] }),
+        invocation,
+    ))
+    .unwrap();
+    assert_eq!(response["statusCode"], 400);
+    assert_eq!(
+        response["body"]["detail"],
+        "sub_region 12 : pmStation 이 비어 있음"
+    );
+    assert_eq!(response["body"]["meta"]["requestId"], "req-1");
+}
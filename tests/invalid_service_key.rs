@@ -0,0 +1,113 @@
+// tests/invalid_service_key.rs
+
+// 서비스 키 미등록(SERVICE_KEY_IS_NOT_REGISTERED_ERROR) 응답이 오면 남은 측정소를 조회하지 않고
+// 실행 전체가 단일 InvalidServiceKeyError 로 끝나는지 확인 (dry-run + payload 측정소 목록이므로 DB 불필요)
+// API_CONCURRENCY / MAX_IN_FLIGHT_TASKS 환경 변수로 측정소를 하나씩 조회하므로 파일을 분리
+
+use environment_lambda::handler::{run_ingest, FetchOptions, InvalidServiceKeyError};
+use environment_lambda::inline_stations::InlineStation;
+use environment_lambda::provider::airkorea::SERVICE_KEY_NOT_REGISTERED;
+use environment_lambda::provider::api_client::ApiFuture;
+use environment_lambda::provider::{ApiClient, ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use reqwest::StatusCode;
+use std::sync::{Arc, Mutex};
+
+// 요청한 측정소 이름을 기록하는 ApiClient
+struct RecordingApiClient {
+    inner: MockApiClient,
+    requested: Mutex<Vec<String>>,
+}
+
+impl ApiClient for RecordingApiClient {
+    fn fetch_station<'a>(
+        &'a self,
+        pm_station: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.requested.lock().unwrap().push(pm_station.to_owned());
+        self.inner.fetch_station(pm_station, params)
+    }
+
+    fn fetch_province<'a>(
+        &'a self,
+        sido_name: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.inner.fetch_province(sido_name, params)
+    }
+
+    fn fetch_weather<'a>(&'a self, grid: &'a str, params: &'a [(String, String)]) -> ApiFuture<'a> {
+        self.inner.fetch_weather(grid, params)
+    }
+
+    fn fetch_nearby_station<'a>(
+        &'a self,
+        point: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.inner.fetch_nearby_station(point, params)
+    }
+}
+
+// 접속하지 않는 풀 (dry-run + payload 측정소 목록은 DB 에 접근하지 않음)
+fn unused_pool() -> deadpool_postgres::Pool {
+    deadpool_postgres::Config {
+        url: Some("postgres://unused@127.0.0.1:1/unused".to_owned()),
+        ..Default::default()
+    }
+    .create_pool(
+        Some(deadpool_postgres::Runtime::Tokio1),
+        tokio_postgres::NoTls,
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn unregistered_service_key_stops_the_run_with_one_error() {
+    // 측정소를 하나씩 조회해야 첫 측정소 이후 요청이 없는지 확인할 수 있음
+    std::env::set_var("API_CONCURRENCY", "1");
+    std::env::set_var("MAX_IN_FLIGHT_TASKS", "1");
+
+    let client = Arc::new(RecordingApiClient {
+        inner: MockApiClient::new().with_envelope(
+            "중구",
+            ApiEnvelope::new(
+                StatusCode::OK,
+                format!(
+                    "<OpenAPI_ServiceResponse><returnAuthMsg>{}</returnAuthMsg></OpenAPI_ServiceResponse>",
+                    SERVICE_KEY_NOT_REGISTERED
+                ),
+            ),
+        ),
+        requested: Mutex::new(Vec::new()),
+    });
+    let state = Arc::new(
+        ServerState::new(unused_pool(), "test-key".to_owned(), None, None)
+            .with_api_client(client.clone()),
+    );
+    let options = FetchOptions {
+        dry_run: true,
+        inline_stations: Some(
+            ["중구", "종로구", "용산구"]
+                .iter()
+                .enumerate()
+                .map(|(index, station)| InlineStation {
+                    sub_region_id: index as i32 + 1,
+                    pm_station: station.to_string(),
+                })
+                .collect(),
+        ),
+        ..Default::default()
+    };
+
+    let e = run_ingest(state, &options).await.unwrap_err();
+    assert!(
+        e.downcast_ref::<InvalidServiceKeyError>().is_some(),
+        "{:?}",
+        e
+    );
+    assert!(e.to_string().contains(SERVICE_KEY_NOT_REGISTERED));
+    // 측정소마다 같은 오류를 반복하지 않음
+    assert_eq!(*client.requested.lock().unwrap(), vec!["중구".to_owned()]);
+}
@@ -0,0 +1,77 @@
+// tests/invocation_deadline.rs
+
+// 호출 정보(InvocationInfo)의 수집 마감 시각과 AWS 요청 ID 가 handle_event 에서 수집 / 응답까지 전달되는지 확인
+// (TEST_DATABASE_URL 필요, shared_state_from_env 의 전역 상태 / sql() 의 전역 스키마를 쓰므로 파일을 분리)
+
+mod common;
+
+use environment_lambda::handler::handle_event;
+use environment_lambda::invocation::InvocationInfo;
+use serde_json::json;
+
+const SCHEMA: &str = "test_invocation_deadline";
+
+// 디버그 빌드에서는 handle_event 의 poll 호출 깊이가 테스트 스레드 기본 스택(2 MiB)을 넘으므로 별도 스레드에서 실행
+fn run_with_large_stack<F>(future: F) -> F::Output
+where
+    F: std::future::Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    std::thread::Builder::new()
+        .stack_size(16 * 1024 * 1024)
+        .spawn(move || {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(future)
+        })
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn exhausted_deadline_reaches_every_station_and_meta_carries_aws_request_id() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    common::fresh_schema(&pool, SCHEMA).await;
+    std::env::set_var("DB_CONN_URL", std::env::var("TEST_DATABASE_URL").unwrap());
+    std::env::set_var("AIR_QUALITY_API_KEY", "test-key");
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+
+    // 제한 시각까지 DEADLINE_MARGIN 보다 적게 남은 호출: 수집 마감이 이미 지남
+    let invocation = InvocationInfo {
+        aws_request_id: "aws-ctx-1".to_owned(),
+        invoked_function_arn: String::new(),
+        function_version: "7".to_owned(),
+        deadline_millis: 0,
+        remaining: Some(std::time::Duration::from_secs(1)),
+        deadline: Some(tokio::time::Instant::now()),
+    };
+    let response = run_with_large_stack(handle_event(
+        "run-1".to_owned(),
+        "req-1".to_owned(),
+        json!({
+            "force": true,
+            "dryRun": true,
+            "stations": [
+                { "subRegionId": 1, "pmStation": "중구" },
+                { "subRegionId": 2, "pmStation": "종로구" },
+            ],
+        }),
+        invocation,
+    ))
+    .unwrap();
+
+    let body = &response["body"];
+    assert_eq!(body["meta"]["awsRequestId"], "aws-ctx-1", "{}", response);
+    assert_eq!(body["meta"]["requestId"], "req-1");
+
+    // 외부 API 를 호출하지 않고 측정소마다 마감 초과로 기록
+    let errors = body["meta"]["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 2, "{}", response);
+    assert!(errors.iter().all(|error| error["kind"] == "TIMEOUT"));
+    assert_eq!(body["data"], json!([]));
+}
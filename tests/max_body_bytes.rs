@@ -0,0 +1,65 @@
+// tests/max_body_bytes.rs
+
+// read_text 가 PM_MAX_BODY_BYTES 보다 큰 응답 본문을 BODY_TOO_LARGE 로 거부하는지 로컬 서버로 확인
+// 프로세스 전역 환경 변수를 바꾸므로 파일을 분리
+
+use environment_lambda::failure::FailureKind;
+use environment_lambda::http_body::read_text;
+use reqwest::StatusCode;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+// 준비된 응답을 연결마다 하나씩 돌려주는 로컬 서버, 주소 반환
+async fn serve(responses: Vec<String>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        for response in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket.write_all(response.as_bytes()).await;
+            let _ = socket.shutdown().await;
+        }
+    });
+    format!("http://{}/", addr)
+}
+
+fn response(status: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+#[tokio::test]
+async fn bodies_over_the_limit_are_rejected() {
+    std::env::set_var("PM_MAX_BODY_BYTES", "16");
+    let body = r#"{"items":[1,2,3]}"#;
+    assert_eq!(body.len(), 17);
+
+    let url = serve(vec![
+        response("200 OK", &body[..16]),
+        response("200 OK", body),
+        response("503 Service Unavailable", body),
+    ])
+    .await;
+    let client = reqwest::Client::new();
+
+    let res = client.get(&url).send().await.unwrap();
+    assert_eq!(read_text(res).await.unwrap(), &body[..16]);
+
+    let res = client.get(&url).send().await.unwrap();
+    let e = read_text(res).await.unwrap_err();
+    assert_eq!(e.kind(), FailureKind::BodyTooLarge);
+    assert_eq!(e.to_string(), "response too large (limit: 16 bytes)");
+
+    // 오류 응답도 같은 제한 적용
+    let res = client.get(&url).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert!(read_text(res).await.is_err());
+
+    std::env::remove_var("PM_MAX_BODY_BYTES");
+}
@@ -0,0 +1,32 @@
+// tests/max_in_flight_tasks.rs
+
+// MAX_IN_FLIGHT_TASKS (동시에 생성해 두는 측정소 수) 해석 확인: 기본은 API 동시 호출 수의 2배,
+// API 동시 호출 수보다 작은 값은 API 퍼밋을 다 쓰지 못하므로 API 동시 호출 수로 올림
+// 환경 변수를 쓰므로 파일을 분리
+
+use environment_lambda::effective_config::effective_config;
+
+fn max_in_flight_tasks() -> serde_json::Value {
+    effective_config()["resolved"]["maxInFlightTasks"].clone()
+}
+
+#[test]
+fn in_flight_tasks_never_drop_below_api_concurrency() {
+    std::env::remove_var("API_CONCURRENCY");
+    std::env::remove_var("MAX_IN_FLIGHT_TASKS");
+    assert_eq!(max_in_flight_tasks(), 20);
+
+    std::env::set_var("API_CONCURRENCY", "4");
+    assert_eq!(max_in_flight_tasks(), 8);
+    std::env::set_var("MAX_IN_FLIGHT_TASKS", "50");
+    assert_eq!(max_in_flight_tasks(), 50);
+    std::env::set_var("MAX_IN_FLIGHT_TASKS", "1");
+    assert_eq!(max_in_flight_tasks(), 4);
+
+    // 숫자가 아니면 기본값
+    std::env::set_var("MAX_IN_FLIGHT_TASKS", "lots");
+    assert_eq!(max_in_flight_tasks(), 8);
+
+    std::env::remove_var("API_CONCURRENCY");
+    std::env::remove_var("MAX_IN_FLIGHT_TASKS");
+}
@@ -0,0 +1,172 @@
+// tests/nearby_station.rs
+
+// pm_station 이 비어 있는 sub_region 이 TM 좌표로 근접 측정소를 찾아 저장한 뒤 그 측정소로 수집하는지 확인 (TEST_DATABASE_URL 필요)
+// sql() 의 전역 스키마를 쓰므로 파일을 분리
+
+mod common;
+
+use chrono::Utc;
+use environment_lambda::db_schema;
+use environment_lambda::failure::FailureKind;
+use environment_lambda::handler::{run_ingest, FetchOptions, StationStatus};
+use environment_lambda::nearby_station::resolve_nearby_station;
+use environment_lambda::provider::{ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::Arc;
+
+const SCHEMA: &str = "test_nearby_station";
+
+fn nearby_body(station_names: &[&str]) -> String {
+    let items: Vec<_> = station_names
+        .iter()
+        .map(|name| json!({ "stationName": name, "tm": 1.2 }))
+        .collect();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": { "totalCount": items.len(), "items": items },
+        }
+    })
+    .to_string()
+}
+
+fn station_body() -> String {
+    let data_time = Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+            },
+        }
+    })
+    .to_string()
+}
+
+async fn stored_pm_station(pool: &deadpool_postgres::Pool, sub_region_id: i32) -> Option<String> {
+    pool.get()
+        .await
+        .unwrap()
+        .query_one(
+            &format!("SELECT pm_station FROM {SCHEMA}.sub_region WHERE sub_region_id = $1"),
+            &[&sub_region_id],
+        )
+        .await
+        .unwrap()
+        .get(0)
+}
+
+#[tokio::test]
+async fn coordinates_resolve_to_the_nearest_station() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+    common::fresh_schema(&pool, SCHEMA).await;
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "CREATE TABLE {SCHEMA}.sub_region (
+                 sub_region_id integer PRIMARY KEY,
+                 pm_station text,
+                 tm_x double precision,
+                 tm_y double precision,
+                 provider text,
+                 nx integer,
+                 ny integer,
+                 ingest_enabled boolean
+             );
+             INSERT INTO {SCHEMA}.sub_region (sub_region_id, tm_x, tm_y) VALUES
+                 (1, 244148.5, 412423.75),
+                 (2, 1.5, 2.5),
+                 (3, 3.5, 4.5);"
+        ))
+        .await
+        .unwrap();
+
+    // 근접측정소 응답은 "tmX,tmY" 로 준비 (목록의 첫 측정소가 가장 가까움)
+    let mock = MockApiClient::new()
+        .with_envelope(
+            "244148.5,412423.75",
+            ApiEnvelope::new(StatusCode::OK, nearby_body(&["중구", "종로구"])),
+        )
+        .with_envelope(
+            "1.5,2.5",
+            ApiEnvelope::new(StatusCode::OK, nearby_body(&[])),
+        )
+        .with_envelope(
+            "3.5,4.5",
+            ApiEnvelope::new(StatusCode::SERVICE_UNAVAILABLE, "unavailable"),
+        )
+        .with_envelope("중구", ApiEnvelope::new(StatusCode::OK, station_body()));
+    let state = Arc::new(
+        ServerState::new(pool.clone(), "test-key".to_owned(), None, None)
+            .with_api_client(Arc::new(mock)),
+    );
+
+    assert_eq!(
+        resolve_nearby_station(&state, 1, 244148.5, 412423.75)
+            .await
+            .unwrap(),
+        "중구"
+    );
+    assert_eq!(stored_pm_station(&pool, 1).await.as_deref(), Some("중구"));
+
+    // 빈 목록 / 오류 응답이면 sub_region 은 그대로
+    for (sub_region_id, tm_x, tm_y) in [(2, 1.5, 2.5), (3, 3.5, 4.5)] {
+        assert!(resolve_nearby_station(&state, sub_region_id, tm_x, tm_y)
+            .await
+            .is_err());
+        assert_eq!(stored_pm_station(&pool, sub_region_id).await, None);
+    }
+
+    // 수집 실행: 좌표만 있는 sub_region 은 근접 측정소 조회 후 그 측정소로 수집
+    pool.get()
+        .await
+        .unwrap()
+        .execute(
+            &format!("UPDATE {SCHEMA}.sub_region SET pm_station = NULL"),
+            &[],
+        )
+        .await
+        .unwrap();
+    let report = run_ingest(state, &FetchOptions::default()).await.unwrap();
+
+    let mut results = report.results;
+    results.sort_by_key(|result| result.sub_region_id);
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].pm_station, "중구");
+    assert!(
+        matches!(results[0].status, StationStatus::Success(_)),
+        "{:?}",
+        results[0].status
+    );
+    for result in &results[1..] {
+        let StationStatus::Failed { kind, .. } = &result.status else {
+            panic!("{} should have failed", result.sub_region_id);
+        };
+        assert_eq!(*kind, FailureKind::NearbyStation);
+    }
+    assert_eq!(stored_pm_station(&pool, 1).await.as_deref(), Some("중구"));
+    let stored: i64 = pool
+        .get()
+        .await
+        .unwrap()
+        .query_one(
+            &format!("SELECT count(*) FROM {SCHEMA}.external_pm WHERE sub_region_id = 1"),
+            &[],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(stored, 1);
+}
@@ -0,0 +1,82 @@
+// tests/no_sub_regions.rs
+
+// sub_region 테이블이 비어 있으면 SUCCESS: 0 대신 meta.outcome NO_SUB_REGIONS 로 구분되고,
+// sub_region_id 를 지정한 수집은 대상이 없어도 정상(OK)으로 끝나는지 확인 (TEST_DATABASE_URL 필요)
+// sql() 의 전역 스키마를 쓰므로 파일을 분리
+
+mod common;
+
+use environment_lambda::db_schema;
+use environment_lambda::handler::{
+    build_response_body, run_ingest, FetchOptions, OUTCOME_NO_SUB_REGIONS, OUTCOME_OK,
+};
+use environment_lambda::messages::{message, MessageKey};
+use environment_lambda::provider::MockApiClient;
+use environment_lambda::state::ServerState;
+use environment_lambda::weather::run_weather_ingest;
+use std::sync::Arc;
+
+const SCHEMA: &str = "test_no_sub_regions";
+
+#[tokio::test]
+async fn empty_sub_region_table_is_reported_as_no_sub_regions() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+    common::fresh_schema(&pool, SCHEMA).await;
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "CREATE TABLE {SCHEMA}.sub_region (
+                 sub_region_id integer PRIMARY KEY,
+                 pm_station text,
+                 tm_x double precision,
+                 tm_y double precision,
+                 provider text,
+                 nx integer,
+                 ny integer,
+                 ingest_enabled boolean
+             );"
+        ))
+        .await
+        .unwrap();
+
+    let state = Arc::new(
+        ServerState::new(
+            pool.clone(),
+            "test-key".to_owned(),
+            Some("weather-key".to_owned()),
+            None,
+        )
+        .with_api_client(Arc::new(MockApiClient::new())),
+    );
+
+    // 전체 수집 (PM / 기상)
+    let report = run_ingest(state.clone(), &FetchOptions::default())
+        .await
+        .unwrap();
+    assert!(report.no_sub_regions);
+    let body = build_response_body(report);
+    assert_eq!(body["meta"]["outcome"], OUTCOME_NO_SUB_REGIONS);
+    assert_eq!(body["meta"]["message"], message(MessageKey::NoSubRegions));
+    assert_eq!(body["data"], serde_json::json!([]));
+
+    let report = run_weather_ingest(state.clone(), &FetchOptions::default())
+        .await
+        .unwrap();
+    assert!(report.no_sub_regions);
+
+    // 지정한 sub_region 이 없는 것은 설정 오류가 아님
+    let options = FetchOptions {
+        sub_region_ids: Some(vec![1]),
+        ..Default::default()
+    };
+    let report = run_ingest(state, &options).await.unwrap();
+    assert!(!report.no_sub_regions);
+    let body = build_response_body(report);
+    assert_eq!(body["meta"]["outcome"], OUTCOME_OK);
+    assert_eq!(body["meta"]["message"], "SUCCESS: 0");
+}
@@ -0,0 +1,242 @@
+// tests/nodata_counter.rs
+
+// 연속 NO_DATA 카운터(pm_nodata_counter): NO_DATA 는 증가, 성공은 초기화, 임계값을 넘으면 SUSPECTED_INVALID_STATION,
+// NODATA_SUSPEND_SKIP 이면 조회 생략, resetNodata 로 초기화, 테이블이 없으면 추적하지 않는지 확인 (TEST_DATABASE_URL 필요)
+// sql() 의 전역 스키마와 NODATA_* 환경 변수를 쓰므로 파일을 분리
+
+mod common;
+
+use chrono::Utc;
+use environment_lambda::db_schema;
+use environment_lambda::failure::FailureKind;
+use environment_lambda::handler::{run_ingest, FetchOptions, StationStatus};
+use environment_lambda::inline_stations::InlineStation;
+use environment_lambda::nodata::{skip_suspected, suspend_threshold};
+use environment_lambda::provider::api_client::ApiFuture;
+use environment_lambda::provider::{ApiClient, ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+const SCHEMA: &str = "test_nodata_counter";
+
+// 조회한 측정소를 기록하는 ApiClient
+struct RecordingApiClient {
+    inner: MockApiClient,
+    stations: Mutex<Vec<String>>,
+}
+
+impl ApiClient for RecordingApiClient {
+    fn fetch_station<'a>(
+        &'a self,
+        pm_station: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.stations.lock().unwrap().push(pm_station.to_owned());
+        self.inner.fetch_station(pm_station, params)
+    }
+
+    fn fetch_province<'a>(
+        &'a self,
+        sido_name: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.inner.fetch_province(sido_name, params)
+    }
+
+    fn fetch_weather<'a>(&'a self, grid: &'a str, params: &'a [(String, String)]) -> ApiFuture<'a> {
+        self.inner.fetch_weather(grid, params)
+    }
+
+    fn fetch_nearby_station<'a>(
+        &'a self,
+        point: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.inner.fetch_nearby_station(point, params)
+    }
+}
+
+fn station_body() -> String {
+    let data_time = Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+            },
+        }
+    })
+    .to_string()
+}
+
+// 잘못된 측정소 이름에도 API 는 정상 코드와 빈 목록을 돌려줌
+fn empty_body() -> String {
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": { "totalCount": 0, "items": [] },
+        }
+    })
+    .to_string()
+}
+
+// 측정소별 (실패 종류, 메시지)
+fn outcome(
+    report: &environment_lambda::handler::IngestReport,
+    sub_region_id: i32,
+) -> (Option<FailureKind>, String) {
+    let result = report
+        .results
+        .iter()
+        .find(|result| result.sub_region_id == sub_region_id)
+        .unwrap();
+    match &result.status {
+        StationStatus::Success(_) => (None, String::new()),
+        StationStatus::Failed { kind, message } => (Some(*kind), message.clone()),
+    }
+}
+
+// 환경 변수를 바꾸고 실행마다 카운터가 쌓이므로 순서대로 하나의 테스트로 구성
+#[tokio::test]
+async fn consecutive_nodata_runs_flag_and_skip_the_station() {
+    // 설정값 해석
+    std::env::remove_var("NODATA_SUSPEND_THRESHOLD");
+    std::env::remove_var("NODATA_SUSPEND_SKIP");
+    assert_eq!(suspend_threshold(), 48);
+    assert!(!skip_suspected());
+
+    std::env::set_var("NODATA_SUSPEND_THRESHOLD", "0");
+    assert_eq!(suspend_threshold(), 48);
+    std::env::set_var("NODATA_SUSPEND_THRESHOLD", "6");
+    assert_eq!(suspend_threshold(), 6);
+    for (value, skip) in [("true", true), ("TRUE", true), ("1", true), ("yes", false)] {
+        std::env::set_var("NODATA_SUSPEND_SKIP", value);
+        assert_eq!(skip_suspected(), skip, "{}", value);
+    }
+
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+    common::fresh_schema(&pool, SCHEMA).await;
+    // 종로구(2)는 이전 실행의 카운터가 남아 있음
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "CREATE TABLE {SCHEMA}.pm_nodata_counter (
+                 sub_region_id integer PRIMARY KEY,
+                 consecutive_nodata integer NOT NULL DEFAULT 0,
+                 updated_at timestamptz
+             );
+             INSERT INTO {SCHEMA}.pm_nodata_counter VALUES (2, 5, now());"
+        ))
+        .await
+        .unwrap();
+
+    let client = Arc::new(RecordingApiClient {
+        inner: MockApiClient::new()
+            .with_envelope("중구", ApiEnvelope::new(StatusCode::OK, empty_body()))
+            .with_envelope("종로구", ApiEnvelope::new(StatusCode::OK, station_body())),
+        stations: Mutex::new(Vec::new()),
+    });
+    let state = Arc::new(
+        ServerState::new(pool.clone(), "test-key".to_owned(), None, None)
+            .with_api_client(client.clone()),
+    );
+    let options = FetchOptions {
+        inline_stations: Some(vec![
+            InlineStation {
+                sub_region_id: 1,
+                pm_station: "중구".to_owned(),
+            },
+            InlineStation {
+                sub_region_id: 2,
+                pm_station: "종로구".to_owned(),
+            },
+        ]),
+        ..Default::default()
+    };
+    let counters = || async {
+        pool.get()
+            .await
+            .unwrap()
+            .query(
+                &format!(
+                    "SELECT sub_region_id, consecutive_nodata FROM {SCHEMA}.pm_nodata_counter ORDER BY 1"
+                ),
+                &[],
+            )
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| (row.get::<_, i32>(0), row.get::<_, i32>(1)))
+            .collect::<Vec<_>>()
+    };
+
+    std::env::set_var("NODATA_SUSPEND_THRESHOLD", "2");
+    std::env::remove_var("NODATA_SUSPEND_SKIP");
+
+    // 1, 2 번째 NO_DATA 는 그대로, 성공한 종로구 카운터는 초기화
+    for count in 1..=2 {
+        let report = run_ingest(state.clone(), &options).await.unwrap();
+        assert_eq!(outcome(&report, 1).0, Some(FailureKind::NoData));
+        assert_eq!(outcome(&report, 2).0, None);
+        assert_eq!(counters().await, vec![(1, count)]);
+    }
+
+    // 임계값(2)을 넘으면 SUSPECTED_INVALID_STATION
+    let report = run_ingest(state.clone(), &options).await.unwrap();
+    let (kind, message) = outcome(&report, 1);
+    assert_eq!(kind, Some(FailureKind::SuspectedInvalidStation));
+    assert!(
+        message.contains("No data for 3 consecutive runs"),
+        "{}",
+        message
+    );
+    assert_eq!(counters().await, vec![(1, 3)]);
+
+    // NODATA_SUSPEND_SKIP: 조회하지 않고 카운터도 그대로
+    std::env::set_var("NODATA_SUSPEND_SKIP", "true");
+    client.stations.lock().unwrap().clear();
+    let report = run_ingest(state.clone(), &options).await.unwrap();
+    let (kind, message) = outcome(&report, 1);
+    assert_eq!(kind, Some(FailureKind::SuspectedInvalidStation));
+    assert!(message.contains(", skipped"), "{}", message);
+    assert_eq!(*client.stations.lock().unwrap(), vec!["종로구".to_owned()]);
+    assert_eq!(counters().await, vec![(1, 3)]);
+
+    // resetNodata 로 초기화하면 다시 조회 (dry-run 은 초기화하지 않음)
+    let reset = |dry_run| FetchOptions {
+        dry_run,
+        reset_nodata: Some(vec![1]),
+        ..options.clone()
+    };
+    run_ingest(state.clone(), &reset(true)).await.unwrap();
+    assert_eq!(counters().await, vec![(1, 3)]);
+    let report = run_ingest(state.clone(), &reset(false)).await.unwrap();
+    assert_eq!(outcome(&report, 1).0, Some(FailureKind::NoData));
+    assert_eq!(counters().await, vec![(1, 1)]);
+
+    // 테이블이 없으면 추적하지 않음
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!("DROP TABLE {SCHEMA}.pm_nodata_counter;"))
+        .await
+        .unwrap();
+    let report = run_ingest(state, &options).await.unwrap();
+    assert_eq!(outcome(&report, 1).0, Some(FailureKind::NoData));
+    assert_eq!(outcome(&report, 2).0, None);
+
+    std::env::remove_var("NODATA_SUSPEND_THRESHOLD");
+    std::env::remove_var("NODATA_SUSPEND_SKIP");
+}
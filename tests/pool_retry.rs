@@ -0,0 +1,84 @@
+// tests/pool_retry.rs
+
+// 풀이 고갈되어 wait_timeout 이 지나면 get_client_with_retry 가 재시도하는지 확인 (TEST_DATABASE_URL 필요)
+
+use deadpool_postgres::{Config, PoolConfig, Runtime, Timeouts};
+use environment_lambda::state::get_client_with_retry;
+use std::time::Duration;
+use tokio_postgres::NoTls;
+
+#[tokio::test]
+async fn retries_checkout_while_pool_is_exhausted() {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL 미설정: DB 테스트 건너뜀");
+        return;
+    };
+    let cfg = Config {
+        url: Some(url),
+        pool: Some(PoolConfig {
+            max_size: 1,
+            timeouts: Timeouts {
+                wait: Some(Duration::from_millis(50)),
+                create: Some(Duration::from_millis(2_000)),
+                recycle: None,
+            },
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let pool = cfg.create_pool(Some(Runtime::Tokio1), NoTls).unwrap();
+
+    // 유일한 커넥션을 잡고 있다가 첫 재시도(100ms) 이후에 반환
+    let held = pool.get().await.unwrap();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        drop(held);
+    });
+
+    let (client, retries) = get_client_with_retry(&pool)
+        .await
+        .expect("checkout after retry");
+    assert!(retries >= 1, "retries = {}", retries);
+    client.simple_query("SELECT 1").await.unwrap();
+}
+
+#[tokio::test]
+async fn gives_up_with_timeout_when_pool_stays_exhausted() {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL 미설정: DB 테스트 건너뜀");
+        return;
+    };
+    let cfg = Config {
+        url: Some(url),
+        pool: Some(PoolConfig {
+            max_size: 1,
+            timeouts: Timeouts {
+                wait: Some(Duration::from_millis(20)),
+                create: Some(Duration::from_millis(2_000)),
+                recycle: None,
+            },
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let pool = cfg.create_pool(Some(Runtime::Tokio1), NoTls).unwrap();
+    let _held = pool.get().await.unwrap();
+
+    // 첫 시도 + 재시도 2번 (100ms, 300ms): 900ms 간격의 네 번째 시도는 없음
+    let started = std::time::Instant::now();
+    match get_client_with_retry(&pool).await {
+        Err(deadpool_postgres::PoolError::Timeout(_)) => {}
+        other => panic!("expected pool timeout, got {:?}", other.map(|(_, r)| r)),
+    }
+    let elapsed = started.elapsed();
+    assert!(
+        elapsed >= Duration::from_millis(460),
+        "elapsed = {:?}",
+        elapsed
+    );
+    assert!(
+        elapsed < Duration::from_millis(1_300),
+        "elapsed = {:?}",
+        elapsed
+    );
+}
@@ -0,0 +1,60 @@
+// tests/pool_timeout_status.rs
+
+// 수집 시작 시 커넥션 풀 오류의 응답 상태 코드: 풀 고갈(타임아웃)은 503, 접속 실패는 500 (TEST_DATABASE_URL 필요)
+
+use deadpool_postgres::{Config, Pool, PoolConfig, PoolError, Runtime, Timeouts};
+use environment_lambda::handler::{pool_error_status, run_ingest, FetchOptions};
+use environment_lambda::state::ServerState;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_postgres::NoTls;
+
+fn pool(url: String) -> Pool {
+    let cfg = Config {
+        url: Some(url),
+        pool: Some(PoolConfig {
+            max_size: 1,
+            timeouts: Timeouts {
+                wait: Some(Duration::from_millis(20)),
+                create: Some(Duration::from_millis(2_000)),
+                recycle: None,
+            },
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    cfg.create_pool(Some(Runtime::Tokio1), NoTls).unwrap()
+}
+
+async fn ingest_error_status(pool: Pool) -> (u16, String) {
+    let state = Arc::new(ServerState::new(pool, "test-key".to_owned(), None, None));
+    let err = match run_ingest(state, &FetchOptions::default()).await {
+        Ok(_) => panic!("ingest must fail without a pooled connection"),
+        Err(e) => e,
+    };
+    let pool_error = err.downcast_ref::<PoolError>().expect("PoolError");
+    pool_error_status(pool_error)
+}
+
+#[tokio::test]
+async fn exhausted_pool_maps_to_503() {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL 미설정: DB 테스트 건너뜀");
+        return;
+    };
+    let pool = pool(url);
+    let _held = pool.get().await.unwrap();
+
+    let (status, message) = ingest_error_status(pool.clone()).await;
+    assert_eq!(status, 503);
+    assert!(!message.is_empty());
+}
+
+#[tokio::test]
+async fn unreachable_database_maps_to_500() {
+    // 열려 있지 않은 포트: 커넥션 생성이 즉시 Backend 오류로 실패
+    let pool = pool("postgres://postgres@127.0.0.1:1/postgres".to_owned());
+
+    let (status, _) = ingest_error_status(pool).await;
+    assert_eq!(status, 500);
+}
@@ -0,0 +1,41 @@
+// tests/pool_warmup.rs
+
+// initialize_state 가 DB_POOL_WARM_CONNECTIONS 개의 커넥션을 미리 열고 소요 시간을 pool_warmup 으로 남기는지,
+// 0 이면 warm-up 없이 빈 풀로 시작하는지 확인 (TEST_DATABASE_URL 필요)
+// 환경 변수를 바꾸므로 파일을 분리
+
+use environment_lambda::db_conn::DbConnConfig;
+use environment_lambda::effective_config::effective_config;
+use environment_lambda::state::initialize_state;
+
+// 환경 변수를 바꾸며 순서대로 실행해야 하므로 하나의 테스트로 구성
+#[tokio::test]
+async fn init_warms_the_configured_number_of_connections() {
+    std::env::remove_var("DB_POOL_MIN_IDLE");
+    std::env::remove_var("DB_POOL_WARM_CONNECTIONS");
+    assert_eq!(effective_config()["resolved"]["dbPoolWarmConnections"], 2);
+    std::env::set_var("DB_POOL_WARM_CONNECTIONS", "3");
+    assert_eq!(effective_config()["resolved"]["dbPoolWarmConnections"], 3);
+
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        return;
+    };
+    std::env::set_var("DB_POOL_MAX_SIZE", "4");
+    let state = initialize_state(&DbConnConfig::Url(url.clone()), "test-key", None, None)
+        .await
+        .unwrap();
+    assert!(state.pool_warmup.is_some());
+    let status = state.pool.status();
+    assert_eq!((status.size, status.available), (3, 3));
+
+    // 0: warm-up 없음 (meta.phases 에도 poolWarmup 이 남지 않음)
+    std::env::set_var("DB_POOL_WARM_CONNECTIONS", "0");
+    let state = initialize_state(&DbConnConfig::Url(url), "test-key", None, None)
+        .await
+        .unwrap();
+    assert_eq!(state.pool_warmup, None);
+    assert_eq!(state.pool.status().size, 0);
+
+    std::env::remove_var("DB_POOL_WARM_CONNECTIONS");
+    std::env::remove_var("DB_POOL_MAX_SIZE");
+}
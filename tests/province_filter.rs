@@ -0,0 +1,170 @@
+// tests/province_filter.rs
+
+// 시도별 수집(sidoName): 시도 전체를 한 번만 조회하고, 시도 응답에 없는 측정소는 실패가 아닌 수집 대상에서 제외되는지 확인
+// (dry-run + payload 측정소 목록이므로 DB 불필요)
+
+use environment_lambda::handler::{run_ingest, FetchOptions, StationStatus};
+use environment_lambda::inline_stations::InlineStation;
+use environment_lambda::provider::api_client::ApiFuture;
+use environment_lambda::provider::{ApiClient, ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+// 호출한 엔드포인트와 대상을 기록하는 ApiClient
+struct RecordingApiClient {
+    inner: MockApiClient,
+    calls: Mutex<Vec<String>>,
+}
+
+impl RecordingApiClient {
+    fn record(&self, call: String) {
+        self.calls.lock().unwrap().push(call);
+    }
+}
+
+impl ApiClient for RecordingApiClient {
+    fn fetch_station<'a>(
+        &'a self,
+        pm_station: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.record(format!("station {}", pm_station));
+        self.inner.fetch_station(pm_station, params)
+    }
+
+    fn fetch_province<'a>(
+        &'a self,
+        sido_name: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.record(format!("province {}", sido_name));
+        self.inner.fetch_province(sido_name, params)
+    }
+
+    fn fetch_weather<'a>(&'a self, grid: &'a str, params: &'a [(String, String)]) -> ApiFuture<'a> {
+        self.inner.fetch_weather(grid, params)
+    }
+
+    fn fetch_nearby_station<'a>(
+        &'a self,
+        point: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.inner.fetch_nearby_station(point, params)
+    }
+}
+
+fn province_body() -> String {
+    let data_time = chrono::Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 2,
+                "items": [
+                    { "stationName": "중구", "dataTime": data_time, "pm10Value": "30", "pm25Value": "12" },
+                    { "stationName": "종로구", "dataTime": data_time, "pm10Value": "31", "pm25Value": "13" },
+                ],
+            },
+        }
+    })
+    .to_string()
+}
+
+// 접속하지 않는 풀 (dry-run + payload 측정소 목록은 DB 에 접근하지 않음)
+fn unused_pool() -> deadpool_postgres::Pool {
+    deadpool_postgres::Config {
+        url: Some("postgres://unused@127.0.0.1:1/unused".to_owned()),
+        ..Default::default()
+    }
+    .create_pool(
+        Some(deadpool_postgres::Runtime::Tokio1),
+        tokio_postgres::NoTls,
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn province_mode_fetches_once_and_skips_other_provinces() {
+    let client = Arc::new(RecordingApiClient {
+        inner: MockApiClient::new()
+            .with_envelope("서울", ApiEnvelope::new(StatusCode::OK, province_body())),
+        calls: Mutex::new(Vec::new()),
+    });
+    let state = Arc::new(
+        ServerState::new(unused_pool(), "test-key".to_owned(), None, None)
+            .with_api_client(client.clone()),
+    );
+    let options = FetchOptions {
+        dry_run: true,
+        sido_name: Some("서울".to_owned()),
+        inline_stations: Some(
+            ["중구", "종로구", "수원"]
+                .iter()
+                .enumerate()
+                .map(|(index, station)| InlineStation {
+                    sub_region_id: index as i32 + 1,
+                    pm_station: station.to_string(),
+                })
+                .collect(),
+        ),
+        ..Default::default()
+    };
+
+    let report = run_ingest(state, &options).await.unwrap();
+    let mut values: Vec<_> = report
+        .results
+        .iter()
+        .map(|result| {
+            let StationStatus::Success(data) = &result.status else {
+                panic!("{:?}", result.status);
+            };
+            (result.sub_region_id, data["pm10Value"].as_f64())
+        })
+        .collect();
+    values.sort_by_key(|(sub_region_id, _)| *sub_region_id);
+    // 다른 시도 측정소(수원)는 결과에 없음
+    assert_eq!(values, vec![(1, Some(30.0)), (2, Some(31.0))]);
+    // 측정소별 조회 없이 시도 조회 한 번
+    assert_eq!(
+        *client.calls.lock().unwrap(),
+        vec!["province 서울".to_owned()]
+    );
+}
+
+#[tokio::test]
+async fn province_request_failure_fails_the_run() {
+    let client = Arc::new(RecordingApiClient {
+        inner: MockApiClient::new().with_envelope(
+            "서울",
+            ApiEnvelope::new(StatusCode::SERVICE_UNAVAILABLE, "unavailable"),
+        ),
+        calls: Mutex::new(Vec::new()),
+    });
+    let state = Arc::new(
+        ServerState::new(unused_pool(), "test-key".to_owned(), None, None)
+            .with_api_client(client.clone()),
+    );
+    let options = FetchOptions {
+        dry_run: true,
+        sido_name: Some("서울".to_owned()),
+        inline_stations: Some(vec![InlineStation {
+            sub_region_id: 1,
+            pm_station: "중구".to_owned(),
+        }]),
+        ..Default::default()
+    };
+
+    // 시도 조회가 실패하면 측정소별로 다시 조회하지 않고 실행 오류
+    assert!(run_ingest(state, &options).await.is_err());
+    assert_eq!(
+        *client.calls.lock().unwrap(),
+        vec!["province 서울".to_owned()]
+    );
+}
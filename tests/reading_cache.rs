@@ -0,0 +1,162 @@
+// tests/reading_cache.rs
+
+// 실행 단위 측정값 캐시: 같은 측정소를 가리키는 sub_region 이 여럿이어도 API 요청은 한 번이고 나머지는 meta.cacheHits 로 집계되는지,
+// 실패한 조회는 캐시하지 않는지 확인 (dry-run + payload 측정소 목록이므로 DB 불필요)
+
+use environment_lambda::handler::{run_ingest, FetchOptions, StationStatus};
+use environment_lambda::inline_stations::InlineStation;
+use environment_lambda::provider::api_client::ApiFuture;
+use environment_lambda::provider::{ApiClient, ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// 측정소별 요청 수를 세는 ApiClient
+struct CountingApiClient {
+    inner: MockApiClient,
+    requests: Mutex<HashMap<String, usize>>,
+}
+
+impl CountingApiClient {
+    fn requests(&self, pm_station: &str) -> usize {
+        self.requests
+            .lock()
+            .unwrap()
+            .get(pm_station)
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+impl ApiClient for CountingApiClient {
+    fn fetch_station<'a>(
+        &'a self,
+        pm_station: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        *self
+            .requests
+            .lock()
+            .unwrap()
+            .entry(pm_station.to_owned())
+            .or_default() += 1;
+        self.inner.fetch_station(pm_station, params)
+    }
+
+    fn fetch_province<'a>(
+        &'a self,
+        sido_name: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.inner.fetch_province(sido_name, params)
+    }
+
+    fn fetch_weather<'a>(&'a self, grid: &'a str, params: &'a [(String, String)]) -> ApiFuture<'a> {
+        self.inner.fetch_weather(grid, params)
+    }
+
+    fn fetch_nearby_station<'a>(
+        &'a self,
+        point: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.inner.fetch_nearby_station(point, params)
+    }
+}
+
+fn station_body() -> String {
+    let data_time = chrono::Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+            },
+        }
+    })
+    .to_string()
+}
+
+// 접속하지 않는 풀 (dry-run + payload 측정소 목록은 DB 에 접근하지 않음)
+fn unused_pool() -> deadpool_postgres::Pool {
+    deadpool_postgres::Config {
+        url: Some("postgres://unused@127.0.0.1:1/unused".to_owned()),
+        ..Default::default()
+    }
+    .create_pool(
+        Some(deadpool_postgres::Runtime::Tokio1),
+        tokio_postgres::NoTls,
+    )
+    .unwrap()
+}
+
+fn dry_run(stations: &[(i32, &str)]) -> FetchOptions {
+    FetchOptions {
+        dry_run: true,
+        inline_stations: Some(
+            stations
+                .iter()
+                .map(|(sub_region_id, station)| InlineStation {
+                    sub_region_id: *sub_region_id,
+                    pm_station: station.to_string(),
+                })
+                .collect(),
+        ),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn sub_regions_sharing_a_station_make_one_request() {
+    let client = Arc::new(CountingApiClient {
+        inner: MockApiClient::new()
+            .with_envelope("중구", ApiEnvelope::new(StatusCode::OK, station_body()))
+            .with_envelope(
+                "점검",
+                ApiEnvelope::new(StatusCode::BAD_REQUEST, "bad request"),
+            ),
+        requests: Mutex::new(HashMap::new()),
+    });
+    let state = Arc::new(
+        ServerState::new(unused_pool(), "test-key".to_owned(), None, None)
+            .with_api_client(client.clone()),
+    );
+
+    let report = run_ingest(
+        state,
+        &dry_run(&[
+            (1, "중구"),
+            (2, "중구"),
+            (3, "중구"),
+            (4, "점검"),
+            (5, "점검"),
+        ]),
+    )
+    .await
+    .unwrap();
+
+    // 성공한 조회는 첫 요청 결과를 나머지 sub_region 이 재사용
+    assert_eq!(client.requests("중구"), 1);
+    assert_eq!(report.cache_hits, 2);
+    for result in report.results.iter().filter(|r| r.pm_station == "중구") {
+        let StationStatus::Success(data) = &result.status else {
+            panic!("{} : {:?}", result.sub_region_id, result.status);
+        };
+        assert_eq!(data["pm10Value"], json!(42.0), "{}", data);
+    }
+
+    // 실패한 조회는 캐시하지 않으므로 sub_region 마다 다시 요청
+    assert_eq!(client.requests("점검"), 2);
+    assert!(report
+        .results
+        .iter()
+        .filter(|r| r.pm_station == "점검")
+        .all(|r| matches!(r.status, StationStatus::Failed { .. })));
+}
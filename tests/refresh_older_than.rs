@@ -0,0 +1,120 @@
+// tests/refresh_older_than.rs
+
+// 증분 수집(refresh_older_than)에서 저장된 측정 시각이 최근인 측정소는 API 호출 없이 건너뛰고
+// meta.skippedFresh 로 집계되는지 확인 (TEST_DATABASE_URL 필요)
+// sql() 의 전역 스키마와 PM_REFRESH_OLDER_THAN_MINUTES 환경 변수를 쓰므로 파일을 분리
+
+mod common;
+
+use chrono::Utc;
+use environment_lambda::db_schema;
+use environment_lambda::handler::{build_response_body, run_ingest, FetchOptions, StationStatus};
+use environment_lambda::inline_stations::InlineStation;
+use environment_lambda::provider::{ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::Arc;
+
+const SCHEMA: &str = "test_refresh_older_than";
+
+fn station_body() -> String {
+    let data_time = Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+            },
+        }
+    })
+    .to_string()
+}
+
+#[test]
+fn refresh_older_than_reads_minutes_from_env() {
+    std::env::remove_var("PM_REFRESH_OLDER_THAN_MINUTES");
+    assert_eq!(FetchOptions::refresh_older_than_from_env(), None);
+
+    std::env::set_var("PM_REFRESH_OLDER_THAN_MINUTES", "50");
+    assert_eq!(
+        FetchOptions::refresh_older_than_from_env(),
+        Some(chrono::Duration::minutes(50))
+    );
+
+    // 숫자가 아니면 전체 수집
+    std::env::set_var("PM_REFRESH_OLDER_THAN_MINUTES", "soon");
+    assert_eq!(FetchOptions::refresh_older_than_from_env(), None);
+    std::env::remove_var("PM_REFRESH_OLDER_THAN_MINUTES");
+}
+
+#[tokio::test]
+async fn recently_recorded_stations_are_skipped() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+    common::fresh_schema(&pool, SCHEMA).await;
+    // 1 은 10분 전, 2 는 3시간 전에 저장됨, 3 은 저장된 적 없음
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "INSERT INTO {SCHEMA}.external_pm (sub_region_id, pm10, pm25, recorded_at) VALUES
+                 (1, 10, 5, now() - interval '10 minutes'),
+                 (2, 10, 5, now() - interval '3 hours');"
+        ))
+        .await
+        .unwrap();
+
+    // 중구(1)는 준비된 응답이 없으므로 조회하면 REQUEST 실패가 됨
+    let mock = ["종로구", "용산구"]
+        .iter()
+        .fold(MockApiClient::new(), |mock, station| {
+            mock.with_envelope(station, ApiEnvelope::new(StatusCode::OK, station_body()))
+        });
+    let state = Arc::new(
+        ServerState::new(pool.clone(), "test-key".to_owned(), None, None)
+            .with_api_client(Arc::new(mock)),
+    );
+    let options = FetchOptions {
+        inline_stations: Some(
+            ["중구", "종로구", "용산구"]
+                .iter()
+                .enumerate()
+                .map(|(index, station)| InlineStation {
+                    sub_region_id: index as i32 + 1,
+                    pm_station: station.to_string(),
+                })
+                .collect(),
+        ),
+        refresh_older_than: Some(chrono::Duration::minutes(50)),
+        ..Default::default()
+    };
+
+    let report = run_ingest(state, &options).await.unwrap();
+    let mut collected: Vec<_> = report
+        .results
+        .iter()
+        .map(|result| {
+            assert!(
+                matches!(result.status, StationStatus::Success(_)),
+                "{:?}",
+                result.status
+            );
+            result.sub_region_id
+        })
+        .collect();
+    collected.sort();
+    assert_eq!(collected, vec![2, 3]);
+    assert_eq!(report.skipped_fresh, 1);
+
+    let body = build_response_body(report);
+    assert_eq!(body["meta"]["skippedFresh"], 1);
+}
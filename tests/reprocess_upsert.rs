@@ -0,0 +1,70 @@
+// tests/reprocess_upsert.rs
+
+// asOf 재처리 upsert: 저장된 값이 더 최신이면 갱신하지 않고, 갱신하면 이전 값을 반환 (TEST_DATABASE_URL 필요)
+
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use environment_lambda::db_schema::apply_schema;
+use environment_lambda::handler::UPSERT_EXTERNAL_PM_QUERY;
+use environment_lambda::reprocess::UPSERT_EXTERNAL_PM_IF_NOT_NEWER_QUERY;
+use environment_lambda::upserted::UpsertedRow;
+
+#[tokio::test]
+async fn not_newer_upsert_returns_previous_values_and_skips_newer_rows() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    let schema = common::fresh_schema(&pool, "test_reprocess_upsert").await;
+    let client = pool.get().await.unwrap();
+    let upsert = apply_schema(UPSERT_EXTERNAL_PM_QUERY, &schema);
+    let if_not_newer = apply_schema(UPSERT_EXTERNAL_PM_IF_NOT_NEWER_QUERY, &schema);
+    let stored_at = Utc.with_ymd_and_hms(2024, 5, 2, 4, 0, 0).unwrap();
+
+    client
+        .execute(&upsert, &[&3, &Some(40.0), &Some(20.0), &stored_at])
+        .await
+        .unwrap();
+
+    // 같은 시각 재처리는 허용하고 이전 값을 함께 반환
+    let row = client
+        .query_opt(&if_not_newer, &[&3, &Some(42.0), &Some(21.0), &stored_at])
+        .await
+        .unwrap()
+        .expect("same hour is reprocessed");
+    let row = UpsertedRow::from_row(&row).unwrap();
+    assert_eq!(row.prev_pm10, Some(40.0));
+    assert_eq!(row.prev_pm25, Some(20.0));
+
+    // 더 오래된 시각은 저장된 값을 덮어쓰지 않음
+    let older = client
+        .query_opt(
+            &if_not_newer,
+            &[
+                &3,
+                &Some(1.0),
+                &Some(1.0),
+                &(stored_at - Duration::hours(1)),
+            ],
+        )
+        .await
+        .unwrap();
+    assert!(older.is_none());
+
+    // overwrite 는 UPSERT_EXTERNAL_PM_QUERY 로 무조건 덮어씀
+    let overwritten = client
+        .query_one(
+            &upsert,
+            &[
+                &3,
+                &Some(1.0),
+                &Some(1.0),
+                &(stored_at - Duration::hours(1)),
+            ],
+        )
+        .await
+        .unwrap();
+    let overwritten = UpsertedRow::from_row(&overwritten).unwrap();
+    assert_eq!(overwritten.prev_pm10, Some(42.0));
+    assert_eq!(overwritten.pm10, Some(1.0));
+}
@@ -0,0 +1,50 @@
+// tests/run_lock.rs
+
+// 실행 락: 다른 세션이 락을 잡으면 ALREADY_RUNNING 과 보유 실행의 run_id 확인 (TEST_DATABASE_URL 필요)
+// 락 키가 하나뿐이므로 병렬로 실행되지 않도록 한 테스트 안에서 순서대로 확인
+
+mod common;
+
+use environment_lambda::run_lock::{self, AlreadyRunningError};
+
+#[tokio::test]
+async fn second_acquire_reports_holder_run_id() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    let holder = run_lock::acquire(pool.get().await.unwrap(), "run-holder")
+        .await
+        .expect("first run takes the lock");
+
+    let err = match run_lock::acquire(pool.get().await.unwrap(), "run-second").await {
+        Ok(_) => panic!("second run must not take the lock"),
+        Err(e) => e,
+    };
+    let already_running = err
+        .downcast_ref::<AlreadyRunningError>()
+        .expect("AlreadyRunningError");
+    assert_eq!(already_running.holder_run_id.as_deref(), Some("run-holder"));
+
+    holder.release().await;
+    let next = run_lock::acquire(pool.get().await.unwrap(), "run-next")
+        .await
+        .expect("lock is free after release");
+
+    // release 없이 취소된 실행: guard 가 커넥션을 닫아 세션 락이 풀림 (서버가 연결 종료를 감지할 때까지 대기)
+    // 같은 세션은 락을 다시 잡을 수 있으므로 락을 잡았던 커넥션이 섞이지 않는 별도 풀로 확인
+    drop(next);
+    let other_pool = common::test_pool().unwrap();
+    let mut retried = 0;
+    let after_drop = loop {
+        match run_lock::acquire(other_pool.get().await.unwrap(), "run-after-drop").await {
+            Ok(lock) => break lock,
+            Err(e) if retried < 50 => {
+                assert!(e.downcast_ref::<AlreadyRunningError>().is_some(), "{:?}", e);
+                retried += 1;
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            }
+            Err(e) => panic!("lock is still held after the guard was dropped: {:?}", e),
+        }
+    };
+    after_drop.release().await;
+}
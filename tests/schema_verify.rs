@@ -0,0 +1,170 @@
+// tests/schema_verify.rs
+
+// verify: "schema" 가 대상 스키마의 테이블/컬럼 누락과 타입 불일치를 보고하는지 확인 (TEST_DATABASE_URL 필요)
+// sql() 의 전역 스키마 / shared_state_from_env 의 전역 상태를 쓰므로 파일을 분리
+
+mod common;
+
+use environment_lambda::db_schema;
+use environment_lambda::handler::handle_event;
+use environment_lambda::invocation::InvocationInfo;
+use environment_lambda::schema_check::{verify_schema, CheckStatus};
+use environment_lambda::sub_region_query::SubRegionQueries;
+use serde_json::json;
+
+const SCHEMA: &str = "test_schema_verify";
+
+// 디버그 빌드에서는 handle_event 의 poll 호출 깊이가 테스트 스레드 기본 스택(2 MiB)을 넘으므로 별도 스레드에서 실행
+fn run_with_large_stack<F>(future: F) -> F::Output
+where
+    F: std::future::Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    std::thread::Builder::new()
+        .stack_size(16 * 1024 * 1024)
+        .spawn(move || {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(future)
+        })
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+fn invoke_verify() -> serde_json::Value {
+    let invocation = InvocationInfo {
+        aws_request_id: "aws-1".to_owned(),
+        invoked_function_arn: String::new(),
+        function_version: "$LATEST".to_owned(),
+        deadline_millis: 0,
+        remaining: None,
+        deadline: None,
+    };
+    run_with_large_stack(handle_event(
+        "run-1".to_owned(),
+        "req-1".to_owned(),
+        json!({ "verify": "schema" }),
+        invocation,
+    ))
+    .unwrap()
+}
+
+// 스키마를 바꾸며 순서대로 확인해야 하므로 하나의 테스트로 구성
+#[tokio::test]
+async fn schema_drift_is_reported() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    common::fresh_schema(&pool, SCHEMA).await;
+    std::env::set_var("DB_CONN_URL", std::env::var("TEST_DATABASE_URL").unwrap());
+    std::env::set_var("AIR_QUALITY_API_KEY", "test-key");
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+
+    // sub_region 없음, external_pm.pm25 타입 불일치
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "ALTER TABLE {SCHEMA}.external_pm ALTER COLUMN pm25 TYPE numeric;"
+        ))
+        .await
+        .unwrap();
+    let client = pool.get().await.unwrap();
+    let report = verify_schema(&client, &SubRegionQueries::default())
+        .await
+        .unwrap();
+    assert!(!report.passed());
+    assert_eq!(report.schema, SCHEMA);
+    let failures: Vec<_> = report
+        .checks
+        .iter()
+        .filter(|check| check.status.is_failure())
+        .map(|check| (check.table.as_str(), check.column.as_deref(), check.status))
+        .collect();
+    assert_eq!(
+        failures,
+        [
+            ("sub_region", None, CheckStatus::TableMissing),
+            ("external_pm", Some("pm25"), CheckStatus::TypeMismatch),
+        ]
+    );
+    drop(client);
+
+    let response = invoke_verify();
+    assert_eq!(response["statusCode"], 422);
+    assert_eq!(response["body"]["meta"]["outcome"], "SCHEMA_MISMATCH");
+    assert_eq!(response["body"]["meta"]["requestId"], "req-1");
+    assert_eq!(
+        response["body"]["meta"]["failures"][1]["detail"],
+        "external_pm.pm25 : expected double precision, found numeric"
+    );
+
+    // 고친 뒤: 선택 테이블(history, weather, nodata 카운터)이 없어도 통과
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "ALTER TABLE {SCHEMA}.external_pm ALTER COLUMN pm25 TYPE double precision;
+             CREATE TABLE {SCHEMA}.sub_region (
+                 sub_region_id integer PRIMARY KEY,
+                 pm_station text NOT NULL,
+                 tm_x double precision,
+                 tm_y double precision,
+                 provider text,
+                 nx integer,
+                 ny integer,
+                 ingest_enabled boolean NOT NULL DEFAULT true
+             );"
+        ))
+        .await
+        .unwrap();
+    let response = invoke_verify();
+    assert_eq!(response["statusCode"], 200);
+    let meta = &response["body"]["meta"];
+    assert_eq!(meta["outcome"], "OK");
+    assert_eq!(meta["failures"], json!([]));
+    let absent: Vec<_> = response["body"]["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|check| check["status"] == "ABSENT")
+        .map(|check| (check["table"].as_str().unwrap(), check["column"].as_str()))
+        .collect();
+    assert_eq!(
+        absent,
+        [
+            ("sub_region", Some("is_active")),
+            ("external_pm_history", None),
+            ("external_weather", None),
+            ("pm_nodata_counter", None),
+            ("api_quota_usage", None),
+        ]
+    );
+
+    // sub_region 쿼리를 덮어쓰면 테이블 대신 쿼리를 prepare 하여 확인
+    let client = pool.get().await.unwrap();
+    let queries = SubRegionQueries::from_query(&format!(
+        "SELECT sub_region_id, pm_station FROM {SCHEMA}.sub_region"
+    ));
+    let report = verify_schema(&client, &queries).await.unwrap();
+    assert!(report.passed());
+    assert_eq!(report.checks[0].table, "sub_region");
+    assert_eq!(report.checks[0].status, CheckStatus::Ok);
+    assert_eq!(
+        report.checks[0].detail.as_deref(),
+        Some("sub_region query override")
+    );
+    assert!(report.checks[1..]
+        .iter()
+        .all(|check| check.table != "sub_region"));
+
+    let queries =
+        SubRegionQueries::from_query(&format!("SELECT sub_region_id FROM {SCHEMA}.sub_region"));
+    let report = verify_schema(&client, &queries).await.unwrap();
+    assert!(!report.passed());
+    assert_eq!(report.checks[0].status, CheckStatus::QueryInvalid);
+}
@@ -0,0 +1,25 @@
+// tests/secret_key_env.rs
+
+// AIR_QUALITY_API_KEY_SECRET_ARN 이 없거나 비어 있으면 Secrets Manager 대신 AIR_QUALITY_API_KEY 환경 변수를 쓰는지 확인
+// 프로세스 전역 환경 변수를 바꾸므로 파일을 분리
+
+use environment_lambda::secrets::{air_quality_api_key, refetch_air_quality_api_key};
+
+#[tokio::test]
+async fn env_key_is_used_without_a_secret_arn() {
+    for secret_arn in [None, Some("")] {
+        match secret_arn {
+            Some(secret_arn) => std::env::set_var("AIR_QUALITY_API_KEY_SECRET_ARN", secret_arn),
+            None => std::env::remove_var("AIR_QUALITY_API_KEY_SECRET_ARN"),
+        }
+
+        std::env::remove_var("AIR_QUALITY_API_KEY");
+        let e = air_quality_api_key().await.unwrap_err();
+        assert!(e.to_string().contains("AIR_QUALITY_API_KEY"), "{}", e);
+
+        std::env::set_var("AIR_QUALITY_API_KEY", "env-key");
+        assert_eq!(air_quality_api_key().await.unwrap(), "env-key");
+        // 환경 변수 키는 실행 중에 바뀌지 않으므로 다시 조회하지 않음
+        assert_eq!(refetch_air_quality_api_key().await.unwrap(), None);
+    }
+}
@@ -0,0 +1,38 @@
+// tests/source_offset_init.rs
+
+// SOURCE_TZ_OFFSET_HOURS 는 초기화 단계에서 검증되어 ServerState.source_offset 으로 전달되는지 확인
+// 환경 변수를 바꾸므로 파일을 분리 (잘못된 값은 DB 접속 전에 실패, 올바른 값 확인은 TEST_DATABASE_URL 필요)
+
+use chrono::FixedOffset;
+use environment_lambda::state::initialize_state_from_env;
+
+#[tokio::test]
+async fn invalid_offset_is_rejected_at_init() {
+    std::env::set_var("AIR_QUALITY_API_KEY", "test-key");
+    // 잘못된 값은 풀에 접속하기 전에 실패하므로 접속할 수 없는 주소로도 확인 가능
+    std::env::set_var("DB_CONN_URL", "postgres://postgres@127.0.0.1:1/postgres");
+
+    for invalid in ["25", "-13", "9.5", "KST"] {
+        std::env::set_var("SOURCE_TZ_OFFSET_HOURS", invalid);
+        let error = initialize_state_from_env()
+            .await
+            .err()
+            .unwrap_or_else(|| panic!("{} must be rejected", invalid));
+        assert!(
+            format!("{:?}", error).contains("SOURCE_TZ_OFFSET_HOURS"),
+            "{:?}",
+            error
+        );
+    }
+
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        return;
+    };
+    std::env::set_var("DB_CONN_URL", url);
+    std::env::set_var("SOURCE_TZ_OFFSET_HOURS", "-5");
+    let state = initialize_state_from_env().await.unwrap();
+    assert_eq!(
+        state.source_offset,
+        FixedOffset::west_opt(5 * 3600).unwrap()
+    );
+}
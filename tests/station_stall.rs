@@ -0,0 +1,136 @@
+// tests/station_stall.rs
+
+// 응답이 오지 않는 측정소 하나가 PM_PER_STATION_TIMEOUT_SECS 에서 TIMEOUT 으로 끝나고 나머지 측정소는 정상 수집되는지 확인
+// (dry-run + payload 측정소 목록이므로 DB 불필요, 환경 변수를 바꾸므로 파일을 분리)
+
+use environment_lambda::failure::FailureKind;
+use environment_lambda::handler::{run_ingest, FetchOptions, StationStatus};
+use environment_lambda::inline_stations::InlineStation;
+use environment_lambda::provider::api_client::ApiFuture;
+use environment_lambda::provider::{ApiClient, ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+const STALLED: &str = "정체";
+
+// STALLED 측정소는 응답하지 않고, 나머지는 준비된 응답 반환
+struct StallingApiClient(MockApiClient);
+
+impl ApiClient for StallingApiClient {
+    fn fetch_station<'a>(
+        &'a self,
+        pm_station: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        if pm_station == STALLED {
+            return Box::pin(std::future::pending());
+        }
+        self.0.fetch_station(pm_station, params)
+    }
+
+    fn fetch_province<'a>(
+        &'a self,
+        sido_name: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.0.fetch_province(sido_name, params)
+    }
+
+    fn fetch_weather<'a>(&'a self, grid: &'a str, params: &'a [(String, String)]) -> ApiFuture<'a> {
+        self.0.fetch_weather(grid, params)
+    }
+
+    fn fetch_nearby_station<'a>(
+        &'a self,
+        point: &'a str,
+        params: &'a [(String, String)],
+    ) -> ApiFuture<'a> {
+        self.0.fetch_nearby_station(point, params)
+    }
+}
+
+fn station_body() -> String {
+    let data_time = chrono::Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+            },
+        }
+    })
+    .to_string()
+}
+
+// 접속하지 않는 풀 (dry-run + payload 측정소 목록은 DB 에 접근하지 않음)
+fn unused_pool() -> deadpool_postgres::Pool {
+    deadpool_postgres::Config {
+        url: Some("postgres://unused@127.0.0.1:1/unused".to_owned()),
+        ..Default::default()
+    }
+    .create_pool(
+        Some(deadpool_postgres::Runtime::Tokio1),
+        tokio_postgres::NoTls,
+    )
+    .unwrap()
+}
+
+#[tokio::test(start_paused = true)]
+async fn stalled_station_times_out_while_the_rest_complete() {
+    std::env::set_var("PM_PER_STATION_TIMEOUT_SECS", "5");
+    // 동시 호출 1개: 제한 시간이 없으면 정체된 측정소가 나머지를 모두 막음
+    std::env::set_var("API_CONCURRENCY", "1");
+
+    let stations = [STALLED, "중구", "종로구", "용산구"];
+    let mock = stations[1..]
+        .iter()
+        .fold(MockApiClient::new(), |mock, station| {
+            mock.with_envelope(station, ApiEnvelope::new(StatusCode::OK, station_body()))
+        });
+    let state = Arc::new(
+        ServerState::new(unused_pool(), "test-key".to_owned(), None, None)
+            .with_api_client(Arc::new(StallingApiClient(mock))),
+    );
+    let options = FetchOptions {
+        dry_run: true,
+        inline_stations: Some(
+            stations
+                .iter()
+                .enumerate()
+                .map(|(index, station)| InlineStation {
+                    sub_region_id: index as i32 + 1,
+                    pm_station: station.to_string(),
+                })
+                .collect(),
+        ),
+        ..Default::default()
+    };
+
+    let start = tokio::time::Instant::now();
+    let report = run_ingest(state, &options).await.unwrap();
+    // 정체된 측정소의 제한 시간만큼만 걸림
+    assert!(
+        start.elapsed() < Duration::from_secs(6),
+        "{:?}",
+        start.elapsed()
+    );
+
+    assert_eq!(report.results.len(), 4);
+    for result in &report.results {
+        match (&result.status, result.pm_station.as_str()) {
+            (StationStatus::Failed { kind, .. }, STALLED) => {
+                assert_eq!(*kind, FailureKind::Timeout)
+            }
+            (StationStatus::Success(_), _) => {}
+            (status, station) => panic!("{} : unexpected {:?}", station, status),
+        }
+    }
+}
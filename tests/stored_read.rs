@@ -0,0 +1,96 @@
+// tests/stored_read.rs
+
+// read: true 모드: 외부 API 호출 없이 저장된 external_pm 행을 측정소 이름과 함께 반환하는지 확인 (TEST_DATABASE_URL 필요)
+// sql() 의 전역 스키마를 쓰므로 파일을 분리
+
+mod common;
+
+use environment_lambda::db_schema;
+use environment_lambda::state::ServerState;
+use environment_lambda::stored::read_stored;
+use serde_json::json;
+
+const SCHEMA: &str = "test_stored_read";
+
+#[tokio::test]
+async fn stored_rows_are_returned_with_station_names() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+    common::fresh_schema(&pool, SCHEMA).await;
+    // 2 는 수집 중지, 9 는 sub_region 에 없는 저장 값
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "CREATE TABLE {SCHEMA}.sub_region (
+                 sub_region_id integer PRIMARY KEY,
+                 pm_station text,
+                 tm_x double precision,
+                 tm_y double precision,
+                 provider text,
+                 nx integer,
+                 ny integer,
+                 ingest_enabled boolean
+             );
+             INSERT INTO {SCHEMA}.sub_region (sub_region_id, pm_station, ingest_enabled) VALUES
+                 (1, '중구', true), (2, '종로구', false), (3, '용산구', true);
+             INSERT INTO {SCHEMA}.external_pm (sub_region_id, pm10, pm25, recorded_at, update_at) VALUES
+                 (2, 31, NULL, '2026-10-16 09:00+09', '2026-10-16 09:12+09'),
+                 (1, 30, 12, '2026-10-16 09:00+09', '2026-10-16 09:10+09'),
+                 (9, 50, 25, '2026-10-16 08:00+09', '2026-10-16 08:10+09');"
+        ))
+        .await
+        .unwrap();
+    let state = ServerState::new(pool, "test-key".to_owned(), None, None);
+
+    let body = read_stored(&state, "run-1", None).await.unwrap();
+    assert_eq!(
+        body,
+        json!({
+            "data": [
+                {
+                    "subRegionId": 1,
+                    "pm10Value": 30.0,
+                    "pm25Value": 12.0,
+                    "dataTime": "2026-10-16T00:00:00Z",
+                    "requestedTime": "2026-10-16T00:10:00Z",
+                    "stationName": "중구",
+                },
+                {
+                    "subRegionId": 2,
+                    "pm10Value": 31.0,
+                    "pm25Value": null,
+                    "dataTime": "2026-10-16T00:00:00Z",
+                    "requestedTime": "2026-10-16T00:12:00Z",
+                    "stationName": "종로구",
+                },
+                {
+                    "subRegionId": 9,
+                    "pm10Value": 50.0,
+                    "pm25Value": 25.0,
+                    "dataTime": "2026-10-15T23:00:00Z",
+                    "requestedTime": "2026-10-15T23:10:00Z",
+                    "stationName": null,
+                },
+            ],
+            "meta": { "runId": "run-1", "outcome": "OK", "count": 3 },
+        })
+    );
+
+    // 지정한 sub_region 중 저장된 것만 (3 은 저장 값 없음)
+    let body = read_stored(&state, "run-2", Some(&vec![2, 3]))
+        .await
+        .unwrap();
+    let ids: Vec<_> = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["subRegionId"].clone())
+        .collect();
+    assert_eq!(ids, vec![json!(2)]);
+    assert_eq!(body["data"][0]["stationName"], "종로구");
+    assert_eq!(body["meta"]["count"], 1);
+}
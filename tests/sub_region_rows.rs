@@ -0,0 +1,112 @@
+// tests/sub_region_rows.rs
+
+// sub_region 에 NULL / 잘못된 타입 값이 있어도 해당 행만 ROW_MAPPING 실패로 남기고 나머지는 수집하는지 확인 (TEST_DATABASE_URL 필요)
+// sql() 의 전역 스키마를 쓰므로 파일을 분리
+
+mod common;
+
+use chrono::Utc;
+use environment_lambda::db_schema;
+use environment_lambda::failure::FailureKind;
+use environment_lambda::handler::{run_ingest, FetchOptions, StationStatus};
+use environment_lambda::provider::{ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::Arc;
+
+const SCHEMA: &str = "test_sub_region_rows";
+
+fn station_body() -> String {
+    let data_time = Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+            },
+        }
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn bad_rows_are_skipped_and_the_rest_are_ingested() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+    common::fresh_schema(&pool, SCHEMA).await;
+    // 이전 스키마처럼 sub_region_id 가 NULL 허용 bigint 인 테이블
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "CREATE TABLE {SCHEMA}.sub_region (
+                 sub_region_id bigint,
+                 pm_station text,
+                 tm_x double precision,
+                 tm_y double precision,
+                 provider text,
+                 nx integer,
+                 ny integer,
+                 ingest_enabled boolean
+             );
+             INSERT INTO {SCHEMA}.sub_region (sub_region_id, pm_station) VALUES
+                 (1, '중구'),
+                 (NULL, '종로구'),
+                 (3000000000, '용산구');"
+        ))
+        .await
+        .unwrap();
+
+    let mock = MockApiClient::new()
+        .with_envelope("중구", ApiEnvelope::new(StatusCode::OK, station_body()));
+    let state = Arc::new(
+        ServerState::new(pool.clone(), "test-key".to_owned(), None, None)
+            .with_api_client(Arc::new(mock)),
+    );
+
+    let report = run_ingest(state, &FetchOptions::default()).await.unwrap();
+
+    assert_eq!(report.results.len(), 3);
+    let ok: Vec<_> = report
+        .results
+        .iter()
+        .filter(|result| matches!(result.status, StationStatus::Success(_)))
+        .map(|result| result.sub_region_id)
+        .collect();
+    assert_eq!(ok, vec![1], "{:#?}", report.results);
+
+    let failures: Vec<_> = report
+        .results
+        .iter()
+        .filter_map(|result| match &result.status {
+            StationStatus::Failed { kind, message } => Some((result.sub_region_id, *kind, message)),
+            StationStatus::Success(_) => None,
+        })
+        .collect();
+    // sub_region_id 를 읽지 못한 행은 0 으로 기록
+    assert_eq!(failures.len(), 2);
+    assert!(
+        failures[0].2.contains("sub_region_id is NULL"),
+        "{}",
+        failures[0].2
+    );
+    assert!(
+        failures[1]
+            .2
+            .contains("sub_region_id 3000000000 is out of i32 range"),
+        "{}",
+        failures[1].2
+    );
+    for (sub_region_id, kind, _) in &failures {
+        assert_eq!((*sub_region_id, *kind), (0, FailureKind::RowMapping));
+    }
+}
@@ -0,0 +1,142 @@
+// tests/trigger_dispatch.rs
+
+// handle_event 가 트리거별로 나눠 처리하는지 확인 (TEST_DATABASE_URL 필요)
+// - EventBridge 예약 이벤트: detail 을 수집 옵션으로, 이벤트 id 를 중복 실행 방지 키로 사용
+// - SQS: 본문을 해석하지 못한 레코드만 batchItemFailures 로 반환
+// - 잘못된 Records: 전체 수집 대신 호출 실패
+// shared_state_from_env 의 전역 상태 / sql() 의 전역 스키마를 쓰므로 파일을 분리
+
+mod common;
+
+use environment_lambda::handler::handle_event;
+use environment_lambda::invocation::InvocationInfo;
+use serde_json::json;
+
+const SCHEMA: &str = "test_trigger_dispatch";
+
+// 디버그 빌드에서는 handle_event 의 poll 호출 깊이가 테스트 스레드 기본 스택(2 MiB)을 넘으므로 별도 스레드에서 실행
+fn run_with_large_stack<F>(future: F) -> F::Output
+where
+    F: std::future::Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    std::thread::Builder::new()
+        .stack_size(16 * 1024 * 1024)
+        .spawn(move || {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(future)
+        })
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+// 수집 마감이 이미 지난 호출 (외부 API 를 부르지 않음)
+fn invoke(
+    payload: serde_json::Value,
+) -> Result<serde_json::Value, environment_lambda::handler::Error> {
+    let invocation = InvocationInfo {
+        aws_request_id: "aws-1".to_owned(),
+        invoked_function_arn: String::new(),
+        function_version: "$LATEST".to_owned(),
+        deadline_millis: 0,
+        remaining: Some(std::time::Duration::from_secs(1)),
+        deadline: Some(tokio::time::Instant::now()),
+    };
+    run_with_large_stack(handle_event(
+        "run-1".to_owned(),
+        "req-1".to_owned(),
+        payload,
+        invocation,
+    ))
+}
+
+fn scheduled_event(id: &str, dry_run: bool) -> serde_json::Value {
+    json!({
+        "version": "0",
+        "id": id,
+        "detail-type": "Scheduled Event",
+        "source": "aws.events",
+        "account": "123456789012",
+        "time": "2024-05-01T04:00:00Z",
+        "region": "ap-northeast-2",
+        "resources": ["arn:aws:events:ap-northeast-2:123456789012:rule/pm-ingest"],
+        "detail": {
+            "dryRun": dry_run,
+            "stations": [{ "subRegionId": 1, "pmStation": "중구" }],
+        },
+    })
+}
+
+// 전역 상태를 공유하므로 순서대로 하나의 테스트로 구성
+#[tokio::test]
+async fn each_trigger_is_dispatched_by_its_shape() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    common::fresh_schema(&pool, SCHEMA).await;
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "CREATE TABLE {SCHEMA}.sub_region (
+                 sub_region_id integer PRIMARY KEY,
+                 pm_station text NOT NULL,
+                 tm_x double precision,
+                 tm_y double precision,
+                 provider text,
+                 nx integer,
+                 ny integer,
+                 ingest_enabled boolean NOT NULL DEFAULT true
+             );
+             CREATE TABLE {SCHEMA}.ingest_idempotency (
+                 event_id text PRIMARY KEY,
+                 processed_at timestamptz NOT NULL,
+                 summary jsonb
+             );"
+        ))
+        .await
+        .unwrap();
+    std::env::set_var("DB_CONN_URL", std::env::var("TEST_DATABASE_URL").unwrap());
+    std::env::set_var("AIR_QUALITY_API_KEY", "test-key");
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+
+    // 예약 이벤트: detail 의 측정소 목록으로 수집
+    let response = invoke(scheduled_event("evt-0", true)).unwrap();
+    assert_eq!(response["statusCode"], 200);
+    let meta = &response["body"]["meta"];
+    assert_eq!(meta["stationListSource"], "payload");
+    assert_eq!(meta["errors"][0]["subRegionId"], 1);
+
+    // 같은 예약 이벤트가 다시 전달되면 이벤트 id 로 중복 처리 생략, 다른 id 는 새로 수집
+    let response = invoke(scheduled_event("evt-1", false)).unwrap();
+    assert!(response["body"].get("deduplicated").is_none());
+    let response = invoke(scheduled_event("evt-1", false)).unwrap();
+    assert_eq!(response["body"]["deduplicated"], true);
+    let response = invoke(scheduled_event("evt-2", false)).unwrap();
+    assert!(response["body"].get("deduplicated").is_none());
+
+    // SQS: 본문이 잘못된 레코드만 실패 (없는 sub_region 은 수집할 측정소가 없으므로 성공)
+    let response = invoke(json!({
+        "Records": [
+            { "messageId": "m-1", "body": r#"{"sub_region_ids": [999]}"#, "eventSource": "aws:sqs" },
+            { "messageId": "m-2", "body": "not json", "eventSource": "aws:sqs" },
+            { "messageId": "m-3", "body": r#"{"sub_region_ids": ["1"]}"#, "eventSource": "aws:sqs" },
+        ],
+    }))
+    .unwrap();
+    assert_eq!(
+        response,
+        json!({
+            "batchItemFailures": [{ "itemIdentifier": "m-2" }, { "itemIdentifier": "m-3" }],
+        })
+    );
+
+    // SQS 형태가 아닌 Records 는 호출 실패
+    let e = invoke(json!({ "Records": [{ "messageId": "m-1", "eventSource": "aws:s3" }] }))
+        .unwrap_err();
+    assert!(e.to_string().starts_with("req-1 : "), "{}", e);
+}
@@ -0,0 +1,143 @@
+// tests/unconfigured_sub_regions.rs
+
+// 측정소 이름/좌표/격자가 없거나 제공처가 잘못된 sub_region 이 API 호출 없이 STATION_CONFIG 로 남고
+// 응답 meta.unconfiguredSubRegions 에 모이는지 확인 (TEST_DATABASE_URL 필요)
+// sql() 의 전역 스키마를 쓰므로 파일을 분리
+
+mod common;
+
+use chrono::Utc;
+use environment_lambda::db_schema;
+use environment_lambda::failure::FailureKind;
+use environment_lambda::handler::{build_response_body, run_ingest, FetchOptions, StationStatus};
+use environment_lambda::provider::{ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use environment_lambda::timeutil::KST_OFFSET;
+use environment_lambda::weather::run_weather_ingest;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::Arc;
+
+const SCHEMA: &str = "test_unconfigured_sub_regions";
+
+fn station_body() -> String {
+    let data_time = Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+            },
+        }
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn unconfigured_sub_regions_are_reported_without_api_calls() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+    common::fresh_schema(&pool, SCHEMA).await;
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "CREATE TABLE {SCHEMA}.sub_region (
+                 sub_region_id integer PRIMARY KEY,
+                 pm_station text,
+                 tm_x double precision,
+                 tm_y double precision,
+                 provider text,
+                 nx integer,
+                 ny integer,
+                 ingest_enabled boolean
+             );
+             INSERT INTO {SCHEMA}.sub_region (sub_region_id, pm_station, tm_x, provider, nx, ny) VALUES
+                 (1, '중구', NULL, NULL, 60, 127),
+                 (2, NULL, NULL, NULL, NULL, NULL),
+                 (3, NULL, 244148.5, NULL, 60, NULL),
+                 (4, '용산구', NULL, 'unknown', NULL, 126),
+                 (5, '  ', NULL, NULL, 60, 127);"
+        ))
+        .await
+        .unwrap();
+
+    // 준비된 응답은 중구뿐이므로 다른 측정소를 조회하면 REQUEST 실패가 됨
+    let mock = MockApiClient::new()
+        .with_envelope("중구", ApiEnvelope::new(StatusCode::OK, station_body()));
+    let state = Arc::new(
+        ServerState::new(
+            pool.clone(),
+            "test-key".to_owned(),
+            Some("weather-key".to_owned()),
+            None,
+        )
+        .with_api_client(Arc::new(mock)),
+    );
+
+    // PM: 이름도 좌표(tm_x, tm_y 둘 다)도 없는 2, 3 과 알 수 없는 제공처 4
+    let report = run_ingest(state.clone(), &FetchOptions::default())
+        .await
+        .unwrap();
+    let mut kinds: Vec<_> = report
+        .results
+        .iter()
+        .map(|result| match &result.status {
+            StationStatus::Success(_) => (result.sub_region_id, None),
+            StationStatus::Failed { kind, .. } => (result.sub_region_id, Some(*kind)),
+        })
+        .collect();
+    kinds.sort_by_key(|(sub_region_id, _)| *sub_region_id);
+    assert_eq!(
+        kinds,
+        vec![
+            (1, None),
+            (2, Some(FailureKind::StationConfig)),
+            (3, Some(FailureKind::StationConfig)),
+            (4, Some(FailureKind::StationConfig)),
+            // 빈 문자열 측정소 이름은 미설정과 구분
+            (5, Some(FailureKind::UnmappedStation)),
+        ]
+    );
+    let body = build_response_body(report);
+    let mut unconfigured: Vec<i64> = body["meta"]["unconfiguredSubRegions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|id| id.as_i64().unwrap())
+        .collect();
+    unconfigured.sort();
+    assert_eq!(unconfigured, vec![2, 3, 4]);
+    assert_eq!(body["meta"]["unmappedStations"], json!([5]));
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+
+    // 기상: nx/ny 가 하나라도 없는 격자만 골라 조회 (API 요청 전에 실패 처리)
+    let options = FetchOptions {
+        sub_region_ids: Some(vec![2, 3, 4]),
+        ..Default::default()
+    };
+    let report = run_weather_ingest(state, &options).await.unwrap();
+    assert_eq!(report.results.len(), 3);
+    for result in &report.results {
+        let StationStatus::Failed { kind, message } = &result.status else {
+            panic!("{} should be unconfigured", result.sub_region_id);
+        };
+        assert_eq!(*kind, FailureKind::StationConfig, "{}", message);
+        assert!(message.contains("nx/ny are not configured"), "{}", message);
+    }
+    let body = build_response_body(report);
+    assert_eq!(
+        body["meta"]["unconfiguredSubRegions"]
+            .as_array()
+            .unwrap()
+            .len(),
+        3
+    );
+}
@@ -0,0 +1,49 @@
+// tests/upsert_delta.rs
+
+// upsert RETURNING 의 갱신 전 값(prev_pm10 / prev_pm25)과 변화량 확인 (TEST_DATABASE_URL 필요)
+
+mod common;
+
+use chrono::{Duration, Utc};
+use environment_lambda::db_schema::apply_schema;
+use environment_lambda::handler::UPSERT_EXTERNAL_PM_QUERY;
+use environment_lambda::upserted::UpsertedRow;
+
+#[tokio::test]
+async fn second_upsert_returns_previous_values_and_delta() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    let schema = common::fresh_schema(&pool, "test_upsert_delta").await;
+    let client = pool.get().await.unwrap();
+    let query = apply_schema(UPSERT_EXTERNAL_PM_QUERY, &schema);
+    let recorded_at = Utc::now() - Duration::hours(1);
+
+    let first = client
+        .query_one(&query, &[&7, &Some(10.0), &Some(5.0), &recorded_at])
+        .await
+        .unwrap();
+    let first = UpsertedRow::from_row(&first).unwrap();
+    assert_eq!(first.prev_pm10, None);
+    assert_eq!(first.to_json("중구")["pm10Delta"], serde_json::Value::Null);
+
+    let second = client
+        .query_one(
+            &query,
+            &[
+                &7,
+                &Some(25.0),
+                &Some(8.0),
+                &(recorded_at + Duration::hours(1)),
+            ],
+        )
+        .await
+        .unwrap();
+    let second = UpsertedRow::from_row(&second).unwrap();
+    assert_eq!(second.prev_pm10, Some(10.0));
+    assert_eq!(second.prev_pm25, Some(5.0));
+
+    let data = second.to_json("중구");
+    assert_eq!(data["pm10Delta"], 15.0);
+    assert_eq!(data["pm25Delta"], 3.0);
+}
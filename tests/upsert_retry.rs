@@ -0,0 +1,124 @@
+// tests/upsert_retry.rs
+
+// 일시적인 DB 오류(serialization_failure)로 실패한 upsert 가 새 커넥션으로 재시도되는지 확인 (TEST_DATABASE_URL 필요)
+// sql() 의 전역 스키마를 쓰므로 파일을 분리
+
+mod common;
+
+use chrono::Utc;
+use environment_lambda::db_schema;
+use environment_lambda::failure::FailureKind;
+use environment_lambda::handler::{run_ingest, FetchOptions, StationStatus};
+use environment_lambda::inline_stations::InlineStation;
+use environment_lambda::provider::{ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::Arc;
+
+const SCHEMA: &str = "test_upsert_retry";
+
+fn station_body() -> String {
+    let data_time = Utc::now()
+        .with_timezone(&KST_OFFSET)
+        .format("%Y-%m-%d %H:00")
+        .to_string();
+    json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{ "dataTime": data_time, "pm10Value": "42", "pm25Value": "20" }],
+            },
+        }
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn transient_upsert_failures_are_retried_on_a_fresh_connection() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+    common::fresh_schema(&pool, SCHEMA).await;
+    // sub_region 1 은 첫 upsert 만, 2 는 매번 40001 로 실패시키는 트리거
+    // (실패한 upsert 의 UPDATE 는 함께 롤백되므로 횟수는 롤백되지 않는 시퀀스로 셈)
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "CREATE SEQUENCE {SCHEMA}.upsert_attempts;
+             CREATE FUNCTION {SCHEMA}.fail_upsert() RETURNS trigger AS $$
+             BEGIN
+                 IF NEW.sub_region_id = 2
+                     OR (NEW.sub_region_id = 1 AND nextval('{SCHEMA}.upsert_attempts') = 1) THEN
+                     RAISE EXCEPTION 'injected' USING ERRCODE = '40001';
+                 END IF;
+                 RETURN NEW;
+             END $$ LANGUAGE plpgsql;
+             CREATE TRIGGER fail_upsert BEFORE INSERT ON {SCHEMA}.external_pm
+                 FOR EACH ROW EXECUTE FUNCTION {SCHEMA}.fail_upsert();"
+        ))
+        .await
+        .unwrap();
+
+    let stations = ["중구", "종로구"];
+    let mock = stations.iter().fold(MockApiClient::new(), |mock, station| {
+        mock.with_envelope(station, ApiEnvelope::new(StatusCode::OK, station_body()))
+    });
+    let state = Arc::new(
+        ServerState::new(pool.clone(), "test-key".to_owned(), None, None)
+            .with_api_client(Arc::new(mock)),
+    );
+    let options = FetchOptions {
+        inline_stations: Some(
+            stations
+                .iter()
+                .enumerate()
+                .map(|(index, station)| InlineStation {
+                    sub_region_id: index as i32 + 1,
+                    pm_station: station.to_string(),
+                })
+                .collect(),
+        ),
+        ..Default::default()
+    };
+
+    let report = run_ingest(state, &options).await.unwrap();
+    let mut results = report.results;
+    results.sort_by_key(|result| result.sub_region_id);
+
+    // 한 번 실패한 측정소는 재시도로 저장됨
+    assert!(
+        matches!(results[0].status, StationStatus::Success(_)),
+        "{:?}",
+        results[0].status
+    );
+    assert_eq!(results[0].upsert_retries, 1);
+
+    // 계속 실패하면 재시도 한도(2회) 후 실패로 기록
+    let StationStatus::Failed { kind, message } = &results[1].status else {
+        panic!("{:?}", results[1].status);
+    };
+    assert_eq!(*kind, FailureKind::DbQuery);
+    assert!(message.contains("[40001]"), "{}", message);
+    assert_eq!(results[1].upsert_retries, 2);
+
+    let stored: Vec<i32> = pool
+        .get()
+        .await
+        .unwrap()
+        .query(
+            &format!("SELECT sub_region_id FROM {SCHEMA}.external_pm"),
+            &[],
+        )
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+    assert_eq!(stored, vec![1]);
+}
@@ -0,0 +1,89 @@
+// tests/upstream_lag.rs
+
+// 응답 Date 헤더로 계산한 상위 데이터 지연이 측정소별 upstreamLagSeconds 로 나가는지,
+// 헤더가 없으면 null 인지 확인 (dry-run + payload 측정소 목록이므로 DB 불필요)
+
+use chrono::{Duration, DurationRound, Utc};
+use environment_lambda::handler::{run_ingest, FetchOptions, StationStatus};
+use environment_lambda::inline_stations::InlineStation;
+use environment_lambda::provider::{ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use environment_lambda::timeutil::KST_OFFSET;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::sync::Arc;
+
+// 접속하지 않는 풀 (dry-run + payload 측정소 목록은 DB 에 접근하지 않음)
+fn unused_pool() -> deadpool_postgres::Pool {
+    deadpool_postgres::Config {
+        url: Some("postgres://unused@127.0.0.1:1/unused".to_owned()),
+        ..Default::default()
+    }
+    .create_pool(
+        Some(deadpool_postgres::Runtime::Tokio1),
+        tokio_postgres::NoTls,
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn lag_comes_from_the_response_date_header() {
+    // 정시 측정값을 25분 뒤 서버 시각으로 응답
+    let recorded_at = Utc::now().duration_trunc(Duration::hours(1)).unwrap();
+    let body = json!({
+        "response": {
+            "header": { "resultCode": "00", "resultMsg": "NORMAL_CODE" },
+            "body": {
+                "totalCount": 1,
+                "items": [{
+                    "dataTime": recorded_at.with_timezone(&KST_OFFSET).format("%Y-%m-%d %H:%M").to_string(),
+                    "pm10Value": "42",
+                    "pm25Value": "20",
+                }],
+            },
+        }
+    })
+    .to_string();
+    let mock = MockApiClient::new()
+        .with_envelope(
+            "중구",
+            ApiEnvelope::new(StatusCode::OK, body.clone())
+                .with_server_date(recorded_at + Duration::minutes(25)),
+        )
+        .with_envelope("종로구", ApiEnvelope::new(StatusCode::OK, body));
+    let state = Arc::new(
+        ServerState::new(unused_pool(), "test-key".to_owned(), None, None)
+            .with_api_client(Arc::new(mock)),
+    );
+
+    let options = FetchOptions {
+        dry_run: true,
+        inline_stations: Some(vec![
+            InlineStation {
+                sub_region_id: 1,
+                pm_station: "중구".to_owned(),
+            },
+            InlineStation {
+                sub_region_id: 2,
+                pm_station: "종로구".to_owned(),
+            },
+        ]),
+        ..Default::default()
+    };
+    let report = run_ingest(state, &options).await.unwrap();
+
+    let lag = |sub_region_id: i32| {
+        let result = report
+            .results
+            .iter()
+            .find(|result| result.sub_region_id == sub_region_id)
+            .unwrap();
+        let StationStatus::Success(data) = &result.status else {
+            panic!("{} : {:?}", sub_region_id, result.status);
+        };
+        data["upstreamLagSeconds"].clone()
+    };
+    assert_eq!(lag(1), json!(25 * 60));
+    // Date 헤더가 없는 응답
+    assert_eq!(lag(2), json!(null));
+}
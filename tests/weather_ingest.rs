@@ -0,0 +1,154 @@
+// tests/weather_ingest.rs
+
+// 날씨 수집: ingest_enabled = false 인 sub_region 제외, 실행 마감 시각 적용 (TEST_DATABASE_URL 필요)
+// 외부 API 는 MockApiClient 의 준비된 응답으로 대신함 (격자 "nx,ny" 별)
+// sql() 이 쓰는 전역 스키마를 이 테스트 전용 스키마로 고정하므로 파일을 분리
+
+mod common;
+
+use deadpool_postgres::Pool;
+use environment_lambda::db_schema;
+use environment_lambda::failure::FailureKind;
+use environment_lambda::handler::FetchOptions;
+use environment_lambda::provider::{ApiEnvelope, MockApiClient};
+use environment_lambda::state::ServerState;
+use environment_lambda::weather::run_weather_ingest;
+use reqwest::StatusCode;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+const SCHEMA: &str = "test_weather_ingest";
+
+// 두 테스트가 같은 스키마를 다시 만들므로 순서대로 실행
+static SCHEMA_LOCK: Mutex<()> = Mutex::const_new(());
+
+async fn reset_schema(pool: &Pool) {
+    std::env::set_var("PM_DB_SCHEMA", SCHEMA);
+    db_schema::init_from_env().unwrap();
+    pool.get()
+        .await
+        .unwrap()
+        .batch_execute(&format!(
+            "DROP SCHEMA IF EXISTS {SCHEMA} CASCADE; CREATE SCHEMA {SCHEMA};
+             CREATE TABLE {SCHEMA}.sub_region (
+                 sub_region_id integer PRIMARY KEY,
+                 nx integer,
+                 ny integer,
+                 ingest_enabled boolean
+             );
+             CREATE TABLE {SCHEMA}.external_weather (
+                 sub_region_id integer PRIMARY KEY,
+                 temperature double precision,
+                 humidity double precision,
+                 wind_speed double precision,
+                 recorded_at timestamptz NOT NULL,
+                 update_at timestamptz NOT NULL DEFAULT now()
+             );
+             INSERT INTO {SCHEMA}.sub_region VALUES
+                 (1, NULL, NULL, true),
+                 (2, NULL, NULL, false),
+                 (3, NULL, NULL, NULL),
+                 (4, 60, 127, true);"
+        ))
+        .await
+        .unwrap();
+}
+
+fn weather_state(pool: Pool) -> ServerState {
+    ServerState {
+        weather_api_key: Some("test-key".to_owned()),
+        ..ServerState::new(pool, "test-key".to_owned(), None, None)
+    }
+}
+
+#[tokio::test]
+async fn skips_disabled_sub_regions_and_honours_the_deadline() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    let _guard = SCHEMA_LOCK.lock().await;
+    reset_schema(&pool).await;
+    let state = Arc::new(weather_state(pool));
+    // 마감 시각이 이미 지났으면 격자가 있는 sub_region 도 조회하지 않고 Timeout
+    let options = FetchOptions {
+        deadline: Some(tokio::time::Instant::now()),
+        ..Default::default()
+    };
+    let report = run_weather_ingest(state.clone(), &options).await.unwrap();
+    let mut kinds: Vec<(i32, Option<FailureKind>)> = report
+        .results
+        .iter()
+        .map(|result| (result.sub_region_id, result.failure_kind()))
+        .collect();
+    kinds.sort_by_key(|(sub_region_id, _)| *sub_region_id);
+    assert_eq!(
+        kinds,
+        vec![
+            (1, Some(FailureKind::StationConfig)),
+            (3, Some(FailureKind::StationConfig)),
+            (4, Some(FailureKind::Timeout)),
+        ]
+    );
+
+    // includeDisabled: true 면 수집 중지된 sub_region 도 포함
+    let options = FetchOptions {
+        sub_region_ids: Some(vec![1, 2]),
+        include_disabled: true,
+        ..Default::default()
+    };
+    let report = run_weather_ingest(state, &options).await.unwrap();
+    let mut ids: Vec<i32> = report.results.iter().map(|r| r.sub_region_id).collect();
+    ids.sort();
+    assert_eq!(ids, vec![1, 2]);
+}
+
+#[tokio::test]
+async fn fetches_through_the_api_client_and_skips_the_upsert_on_dry_run() {
+    let Some(pool) = common::test_pool() else {
+        return;
+    };
+    let _guard = SCHEMA_LOCK.lock().await;
+    reset_schema(&pool).await;
+    let body = serde_json::json!({ "response": {
+        "header": { "resultCode": "00", "resultMsg": "NORMAL_SERVICE" },
+        "body": { "items": { "item": [
+            { "category": "T1H", "obsrValue": "3.5" },
+            { "category": "REH", "obsrValue": "61" },
+        ] } },
+    } })
+    .to_string();
+    let api_client =
+        MockApiClient::new().with_envelope("60,127", ApiEnvelope::new(StatusCode::OK, body));
+    let state = Arc::new(weather_state(pool.clone()).with_api_client(Arc::new(api_client)));
+    let stored = || async {
+        pool.get()
+            .await
+            .unwrap()
+            .query_one(
+                &format!("SELECT count(*) FROM {SCHEMA}.external_weather"),
+                &[],
+            )
+            .await
+            .unwrap()
+            .get::<_, i64>(0)
+    };
+
+    // dryRun: 조회 결과는 돌려주지만 저장하지 않음
+    let options = FetchOptions {
+        sub_region_ids: Some(vec![4]),
+        dry_run: true,
+        ..Default::default()
+    };
+    let report = run_weather_ingest(state.clone(), &options).await.unwrap();
+    assert_eq!(report.results.len(), 1);
+    assert_eq!(report.results[0].failure_kind(), None);
+    assert_eq!(stored().await, 0);
+
+    let options = FetchOptions {
+        sub_region_ids: Some(vec![4]),
+        ..Default::default()
+    };
+    let report = run_weather_ingest(state, &options).await.unwrap();
+    assert_eq!(report.results[0].failure_kind(), None);
+    assert_eq!(stored().await, 1);
+}